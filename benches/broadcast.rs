@@ -1,6 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
 use postage::broadcast;
 use postage::{sink::Sink, stream::Stream};
+
+use futures::FutureExt;
 #[derive(Clone, Debug)]
 struct Message;
 
@@ -37,5 +39,76 @@ pub fn recv_empty(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, send_recv, send_full, recv_empty);
+// each message is received by every receiver, and the sender is never blocked
+pub fn throughput(c: &mut Criterion) {
+    const MESSAGES: usize = 1024;
+
+    let mut group = c.benchmark_group("broadcast::throughput");
+    for receivers in [1usize, 4, 16].iter().copied() {
+        let (mut tx, rx) = broadcast::channel::<usize>(64);
+        let mut receivers: Vec<_> = (1..receivers)
+            .map(|_| tx.subscribe())
+            .chain(Some(rx))
+            .collect();
+
+        group.throughput(Throughput::Elements((MESSAGES * receivers.len()) as u64));
+        group.bench_function(format!("{}_receivers", receivers.len()), |b| {
+            b.iter(|| {
+                for i in 0..MESSAGES {
+                    tx.try_send(black_box(i)).unwrap();
+                    for rx in receivers.iter_mut() {
+                        black_box(rx.try_recv().unwrap());
+                    }
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+// a stand-in for serializing a frame
+fn encode(frame: usize) -> Vec<u8> {
+    (0..1024).map(|i| (i ^ frame) as u8).collect()
+}
+
+// the receivers lag, so most messages are dropped before they are read
+pub fn lossy_send_encode(c: &mut Criterion) {
+    let (mut tx, rx) = broadcast::lossy_fanout_channel::<Vec<u8>>(4);
+    let _receivers: Vec<_> = (0..8).map(|_| tx.subscribe()).chain(Some(rx)).collect();
+
+    let mut frame = 0usize;
+    c.bench_function("broadcast::lossy_send_encode", |b| {
+        b.iter(|| {
+            frame += 1;
+            tx.try_send(encode(black_box(frame))).unwrap();
+        });
+    });
+}
+
+pub fn lossy_send_with(c: &mut Criterion) {
+    let (mut tx, rx) = broadcast::lossy_fanout_channel::<Vec<u8>>(4);
+    let _receivers: Vec<_> = (0..8).map(|_| tx.subscribe()).chain(Some(rx)).collect();
+
+    let mut frame = 0usize;
+    c.bench_function("broadcast::lossy_send_with", |b| {
+        b.iter(|| {
+            frame += 1;
+            let frame = black_box(frame);
+            tx.send_with(move || encode(frame))
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    send_recv,
+    send_full,
+    recv_empty,
+    throughput,
+    lossy_send_encode,
+    lossy_send_with
+);
 criterion_main!(benches);
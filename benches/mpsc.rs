@@ -38,5 +38,84 @@ pub fn recv_empty(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, send_recv, send_full, recv_empty);
+pub fn static_send_recv(c: &mut Criterion) {
+    let (mut tx, mut rx) = mpsc::static_channel::<Message, 8>();
+    c.bench_function("mpsc::static_send_recv", |b| {
+        b.iter(|| {
+            tx.try_send(black_box(Message {})).unwrap();
+            rx.try_recv().unwrap();
+        });
+    });
+}
+
+pub fn static_send_full(c: &mut Criterion) {
+    let (mut tx, _rx) = mpsc::static_channel::<Message, 4>();
+    for _ in 0..4 {
+        tx.try_send(Message {}).unwrap();
+    }
+
+    c.bench_function("mpsc::static_send_full", |b| {
+        b.iter(|| {
+            tx.try_send(black_box(Message {})).ok();
+        });
+    });
+}
+
+pub fn static_recv_empty(c: &mut Criterion) {
+    let (_tx, mut rx) = mpsc::static_channel::<Message, 4>();
+
+    c.bench_function("mpsc::static_recv_empty", |b| {
+        b.iter(|| {
+            black_box(rx.try_recv().ok());
+        });
+    });
+}
+
+// a fast consumer on another thread, with a producer that frequently finds the channel full
+#[cfg(feature = "blocking")]
+fn send_contended(c: &mut Criterion, name: &str, spins: usize) {
+    let (tx, rx) = mpsc::channel::<Message>(1);
+    let mut tx = tx.spin_sends(spins);
+    let mut rx = rx.spin_recvs(spins);
+
+    let consumer = std::thread::spawn(move || while rx.blocking_recv().is_some() {});
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            tx.blocking_send(black_box(Message {})).unwrap();
+        });
+    });
+
+    drop(tx);
+    consumer.join().unwrap();
+}
+
+#[cfg(feature = "blocking")]
+pub fn send_contended_park(c: &mut Criterion) {
+    send_contended(c, "mpsc::send_contended_park", 0);
+}
+
+#[cfg(feature = "blocking")]
+pub fn send_contended_spin(c: &mut Criterion) {
+    send_contended(c, "mpsc::send_contended_spin", 256);
+}
+
+criterion_group!(
+    benches,
+    send_recv,
+    send_full,
+    recv_empty,
+    static_send_recv,
+    static_send_full,
+    static_recv_empty
+);
+
+// the contended benchmarks block the producer and consumer threads
+#[cfg(feature = "blocking")]
+criterion_group!(contended, send_contended_park, send_contended_spin);
+
+#[cfg(feature = "blocking")]
+criterion_main!(benches, contended);
+
+#[cfg(not(feature = "blocking"))]
 criterion_main!(benches);
@@ -0,0 +1,164 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use postage::spsc;
+use postage::{sink::Sink, stream::Stream};
+
+// Counts allocations, so the benchmark can check that the try paths never allocate
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Clone, Debug)]
+struct Message;
+
+fn assert_no_allocations(tx: &mut spsc::Sender<Message>, rx: &mut spsc::Receiver<Message>) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..10_000 {
+        tx.try_send(black_box(Message {})).unwrap();
+        rx.try_recv().unwrap();
+        rx.try_recv().ok();
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+
+    assert_eq!(0, after - before, "the spsc try paths allocated");
+}
+
+// Runs the try paths in a forked child under a seccomp filter which kills the process on any
+// syscall other than exit_group, so the benchmark can check that the try paths never make one
+#[cfg(target_os = "linux")]
+fn assert_no_syscalls(tx: &mut spsc::Sender<Message>, rx: &mut spsc::Receiver<Message>) {
+    let offset = 0; // offset_of!(seccomp_data, nr)
+    let mut filter = unsafe {
+        [
+            libc::BPF_STMT((libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16, offset),
+            libc::BPF_JUMP(
+                (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+                libc::SYS_exit_group as u32,
+                0,
+                1,
+            ),
+            libc::BPF_STMT(
+                (libc::BPF_RET | libc::BPF_K) as u16,
+                libc::SECCOMP_RET_ALLOW,
+            ),
+            libc::BPF_STMT(
+                (libc::BPF_RET | libc::BPF_K) as u16,
+                libc::SECCOMP_RET_KILL_PROCESS,
+            ),
+        ]
+    };
+    let program = libc::sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_mut_ptr(),
+    };
+
+    match unsafe { libc::fork() } {
+        -1 => panic!("fork failed"),
+        0 => unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0
+                || libc::prctl(
+                    libc::PR_SET_SECCOMP,
+                    libc::SECCOMP_MODE_FILTER,
+                    &program as *const libc::sock_fprog,
+                ) != 0
+            {
+                libc::_exit(2);
+            }
+
+            // the child must not panic, as formatting the message would make a syscall
+            for _ in 0..10_000 {
+                if tx.try_send(black_box(Message {})).is_err() || rx.try_recv().is_err() {
+                    libc::_exit(1);
+                }
+                rx.try_recv().ok();
+            }
+
+            libc::_exit(0);
+        },
+        child => {
+            let mut status = 0;
+            assert_eq!(child, unsafe { libc::waitpid(child, &mut status, 0) });
+
+            if libc::WIFSIGNALED(status) {
+                assert_ne!(
+                    libc::SIGSYS,
+                    libc::WTERMSIG(status),
+                    "the spsc try paths made a syscall"
+                );
+                panic!(
+                    "the syscall check was killed by signal {}",
+                    libc::WTERMSIG(status)
+                );
+            }
+
+            match libc::WEXITSTATUS(status) {
+                0 => {}
+                2 => eprintln!("seccomp is unavailable, skipping the spsc syscall check"),
+                code => panic!("the syscall check failed with status {}", code),
+            }
+        }
+    }
+}
+
+pub fn send_recv(c: &mut Criterion) {
+    let (mut tx, mut rx) = spsc::channel::<Message>(8);
+    assert_no_allocations(&mut tx, &mut rx);
+    #[cfg(target_os = "linux")]
+    assert_no_syscalls(&mut tx, &mut rx);
+
+    c.bench_function("spsc::send_recv", |b| {
+        b.iter(|| {
+            tx.try_send(black_box(Message {})).unwrap();
+            rx.try_recv().unwrap();
+        });
+    });
+}
+
+pub fn send_full(c: &mut Criterion) {
+    let (mut tx, _rx) = spsc::channel::<Message>(4);
+    for _ in 0..4 {
+        tx.try_send(Message {}).unwrap();
+    }
+
+    c.bench_function("spsc::send_full", |b| {
+        b.iter(|| {
+            tx.try_send(black_box(Message {})).ok();
+        });
+    });
+}
+
+pub fn recv_empty(c: &mut Criterion) {
+    let (_tx, mut rx) = spsc::channel::<Message>(4);
+
+    c.bench_function("spsc::recv_empty", |b| {
+        b.iter(|| {
+            black_box(rx.try_recv().ok());
+        });
+    });
+}
+
+criterion_group!(benches, send_recv, send_full, recv_empty);
+criterion_main!(benches);
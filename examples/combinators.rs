@@ -1,5 +1,6 @@
 use postage::{mpsc, oneshot, prelude::Stream, sink::Sink};
 
+#[allow(dead_code)]
 #[derive(Debug)]
 enum Message {
     Str(&'static str),
@@ -16,9 +17,9 @@ async fn main() {
 
     let mut rx = rx_a
         // map the first reciever to a common enum type
-        .map(|a| Message::Str(a))
+        .map(Message::Str)
         // map the 2nd receiver to the enum type, and then merge it with the first
-        .merge(rx_b.map(|b| Message::Code(b)));
+        .merge(rx_b.map(Message::Code));
 
     while let Some(message) = rx.recv().await {
         println!("Sender says {:?}", message)
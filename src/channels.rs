@@ -1,19 +1,28 @@
 pub mod barrier;
 pub mod broadcast;
+mod capacity;
 pub mod dispatch;
+mod expiry;
+mod idle;
+mod mapped;
+mod meta;
 pub mod mpsc;
 pub mod oneshot;
+mod prefill;
+pub mod spsc;
 pub mod watch;
 
-use std::{cell::Cell, marker::Sync};
+pub use capacity::{Capacity, InvalidCapacity};
 
-use static_assertions::{assert_impl_all, assert_not_impl_all};
+use std::{cell::Cell, marker::Sync, rc::Rc};
+
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
 
 // Testing types for static assertions on channel endpoints
-// Some channel implementations have unsafe Sync impls,
-//   even if their generic type does not impl Sync (or &T impl Send)
+// Endpoints should be Send and Sync if T: Send, unless receivers share references to messages
+//   (broadcast and watch), which requires T: Sync.
 // In order to pin down this behavior, these testing messages
-//   are used as generics in static assertions.
+//   are used as generics in positive and negative static assertions.
 #[allow(dead_code)]
 struct SendMessage {
     cell: Cell<u8>,
@@ -25,3 +34,21 @@ assert_not_impl_all!(SendMessage: Sync);
 #[allow(dead_code)]
 struct SendSyncMessage;
 assert_impl_all!(SendSyncMessage: Send, Sync);
+
+#[allow(dead_code)]
+struct NotSendMessage {
+    rc: Rc<u8>,
+}
+
+assert_not_impl_any!(NotSendMessage: Send, Sync);
+
+// Channels can be constructed for messages which are not Send
+#[cfg(test)]
+#[allow(dead_code)]
+fn not_send_channels() {
+    let _ = mpsc::channel::<NotSendMessage>(1);
+    let _ = spsc::channel::<NotSendMessage>(1);
+    let _ = dispatch::channel::<NotSendMessage>(1);
+    let _ = broadcast::channel::<Rc<u8>>(1);
+    let _ = watch::channel::<Rc<u8>>();
+}
@@ -1,6 +1,8 @@
 //! Barriers transmit when the sender half is dropped, and can synchronize events in async tasks.
 //!
 //! The barrier can also be triggered with `tx.send(())`.
+//!
+//! A fixed set of participants can be synchronized with `group`, which completes once each participant has arrived.
 
 use std::fmt;
 use std::sync::Arc;
@@ -14,6 +16,10 @@ use crate::{
     sync::notifier::Notifier,
 };
 
+mod group;
+
+pub use group::{group, group_with, DropPolicy, Participant, Poisoned, Waiter};
+
 /// Constructs a pair of barrier endpoints, which transmits when the sender is dropped.
 pub fn channel() -> (Sender, Receiver) {
     #[cfg(feature = "debug")]
@@ -27,7 +33,11 @@ pub fn channel() -> (Sender, Receiver) {
         shared: shared.clone(),
     };
 
-    let receiver = Receiver { shared };
+    let receiver = Receiver {
+        shared,
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
 
     (sender, receiver)
 }
@@ -58,6 +68,12 @@ impl Sink for Sender {
             State::Sent => PollSend::Rejected(()),
         }
     }
+
+    // like dropping the sender, closing transmits the barrier
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
+    }
 }
 
 impl fmt::Debug for Sender {
@@ -122,6 +138,8 @@ impl Drop for Sender {
 #[derive(Clone)]
 pub struct Receiver {
     pub(in crate::channels::barrier) shared: Arc<Shared>,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
 }
 
 assert_impl_all!(Receiver: Clone, Send, Sync, fmt::Debug);
@@ -164,6 +182,14 @@ impl Stream for Receiver {
             State::Sent => PollRecv::Ready(()),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // once the barrier is released, the receiver is ready forever
+        match self.shared.state.load(Ordering::Acquire) {
+            State::Pending => (0, None),
+            State::Sent => (usize::MAX, None),
+        }
+    }
 }
 
 impl fmt::Debug for Receiver {
@@ -174,14 +200,14 @@ impl fmt::Debug for Receiver {
 
 #[cfg(test)]
 mod tests {
-    use std::{pin::Pin, task::Context};
+    use std::pin::Pin;
 
     use crate::{
+        poll_pending_then_wake,
         sink::{PollSend, Sink},
         stream::{PollRecv, Stream},
         test::{noop_context, panic_context},
     };
-    use futures_test::task::new_count_waker;
 
     use super::channel;
 
@@ -271,45 +297,24 @@ mod tests {
         let mut cx = panic_context();
         let (mut tx, mut rx) = channel();
 
-        let (w, w_count) = new_count_waker();
-        let w_context = Context::from_waker(&w);
-
-        assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w_context.into())
+        let probe = poll_pending_then_wake!(
+            |w_cx| Pin::new(&mut rx).poll_recv(w_cx),
+            assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, ()))
         );
 
-        assert_eq!(0, w_count.get());
-
-        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, ()));
-
-        assert_eq!(1, w_count.get());
-
         assert_eq!(
             PollSend::Rejected(()),
             Pin::new(&mut tx).poll_send(&mut cx, ())
         );
 
-        assert_eq!(1, w_count.get());
+        probe.assert_woken(1);
     }
 
     #[test]
     fn wake_receiver_on_disconnect() {
         let (tx, mut rx) = channel();
 
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
-
-        assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
-        );
-
-        assert_eq!(0, w1_count.get());
-
-        drop(tx);
-
-        assert_eq!(1, w1_count.get());
+        poll_pending_then_wake!(|w_cx| Pin::new(&mut rx).poll_recv(w_cx), drop(tx));
     }
 }
 
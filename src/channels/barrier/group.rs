@@ -0,0 +1,262 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+use static_assertions::{assert_impl_all, assert_not_impl_all};
+use thiserror::Error;
+
+use crate::sync::notifier::Notifier;
+
+/// Constructs a group of `N` participants, and a waiter which completes once every participant has arrived.
+///
+/// Each participant arrives by calling `arrive`, or by being dropped.  The participants are returned as an array,
+/// so they can be destructured, and a participant which is never handed out is reported as an unused variable:
+/// ```rust
+/// use postage::barrier;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let ([network, storage], waiter) = barrier::group::<2>();
+///
+///     tokio::spawn(async move { network.arrive() });
+///     tokio::spawn(async move { storage.arrive() });
+///
+///     waiter.await.expect("all subsystems are ready");
+/// }
+/// ```
+pub fn group<const N: usize>() -> ([Participant; N], Waiter) {
+    group_with(DropPolicy::Arrive)
+}
+
+/// Constructs a group of `N` participants, with the given policy for participants which are dropped without
+/// calling `arrive`.  See [group](./fn.group.html).
+pub fn group_with<const N: usize>(policy: DropPolicy) -> ([Participant; N], Waiter) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating barrier group with {} participants", N);
+    let shared = Arc::new(GroupShared {
+        remaining: AtomicUsize::new(N),
+        poisoned: AtomicBool::new(false),
+        policy,
+        notify: Notifier::new(),
+    });
+
+    let participants = std::array::from_fn(|_| Participant {
+        shared: shared.clone(),
+        arrived: false,
+    });
+
+    (participants, Waiter { shared })
+}
+
+/// Determines how a participant which is dropped without calling `arrive` affects the group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// The dropped participant counts as arrived.
+    Arrive,
+    /// The waiter completes immediately, with a `Poisoned` error.
+    Poison,
+}
+
+/// An error returned by the waiter, if a participant was dropped without arriving in a `DropPolicy::Poison` group.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("Poisoned")]
+pub struct Poisoned;
+
+/// A participant in a barrier group.  Must arrive exactly once, which consumes the participant.
+pub struct Participant {
+    shared: Arc<GroupShared>,
+    arrived: bool,
+}
+
+assert_impl_all!(Participant: Send, Sync, fmt::Debug);
+assert_not_impl_all!(Participant: Clone);
+
+impl Participant {
+    /// Marks the participant as arrived.  The waiter completes once every participant has arrived.
+    pub fn arrive(mut self) {
+        // the arrival is recorded when the participant is dropped
+        self.arrived = true;
+    }
+}
+
+impl Drop for Participant {
+    fn drop(&mut self) {
+        if !self.arrived && self.shared.policy == DropPolicy::Poison {
+            self.shared.poisoned.store(true, Ordering::Release);
+        }
+
+        self.shared.arrive();
+    }
+}
+
+impl fmt::Debug for Participant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Participant").finish()
+    }
+}
+
+/// A future which completes once every participant of the group has arrived.
+///
+/// Resolves to `Err(Poisoned)` as soon as a participant is dropped without arriving, if the group was constructed
+/// with `DropPolicy::Poison`.  Can be cloned.
+#[derive(Clone)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Waiter {
+    shared: Arc<GroupShared>,
+}
+
+assert_impl_all!(Waiter: Clone, Send, Sync, fmt::Debug);
+
+impl Waiter {
+    /// Returns the number of participants which have not yet arrived.
+    pub fn remaining(&self) -> usize {
+        self.shared.remaining.load(Ordering::Acquire)
+    }
+
+    /// Polls the waiter.  See [Waiter](./struct.Waiter.html).
+    pub fn poll_wait(&self, cx: &crate::Context<'_>) -> Poll<Result<(), Poisoned>> {
+        if let Poll::Ready(result) = self.shared.result() {
+            return Poll::Ready(result);
+        }
+
+        self.shared.notify.subscribe(cx);
+
+        // a participant may have arrived before the waker was subscribed
+        self.shared.result()
+    }
+}
+
+impl Future for Waiter {
+    type Output = Result<(), Poisoned>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let cx: crate::Context<'_> = cx.into();
+        self.poll_wait(&cx)
+    }
+}
+
+impl fmt::Debug for Waiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waiter")
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+struct GroupShared {
+    remaining: AtomicUsize,
+    poisoned: AtomicBool,
+    policy: DropPolicy,
+    notify: Notifier,
+}
+
+impl GroupShared {
+    fn arrive(&self) {
+        self.remaining.fetch_sub(1, Ordering::AcqRel);
+        self.notify.notify();
+    }
+
+    fn result(&self) -> Poll<Result<(), Poisoned>> {
+        if self.poisoned.load(Ordering::Acquire) {
+            Poll::Ready(Err(Poisoned))
+        } else if self.remaining.load(Ordering::Acquire) == 0 {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Poll;
+
+    use crate::{test::noop_context, test_util::WakeProbe};
+
+    use super::{group, group_with, DropPolicy, Poisoned};
+
+    #[test]
+    fn arrive() {
+        let cx = noop_context();
+        let ([a, b, c], waiter) = group::<3>();
+
+        a.arrive();
+        b.arrive();
+        assert_eq!(1, waiter.remaining());
+
+        let probe = WakeProbe::new();
+        assert_eq!(Poll::Pending, waiter.poll_wait(&probe.context()));
+
+        c.arrive();
+        assert!(probe.count() > 0);
+        assert_eq!(Poll::Ready(Ok(())), waiter.poll_wait(&cx));
+    }
+
+    #[test]
+    fn drop_counts_as_arrival() {
+        let cx = noop_context();
+        let ([a, b], waiter) = group::<2>();
+
+        a.arrive();
+        drop(b);
+
+        assert_eq!(0, waiter.remaining());
+        assert_eq!(Poll::Ready(Ok(())), waiter.poll_wait(&cx));
+    }
+
+    #[test]
+    fn drop_poisons() {
+        let cx = noop_context();
+        let ([a, b, c], waiter) = group_with::<3>(DropPolicy::Poison);
+
+        a.arrive();
+        assert_eq!(Poll::Pending, waiter.poll_wait(&cx));
+
+        // the waiter completes without waiting for the remaining participant
+        let probe = WakeProbe::new();
+        assert_eq!(Poll::Pending, waiter.poll_wait(&probe.context()));
+        drop(b);
+        assert!(probe.count() > 0);
+        assert_eq!(Poll::Ready(Err(Poisoned)), waiter.poll_wait(&cx));
+
+        c.arrive();
+        assert_eq!(Poll::Ready(Err(Poisoned)), waiter.poll_wait(&cx));
+    }
+
+    #[test]
+    fn arrive_does_not_poison() {
+        let cx = noop_context();
+        let ([a, b], waiter) = group_with::<2>(DropPolicy::Poison);
+
+        a.arrive();
+        b.arrive();
+
+        assert_eq!(Poll::Ready(Ok(())), waiter.poll_wait(&cx));
+    }
+
+    #[test]
+    fn empty_group() {
+        let ([], waiter) = group::<0>();
+
+        assert_eq!(Poll::Ready(Ok(())), waiter.poll_wait(&noop_context()));
+    }
+
+    #[tokio::test]
+    async fn waiter_future() {
+        let ([a, b], waiter) = group::<2>();
+        let waiter2 = waiter.clone();
+
+        tokio::spawn(async move { a.arrive() });
+        tokio::spawn(async move { drop(b) });
+
+        assert_eq!(Ok(()), waiter.await);
+        assert_eq!(Ok(()), waiter2.await);
+    }
+}
@@ -2,29 +2,108 @@
 //!
 //! When a receiver is cloned, the new receive will observe the same series of messages as the original.
 //! When a receiver is created with `Sender::subscribe`, it will observe new messages.
+//!
+//! The `fanout_channel` and `lossy_fanout_channel` constructors provide an alternative, in which each receiver
+//! has a separate bounded queue, instead of a ring buffer shared by all receivers.
+//!
+//! Senders can defer the construction of a message with `send_with`.  The closure is called when the first receiver
+//! reads the message, and the value is cloned for the other receivers.  In a lossy fanout channel, messages which are
+//! dropped before they are read are never constructed.
+//!
+//! Receivers of the broadcast channel clone messages concurrently, so the endpoints are `Send` and `Sync` if `T: Send + Sync`.
+//! Fanout channels clone messages in the sender, and only require `T: Send`.
+//!
+//! A channel which starts with messages in the buffer can be constructed with `builder`.  The builder can also
+//! configure the channel to buffer messages while it has no receivers, for a receiver created later by `subscribe`.
+//! Otherwise, the sender rejects messages once all receivers are dropped.  `send_checked` reports whether
+//! the message was rejected because the channel has no receivers, or because it was closed.
 
-use std::fmt;
+use std::{cmp::max, fmt, future::Future, pin::Pin, task::Poll, task::Waker};
 
-use super::SendMessage;
-use static_assertions::assert_impl_all;
+mod builder;
+mod fanout;
+mod send_with;
+
+pub use super::mapped::MappedReceiver;
+pub use builder::{builder, try_builder, Builder};
+pub use fanout::{fanout_channel, lossy_fanout_channel, FanoutReceiver, FanoutSender};
+pub use send_with::SendWithFuture;
+
+use send_with::SendEntry;
+
+use super::{
+    capacity::{Capacity, InvalidCapacity},
+    NotSendMessage, SendMessage, SendSyncMessage,
+};
+use static_assertions::{assert_impl_all, assert_not_impl_any};
 
 use crate::{
-    sink::{PollSend, Sink},
+    coop::Budget,
+    sink::{self, PollSend, Sink},
     stream::{PollRecv, Stream},
     sync::{
-        mpmc_circular_buffer::{BufferReader, MpmcCircularBuffer, TryRead, TryWrite},
+        deferred::Entry,
+        mpmc_circular_buffer::{BufferReader, MpmcCircularBuffer, Slot, TryRead, TryWrite},
         shared, ReceiverShared, SenderShared,
     },
 };
+use parking_lot::Mutex;
 
 /// Constructs a pair of broadcast endpoints, with a fixed-size buffer of the given capacity
+///
+/// The buffer has at least two slots, so smaller capacities are rounded up.
+///
+/// Panics if the capacity is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+/// `try_channel` returns the error instead.
+#[track_caller]
 pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    new_channel(expect_capacity::<T>(capacity).get(), false)
+}
+
+/// Constructs a pair of broadcast endpoints, with a fixed-size buffer of the given capacity
+///
+/// The buffer has at least two slots, so smaller capacities are rounded up.
+///
+/// Returns an error if the capacity is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+pub fn try_channel<T: Clone>(capacity: usize) -> Result<(Sender<T>, Receiver<T>), InvalidCapacity> {
+    let capacity = try_capacity::<T>(capacity)?;
+    Ok(new_channel(capacity.get(), false))
+}
+
+// the buffer needs two slots, so that unique slots can be acquired and released
+pub(in crate::channels::broadcast) fn try_capacity<T>(
+    capacity: usize,
+) -> Result<Capacity, InvalidCapacity> {
+    Capacity::for_slots::<Slot<Entry<T>>>(max(2, capacity))
+}
+
+#[track_caller]
+pub(in crate::channels::broadcast) fn expect_capacity<T>(capacity: usize) -> Capacity {
+    Capacity::expect::<Slot<Entry<T>>>(max(2, capacity))
+}
+
+pub(in crate::channels::broadcast) fn new_channel<T: Clone>(
+    capacity: usize,
+    buffer_without_receivers: bool,
+) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
     log::error!("Creating broadcast channel with capacity {}", capacity);
     // we add one spare capacity so that receivers have an empty slot to wait on
     let (buffer, reader) = MpmcCircularBuffer::new(capacity);
 
-    let (tx_shared, rx_shared) = shared(buffer);
+    let (tx_shared, rx_shared) = shared(StateExtension {
+        buffer,
+        buffer_without_receivers,
+        parked: Mutex::new(None),
+    });
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        "broadcast::channel",
+        std::any::type_name::<T>(),
+        Some(capacity),
+        |extension| extension.buffer.max_lag(),
+    );
+
     let sender = Sender { shared: tx_shared };
 
     let receiver = Receiver::new(rx_shared, reader);
@@ -32,18 +111,69 @@ pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
     (sender, receiver)
 }
 
+struct StateExtension<T> {
+    buffer: MpmcCircularBuffer<Entry<T>>,
+    buffer_without_receivers: bool,
+    // if buffer_without_receivers is set, the reader of the last receiver is parked here when it is dropped.
+    // the reader holds the unread messages in the buffer, and is taken by the next receiver created by subscribe
+    parked: Mutex<Option<BufferReader>>,
+}
+
+impl<T> fmt::Debug for StateExtension<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateExtension")
+            .field("buffer", &self.buffer)
+            .field("buffer_without_receivers", &self.buffer_without_receivers)
+            .finish()
+    }
+}
+
+/// An error returned by [send_checked](./struct.Sender.html#method.send_checked), with the reason the message was rejected.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendError<T> {
+    /// All receivers have been dropped.  A receiver created with `Sender::subscribe` would accept later messages.
+    NoReceivers(T),
+    /// The channel was closed by a sender.
+    Closed(T),
+}
+
+impl<T> SendError<T> {
+    /// Returns the rejected message
+    pub fn into_inner(self) -> T {
+        match self {
+            SendError::NoReceivers(value) | SendError::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> From<SendError<T>> for sink::SendError<T> {
+    fn from(error: SendError<T>) -> Self {
+        sink::SendError(error.into_inner())
+    }
+}
+
+impl<T> fmt::Display for SendError<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("{:?}", &self))?;
+
+        Ok(())
+    }
+}
+
+impl<T> std::error::Error for SendError<T> where T: fmt::Debug {}
+
 /// A broadcast sender that can be used with the postage::Sink trait.  Can be cloned.
 ///
 /// The sender task is suspended when the internal buffer is filled.
 ///
 /// Note: no implementation of the `futures::Sink` trait is provided for the broadcast Sender.
 pub struct Sender<T> {
-    pub(in crate::channels::broadcast) shared: SenderShared<MpmcCircularBuffer<T>>,
+    pub(in crate::channels::broadcast) shared: SenderShared<StateExtension<T>>,
 }
 
-unsafe impl<T: Send> Send for Sender<T> {}
-unsafe impl<T: Send> Sync for Sender<T> {}
-
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
         Self {
@@ -52,7 +182,10 @@ impl<T> Clone for Sender<T> {
     }
 }
 
-assert_impl_all!(Sender<SendMessage>: Send, Sync, Clone, fmt::Debug);
+// receivers clone messages concurrently through shared references, so the channel requires T: Sync
+assert_impl_all!(Sender<SendSyncMessage>: Send, Sync, Clone, fmt::Debug);
+assert_not_impl_any!(Sender<SendMessage>: Send, Sync);
+assert_not_impl_any!(Sender<NotSendMessage>: Send, Sync);
 
 impl<T> Sink for Sender<T>
 where
@@ -65,12 +198,31 @@ where
         cx: &mut crate::Context<'_>,
         value: Self::Item,
     ) -> PollSend<Self::Item> {
+        match self.get_mut().poll_send_entry(cx, Entry::Value(value)) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(entry) => PollSend::Pending(entry.take()),
+            PollSend::Rejected(entry) => PollSend::Rejected(entry.take()),
+        }
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
+    }
+}
+
+impl<T> SendEntry<T> for Sender<T> {
+    fn poll_send_entry(
+        &mut self,
+        cx: &mut crate::Context<'_>,
+        entry: Entry<T>,
+    ) -> PollSend<Entry<T>> {
         // if all receivers have disconnected, we return Rejected like other channels.
         // tx.subscribe() can be used to produce a new receiver.
         // however, it would not receive this item, as it would need to be called
         //   before the message is sent.
-        if self.shared.is_closed() {
-            return PollSend::Rejected(value);
+        if self.is_rejecting() {
+            return PollSend::Rejected(entry);
         }
 
         // start at the head
@@ -78,26 +230,121 @@ where
         //   register for wakeup
         // else
         //   overwrite the element
-        let buffer = self.shared.extension();
-        match buffer.try_write(value, cx) {
-            TryWrite::Pending(value) => PollSend::Pending(value),
-            TryWrite::Ready => PollSend::Ready,
+        let buffer = &self.shared.extension().buffer;
+        match buffer.try_write(entry, cx) {
+            TryWrite::Pending(entry) => PollSend::Pending(entry),
+            TryWrite::Ready => {
+                self.shared.publish_len();
+                PollSend::Ready
+            }
         }
     }
 }
 
+impl<T> Sender<T>
+where
+    T: Send + Sync,
+{
+    /// Sends a message which is constructed when it is first received.  The value is cloned for the other receivers.
+    ///
+    /// If the channel is closed, the closure is dropped without being called.
+    pub fn send_with<F>(&mut self, init: F) -> SendWithFuture<'_, T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        SendWithFuture::new(self, Entry::deferred(init))
+    }
+}
+
 impl<T> Sender<T> {
     /// Subscribes to the channel, creating a new receiver.  The receiver
     /// will observe all messages sent after the call to subscribe.
     ///
-    /// Messages currently in the buffer are not received.
+    /// Messages currently in the buffer are not received, unless the channel was built with
+    /// [buffer_without_receivers](./struct.Builder.html#method.buffer_without_receivers), and all receivers were dropped.
+    /// Then the receiver observes the messages which the last receiver did not receive, and the messages sent since.
     pub fn subscribe(&self) -> Receiver<T> {
         let shared = self.shared.clone_receiver();
-        let reader = shared.extension().new_reader();
+        let extension = shared.extension();
+
+        let reader = {
+            let mut parked = extension.parked.lock();
+            match parked.take() {
+                Some(reader) => reader,
+                None => extension.buffer.new_reader(),
+            }
+        };
         self.shared.notify_self();
 
         Receiver::new(shared, reader)
     }
+
+    /// Returns the number of buffered messages that the slowest receiver has not yet received.
+    ///
+    /// The sender is suspended when this reaches the capacity of the channel.
+    pub fn max_lag(&self) -> usize {
+        self.shared.extension().buffer.max_lag()
+    }
+
+    /// Sends a message, and reports the reason if it is rejected.
+    pub fn send_checked(&mut self, value: T) -> SendCheckedFuture<'_, T> {
+        SendCheckedFuture {
+            sender: self,
+            value: Some(value),
+        }
+    }
+
+    // messages are rejected if the channel is closed, or if there are no receivers, and messages are not buffered
+    fn is_rejecting(&self) -> bool {
+        if self.shared.extension().buffer_without_receivers {
+            self.shared.is_sender_closed()
+        } else {
+            self.shared.is_closed()
+        }
+    }
+
+    fn rejection(&self, value: T) -> SendError<T> {
+        if self.shared.is_sender_closed() {
+            SendError::Closed(value)
+        } else {
+            SendError::NoReceivers(value)
+        }
+    }
+}
+
+/// A future returned by [send_checked](./struct.Sender.html#method.send_checked).
+#[must_use = "futures do nothing unless polled"]
+pub struct SendCheckedFuture<'s, T> {
+    sender: &'s mut Sender<T>,
+    value: Option<T>,
+}
+
+// the value is never pinned
+impl<'s, T> Unpin for SendCheckedFuture<'s, T> {}
+
+impl<'s, T> Future for SendCheckedFuture<'s, T>
+where
+    T: Clone,
+{
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let value = match this.value.take() {
+            Some(value) => value,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let mut cx: crate::Context<'_> = cx.into();
+        match Pin::new(&mut *this.sender).poll_send(&mut cx, value) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(value) => {
+                this.value = Some(value);
+                Poll::Pending
+            }
+            PollSend::Rejected(value) => Poll::Ready(Err(this.sender.rejection(value))),
+        }
+    }
 }
 
 impl<T> fmt::Debug for Sender<T> {
@@ -110,18 +357,74 @@ impl<T> fmt::Debug for Sender<T> {
 ///
 /// When cloned, the new receiver will begin processing messages at the same location as the original.
 pub struct Receiver<T> {
-    shared: ReceiverShared<MpmcCircularBuffer<T>>,
+    shared: ReceiverShared<StateExtension<T>>,
     reader: BufferReader,
+    budget: Budget,
+    // the waker subscribed to closure of the channel
+    closed_waker: Option<Waker>,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
 }
 
-unsafe impl<T: Send> Send for Receiver<T> {}
-unsafe impl<T: Send> Sync for Receiver<T> {}
-
-assert_impl_all!(Receiver<SendMessage>: Send, Sync, Clone, fmt::Debug);
+assert_impl_all!(Receiver<SendSyncMessage>: Send, Sync, Clone, fmt::Debug);
+assert_not_impl_any!(Receiver<SendMessage>: Send, Sync);
+assert_not_impl_any!(Receiver<NotSendMessage>: Send, Sync);
 
 impl<T> Receiver<T> {
-    fn new(shared: ReceiverShared<MpmcCircularBuffer<T>>, reader: BufferReader) -> Self {
-        Self { shared, reader }
+    /// Returns true if a sender was dropped while its thread was panicking.
+    ///
+    /// Channels are only poisoned if they were built with `poison_on_panic`.  Buffered messages can still be
+    /// received, and once the channel is closed, this distinguishes a crashed producer from a graceful shutdown.
+    pub fn is_poisoned(&self) -> bool {
+        self.shared.is_poisoned()
+    }
+
+    fn new(shared: ReceiverShared<StateExtension<T>>, reader: BufferReader) -> Self {
+        Self {
+            shared,
+            reader,
+            budget: Budget::new(),
+            closed_waker: None,
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
+        }
+    }
+
+    // receivers are only notified when the channel closes, so each task is subscribed once.
+    // otherwise, the waker list would grow each time the receiver is pending
+    fn subscribe_closed(&mut self, cx: &crate::Context<'_>) {
+        if let Some(waker) = cx.waker() {
+            if let Some(subscribed) = &self.closed_waker {
+                if subscribed.will_wake(waker) {
+                    return;
+                }
+            }
+
+            self.closed_waker = Some(waker.clone());
+        }
+
+        self.shared.subscribe_send(cx);
+    }
+
+    /// Returns the number of buffered messages that this receiver has not yet received.
+    pub fn lag(&self) -> usize {
+        self.reader.lag(&self.shared.extension().buffer)
+    }
+}
+
+impl<T> Receiver<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Subscribes a receiver which maps each message with `map`, beginning at the same location as this receiver.
+    ///
+    /// The mapped receiver has a nameable type, which does not depend on `T`.  Messages are mapped after they are
+    /// received, so the channel and other receivers are unaffected.
+    pub fn map_subscribe<U>(&self, map: fn(&T) -> U) -> MappedReceiver<U>
+    where
+        U: 'static,
+    {
+        MappedReceiver::new(self.clone(), map)
     }
 }
 
@@ -137,27 +440,44 @@ where
     ) -> PollRecv<Self::Item> {
         // unpin self, so Rust can infer that the borrows of reader and buffer are disjoint
         let this = self.get_mut();
+
+        if this.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
         let reader = &mut this.reader;
-        let buffer = this.shared.extension();
+        let buffer = &this.shared.extension().buffer;
 
         match reader.try_read(buffer, cx) {
             TryRead::Pending => {
-                this.shared.subscribe_send(cx);
+                this.subscribe_closed(cx);
 
                 if this.shared.is_closed() {
                     return PollRecv::Closed;
                 }
 
+                this.budget.reset();
                 PollRecv::Pending
             }
-            TryRead::Ready(value) => PollRecv::Ready(value),
+            TryRead::Ready(entry) => {
+                this.shared.publish_len();
+                this.budget.consume();
+                PollRecv::Ready(entry.take())
+            }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let closed = self.shared.is_closed();
+        let lag = self.lag();
+
+        (lag, if closed { Some(lag) } else { None })
+    }
 }
 
 impl<T> Clone for Receiver<T> {
     fn clone(&self) -> Self {
-        let buffer = self.shared.extension();
+        let buffer = &self.shared.extension().buffer;
         let reader = self.reader.clone_with(buffer);
 
         Self::new(self.shared.clone(), reader)
@@ -166,8 +486,22 @@ impl<T> Clone for Receiver<T> {
 
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
-        let buffer = self.shared.extension();
-        self.reader.drop_with(buffer);
+        let extension = self.shared.extension();
+
+        if extension.buffer_without_receivers {
+            // subscribe and drop hold the lock, so the count can't change unless another receiver is cloned.
+            // the lock is held until the reader is dropped, so the last of two concurrent drops parks its reader
+            let mut parked = extension.parked.lock();
+            if extension.buffer.readers() == 1 {
+                *parked = Some(self.reader.hand_over());
+            } else {
+                self.reader.drop_with(&extension.buffer);
+            }
+
+            return;
+        }
+
+        self.reader.drop_with(&extension.buffer);
     }
 }
 
@@ -182,17 +516,117 @@ mod tests {
     use std::pin::Pin;
 
     use crate::{
-        sink::{PollSend, Sink},
+        coop::BUDGET,
+        sink::{PollSend, SendError, Sink},
         stream::{PollRecv, Stream},
         test::{noop_context, panic_context},
         Context,
     };
+    use futures::FutureExt;
     use futures_test::task::new_count_waker;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
 
     use super::{channel, Receiver, Sender};
 
+    // the debug feature formats log messages, which allocates
+    #[test]
+    #[cfg(not(feature = "debug"))]
+    fn send_recv_does_not_allocate() {
+        let (mut tx, mut rx) = channel::<usize>(4);
+        let mut rx2 = rx.clone();
+        let (waker, _count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut send_recv = |i| {
+            assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, i));
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut rx).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut rx2).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+        };
+
+        for i in 0..100 {
+            send_recv(i);
+        }
+
+        let allocations = crate::test::alloc::count_allocations(|| {
+            for i in 0..1000 {
+                send_recv(i);
+            }
+        });
+        assert_eq!(0, allocations);
+    }
+
+    // the debug feature formats log messages, which allocates
+    #[test]
+    #[cfg(not(feature = "debug"))]
+    fn blocked_send_does_not_allocate() {
+        let (mut tx, mut rx) = channel::<usize>(2);
+        let (waker, _count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 0));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+        // the receiver is never pending, so it is polled without a waker (and without a budget)
+        let mut rx_cx = noop_context();
+
+        // the sender waits for the oldest slot to be released, and the receiver moves it forward
+        let mut send_recv = |i| {
+            assert_eq!(
+                PollSend::Pending(i),
+                Pin::new(&mut tx).poll_send(&mut cx, i)
+            );
+            assert_eq!(
+                PollRecv::Ready(i - 2),
+                Pin::new(&mut rx).poll_recv(&mut rx_cx)
+            );
+            assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, i));
+        };
+
+        for i in 2..100 {
+            send_recv(i);
+        }
+
+        let allocations = crate::test::alloc::count_allocations(|| {
+            for i in 100..1000 {
+                send_recv(i);
+            }
+        });
+        assert_eq!(0, allocations);
+    }
+
+    #[test]
+    fn full_channel_yields() {
+        let (mut tx, mut rx) = channel::<usize>(4);
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut tx_cx = noop_context();
+
+        // the sender refills the channel after every message, so it is never empty
+        let mut ready = 0;
+        for round in 0..3 {
+            loop {
+                while let PollSend::Ready = Pin::new(&mut tx).poll_send(&mut tx_cx, ready) {}
+
+                match Pin::new(&mut rx).poll_recv(&mut cx) {
+                    PollRecv::Ready(_) => ready += 1,
+                    PollRecv::Pending => break,
+                    PollRecv::Closed => panic!("channel closed"),
+                }
+            }
+
+            assert_eq!((round + 1) * BUDGET, ready);
+            assert_eq!(round + 1, count.get());
+        }
+    }
+
     //TODO: add test covering rx location when cloned on an in-progress channel (exercising tail)
-    fn pin<'a, 'b>(
+    fn pin(
         chan: &mut (Sender<Message>, Receiver<Message>),
     ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
         let tx = Pin::new(&mut chan.0);
@@ -236,6 +670,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn try_channel_capacity() {
+        use crate::{Capacity, InvalidCapacity};
+
+        // small capacities are rounded up
+        assert!(super::try_channel::<Message>(0).is_ok());
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(usize::MAX)),
+            super::try_channel::<Message>(usize::MAX).err()
+        );
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(Capacity::MAX)),
+            super::try_channel::<u64>(Capacity::MAX).err()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is too large for the channel buffer")]
+    fn too_large() {
+        let _ = channel::<u64>(crate::Capacity::MAX);
+    }
+
     #[test]
     fn empty_send_recv() {
         let mut cx = noop_context();
@@ -447,6 +903,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn send_checked_no_receivers() {
+        let (mut tx, rx) = channel(4);
+        assert_eq!(Some(Ok(())), tx.send_checked(Message(1)).now_or_never());
+
+        // the message is rejected promptly, although the buffer has capacity
+        drop(rx);
+        assert_eq!(
+            Some(Err(super::SendError::NoReceivers(Message(2)))),
+            tx.send_checked(Message(2)).now_or_never()
+        );
+
+        let _rx = tx.subscribe();
+        assert_eq!(Some(Ok(())), tx.send_checked(Message(3)).now_or_never());
+    }
+
+    #[test]
+    fn send_checked_closed() {
+        let (mut tx, _rx) = channel(4);
+        let mut tx2 = tx.clone();
+
+        assert_eq!(Some(Ok(())), tx.close().now_or_never());
+        assert_eq!(
+            Some(Err(super::SendError::Closed(Message(1)))),
+            tx2.send_checked(Message(1)).now_or_never()
+        );
+    }
+
     #[test]
     fn receiver_reconnect() {
         let mut cx = panic_context();
@@ -482,14 +966,14 @@ mod tests {
         );
 
         let (w2, w2_count) = new_count_waker();
-        let w2_context = Context::from_waker(&w2);
+        let mut w2_context = Context::from_waker(&w2);
         assert_eq!(
             PollSend::Ready,
             Pin::new(&mut tx).poll_send(&mut cx, Message(2))
         );
         assert_eq!(
             PollSend::Pending(Message(3)),
-            Pin::new(&mut tx).poll_send(&mut w2_context.into(), Message(3))
+            Pin::new(&mut tx).poll_send(&mut w2_context, Message(3))
         );
 
         assert_eq!(0, w2_count.get());
@@ -519,11 +1003,11 @@ mod tests {
         let (mut tx, mut rx) = channel(100);
 
         let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
+        let mut w1_context = Context::from_waker(&w1);
 
         assert_eq!(
             PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
         );
 
         assert_eq!(0, w1_count.get());
@@ -611,8 +1095,7 @@ mod tests {
         let (mut tx, rx) = channel(2);
 
         let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
-        let mut w1_context: crate::Context<'_> = w1_context.into();
+        let mut w1_context = Context::from_waker(&w1);
 
         assert_eq!(
             PollSend::Ready,
@@ -641,11 +1124,11 @@ mod tests {
         let (tx, mut rx) = channel::<()>(100);
 
         let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
+        let mut w1_context = Context::from_waker(&w1);
 
         assert_eq!(
             PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
         );
 
         assert_eq!(0, w1_count.get());
@@ -774,6 +1257,158 @@ mod tests {
             Pin::new(&mut tx).poll_send(&mut cx, Message(3))
         );
     }
+
+    #[test]
+    fn receiver_lag() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+        assert_eq!(0, rx.lag());
+
+        for i in 0..3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+        assert_eq!(3, rx.lag());
+
+        assert_eq!(
+            PollRecv::Ready(Message(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(2, rx.lag());
+
+        let rx2 = tx.subscribe();
+        assert_eq!(0, rx2.lag());
+    }
+
+    #[test]
+    fn receiver_size_hint() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+        assert_eq!((0, None), rx.size_hint());
+
+        for i in 0..2 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+
+        let rx2 = rx.clone();
+        assert_eq!(
+            PollRecv::Ready(Message(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        // each receiver reports its own lag
+        assert_eq!((1, None), rx.size_hint());
+        assert_eq!((2, None), rx2.size_hint());
+
+        drop(tx);
+        assert_eq!((1, Some(1)), rx.size_hint());
+    }
+
+    #[test]
+    fn sender_max_lag() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+        let mut rx2 = rx.clone();
+        assert_eq!(0, tx.max_lag());
+
+        for i in 0..3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+        assert_eq!(3, tx.max_lag());
+
+        assert_eq!(
+            PollRecv::Ready(Message(0)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(3, tx.max_lag());
+
+        for i in 0..3 {
+            assert_eq!(
+                PollRecv::Ready(Message(i)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+        }
+        assert_eq!(1, tx.max_lag());
+
+        drop(rx2);
+        assert_eq!(0, tx.max_lag());
+    }
+
+    #[test]
+    fn send_with_constructs_once() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+        let mut rx2 = rx.clone();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls2 = calls.clone();
+        let send = tx.send_with(move || {
+            calls2.fetch_add(1, Ordering::SeqCst);
+            Message(1)
+        });
+        assert_eq!(Some(Ok(())), send.now_or_never());
+        assert_eq!(0, calls.load(Ordering::SeqCst));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn send_with_blocks() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(Some(Ok(())), tx.send_with(|| Message(1)).now_or_never());
+        assert_eq!(Some(Ok(())), tx.send_with(|| Message(2)).now_or_never());
+
+        let mut send = tx.send_with(|| Message(3));
+        assert_eq!(None, (&mut send).now_or_never());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(Some(Ok(())), send.now_or_never());
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn send_with_rejected() {
+        let (mut tx, rx) = channel(4);
+        drop(rx);
+
+        assert_eq!(
+            Some(Err(SendError(()))),
+            tx.send_with(|| -> Message { panic!("closure should not be called") })
+                .now_or_never()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -958,7 +1593,7 @@ mod tokio_tests {
                 loop {
                     let next = rx2.try_recv();
 
-                    if let Ok(_) = next {
+                    if next.is_ok() {
                         continue;
                     }
 
@@ -966,7 +1601,7 @@ mod tokio_tests {
                         break;
                     }
 
-                    if let Ok(_) = sender_quit.try_recv() {
+                    if sender_quit.try_recv().is_ok() {
                         break;
                     }
 
@@ -1178,7 +1813,7 @@ mod async_std_tests {
                 loop {
                     let next = rx2.try_recv();
 
-                    if let Ok(_) = next {
+                    if next.is_ok() {
                         continue;
                     }
 
@@ -1186,7 +1821,7 @@ mod async_std_tests {
                         break;
                     }
 
-                    if let Ok(_) = sender_quit.try_recv() {
+                    if sender_quit.try_recv().is_ok() {
                         break;
                     }
 
@@ -0,0 +1,321 @@
+use std::fmt;
+
+use crate::channels::{
+    capacity::{Capacity, InvalidCapacity},
+    prefill::Prefill,
+};
+
+use super::{expect_capacity, new_channel, try_capacity, Receiver, Sender};
+
+/// Constructs a builder for a broadcast channel with the given capacity.
+///
+/// The channel can be prefilled with messages.  The receiver (and its clones) observe them before any messages
+/// sent later.  Receivers created with `Sender::subscribe` only observe later messages.
+///
+/// The buffer has at least two slots, so smaller capacities are rounded up.
+///
+/// Panics if the capacity is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+/// `try_builder` returns the error instead.
+#[track_caller]
+pub fn builder<T>(capacity: usize) -> Builder<T> {
+    new_builder(expect_capacity::<T>(capacity))
+}
+
+/// Constructs a builder for a broadcast channel with the given capacity.
+///
+/// The buffer has at least two slots, so smaller capacities are rounded up.
+///
+/// Returns an error if the capacity is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+pub fn try_builder<T>(capacity: usize) -> Result<Builder<T>, InvalidCapacity> {
+    try_capacity::<T>(capacity).map(new_builder)
+}
+
+fn new_builder<T>(capacity: Capacity) -> Builder<T> {
+    Builder {
+        capacity,
+        prefill: Prefill::new(),
+        poison_on_panic: false,
+        buffer_without_receivers: false,
+    }
+}
+
+/// A builder for a broadcast channel, created by [builder](./fn.builder.html).
+pub struct Builder<T> {
+    capacity: Capacity,
+    prefill: Prefill<T>,
+    poison_on_panic: bool,
+    buffer_without_receivers: bool,
+}
+
+impl<T> Builder<T> {
+    /// Seeds the channel with the messages, in order.  The messages count against the capacity.
+    ///
+    /// If called more than once, the messages are appended.
+    pub fn prefill<I>(mut self, messages: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.prefill.extend(messages);
+        self
+    }
+
+    /// Configures whether the capacity grows to fit the prefilled messages.
+    ///
+    /// If disabled (the default), `build` panics if the messages exceed the capacity.
+    pub fn grow_to_fit(mut self, grow: bool) -> Self {
+        self.prefill.set_grow_to_fit(grow);
+        self
+    }
+
+    /// Configures whether messages are buffered while the channel has no receivers.
+    ///
+    /// If disabled (the default), messages are rejected once all receivers are dropped.  If enabled, messages are
+    /// buffered up to the capacity, and the next receiver created with `Sender::subscribe` observes them.
+    /// The sender is suspended while the buffer is full.
+    pub fn buffer_without_receivers(mut self, buffer: bool) -> Self {
+        self.buffer_without_receivers = buffer;
+        self
+    }
+}
+
+impl<T> Builder<T>
+where
+    T: Clone,
+{
+    /// Configures whether the channel is poisoned when a sender is dropped while its thread is panicking.
+    ///
+    /// Receivers can then distinguish a crashed producer from a graceful shutdown with `Receiver::is_poisoned`.
+    /// The default is disabled.
+    pub fn poison_on_panic(mut self, poison: bool) -> Self {
+        self.poison_on_panic = poison;
+        self
+    }
+
+    /// Constructs the channel, and sends the prefilled messages.
+    #[track_caller]
+    pub fn build(self) -> (Sender<T>, Receiver<T>) {
+        let capacity = self.prefill.capacity(self.capacity);
+        let (mut tx, rx) = new_channel(capacity, self.buffer_without_receivers);
+        tx.shared.set_poison_on_panic(self.poison_on_panic);
+        self.prefill.send_into(&mut tx);
+
+        (tx, rx)
+    }
+}
+
+impl<T> fmt::Debug for Builder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("capacity", &self.capacity.get())
+            .field("prefill", &self.prefill.len())
+            .field("poison_on_panic", &self.poison_on_panic)
+            .field("buffer_without_receivers", &self.buffer_without_receivers)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+        test_util::WakeProbe,
+    };
+
+    use super::builder;
+
+    #[test]
+    fn prefill_before_sends() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(3).prefill(vec![1, 2]).build();
+        let mut rx2 = rx.clone();
+        let mut subscriber = tx.subscribe();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+        assert_eq!(
+            PollSend::Pending(4),
+            Pin::new(&mut tx).poll_send(&mut cx, 4)
+        );
+
+        for i in 1..=3 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut rx).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut rx2).poll_recv(&mut cx));
+        }
+
+        assert_eq!(
+            PollRecv::Ready(3),
+            Pin::new(&mut subscriber).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn grow_to_fit() {
+        let (tx, rx) = builder(2).prefill(0..5).grow_to_fit(true).build();
+
+        assert_eq!(5, tx.max_lag());
+        assert_eq!(5, rx.lag());
+    }
+
+    #[test]
+    fn buffer_without_receivers() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = builder(2).buffer_without_receivers(true).build();
+        drop(rx);
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        let probe = WakeProbe::new();
+        assert_eq!(
+            PollSend::Pending(3),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 3)
+        );
+
+        // the subscriber replays the buffered messages, and releases the sender
+        let mut rx = tx.subscribe();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(probe.count() > 0);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn resume_after_last_receiver() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(4).buffer_without_receivers(true).build();
+        let mut rx2 = rx.clone();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx2).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx2).poll_recv(&mut cx));
+
+        // other receivers are still subscribed, so the reader is dropped
+        drop(rx2);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+
+        // the next subscriber resumes where the last receiver stopped
+        drop(rx);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 4));
+
+        let mut rx = tx.subscribe();
+        for i in 2..=4 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut rx).poll_recv(&mut cx));
+        }
+
+        // only the first subscriber takes over the buffered messages
+        let mut rx2 = tx.subscribe();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn concurrent_drop_parks_reader() {
+        use std::sync::{Arc, Barrier};
+
+        let mut cx = noop_context();
+        for _ in 0..10_000 {
+            let (mut tx, rx) = builder(2).buffer_without_receivers(true).build();
+            let rx2 = rx.clone();
+            assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+            let barrier = Arc::new(Barrier::new(2));
+            let handles: Vec<_> = vec![rx, rx2]
+                .into_iter()
+                .map(|rx| {
+                    let barrier = barrier.clone();
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        drop(rx);
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle.join().expect("the receiver drop panicked");
+            }
+
+            // exactly one of the receivers parked its reader
+            let mut rx = tx.subscribe();
+            assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+            let mut rx2 = tx.subscribe();
+            assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+            assert_eq!(0, tx.max_lag());
+        }
+    }
+
+    #[test]
+    fn poison_on_panic() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(4).poison_on_panic(true).build();
+        let mut rx2 = rx.clone();
+
+        let producer = std::thread::spawn(move || {
+            tx.try_send(1).expect("the channel has capacity");
+            panic!("the producer failed");
+        });
+        assert!(producer.join().is_err());
+
+        for rx in [&mut rx, &mut rx2] {
+            assert_eq!(PollRecv::Ready(1), Pin::new(&mut *rx).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Closed, Pin::new(&mut *rx).poll_recv(&mut cx));
+            assert!(rx.is_poisoned());
+        }
+    }
+
+    #[tokio::test]
+    async fn poison_on_panic_task() {
+        let (mut tx, mut rx) = builder(4).poison_on_panic(true).build();
+
+        let producer = tokio::spawn(async move {
+            tx.send(1usize).await.expect("the receiver is alive");
+            panic!("the producer failed");
+        });
+        assert!(producer.await.unwrap_err().is_panic());
+
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+        assert!(rx.is_poisoned());
+    }
+
+    #[test]
+    fn graceful_drop_does_not_poison() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = builder::<usize>(4).poison_on_panic(true).build();
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(!rx.is_poisoned());
+    }
+
+    #[test]
+    #[should_panic]
+    fn exceeds_capacity() {
+        let _ = builder::<usize>(2).prefill(0..3).build();
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let (_tx, mut rx) = builder(0).prefill(vec![1, 2]).build();
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+    }
+
+    #[test]
+    fn try_builder_capacity() {
+        use crate::InvalidCapacity;
+
+        assert!(super::try_builder::<usize>(0).is_ok());
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(usize::MAX)),
+            super::try_builder::<usize>(usize::MAX).err()
+        );
+
+        let (_tx, mut rx) = super::try_builder(1).unwrap().prefill(vec![1]).build();
+        assert_eq!(Ok(1), rx.try_recv());
+    }
+}
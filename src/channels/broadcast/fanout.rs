@@ -0,0 +1,690 @@
+//! A broadcast channel in which each receiver has a separate, bounded queue.
+//!
+//! The sender clones each message into the queue of every receiver.  This uses more memory than the shared
+//! ring buffer, but the receivers are isolated from each other.  In lossy mode, a slow receiver loses its own
+//! oldest messages, and never blocks the sender or other receivers.
+//!
+//! Messages sent with `send_with` are shared by the queues, and constructed when first received.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crossbeam_queue::ArrayQueue;
+use parking_lot::Mutex;
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+use crate::{
+    channels::{
+        capacity::{Capacity, QueueSlot},
+        NotSendMessage, SendMessage,
+    },
+    coop::Budget,
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+    sync::{deferred::Entry, shared, ReceiverShared, SenderShared},
+};
+
+use super::{send_with::SendEntry, SendWithFuture};
+
+/// Constructs a pair of lossless fanout endpoints.  Each receiver buffers up to `capacity` messages,
+/// and the sender is suspended while the queue of any receiver is full.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+#[track_caller]
+pub fn fanout_channel<T: Clone>(capacity: usize) -> (FanoutSender<T>, FanoutReceiver<T>) {
+    new_channel(
+        Capacity::expect::<QueueSlot<Entry<T>>>(capacity).get(),
+        Mode::Lossless,
+    )
+}
+
+/// Constructs a pair of lossy fanout endpoints.  Each receiver buffers up to `capacity` messages.
+/// The sender is never suspended, and when the queue of a receiver is full, its oldest message is dropped.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+#[track_caller]
+pub fn lossy_fanout_channel<T: Clone>(capacity: usize) -> (FanoutSender<T>, FanoutReceiver<T>) {
+    new_channel(
+        Capacity::expect::<QueueSlot<Entry<T>>>(capacity).get(),
+        Mode::Lossy,
+    )
+}
+
+fn new_channel<T>(capacity: usize, mode: Mode) -> (FanoutSender<T>, FanoutReceiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating fanout channel with capacity {}", capacity);
+
+    let (tx_shared, rx_shared) = shared(StateExtension::new(capacity, mode));
+
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        match mode {
+            Mode::Lossless => "broadcast::fanout_channel",
+            Mode::Lossy => "broadcast::lossy_fanout_channel",
+        },
+        std::any::type_name::<T>(),
+        Some(capacity),
+        StateExtension::max_lag,
+    );
+    let queue = rx_shared.extension().add_queue();
+
+    let sender = FanoutSender { shared: tx_shared };
+    let receiver = FanoutReceiver {
+        shared: rx_shared,
+        queue,
+        budget: Budget::new(),
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
+
+    (sender, receiver)
+}
+
+/// The sender half of a fanout channel.  Can send messages with the postage::Sink trait.
+///
+/// Can be cloned, and new receivers can be created with `subscribe()`.
+pub struct FanoutSender<T> {
+    shared: SenderShared<StateExtension<T>>,
+}
+
+assert_impl_all!(FanoutSender<SendMessage>: Send, Sync, Clone, fmt::Debug);
+assert_not_impl_any!(FanoutSender<NotSendMessage>: Send, Sync);
+
+impl<T> Clone for FanoutSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> FanoutSender<T> {
+    /// Subscribes to the channel, creating a new receiver with an empty queue.
+    /// The receiver will observe all messages sent after the call to subscribe.
+    pub fn subscribe(&self) -> FanoutReceiver<T> {
+        let shared = self.shared.clone_receiver();
+        let queue = shared.extension().add_queue();
+
+        FanoutReceiver {
+            shared,
+            queue,
+            budget: Budget::new(),
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
+        }
+    }
+
+    /// Returns the number of messages buffered by the slowest receiver.
+    pub fn max_lag(&self) -> usize {
+        self.shared.extension().max_lag()
+    }
+}
+
+impl<T> Sink for FanoutSender<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_send(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        match self.get_mut().poll_send_entry(cx, Entry::Value(value)) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(entry) => PollSend::Pending(entry.take()),
+            PollSend::Rejected(entry) => PollSend::Rejected(entry.take()),
+        }
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
+    }
+}
+
+impl<T> SendEntry<T> for FanoutSender<T>
+where
+    T: Clone,
+{
+    fn poll_send_entry(
+        &mut self,
+        cx: &mut crate::Context<'_>,
+        entry: Entry<T>,
+    ) -> PollSend<Entry<T>> {
+        loop {
+            if self.shared.is_closed() {
+                return PollSend::Rejected(entry);
+            }
+
+            let guard = self.shared.recv_guard();
+            let extension = self.shared.extension();
+
+            // the queue lock serializes senders, so every receiver observes messages in the same order,
+            // and a lossless send is delivered to all receivers or none.
+            let queues = extension.queues.lock();
+
+            if let Mode::Lossless = extension.mode {
+                if queues.iter().any(|queue| queue.buffer.is_full()) {
+                    self.shared.subscribe_recv(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    return PollSend::Pending(entry);
+                }
+            }
+
+            // deferred values are shared by the queues, and cloning the entry does not construct the value
+            if let Some((last, rest)) = queues.split_last() {
+                for queue in rest {
+                    queue.push(entry.clone());
+                }
+
+                last.push(entry);
+            }
+
+            drop(queues);
+            self.shared.notify_receivers();
+            return PollSend::Ready;
+        }
+    }
+}
+
+impl<T> FanoutSender<T>
+where
+    T: Clone + Send,
+{
+    /// Sends a message which is constructed when it is first received.  The value is cloned for the other receivers.
+    ///
+    /// In lossy mode, if the message is dropped from every queue before it is received, the closure is never called.
+    pub fn send_with<F>(&mut self, init: F) -> SendWithFuture<'_, T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        SendWithFuture::new(self, Entry::deferred(init))
+    }
+}
+
+impl<T> fmt::Debug for FanoutSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanoutSender").finish()
+    }
+}
+
+/// The receiver half of a fanout channel.  Can receive messages with the postage::Stream trait.
+///
+/// Cannot be cloned.  Additional receivers can be created with `FanoutSender::subscribe()`.
+pub struct FanoutReceiver<T> {
+    shared: ReceiverShared<StateExtension<T>>,
+    queue: Arc<Queue<Entry<T>>>,
+    budget: Budget,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
+}
+
+assert_impl_all!(FanoutReceiver<SendMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(FanoutReceiver<SendMessage>: Clone);
+assert_not_impl_any!(FanoutReceiver<NotSendMessage>: Send, Sync);
+
+impl<T> FanoutReceiver<T> {
+    /// Returns the number of messages in the queue of this receiver.
+    pub fn lag(&self) -> usize {
+        self.queue.buffer.len()
+    }
+
+    /// Returns the number of messages that were dropped from the queue of this receiver (in lossy mode).
+    pub fn dropped(&self) -> usize {
+        self.queue.dropped.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Stream for FanoutReceiver<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        if this.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
+        loop {
+            let guard = this.shared.send_guard();
+            match this.queue.buffer.pop() {
+                Some(entry) => {
+                    this.shared.notify_senders();
+                    this.budget.consume();
+                    return PollRecv::Ready(entry.take());
+                }
+                None => {
+                    if this.shared.is_closed() {
+                        return PollRecv::Closed;
+                    }
+
+                    this.shared.subscribe_send(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    this.budget.reset();
+                    return PollRecv::Pending;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let closed = self.shared.is_closed();
+        let lag = self.lag();
+
+        (lag, if closed { Some(lag) } else { None })
+    }
+}
+
+impl<T> Drop for FanoutReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.extension().remove_queue(&self.queue);
+
+        // removing a full queue may allow a lossless sender to proceed
+        self.shared.notify_senders();
+    }
+}
+
+impl<T> fmt::Debug for FanoutReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanoutReceiver").finish()
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Mode {
+    Lossless,
+    Lossy,
+}
+
+struct Queue<T> {
+    buffer: ArrayQueue<T>,
+    dropped: AtomicUsize,
+}
+
+impl<T> Queue<T> {
+    pub fn push(&self, value: T) {
+        if self.buffer.force_push(value).is_some() {
+            self.dropped.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+}
+
+struct StateExtension<T> {
+    capacity: usize,
+    mode: Mode,
+    queues: Mutex<Vec<Arc<Queue<Entry<T>>>>>,
+}
+
+impl<T> StateExtension<T> {
+    pub fn new(capacity: usize, mode: Mode) -> Self {
+        Self {
+            capacity: std::cmp::max(1, capacity),
+            mode,
+            queues: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn add_queue(&self) -> Arc<Queue<Entry<T>>> {
+        let queue = Arc::new(Queue {
+            buffer: ArrayQueue::new(self.capacity),
+            dropped: AtomicUsize::new(0),
+        });
+
+        self.queues.lock().push(queue.clone());
+        queue
+    }
+
+    pub fn max_lag(&self) -> usize {
+        self.queues
+            .lock()
+            .iter()
+            .map(|queue| queue.buffer.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn remove_queue(&self, queue: &Arc<Queue<Entry<T>>>) {
+        self.queues
+            .lock()
+            .retain(|existing| !Arc::ptr_eq(existing, queue));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context};
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::{noop_context, panic_context},
+    };
+    use futures::FutureExt;
+    use futures_test::task::new_count_waker;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::{fanout_channel, lossy_fanout_channel};
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Message(usize);
+
+    #[test]
+    fn send_recv() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = fanout_channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn subscribe_receives_later_messages() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = fanout_channel(4);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        let mut rx2 = tx.subscribe();
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn lossless_slowest_receiver_blocks() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = fanout_channel(1);
+        let mut rx2 = tx.subscribe();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+        );
+        assert_eq!(1, tx.max_lag());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+    }
+
+    #[test]
+    fn lossless_drop_slow_receiver_wakes_sender() {
+        let mut cx = panic_context();
+        let (mut tx, _rx) = fanout_channel(1);
+        let rx2 = tx.subscribe();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+        );
+
+        drop(rx2);
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn lossy_drops_oldest() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = lossy_fanout_channel(2);
+        let mut rx2 = tx.subscribe();
+
+        for i in 1..=3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+
+            assert_eq!(
+                PollRecv::Ready(Message(i)),
+                Pin::new(&mut rx2).poll_recv(&mut cx)
+            );
+        }
+
+        assert_eq!(2, rx.lag());
+        assert_eq!(1, rx.dropped());
+        assert_eq!(0, rx2.dropped());
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = fanout_channel(4);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn receiver_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, rx) = fanout_channel(4);
+        let rx2 = tx.subscribe();
+
+        drop(rx);
+        drop(rx2);
+
+        assert_eq!(
+            PollSend::Rejected(Message(1)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+    }
+
+    #[test]
+    fn wake_receiver() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = fanout_channel(4);
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn send_with_constructs_once() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = fanout_channel(4);
+        let mut rx2 = tx.subscribe();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls2 = calls.clone();
+        let send = tx.send_with(move || {
+            calls2.fetch_add(1, Ordering::SeqCst);
+            Message(1)
+        });
+        assert_eq!(Some(Ok(())), send.now_or_never());
+        assert_eq!(0, calls.load(Ordering::SeqCst));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn lossy_send_with_skips_dropped() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = lossy_fanout_channel(2);
+        let mut rx2 = tx.subscribe();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        for i in 0..4 {
+            let calls = calls.clone();
+            let send = tx.send_with(move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Message(i)
+            });
+            assert_eq!(Some(Ok(())), send.now_or_never());
+        }
+
+        // the first two messages were dropped from both queues, and never constructed
+        for i in 2..4 {
+            assert_eq!(
+                PollRecv::Ready(Message(i)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+        }
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::{task::spawn, time::timeout};
+
+    use crate::{
+        sink::Sink,
+        stream::Stream,
+        test::{capacity_iter, Channel, Message, CHANNEL_TEST_RECEIVERS, TEST_TIMEOUT},
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn multi_receiver() {
+        for cap in capacity_iter() {
+            let (mut tx, rx) = super::fanout_channel(cap);
+            let receivers: Vec<_> = std::iter::once(rx)
+                .chain((1..CHANNEL_TEST_RECEIVERS).map(|_| tx.subscribe()))
+                .collect();
+
+            spawn(async move {
+                for message in Message::new_iter(0) {
+                    tx.send(message).await.expect("send failed");
+                }
+            });
+
+            let handles: Vec<_> = receivers
+                .into_iter()
+                .map(|mut rx| {
+                    spawn(async move {
+                        let mut channel = Channel::new(0);
+                        while let Some(message) = rx.recv().await {
+                            channel.assert_message(&message);
+                        }
+                    })
+                })
+                .collect();
+
+            timeout(TEST_TIMEOUT, async move {
+                for handle in handles {
+                    handle.await.expect("join failed");
+                }
+            })
+            .await
+            .expect("test timeout");
+        }
+    }
+}
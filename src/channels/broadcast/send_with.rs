@@ -0,0 +1,55 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use crate::{
+    sink::{PollSend, SendError},
+    sync::deferred::Entry,
+    Context,
+};
+
+// Implemented by broadcast senders which can buffer deferred values
+pub(crate) trait SendEntry<T> {
+    fn poll_send_entry(&mut self, cx: &mut Context<'_>, entry: Entry<T>) -> PollSend<Entry<T>>;
+}
+
+/// A future returned by `send_with`, which sends a deferred value.
+///
+/// If the channel is closed, the closure is dropped without being called.
+#[must_use = "futures do nothing unless polled"]
+pub struct SendWithFuture<'s, T> {
+    sender: &'s mut (dyn SendEntry<T> + Send),
+    entry: Option<Entry<T>>,
+}
+
+impl<'s, T> SendWithFuture<'s, T> {
+    pub(crate) fn new(sender: &'s mut (dyn SendEntry<T> + Send), entry: Entry<T>) -> Self {
+        Self {
+            sender,
+            entry: Some(entry),
+        }
+    }
+}
+
+// the entry is never pinned
+impl<'s, T> Unpin for SendWithFuture<'s, T> {}
+
+impl<'s, T> Future for SendWithFuture<'s, T> {
+    type Output = Result<(), SendError<()>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let entry = match this.entry.take() {
+            Some(entry) => entry,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let mut cx: Context<'_> = cx.into();
+        match this.sender.poll_send_entry(&mut cx, entry) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(entry) => {
+                this.entry = Some(entry);
+                Poll::Pending
+            }
+            PollSend::Rejected(_) => Poll::Ready(Err(SendError(()))),
+        }
+    }
+}
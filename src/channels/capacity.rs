@@ -0,0 +1,172 @@
+use std::{convert::TryFrom, fmt, mem, num::NonZeroUsize};
+
+use thiserror::Error;
+
+// the slot of a crossbeam ArrayQueue, which stores a stamp with each message.  bounds the capacity of queues
+pub(crate) type QueueSlot<T> = (std::sync::atomic::AtomicUsize, T);
+
+/// The validated capacity of a channel buffer, shared by every channel with a buffer of runtime size.
+///
+/// A capacity is at least 1.  There are no rendezvous channels, so a sender can always buffer a message for a
+/// receiver which isn't waiting.  A capacity is at most `Capacity::MAX`, so the buffer can be indexed without
+/// overflow.  Channels which allocate their buffer up front also limit the capacity to
+/// [max_for](#method.max_for) their slot type, so the allocation fits in `isize::MAX` bytes.  Memory is likely to be
+/// exhausted well before the maximum.
+///
+/// The convenience constructors, such as `mpsc::channel` and `mpsc::builder`, panic if the capacity is invalid.
+/// The `try_channel` and `try_builder` constructors return the error instead.  The broadcast channel rounds
+/// capacities below 2 up to 2, so its constructors only reject capacities which are too large.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Capacity(NonZeroUsize);
+
+impl Capacity {
+    /// The largest valid capacity.
+    pub const MAX: usize = isize::MAX as usize;
+
+    /// Validates the capacity, returning an error if it is zero, or exceeds `Capacity::MAX`.
+    pub fn new(capacity: usize) -> Result<Self, InvalidCapacity> {
+        if capacity > Self::MAX {
+            return Err(InvalidCapacity::TooLarge(capacity));
+        }
+
+        NonZeroUsize::new(capacity)
+            .map(Self)
+            .ok_or(InvalidCapacity::Zero)
+    }
+
+    /// Returns the largest capacity of a buffer of `S` slots, which fits in `isize::MAX` bytes.
+    ///
+    /// Each slot stores a message, along with the bookkeeping of the channel, so the maximum is lower than
+    /// `Capacity::MAX / size_of::<T>()` for messages of type `T`.
+    pub fn max_for<S>() -> usize {
+        match mem::size_of::<S>() {
+            0 => Self::MAX,
+            size => Self::MAX / size,
+        }
+    }
+
+    /// Returns the capacity as a usize.
+    pub fn get(self) -> usize {
+        self.0.get()
+    }
+
+    // validates the capacity of a buffer of `S` slots
+    pub(crate) fn for_slots<S>(capacity: usize) -> Result<Self, InvalidCapacity> {
+        let capacity = Self::new(capacity)?;
+
+        if capacity.get() > Self::max_for::<S>() {
+            return Err(InvalidCapacity::TooLarge(capacity.get()));
+        }
+
+        Ok(capacity)
+    }
+
+    // validates the capacity for the convenience constructors, which panic if it is invalid
+    #[track_caller]
+    pub(crate) fn expect<S>(capacity: usize) -> Self {
+        match Self::for_slots::<S>(capacity) {
+            Ok(capacity) => capacity,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl TryFrom<usize> for Capacity {
+    type Error = InvalidCapacity;
+
+    fn try_from(capacity: usize) -> Result<Self, Self::Error> {
+        Self::new(capacity)
+    }
+}
+
+impl From<Capacity> for usize {
+    fn from(capacity: Capacity) -> Self {
+        capacity.get()
+    }
+}
+
+impl fmt::Display for Capacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An error returned when a channel is constructed with an invalid [Capacity](./struct.Capacity.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum InvalidCapacity {
+    /// The capacity was zero
+    #[error("the capacity must be greater than zero")]
+    Zero,
+    /// The capacity exceeded `Capacity::MAX`, or the largest buffer of the channel's slots
+    #[error("the capacity {0} is too large for the channel buffer")]
+    TooLarge(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::{Capacity, InvalidCapacity};
+
+    #[test]
+    fn zero() {
+        assert_eq!(Err(InvalidCapacity::Zero), Capacity::new(0));
+    }
+
+    #[test]
+    fn one() {
+        assert_eq!(1, Capacity::new(1).unwrap().get());
+    }
+
+    #[test]
+    fn max() {
+        assert_eq!(Capacity::MAX, Capacity::new(Capacity::MAX).unwrap().get());
+    }
+
+    #[test]
+    fn too_large() {
+        assert_eq!(
+            Err(InvalidCapacity::TooLarge(Capacity::MAX + 1)),
+            Capacity::new(Capacity::MAX + 1)
+        );
+        assert_eq!(
+            Err(InvalidCapacity::TooLarge(usize::MAX)),
+            Capacity::new(usize::MAX)
+        );
+    }
+
+    #[test]
+    fn try_from() {
+        assert_eq!(Capacity::new(4), Capacity::try_from(4));
+        assert_eq!(4usize, Capacity::new(4).unwrap().into());
+    }
+
+    #[test]
+    fn max_for() {
+        assert_eq!(Capacity::MAX, Capacity::max_for::<()>());
+        assert_eq!(Capacity::MAX, Capacity::max_for::<u8>());
+        assert_eq!(Capacity::MAX / 8, Capacity::max_for::<u64>());
+
+        assert_eq!(
+            Ok(Capacity::max_for::<u64>()),
+            Capacity::for_slots::<u64>(Capacity::max_for::<u64>()).map(Capacity::get)
+        );
+        assert_eq!(
+            Err(InvalidCapacity::TooLarge(Capacity::MAX)),
+            Capacity::for_slots::<u64>(Capacity::MAX)
+        );
+        assert_eq!(Err(InvalidCapacity::Zero), Capacity::for_slots::<u64>(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "the capacity must be greater than zero")]
+    fn expect_zero() {
+        Capacity::expect::<u64>(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "is too large for the channel buffer")]
+    fn expect_too_large() {
+        Capacity::expect::<u64>(Capacity::MAX);
+    }
+}
@@ -3,26 +3,86 @@
 //! Senders and recievers can be cloned, and additional recievers can be created with `tx.subscribe()`
 //!
 //! The producer can be cloned, and the sender task is suspended if the channel becomes full.
+//!
+//! Channels created with `keyed_channel` route items with the same key to the same receiver.
+//!
+//! For at-least-once processing, `Receiver::claim` receives a message which is returned to the channel unless the
+//! claim is committed.
+//!
+//! A channel which starts with messages in the buffer, or drops messages which have waited too long, can be
+//! constructed with `builder`.
 
-use std::fmt;
+use std::{fmt, time::Instant};
 
-use super::SendMessage;
+use self::claim::Redelivery;
+use super::{
+    capacity::{Capacity, InvalidCapacity, QueueSlot},
+    expiry::{ExpiringQueue, Ttl},
+    meta::{PollRecvMeta, PollSendDeadline},
+    NotSendMessage, SendMessage,
+};
 use crate::{
-    sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
+    coop::Budget,
+    sink::{PollSend, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
     sync::{shared, ReceiverShared, SenderShared},
 };
-use crossbeam_queue::ArrayQueue;
-use static_assertions::assert_impl_all;
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+mod builder;
+mod claim;
+mod keyed;
+
+pub use super::meta::{MessageMeta, RecvMetaFuture, SendDeadlineFuture};
+pub use builder::{builder, try_builder, Builder};
+pub use claim::{Claim, ClaimFuture};
+pub use keyed::{keyed_channel, KeyedReceiver, KeyedSender};
 
 /// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+/// `try_channel` returns the error instead.
+#[track_caller]
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = Capacity::expect::<QueueSlot<T>>(capacity);
+    new_channel(StateExtension::new(capacity.get(), None, false))
+}
+
+/// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity
+///
+/// Returns an error if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+pub fn try_channel<T>(capacity: usize) -> Result<(Sender<T>, Receiver<T>), InvalidCapacity> {
+    let capacity = Capacity::for_slots::<QueueSlot<T>>(capacity)?;
+    Ok(new_channel(StateExtension::new(
+        capacity.get(),
+        None,
+        false,
+    )))
+}
+
+fn new_channel<T>(extension: StateExtension<T>) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
-    log::error!("Creating dispatch channel with capacity {}", capacity);
-    let (tx_shared, rx_shared) = shared(StateExtension::new(capacity));
+    log::error!(
+        "Creating dispatch channel with capacity {}",
+        extension.queue.capacity()
+    );
+    let (tx_shared, rx_shared) = shared(extension);
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        "dispatch::channel",
+        std::any::type_name::<T>(),
+        Some(tx_shared.extension().queue.capacity()),
+        |extension| extension.queue.len() + extension.redelivery.len(),
+    );
+
     let sender = Sender { shared: tx_shared };
 
-    let receiver = Receiver { shared: rx_shared };
+    let receiver = Receiver {
+        shared: rx_shared,
+        budget: Budget::new(),
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
 
     (sender, receiver)
 }
@@ -34,7 +94,8 @@ pub struct Sender<T> {
     shared: SenderShared<StateExtension<T>>,
 }
 
-assert_impl_all!(Sender<String>: Clone, Send, Sync, fmt::Debug);
+assert_impl_all!(Sender<SendMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(Sender<NotSendMessage>: Send, Sync);
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
@@ -50,32 +111,14 @@ impl<T> Sink for Sender<T> {
     fn poll_send(
         self: std::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
-        mut value: Self::Item,
+        value: Self::Item,
     ) -> PollSend<Self::Item> {
-        loop {
-            if self.shared.is_closed() {
-                return PollSend::Rejected(value);
-            }
-
-            let queue = &self.shared.extension().queue;
-            let guard = self.shared.recv_guard();
-
-            match queue.push(value) {
-                Ok(_) => {
-                    self.shared.notify_receivers();
-                    return PollSend::Ready;
-                }
-                Err(v) => {
-                    self.shared.subscribe_recv(cx);
-                    if guard.is_expired() {
-                        value = v;
-                        continue;
-                    }
+        self.get_mut().poll_push(cx, value, None)
+    }
 
-                    return PollSend::Pending(v);
-                }
-            }
-        }
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
     }
 }
 
@@ -106,8 +149,8 @@ mod impl_futures {
                 let guard = self.shared.recv_guard();
 
                 if queue.is_full() {
-                    let mut cx = cx.into();
-                    self.shared.subscribe_recv(&mut cx);
+                    let cx = cx.into();
+                    self.shared.subscribe_recv(&cx);
 
                     if guard.is_expired() {
                         continue;
@@ -160,18 +203,211 @@ impl<T> Sender<T> {
     pub fn subscribe(&self) -> Receiver<T> {
         Receiver {
             shared: self.shared.clone_receiver(),
+            budget: Budget::new(),
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
+        }
+    }
+
+    /// Sends a message with a deadline, which receivers observe with
+    /// [Receiver::recv_with_meta](./struct.Receiver.html#method.recv_with_meta).
+    ///
+    /// The deadline is only stored if the channel was built with
+    /// [Builder::message_meta](./struct.Builder.html#method.message_meta).  If the channel also has a
+    /// [message_ttl](./struct.Builder.html#method.message_ttl), the message expires once the deadline has passed.
+    pub fn send_with_deadline(
+        &mut self,
+        value: T,
+        deadline: Instant,
+    ) -> SendDeadlineFuture<'_, Self, T> {
+        SendDeadlineFuture::new(self, value, deadline)
+    }
+
+    /// Attempts to send a message with a deadline, without blocking.  See [send_with_deadline](#method.send_with_deadline).
+    pub fn try_send_with_deadline(
+        &mut self,
+        value: T,
+        deadline: Instant,
+    ) -> Result<(), TrySendError<T>> {
+        match self.poll_push(&mut crate::Context::empty(), value, Some(deadline)) {
+            PollSend::Ready => Ok(()),
+            PollSend::Pending(value) => Err(TrySendError::Pending(value)),
+            PollSend::Rejected(value) => Err(TrySendError::Rejected(value)),
+        }
+    }
+
+    fn poll_push(
+        &mut self,
+        cx: &mut crate::Context<'_>,
+        mut value: T,
+        deadline: Option<Instant>,
+    ) -> PollSend<T> {
+        loop {
+            if self.shared.is_closed() {
+                return PollSend::Rejected(value);
+            }
+
+            let queue = &self.shared.extension().queue;
+            let guard = self.shared.recv_guard();
+
+            match queue.push_with_deadline(value, deadline) {
+                Ok(_) => {
+                    self.shared.notify_receivers();
+                    return PollSend::Ready;
+                }
+                Err(v) => {
+                    self.shared.subscribe_recv(cx);
+                    if guard.is_expired() {
+                        value = v;
+                        continue;
+                    }
+
+                    return PollSend::Pending(v);
+                }
+            }
         }
     }
 }
 
+impl<T> PollSendDeadline for Sender<T> {
+    type Item = T;
+
+    fn poll_send_deadline(
+        &mut self,
+        cx: &mut crate::Context<'_>,
+        value: T,
+        deadline: Instant,
+    ) -> PollSend<T> {
+        self.poll_push(cx, value, Some(deadline))
+    }
+}
+
 /// The receiver half of a dispatch channel.
 ///
 /// Can receive messages with the `postage::Stream` trait.
 pub struct Receiver<T> {
     shared: ReceiverShared<StateExtension<T>>,
+    budget: Budget,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
 }
 
 assert_impl_all!(Receiver<SendMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(Receiver<NotSendMessage>: Send, Sync);
+
+impl<T> Receiver<T> {
+    /// Returns the number of messages which were dropped because they expired before any receiver could receive
+    /// them.  The count is shared by all receivers of the channel.
+    ///
+    /// Messages only expire if the channel was built with [Builder::message_ttl](./struct.Builder.html#method.message_ttl).
+    pub fn expired_count(&self) -> usize {
+        self.shared.extension().queue.expired()
+    }
+
+    /// Claims the next message.  The message is removed from the channel when the claim is committed, and
+    /// returned to the channel if the claim is dropped.  See [Claim](./struct.Claim.html).
+    ///
+    /// Returns None once the channel is closed, and no claims are outstanding.
+    ///
+    /// ```rust
+    /// use postage::{dispatch, sink::Sink};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (mut tx, mut rx) = dispatch::channel(4);
+    ///     tx.send("job").await.ok();
+    ///
+    ///     // the handler failed, so the job is returned to the channel
+    ///     let claim = rx.claim().await.unwrap();
+    ///     drop(claim);
+    ///
+    ///     let claim = rx.claim().await.unwrap();
+    ///     assert_eq!("job", claim.commit());
+    /// }
+    /// ```
+    pub fn claim(&mut self) -> ClaimFuture<'_, T> {
+        ClaimFuture::new(self)
+    }
+
+    /// Attempts to claim the next message.  See [claim](#method.claim).
+    pub fn poll_claim(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<Claim<T>> {
+        match self.poll_pop(cx, true) {
+            PollRecv::Ready((value, _meta)) => {
+                PollRecv::Ready(Claim::new(value, self.shared.clone()))
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    /// Receives a message, and the [metadata](./struct.MessageMeta.html) it was sent with.
+    ///
+    /// The metadata is only recorded if the channel was built with
+    /// [Builder::message_meta](./struct.Builder.html#method.message_meta).  Otherwise, and for messages returned by
+    /// abandoned claims, the metadata is empty.
+    pub fn recv_with_meta(&mut self) -> RecvMetaFuture<'_, Self, T> {
+        RecvMetaFuture::new(self)
+    }
+
+    /// Attempts to receive a message and its metadata, without blocking.  See [recv_with_meta](#method.recv_with_meta).
+    pub fn try_recv_with_meta(&mut self) -> Result<(T, MessageMeta), TryRecvError> {
+        match self.poll_pop(&mut crate::Context::empty(), false) {
+            PollRecv::Ready(value) => Ok(value),
+            PollRecv::Pending => Err(TryRecvError::Pending),
+            PollRecv::Closed => Err(TryRecvError::Closed),
+        }
+    }
+
+    // if `claim` is set, the message is counted as in flight before it is removed from the channel
+    fn poll_pop(&mut self, cx: &mut crate::Context<'_>, claim: bool) -> PollRecv<(T, MessageMeta)> {
+        if self.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
+        let extension = self.shared.extension();
+        loop {
+            let guard = self.shared.send_guard();
+            if claim {
+                extension.redelivery.begin_claim();
+            }
+
+            let mut freed = 0;
+            let popped = extension.pop(&mut freed);
+
+            // expired messages free capacity, even if none could be received
+            if freed > 0 {
+                self.shared.notify_senders();
+            }
+
+            if let Some(v) = popped {
+                self.budget.consume();
+                return PollRecv::Ready(v);
+            }
+
+            if claim {
+                extension.redelivery.end_claim();
+
+                // other receivers may be waiting for the claim to resolve
+                if self.shared.is_closed() {
+                    self.shared.notify_receivers();
+                }
+            }
+
+            // outstanding claims can return messages to a closed channel
+            if self.shared.is_closed() && extension.redelivery.is_settled() {
+                return PollRecv::Closed;
+            }
+
+            self.shared.subscribe_send(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            self.budget.reset();
+            return PollRecv::Pending;
+        }
+    }
+}
 
 impl<T> Stream for Receiver<T> {
     type Item = T;
@@ -180,27 +416,30 @@ impl<T> Stream for Receiver<T> {
         self: std::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
-        loop {
-            let guard = self.shared.send_guard();
-            match self.shared.extension().queue.pop() {
-                Some(v) => {
-                    self.shared.notify_senders();
-                    return PollRecv::Ready(v);
-                }
-                None => {
-                    if self.shared.is_closed() {
-                        return PollRecv::Closed;
-                    }
+        match self.get_mut().poll_pop(cx, false) {
+            PollRecv::Ready((value, _meta)) => PollRecv::Ready(value),
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
 
-                    self.shared.subscribe_send(cx);
-                    if guard.is_expired() {
-                        continue;
-                    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // the queue is shared with the other receivers
+        let closed = self.shared.is_closed();
+        let extension = self.shared.extension();
+        let len = extension.queue.len() + extension.redelivery.len();
 
-                    return PollRecv::Pending;
-                }
-            }
-        }
+        // outstanding claims may return their messages
+        let upper = len + extension.redelivery.in_flight();
+        (len, if closed { Some(upper) } else { None })
+    }
+}
+
+impl<T> PollRecvMeta for Receiver<T> {
+    type Item = T;
+
+    fn poll_recv_meta(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<(T, MessageMeta)> {
+        self.poll_pop(cx, false)
     }
 }
 
@@ -208,6 +447,9 @@ impl<T> Clone for Receiver<T> {
     fn clone(&self) -> Self {
         Self {
             shared: self.shared.clone(),
+            budget: Budget::new(),
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
         }
     }
 }
@@ -219,13 +461,31 @@ impl<T> fmt::Debug for Receiver<T> {
 }
 
 struct StateExtension<T> {
-    queue: ArrayQueue<T>,
+    queue: ExpiringQueue<T>,
+    // messages returned by abandoned claims
+    redelivery: Redelivery<T>,
 }
 
 impl<T> StateExtension<T> {
-    pub fn new(capacity: usize) -> Self {
+    pub fn new(capacity: usize, ttl: Option<Ttl>, redeliver_to_front: bool) -> Self {
         Self {
-            queue: ArrayQueue::new(capacity),
+            queue: ExpiringQueue::new(capacity, ttl),
+            redelivery: Redelivery::new(redeliver_to_front),
+        }
+    }
+
+    // redelivered messages have no metadata
+    fn pop(&self, freed: &mut usize) -> Option<(T, MessageMeta)> {
+        let redelivered = || {
+            self.redelivery
+                .pop()
+                .map(|value| (value, MessageMeta::default()))
+        };
+
+        if self.redelivery.to_front() {
+            redelivered().or_else(|| self.queue.pop_with_meta(freed))
+        } else {
+            self.queue.pop_with_meta(freed).or_else(redelivered)
         }
     }
 }
@@ -235,15 +495,69 @@ mod tests {
     use std::{pin::Pin, task::Context};
 
     use crate::{
+        coop::BUDGET,
         sink::{PollSend, Sink},
         stream::{PollRecv, Stream},
         test::{noop_context, panic_context},
     };
     use futures_test::task::new_count_waker;
 
-    use super::{channel, Receiver, Sender};
+    use super::{channel, try_channel, Receiver, Sender};
+
+    #[test]
+    #[should_panic(expected = "the capacity must be greater than zero")]
+    fn zero_capacity() {
+        let _ = channel::<usize>(0);
+    }
+
+    #[test]
+    fn try_channel_capacity() {
+        use crate::InvalidCapacity;
+
+        assert_eq!(Some(InvalidCapacity::Zero), try_channel::<usize>(0).err());
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(usize::MAX)),
+            try_channel::<usize>(usize::MAX).err()
+        );
+
+        // the buffer of a valid capacity must fit in memory
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(crate::Capacity::MAX)),
+            try_channel::<u64>(crate::Capacity::MAX).err()
+        );
+
+        let (mut tx, _rx) = try_channel::<usize>(1).unwrap();
+        assert!(tx.try_send(1).is_ok());
+        assert!(tx.try_send(2).is_err());
+    }
+
+    #[test]
+    fn full_channel_yields() {
+        let (mut tx, mut rx) = channel::<usize>(4);
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker).into();
+
+        let mut tx_cx = noop_context();
+
+        // the sender refills the channel after every message, so it is never empty
+        let mut ready = 0;
+        for round in 0..3 {
+            loop {
+                while let PollSend::Ready = Pin::new(&mut tx).poll_send(&mut tx_cx, ready) {}
+
+                match Pin::new(&mut rx).poll_recv(&mut cx) {
+                    PollRecv::Ready(_) => ready += 1,
+                    PollRecv::Pending => break,
+                    PollRecv::Closed => panic!("channel closed"),
+                }
+            }
+
+            assert_eq!((round + 1) * BUDGET, ready);
+            assert_eq!(round + 1, count.get());
+        }
+    }
 
-    fn pin<'a, 'b>(
+    fn pin(
         chan: &mut (Sender<Message>, Receiver<Message>),
     ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
         let tx = Pin::new(&mut chan.0);
@@ -704,7 +1018,7 @@ mod tokio_tests {
 
             spawn(async move {
                 loop {
-                    if let Ok(_) = sender_quit.try_recv() {
+                    if sender_quit.try_recv().is_ok() {
                         break;
                     }
 
@@ -910,7 +1224,7 @@ mod async_std_tests {
 
             spawn(async move {
                 loop {
-                    if let Ok(_) = sender_quit.try_recv() {
+                    if sender_quit.try_recv().is_ok() {
                         break;
                     }
 
@@ -0,0 +1,287 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::channels::{
+    capacity::{Capacity, InvalidCapacity},
+    expiry::{Clock, ExpiringSlot, Ttl},
+    prefill::Prefill,
+};
+
+use super::{new_channel, Receiver, Sender, StateExtension};
+
+/// Constructs a builder for a dispatch channel with the given capacity.
+///
+/// The channel can be prefilled with messages, which are received before any messages sent later.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+/// `try_builder` returns the error instead.
+#[track_caller]
+pub fn builder<T>(capacity: usize) -> Builder<T> {
+    new_builder(Capacity::expect::<ExpiringSlot<T>>(capacity))
+}
+
+/// Constructs a builder for a dispatch channel with the given capacity.
+///
+/// Returns an error if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+pub fn try_builder<T>(capacity: usize) -> Result<Builder<T>, InvalidCapacity> {
+    Capacity::for_slots::<ExpiringSlot<T>>(capacity).map(new_builder)
+}
+
+fn new_builder<T>(capacity: Capacity) -> Builder<T> {
+    Builder {
+        capacity,
+        prefill: Prefill::new(),
+        ttl: None,
+        message_meta: false,
+        clock: None,
+        redeliver_to_front: false,
+    }
+}
+
+/// A builder for a dispatch channel, created by [builder](./fn.builder.html).
+pub struct Builder<T> {
+    capacity: Capacity,
+    prefill: Prefill<T>,
+    ttl: Option<Duration>,
+    message_meta: bool,
+    clock: Option<Clock>,
+    redeliver_to_front: bool,
+}
+
+impl<T> Builder<T> {
+    /// Seeds the channel with the messages, in order.  The messages count against the capacity.
+    ///
+    /// If called more than once, the messages are appended.
+    pub fn prefill<I>(mut self, messages: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.prefill.extend(messages);
+        self
+    }
+
+    /// Configures whether the capacity grows to fit the prefilled messages.
+    ///
+    /// If disabled (the default), `build` panics if the messages exceed the capacity.
+    pub fn grow_to_fit(mut self, grow: bool) -> Self {
+        self.prefill.set_grow_to_fit(grow);
+        self
+    }
+
+    /// Drops messages which have been queued for longer than `ttl`, rather than delivering them.
+    ///
+    /// Expiry is evaluated when messages are received, so no timer or runtime is needed.  Expired messages are
+    /// skipped, and counted by `Receiver::expired_count`.  Prefilled messages are stamped when the channel is built.
+    pub fn message_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Configures whether messages keep the time they were sent, and the deadline they were sent with.
+    ///
+    /// The metadata is returned by `Receiver::recv_with_meta`, and deadlines are attached with
+    /// `Sender::send_with_deadline`.  If the channel also has a [message_ttl](#method.message_ttl), messages
+    /// expire once their deadline has passed.  The default is disabled, and deadlines are discarded.
+    pub fn message_meta(mut self, enabled: bool) -> Self {
+        self.message_meta = enabled;
+        self
+    }
+
+    /// Replaces the monotonic clock which measures the age of messages, and their send time.
+    /// The default is `Instant::now`.
+    pub fn clock<F>(mut self, now: F) -> Self
+    where
+        F: Fn() -> Instant + Send + Sync + 'static,
+    {
+        self.clock = Some(Arc::new(now));
+        self
+    }
+
+    /// Configures where messages of abandoned claims are returned.
+    ///
+    /// If enabled, they are received before the messages in the queue.  If disabled (the default), they are
+    /// received once the queue is empty, so a message which repeatedly fails does not block the others.
+    pub fn redeliver_to_front(mut self, front: bool) -> Self {
+        self.redeliver_to_front = front;
+        self
+    }
+
+    /// Constructs the channel, and sends the prefilled messages.
+    #[track_caller]
+    pub fn build(self) -> (Sender<T>, Receiver<T>) {
+        let clock = self.clock;
+        let ttl = self.ttl.map(|ttl| Ttl::new(ttl, clock.clone()));
+        let mut extension = StateExtension::new(
+            self.prefill.capacity(self.capacity),
+            ttl,
+            self.redeliver_to_front,
+        );
+        if self.message_meta {
+            extension.queue.record_meta(clock);
+        }
+        let (mut tx, rx) = new_channel(extension);
+        self.prefill.send_into(&mut tx);
+
+        (tx, rx)
+    }
+}
+
+impl<T> fmt::Debug for Builder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("capacity", &self.capacity.get())
+            .field("prefill", &self.prefill.len())
+            .field("ttl", &self.ttl)
+            .field("message_meta", &self.message_meta)
+            .field("redeliver_to_front", &self.redeliver_to_front)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::{
+        dispatch::MessageMeta,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::{noop_context, time::MockClock},
+    };
+
+    use super::builder;
+
+    #[test]
+    fn prefill_shared_by_receivers() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(3).prefill(vec![1, 2]).build();
+        let mut rx2 = rx.clone();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+        assert_eq!(
+            PollSend::Pending(4),
+            Pin::new(&mut tx).poll_send(&mut cx, 4)
+        );
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx2).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+    }
+
+    #[test]
+    #[should_panic]
+    fn exceeds_capacity() {
+        let _ = builder(2).prefill(0..3).build();
+    }
+
+    #[test]
+    #[should_panic(expected = "the capacity must be greater than zero")]
+    fn zero_capacity() {
+        let _ = builder::<usize>(0);
+    }
+
+    #[test]
+    fn try_builder_capacity() {
+        use crate::InvalidCapacity;
+
+        assert_eq!(
+            Some(InvalidCapacity::Zero),
+            super::try_builder::<usize>(0).err()
+        );
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(usize::MAX)),
+            super::try_builder::<usize>(usize::MAX).err()
+        );
+
+        let (_tx, mut rx) = super::try_builder(1).unwrap().prefill(vec![1]).build();
+        assert_eq!(Ok(1), rx.try_recv());
+    }
+
+    #[test]
+    fn message_ttl() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let (mut tx, mut rx) = builder(4)
+            .message_ttl(Duration::from_secs(1))
+            .clock(clock.now_fn())
+            .build();
+        let mut rx2 = rx.clone();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+
+        // the count is shared by the receivers
+        assert_eq!(2, rx.expired_count());
+        assert_eq!(2, rx2.expired_count());
+    }
+
+    #[test]
+    fn message_meta() {
+        let clock = MockClock::new();
+        let now = clock.now_fn();
+        let (mut tx, mut rx) = builder(4).message_meta(true).clock(clock.now_fn()).build();
+        let mut rx2 = rx.clone();
+
+        let sent = now();
+        let deadline = sent + Duration::from_secs(1);
+        tx.try_send_with_deadline(1, deadline).unwrap();
+        tx.try_send(2).unwrap();
+        clock.advance(Duration::from_secs(2));
+
+        let (value, meta) = rx2.try_recv_with_meta().unwrap();
+        assert_eq!(1, value);
+        assert_eq!(Some(sent), meta.enqueued_at);
+        assert_eq!(Some(deadline), meta.deadline);
+        assert!(meta.is_expired(now()));
+
+        let (value, meta) = rx.try_recv_with_meta().unwrap();
+        assert_eq!(2, value);
+        assert_eq!(Some(sent), meta.enqueued_at);
+        assert_eq!(None, meta.deadline);
+    }
+
+    #[test]
+    fn message_ttl_and_deadline() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let now = clock.now_fn();
+        let (mut tx, mut rx) = builder(4)
+            .message_ttl(Duration::from_secs(10))
+            .message_meta(true)
+            .clock(clock.now_fn())
+            .build();
+
+        tx.try_send_with_deadline(1, now() + Duration::from_secs(1))
+            .unwrap();
+        tx.try_send_with_deadline(2, now() + Duration::from_secs(5))
+            .unwrap();
+        clock.advance(Duration::from_secs(2));
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(1, rx.expired_count());
+    }
+
+    #[test]
+    fn redelivered_without_meta() {
+        let clock = MockClock::new();
+        let now = clock.now_fn();
+        let (mut tx, mut rx) = builder(4).message_meta(true).clock(clock.now_fn()).build();
+
+        tx.try_send_with_deadline(1, now() + Duration::from_secs(1))
+            .unwrap();
+        let claim = rx.poll_claim(&mut noop_context());
+        drop(claim);
+
+        // the claim was abandoned, and its message lost the metadata
+        assert_eq!(Ok((1, MessageMeta::default())), rx.try_recv_with_meta());
+    }
+}
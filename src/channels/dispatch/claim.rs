@@ -0,0 +1,341 @@
+//! Claims, which receive a message from the dispatch channel, and return it to the queue unless committed.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Poll,
+};
+
+use parking_lot::Mutex;
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+use crate::{
+    channels::{NotSendMessage, SendMessage, SendSyncMessage},
+    stream::PollRecv,
+    sync::ReceiverShared,
+};
+
+use super::{Receiver, StateExtension};
+
+// Messages returned by abandoned claims, and the number of claims which are still in flight.
+pub(super) struct Redelivery<T> {
+    items: Mutex<VecDeque<T>>,
+    // the length of `items`, which can be read without the lock
+    len: AtomicUsize,
+    in_flight: AtomicUsize,
+    to_front: bool,
+}
+
+impl<T> Redelivery<T> {
+    pub fn new(to_front: bool) -> Self {
+        Self {
+            items: Mutex::new(VecDeque::new()),
+            len: AtomicUsize::new(0),
+            in_flight: AtomicUsize::new(0),
+            to_front,
+        }
+    }
+
+    // if set, returned messages are received before the queue.  otherwise they are received after it
+    pub fn to_front(&self) -> bool {
+        self.to_front
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        if self.len.load(Ordering::Acquire) == 0 {
+            return None;
+        }
+
+        let mut items = self.items.lock();
+        let item = items.pop_front();
+        self.len.store(items.len(), Ordering::Release);
+
+        item
+    }
+
+    fn push(&self, item: T) {
+        let mut items = self.items.lock();
+        if self.to_front {
+            items.push_front(item);
+        } else {
+            items.push_back(item);
+        }
+
+        self.len.store(items.len(), Ordering::Release);
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    // called before a claiming receiver pops a message, so no other receiver observes a closed channel while
+    // the message is in transit
+    pub fn begin_claim(&self) {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn end_claim(&self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    // true if no message can be returned to the channel
+    pub fn is_settled(&self) -> bool {
+        self.in_flight() == 0 && self.len() == 0
+    }
+}
+
+/// A message claimed from a dispatch channel with [Receiver::claim](./struct.Receiver.html#method.claim).
+///
+/// The message can be accessed through `Deref`.  `commit` removes it from the channel permanently.
+/// If the claim is dropped without committing, including while a task panics, the message is returned to the channel
+/// so another receiver can retry it.  While claims are outstanding, receivers of a closed channel wait for them
+/// to be resolved.
+pub struct Claim<T> {
+    value: Option<T>,
+    shared: ReceiverShared<StateExtension<T>>,
+}
+
+assert_impl_all!(Claim<SendSyncMessage>: Send, Sync);
+assert_impl_all!(Claim<SendMessage>: Send);
+assert_not_impl_any!(Claim<NotSendMessage>: Send, Sync);
+
+impl<T> Claim<T> {
+    pub(super) fn new(value: T, shared: ReceiverShared<StateExtension<T>>) -> Self {
+        Self {
+            value: Some(value),
+            shared,
+        }
+    }
+
+    /// Removes the message from the channel permanently, and returns it.
+    pub fn commit(mut self) -> T {
+        let value = self
+            .value
+            .take()
+            .expect("the claim holds a value until it is resolved");
+        self.resolve();
+
+        value
+    }
+
+    fn resolve(&self) {
+        self.shared.extension().redelivery.end_claim();
+
+        // receivers of a closed channel may be waiting for the claim
+        self.shared.notify_receivers();
+    }
+}
+
+impl<T> Deref for Claim<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+            .as_ref()
+            .expect("the claim holds a value until it is resolved")
+    }
+}
+
+impl<T> DerefMut for Claim<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+            .as_mut()
+            .expect("the claim holds a value until it is resolved")
+    }
+}
+
+impl<T> Drop for Claim<T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.shared.extension().redelivery.push(value);
+            self.resolve();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Claim<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Claim").field(&self.value).finish()
+    }
+}
+
+/// A future returned by [Receiver::claim](./struct.Receiver.html#method.claim).
+#[must_use = "futures do nothing unless polled"]
+pub struct ClaimFuture<'r, T> {
+    receiver: &'r mut Receiver<T>,
+}
+
+impl<'r, T> ClaimFuture<'r, T> {
+    pub(super) fn new(receiver: &'r mut Receiver<T>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl<'r, T> Future for ClaimFuture<'r, T> {
+    type Output = Option<Claim<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut cx: crate::Context<'_> = cx.into();
+
+        match self.get_mut().receiver.poll_claim(&mut cx) {
+            PollRecv::Ready(claim) => Poll::Ready(Some(claim)),
+            PollRecv::Pending => Poll::Pending,
+            PollRecv::Closed => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{
+        dispatch::{builder, channel},
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+        test_util::WakeProbe,
+    };
+
+    fn ready<T>(poll: PollRecv<T>) -> T {
+        match poll {
+            PollRecv::Ready(value) => value,
+            PollRecv::Pending => panic!("the receiver is pending"),
+            PollRecv::Closed => panic!("the receiver is closed"),
+        }
+    }
+
+    #[test]
+    fn commit_removes_message() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+        let claim = ready(rx.poll_claim(&mut cx));
+        assert_eq!(1, *claim);
+        assert_eq!(1, claim.commit());
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn drop_redelivers_to_back() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+
+        let claim = ready(rx.poll_claim(&mut cx));
+        drop(claim);
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn drop_redelivers_to_front() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(2).redeliver_to_front(true).build();
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+
+        let claim = ready(rx.poll_claim(&mut cx));
+        drop(claim);
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn claim_frees_capacity() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(1);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+        // the claimed message no longer occupies the queue, and is redelivered after the queue
+        let claim = ready(rx.poll_claim(&mut cx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        drop(claim);
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_channel_waits_for_claims() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+        let mut rx2 = rx.clone();
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        drop(tx);
+
+        let claim = ready(rx.poll_claim(&mut cx));
+        let committed = ready(rx.poll_claim(&mut cx));
+
+        let probe = WakeProbe::new();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut probe.context())
+        );
+
+        assert_eq!(2, committed.commit());
+        assert!(probe.count() > 0);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+
+        drop(claim);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx2).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx2).poll_recv(&mut cx));
+        assert!(matches!(rx.poll_claim(&mut cx), PollRecv::Closed));
+    }
+
+    #[test]
+    fn panic_in_handler_redelivers() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(1);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+        let claim = ready(rx.poll_claim(&mut cx));
+        let handler = std::thread::spawn(move || {
+            if *claim == 1 {
+                panic!("the handler failed");
+            }
+
+            claim.commit()
+        });
+        assert!(handler.join().is_err());
+
+        let claim = ready(rx.poll_claim(&mut cx));
+        assert_eq!(1, claim.commit());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn claim_future() {
+        let (mut tx, mut rx) = channel::<usize>(1);
+        let handle = tokio::spawn(async move {
+            let claim = rx.claim().await.expect("the message is sent");
+            let value = claim.commit();
+
+            (value, rx.claim().await.is_none())
+        });
+
+        tx.send(1).await.expect("the receiver is alive");
+        drop(tx);
+
+        assert_eq!((1, true), handle.await.unwrap());
+    }
+}
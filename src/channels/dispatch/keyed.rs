@@ -0,0 +1,650 @@
+//! A dispatch channel which routes items with the same key to the same receiver.
+//!
+//! Each receiver has a local backlog.  Keys are assigned to receivers with rendezvous hashing, so when receivers
+//! join or leave the channel, only the keys owned by that receiver move.  If the preferred receiver's backlog is full,
+//! the item is placed in a shared overflow queue, which is drained by any idle receiver.
+//!
+//! When a receiver is dropped, its backlog is moved to the overflow queue.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::{Mutex, RwLock};
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+use crate::{
+    channels::{capacity::Capacity, NotSendMessage, SendMessage},
+    coop::Budget,
+    sink::{PollSend, SendFuture, Sink},
+    stream::{PollRecv, Stream},
+    sync::{shared, ReceiverShared, SenderShared},
+    Context,
+};
+
+/// Constructs a pair of keyed dispatch endpoints, with a buffer of the given capacity.
+///
+/// Keys are hashed with the provided hasher.  By default, a receiver's backlog holds at most a quarter of the capacity,
+/// which can be configured with `KeyedSender::max_backlog`.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+#[track_caller]
+pub fn keyed_channel<K, T, H>(
+    capacity: usize,
+    hasher: H,
+) -> (KeyedSender<K, T, H>, KeyedReceiver<T>)
+where
+    K: Hash,
+    H: BuildHasher,
+{
+    let capacity = Capacity::expect::<T>(capacity).get();
+
+    #[cfg(feature = "debug")]
+    log::error!("Creating keyed dispatch channel with capacity {}", capacity);
+
+    let (tx_shared, rx_shared) = shared(KeyedExtension::new(capacity));
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        "dispatch::keyed_channel",
+        std::any::type_name::<T>(),
+        Some(capacity),
+        |extension| extension.len.load(Ordering::Acquire),
+    );
+
+    let local = rx_shared.extension().register();
+
+    let sender = KeyedSender {
+        shared: tx_shared,
+        hasher,
+        max_backlog: (capacity / 4).max(1),
+        _key: PhantomData,
+    };
+
+    let receiver = KeyedReceiver {
+        shared: rx_shared,
+        local,
+        budget: Budget::new(),
+    };
+
+    (sender, receiver)
+}
+
+/// The sender half of a keyed dispatch channel.  Can send `(key, value)` pairs with the `postage::Sink` trait.
+///
+/// Can be cloned.
+pub struct KeyedSender<K, T, H> {
+    shared: SenderShared<KeyedExtension<T>>,
+    hasher: H,
+    max_backlog: usize,
+    _key: PhantomData<fn(K)>,
+}
+
+// keys are hashed by the sender, and are not stored in the channel
+assert_impl_all!(KeyedSender<NotSendMessage, SendMessage, std::collections::hash_map::RandomState>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(KeyedSender<String, NotSendMessage, std::collections::hash_map::RandomState>: Send, Sync);
+
+impl<K, T, H> KeyedSender<K, T, H>
+where
+    K: Hash,
+    H: BuildHasher,
+{
+    /// Sends a value to the receiver which owns the key, or to any idle receiver if that receiver's backlog is full.
+    pub fn send_keyed(&mut self, key: K, value: T) -> SendFuture<'_, Self> {
+        self.send((key, value))
+    }
+
+    /// Sets the number of items which can be queued for a single receiver.
+    /// Once the backlog is full, items are sent to an overflow queue that is shared by all receivers.
+    ///
+    /// This bounds the imbalance between receivers.  Clones of the sender inherit the setting.
+    pub fn max_backlog(mut self, max_backlog: usize) -> Self {
+        self.max_backlog = max_backlog;
+        self
+    }
+
+    /// Creates a new Receiver that listens to this channel.  The new receiver takes ownership of a share of the keys.
+    pub fn subscribe(&self) -> KeyedReceiver<T> {
+        let shared = self.shared.clone_receiver();
+        let local = shared.extension().register();
+
+        KeyedReceiver {
+            shared,
+            local,
+            budget: Budget::new(),
+        }
+    }
+}
+
+impl<K, T, H> Clone for KeyedSender<K, T, H>
+where
+    H: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            hasher: self.hasher.clone(),
+            max_backlog: self.max_backlog,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K, T, H> Sink for KeyedSender<K, T, H>
+where
+    K: Hash,
+    H: BuildHasher,
+{
+    type Item = (K, T);
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let key_hash = self.hasher.hash_one(&value.0);
+
+        loop {
+            if self.shared.is_closed() {
+                return PollSend::Rejected(value);
+            }
+
+            let guard = self.shared.recv_guard();
+            if self.shared.extension().reserve() {
+                self.shared
+                    .extension()
+                    .push(key_hash, value.1, self.max_backlog);
+                self.shared.notify_receivers();
+                return PollSend::Ready;
+            }
+
+            self.shared.subscribe_recv(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollSend::Pending(value);
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
+    }
+}
+
+impl<K, T, H> fmt::Debug for KeyedSender<K, T, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedSender").finish()
+    }
+}
+
+/// The receiver half of a keyed dispatch channel.
+///
+/// Can receive messages with the `postage::Stream` trait.  Receives items for the keys it owns,
+/// and items from the overflow queue.  Clones own a separate share of the keys.
+pub struct KeyedReceiver<T> {
+    shared: ReceiverShared<KeyedExtension<T>>,
+    local: Arc<Local<T>>,
+    budget: Budget,
+}
+
+assert_impl_all!(KeyedReceiver<SendMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(KeyedReceiver<NotSendMessage>: Send, Sync);
+
+impl<T> Stream for KeyedReceiver<T> {
+    type Item = T;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        if this.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
+        loop {
+            let guard = this.shared.send_guard();
+            match this.shared.extension().pop(&this.local) {
+                Some(v) => {
+                    this.shared.notify_senders();
+                    this.budget.consume();
+                    return PollRecv::Ready(v);
+                }
+                None => {
+                    if this.shared.is_closed() {
+                        return PollRecv::Closed;
+                    }
+
+                    this.shared.subscribe_send(cx);
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    this.budget.reset();
+                    return PollRecv::Pending;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // backlogs of dropped receivers move to the overflow queue, so any item in the channel may be received
+        let closed = self.shared.is_closed();
+        let (available, len) = self.shared.extension().size_hint(&self.local);
+
+        (available, if closed { Some(len) } else { None })
+    }
+}
+
+impl<T> Clone for KeyedReceiver<T> {
+    fn clone(&self) -> Self {
+        let shared = self.shared.clone();
+        let local = shared.extension().register();
+
+        Self {
+            shared,
+            local,
+            budget: Budget::new(),
+        }
+    }
+}
+
+impl<T> Drop for KeyedReceiver<T> {
+    fn drop(&mut self) {
+        // the backlog is moved to the overflow queue, where it can be received by the remaining receivers
+        if self.shared.extension().unregister(&self.local) {
+            self.shared.notify_receivers();
+        }
+    }
+}
+
+impl<T> fmt::Debug for KeyedReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedReceiver").finish()
+    }
+}
+
+struct Local<T> {
+    id: u64,
+    queue: Mutex<VecDeque<T>>,
+}
+
+struct KeyedExtension<T> {
+    capacity: usize,
+    len: AtomicUsize,
+    next_id: AtomicU64,
+    // senders hold the read lock while pushing to a backlog, so a receiver can't leave with items in flight
+    receivers: RwLock<Vec<Arc<Local<T>>>>,
+    overflow: Mutex<VecDeque<T>>,
+}
+
+impl<T> KeyedExtension<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            len: AtomicUsize::new(0),
+            next_id: AtomicU64::new(0),
+            receivers: RwLock::new(Vec::new()),
+            overflow: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn register(&self) -> Arc<Local<T>> {
+        let local = Arc::new(Local {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            queue: Mutex::new(VecDeque::new()),
+        });
+
+        self.receivers.write().push(local.clone());
+        local
+    }
+
+    // returns true if the backlog was not empty
+    pub fn unregister(&self, local: &Arc<Local<T>>) -> bool {
+        let mut receivers = self.receivers.write();
+        receivers.retain(|other| !Arc::ptr_eq(other, local));
+
+        let mut queue = local.queue.lock();
+        if queue.is_empty() {
+            return false;
+        }
+
+        self.overflow.lock().extend(queue.drain(..));
+        true
+    }
+
+    // reserves a slot in the buffer, or returns false if the channel is full
+    pub fn reserve(&self) -> bool {
+        self.len
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |len| {
+                if len < self.capacity {
+                    Some(len + 1)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
+
+    pub fn push(&self, key_hash: u64, value: T, max_backlog: usize) {
+        let receivers = self.receivers.read();
+        let preferred = receivers
+            .iter()
+            .max_by_key(|local| rendezvous_weight(key_hash, local.id));
+
+        if let Some(local) = preferred {
+            let mut queue = local.queue.lock();
+            if queue.len() < max_backlog {
+                queue.push_back(value);
+                return;
+            }
+        }
+
+        self.overflow.lock().push_back(value);
+    }
+
+    pub fn pop(&self, local: &Local<T>) -> Option<T> {
+        let value = local
+            .queue
+            .lock()
+            .pop_front()
+            .or_else(|| self.overflow.lock().pop_front());
+
+        if value.is_some() {
+            self.len.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        value
+    }
+
+    // the number of items this receiver can pop, and the number of items in the channel
+    pub fn size_hint(&self, local: &Local<T>) -> (usize, usize) {
+        let available = local.queue.lock().len() + self.overflow.lock().len();
+        (available, self.len.load(Ordering::Acquire))
+    }
+}
+
+// the receiver with the highest weight owns the key.  this is the splitmix64 finalizer
+fn rendezvous_weight(key_hash: u64, id: u64) -> u64 {
+    let mut z = key_hash ^ id.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault, pin::Pin};
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+    };
+
+    use super::{keyed_channel, KeyedReceiver, KeyedSender};
+
+    type Hasher = BuildHasherDefault<DefaultHasher>;
+
+    fn channel(capacity: usize) -> (KeyedSender<usize, usize, Hasher>, KeyedReceiver<usize>) {
+        keyed_channel(capacity, Hasher::default())
+    }
+
+    fn drain(rx: &mut KeyedReceiver<usize>) -> Vec<usize> {
+        let mut cx = noop_context();
+        let mut values = Vec::new();
+        while let PollRecv::Ready(v) = Pin::new(&mut *rx).poll_recv(&mut cx) {
+            values.push(v);
+        }
+
+        values
+    }
+
+    #[test]
+    fn send_recv() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, (1, 1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, (2, 2))
+        );
+        assert_eq!(
+            PollSend::Pending((3, 3)),
+            Pin::new(&mut tx).poll_send(&mut cx, (3, 3))
+        );
+
+        assert_eq!(vec![1, 2], drain(&mut rx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn affinity() {
+        let mut cx = noop_context();
+        let (tx, rx) = channel(256);
+        let mut tx = tx.max_backlog(256);
+        let mut receivers = [rx, tx.subscribe(), tx.subscribe()];
+
+        for round in 0..2 {
+            for key in 0..64 {
+                assert_eq!(
+                    PollSend::Ready,
+                    Pin::new(&mut tx).poll_send(&mut cx, (key, key * 2 + round))
+                );
+            }
+        }
+
+        let received: Vec<Vec<usize>> = receivers.iter_mut().map(drain).collect();
+
+        // every key is received by exactly one receiver, in order
+        for key in 0..64 {
+            let owners: Vec<&Vec<usize>> = received
+                .iter()
+                .filter(|values| values.contains(&(key * 2)))
+                .collect();
+
+            assert_eq!(1, owners.len());
+            let position = |v| owners[0].iter().position(|x| *x == v);
+            assert!(position(key * 2) < position(key * 2 + 1));
+        }
+
+        // and the keys are distributed across the receivers
+        for values in received {
+            assert!(!values.is_empty());
+        }
+    }
+
+    #[test]
+    fn overflow_fallback() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel(16);
+        let mut tx = tx.max_backlog(1);
+        let mut rx2 = tx.subscribe();
+
+        for i in 0..3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, (0, i))
+            );
+        }
+
+        // one item is queued for the preferred receiver, and the rest can be received by the idle receiver
+        assert!(matches!(
+            Pin::new(&mut rx).poll_recv(&mut cx),
+            PollRecv::Ready(_)
+        ));
+        assert!(matches!(
+            Pin::new(&mut rx2).poll_recv(&mut cx),
+            PollRecv::Ready(_)
+        ));
+        assert_eq!(1, drain(&mut rx).len() + drain(&mut rx2).len());
+    }
+
+    #[test]
+    fn no_fallback_without_overload() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel(16);
+        let mut tx = tx.max_backlog(4);
+        let mut rx2 = tx.subscribe();
+
+        for i in 0..3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, (0, i))
+            );
+        }
+
+        let mut both = [drain(&mut rx), drain(&mut rx2)];
+        both.sort();
+        assert_eq!([vec![], vec![0, 1, 2]], both);
+    }
+
+    #[test]
+    fn receiver_leave_rebalances() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel(256);
+        let mut tx = tx.max_backlog(256);
+        let rx2 = tx.subscribe();
+
+        for key in 0..64 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, (key, key))
+            );
+        }
+
+        // the backlog of the dropped receiver is recovered, and new items are routed to the remaining receiver
+        drop(rx2);
+        assert_eq!(64, drain(&mut rx).len());
+
+        for key in 0..64 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, (key, key))
+            );
+        }
+        assert_eq!(64, drain(&mut rx).len());
+    }
+
+    #[test]
+    fn receiver_join_rebalances() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel(256);
+        let mut tx = tx.max_backlog(256);
+
+        for key in 0..64 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, (key, key))
+            );
+        }
+        assert_eq!(64, drain(&mut rx).len());
+
+        let mut rx2 = rx.clone();
+        for key in 0..64 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, (key, key))
+            );
+        }
+
+        let first = drain(&mut rx);
+        let second = drain(&mut rx2);
+        assert_eq!(64, first.len() + second.len());
+        assert!(!first.is_empty());
+        assert!(!second.is_empty());
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, (1, 1))
+        );
+        drop(tx);
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn receiver_disconnect() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = channel(4);
+
+        drop(rx);
+        assert_eq!(
+            PollSend::Rejected((1, 1)),
+            Pin::new(&mut tx).poll_send(&mut cx, (1, 1))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use std::collections::{hash_map::RandomState, HashMap};
+
+    use tokio::{spawn, time::timeout};
+
+    use crate::{stream::Stream, test::TEST_TIMEOUT};
+
+    use super::keyed_channel;
+
+    #[tokio::test]
+    async fn send_keyed() {
+        const KEYS: usize = 16;
+        const MESSAGES: usize = 100;
+
+        let (tx, rx) = keyed_channel(8, RandomState::new());
+        let mut tx = tx.max_backlog(8);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mut rx = rx.clone();
+                spawn(async move {
+                    let mut received = Vec::new();
+                    while let Some(message) = rx.recv().await {
+                        received.push(message);
+                    }
+                    received
+                })
+            })
+            .collect();
+        drop(rx);
+
+        spawn(async move {
+            for i in 0..MESSAGES {
+                tx.send_keyed(i % KEYS, i).await.expect("send failed");
+            }
+        });
+
+        timeout(TEST_TIMEOUT, async move {
+            let mut counts = HashMap::new();
+            for handle in handles {
+                for message in handle.await.expect("join failed") {
+                    *counts.entry(message).or_insert(0) += 1;
+                }
+            }
+
+            // every message is received exactly once
+            assert_eq!(MESSAGES, counts.len());
+            assert!(counts.values().all(|count| *count == 1));
+        })
+        .await
+        .expect("test timeout");
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crossbeam_queue::ArrayQueue;
+
+use super::meta::MessageMeta;
+
+// The source of the current time, which can be replaced in tests
+pub(crate) type Clock = Arc<dyn Fn() -> Instant + Send + Sync>;
+
+// The lifetime of queued messages, and the clock used to measure it.
+#[derive(Clone)]
+pub(crate) struct Ttl {
+    duration: Duration,
+    clock: Clock,
+}
+
+impl Ttl {
+    pub fn new(duration: Duration, clock: Option<Clock>) -> Self {
+        Self {
+            duration,
+            clock: clock.unwrap_or_else(|| Arc::new(Instant::now)),
+        }
+    }
+}
+
+impl fmt::Debug for Ttl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.duration.fmt(f)
+    }
+}
+
+// the largest slot of an expiring queue, which is stamped if the channel has a TTL or records metadata
+pub(crate) type ExpiringSlot<T> = super::capacity::QueueSlot<Stamped<T>>;
+
+// A message with the time it was sent, and the deadline it was sent with
+pub(crate) struct Stamped<T> {
+    value: T,
+    sent: Instant,
+    deadline: Option<Instant>,
+}
+
+// Channels without a TTL or metadata store plain messages, so they don't pay for the timestamps
+enum Slots<T> {
+    Plain(ArrayQueue<T>),
+    Stamped(ArrayQueue<Stamped<T>>),
+}
+
+// A fixed-capacity queue which stamps messages with the time they were sent, if a TTL is configured or
+// metadata is recorded.  Expired messages are dropped (and counted) when they reach the front of the queue,
+// so no timer is needed.
+pub(crate) struct ExpiringQueue<T> {
+    slots: Slots<T>,
+    ttl: Option<Ttl>,
+    // if set, messages keep their send time and deadline, which are returned by `pop_with_meta`
+    meta: Option<Clock>,
+    expired: AtomicUsize,
+}
+
+impl<T> ExpiringQueue<T> {
+    pub fn new(capacity: usize, ttl: Option<Ttl>) -> Self {
+        let slots = match ttl {
+            Some(_) => Slots::Stamped(ArrayQueue::new(capacity)),
+            None => Slots::Plain(ArrayQueue::new(capacity)),
+        };
+
+        Self {
+            slots,
+            ttl,
+            meta: None,
+            expired: AtomicUsize::new(0),
+        }
+    }
+
+    // records the metadata of each message, measuring the send time with the clock.  must be called before any
+    // message is pushed
+    pub fn record_meta(&mut self, clock: Option<Clock>) {
+        if let Slots::Plain(queue) = &self.slots {
+            debug_assert!(queue.is_empty());
+            self.slots = Slots::Stamped(ArrayQueue::new(queue.capacity()));
+        }
+
+        self.meta = Some(clock.unwrap_or_else(|| Arc::new(Instant::now)));
+    }
+
+    #[cfg(any(test, feature = "futures-traits"))]
+    pub fn push(&self, value: T) -> Result<(), T> {
+        self.push_with_deadline(value, None)
+    }
+
+    // the deadline is discarded unless metadata is recorded
+    pub fn push_with_deadline(&self, value: T, deadline: Option<Instant>) -> Result<(), T> {
+        let queue = match &self.slots {
+            Slots::Plain(queue) => return queue.push(value),
+            Slots::Stamped(queue) => queue,
+        };
+
+        let sent = self.now();
+        let deadline = self.meta.as_ref().and(deadline);
+
+        queue
+            .push(Stamped {
+                value,
+                sent,
+                deadline,
+            })
+            .map_err(|stamped| stamped.value)
+    }
+
+    // pops the oldest message which has not expired.  `freed` is incremented for each slot which was freed, including
+    // the slots of expired messages, so the caller can notify blocked senders
+    pub fn pop(&self, freed: &mut usize) -> Option<T> {
+        self.pop_with_meta(freed).map(|(value, _meta)| value)
+    }
+
+    // like `pop`, but also returns the metadata, which is empty unless metadata is recorded
+    pub fn pop_with_meta(&self, freed: &mut usize) -> Option<(T, MessageMeta)> {
+        let queue = match &self.slots {
+            Slots::Plain(queue) => {
+                let value = queue.pop()?;
+                *freed += 1;
+                return Some((value, MessageMeta::default()));
+            }
+            Slots::Stamped(queue) => queue,
+        };
+
+        while let Some(stamped) = queue.pop() {
+            *freed += 1;
+
+            if self.is_expired(&stamped) {
+                self.expired.fetch_add(1, Ordering::AcqRel);
+                continue;
+            }
+
+            let meta = match self.meta {
+                Some(_) => MessageMeta {
+                    enqueued_at: Some(stamped.sent),
+                    deadline: stamped.deadline,
+                },
+                None => MessageMeta::default(),
+            };
+
+            return Some((stamped.value, meta));
+        }
+
+        None
+    }
+
+    // only called for stamped queues, which have a TTL or a metadata clock
+    fn now(&self) -> Instant {
+        match (&self.ttl, &self.meta) {
+            (Some(ttl), _) => (ttl.clock)(),
+            (None, Some(clock)) => clock(),
+            (None, None) => Instant::now(),
+        }
+    }
+
+    // with a TTL, messages also expire once their deadline has passed
+    fn is_expired(&self, stamped: &Stamped<T>) -> bool {
+        match &self.ttl {
+            Some(ttl) => {
+                let now = (ttl.clock)();
+                now.saturating_duration_since(stamped.sent) > ttl.duration
+                    || stamped.deadline.is_some_and(|deadline| deadline <= now)
+            }
+            None => false,
+        }
+    }
+
+    // the number of messages which expired before they could be received
+    pub fn expired(&self) -> usize {
+        self.expired.load(Ordering::Acquire)
+    }
+
+    // includes messages which may expire before they are received
+    pub fn len(&self) -> usize {
+        match &self.slots {
+            Slots::Plain(queue) => queue.len(),
+            Slots::Stamped(queue) => queue.len(),
+        }
+    }
+
+    #[cfg(feature = "futures-traits")]
+    pub fn is_full(&self) -> bool {
+        match &self.slots {
+            Slots::Plain(queue) => queue.is_full(),
+            Slots::Stamped(queue) => queue.is_full(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.slots {
+            Slots::Plain(queue) => queue.capacity(),
+            Slots::Stamped(queue) => queue.capacity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::test::time::MockClock;
+
+    use super::{Clock, ExpiringQueue, Slots, Ttl};
+
+    fn queue(capacity: usize, clock: &MockClock) -> ExpiringQueue<usize> {
+        let ttl = Ttl::new(Duration::from_secs(1), Some(Arc::new(clock.now_fn())));
+        ExpiringQueue::new(capacity, Some(ttl))
+    }
+
+    #[test]
+    fn expired_messages_are_skipped() {
+        let clock = MockClock::new();
+        let queue = queue(4, &clock);
+
+        queue.push(1).unwrap();
+        clock.advance(Duration::from_millis(800));
+        queue.push(2).unwrap();
+        clock.advance(Duration::from_millis(400));
+
+        let mut freed = 0;
+        assert_eq!(Some(2), queue.pop(&mut freed));
+        assert_eq!(2, freed);
+        assert_eq!(1, queue.expired());
+    }
+
+    #[test]
+    fn expired_message_frees_slot() {
+        let clock = MockClock::new();
+        let queue = queue(1, &clock);
+
+        queue.push(1).unwrap();
+        assert_eq!(Err(2), queue.push(2));
+        clock.advance(Duration::from_secs(2));
+
+        let mut freed = 0;
+        assert_eq!(None, queue.pop(&mut freed));
+        assert_eq!(1, freed);
+        assert_eq!(1, queue.expired());
+        assert_eq!(Ok(()), queue.push(2));
+    }
+
+    #[test]
+    fn meta_round_trip() {
+        let clock = MockClock::new();
+        let now: Clock = Arc::new(clock.now_fn());
+        let mut queue = ExpiringQueue::new(2, None);
+        queue.record_meta(Some(now.clone()));
+
+        let deadline = now() + Duration::from_secs(1);
+        queue.push_with_deadline(1, Some(deadline)).unwrap();
+        let sent = now();
+        clock.advance(Duration::from_secs(2));
+
+        // without a TTL, a missed deadline is reported rather than skipped
+        let mut freed = 0;
+        let (value, meta) = queue.pop_with_meta(&mut freed).unwrap();
+        assert_eq!(1, value);
+        assert_eq!(Some(sent), meta.enqueued_at);
+        assert_eq!(Some(deadline), meta.deadline);
+        assert_eq!(0, queue.expired());
+    }
+
+    #[test]
+    fn deadline_without_meta() {
+        let clock = MockClock::new();
+        let queue = queue(2, &clock);
+
+        let now = clock.now_fn();
+        queue.push_with_deadline(1, Some(now())).unwrap();
+
+        let mut freed = 0;
+        let (value, meta) = queue.pop_with_meta(&mut freed).unwrap();
+        assert_eq!(1, value);
+        assert_eq!(None, meta.enqueued_at);
+        assert_eq!(None, meta.deadline);
+    }
+
+    #[test]
+    fn ttl_skips_missed_deadline() {
+        let clock = MockClock::new();
+        let now: Clock = Arc::new(clock.now_fn());
+        let ttl = Ttl::new(Duration::from_secs(10), Some(now.clone()));
+        let mut queue = ExpiringQueue::new(4, Some(ttl));
+        queue.record_meta(Some(now.clone()));
+
+        queue
+            .push_with_deadline(1, Some(now() + Duration::from_millis(500)))
+            .unwrap();
+        queue
+            .push_with_deadline(2, Some(now() + Duration::from_secs(5)))
+            .unwrap();
+        queue.push(3).unwrap();
+        clock.advance(Duration::from_secs(1));
+
+        let mut freed = 0;
+        assert_eq!(Some(2), queue.pop(&mut freed));
+        assert_eq!(1, queue.expired());
+
+        // the TTL still applies to messages with a later deadline
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(None, queue.pop(&mut freed));
+        assert_eq!(2, queue.expired());
+    }
+
+    #[test]
+    fn stamped_only_when_needed() {
+        let clock = MockClock::new();
+        let plain = ExpiringQueue::<usize>::new(2, None);
+        assert!(matches!(plain.slots, Slots::Plain(_)));
+
+        let mut meta = ExpiringQueue::<usize>::new(2, None);
+        meta.record_meta(Some(Arc::new(clock.now_fn())));
+        assert!(matches!(meta.slots, Slots::Stamped(_)));
+        assert_eq!(2, meta.capacity());
+
+        assert!(matches!(queue(2, &clock).slots, Slots::Stamped(_)));
+    }
+
+    #[test]
+    fn without_ttl() {
+        let queue = ExpiringQueue::new(2, None);
+        queue.push(1).unwrap();
+
+        let mut freed = 0;
+        assert_eq!(Some(1), queue.pop(&mut freed));
+        assert_eq!(0, queue.expired());
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use super::expiry::Clock;
+use crate::{time::Sleep, Context};
+
+// The idle limit of a channel, and the time of the last send or receive.
+// Expiry is checked lazily by each operation, so no timer or runtime is needed.
+pub(crate) struct IdleTimeout {
+    duration: Duration,
+    clock: Clock,
+    last_activity: Mutex<Instant>,
+}
+
+impl IdleTimeout {
+    pub fn new(duration: Duration, clock: Option<Clock>) -> Self {
+        let clock = clock.unwrap_or_else(runtime_clock);
+        let now = clock();
+
+        Self {
+            duration,
+            clock,
+            last_activity: Mutex::new(now),
+        }
+    }
+
+    // records a send or receive, which restarts the idle period
+    pub fn touch(&self) {
+        let now = (self.clock)();
+        *self.last_activity.lock() = now;
+    }
+
+    pub fn deadline(&self) -> Instant {
+        *self.last_activity.lock() + self.duration
+    }
+
+    // the time until the channel is idle, or zero if it is already idle
+    pub fn remaining(&self) -> Duration {
+        self.deadline().saturating_duration_since((self.clock)())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining() == Duration::ZERO
+    }
+}
+
+// the idle period is measured by the runtime clock, so that it agrees with the timer of the sweep
+#[cfg(feature = "tokio")]
+fn runtime_clock() -> Clock {
+    Arc::new(|| tokio::time::Instant::now().into_std())
+}
+
+#[cfg(not(feature = "tokio"))]
+fn runtime_clock() -> Clock {
+    Arc::new(Instant::now)
+}
+
+impl fmt::Debug for IdleTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.duration.fmt(f)
+    }
+}
+
+type BoxSleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+// A timer which wakes a parked receiver when the channel becomes idle, so that it observes the closure
+// even if no other operation occurs.
+pub(crate) struct IdleSweep {
+    sleep_fn: Box<dyn Fn(Duration) -> BoxSleep + Send + Sync>,
+    // the pending sleep, and the deadline it was created for
+    sleep: Option<(Instant, BoxSleep)>,
+}
+
+impl IdleSweep {
+    pub fn new<T>(timer: T) -> Self
+    where
+        T: Sleep + Send + Sync + 'static,
+        T::Future: Send + 'static,
+    {
+        Self {
+            sleep_fn: Box::new(move |duration| Box::pin(timer.sleep(duration))),
+            sleep: None,
+        }
+    }
+
+    // the sweep of the enabled async runtime, if any
+    #[cfg(feature = "tokio")]
+    pub fn runtime() -> Option<Self> {
+        Some(Self {
+            // outside of a runtime, such as a blocking receive, the idle period is only checked lazily
+            sleep_fn: Box::new(|duration| match tokio::runtime::Handle::try_current() {
+                Ok(_) => Box::pin(tokio::time::sleep(duration)),
+                Err(_) => Box::pin(std::future::pending()),
+            }),
+            sleep: None,
+        })
+    }
+
+    #[cfg(all(feature = "async-std", not(feature = "tokio")))]
+    pub fn runtime() -> Option<Self> {
+        Some(Self::new(crate::time::AsyncStd))
+    }
+
+    #[cfg(not(any(feature = "tokio", feature = "async-std")))]
+    pub fn runtime() -> Option<Self> {
+        None
+    }
+
+    // resolves once the channel is idle, registering the waker in `cx` with the timer until then.
+    // activity moves the deadline forward, and the sleep is replaced when it is next polled
+    pub fn poll_idle(&mut self, idle: &IdleTimeout, cx: &Context<'_>) -> Poll<()> {
+        loop {
+            let remaining = idle.remaining();
+            if remaining == Duration::ZERO {
+                self.sleep = None;
+                return Poll::Ready(());
+            }
+
+            let deadline = idle.deadline();
+            let stale =
+                !matches!(&self.sleep, Some((sleep_deadline, _)) if *sleep_deadline == deadline);
+            if stale {
+                self.sleep = Some((deadline, (self.sleep_fn)(remaining)));
+            }
+
+            let (_, sleep) = self.sleep.as_mut().unwrap();
+            if cx.with_std(|cx| sleep.as_mut().poll(cx)).is_pending() {
+                return Poll::Pending;
+            }
+
+            // the sleep elapsed, so the deadline is checked again
+            self.sleep = None;
+        }
+    }
+}
+
+impl fmt::Debug for IdleSweep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdleSweep").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::test::time::MockClock;
+
+    use super::IdleTimeout;
+
+    #[test]
+    fn touch_restarts_idle_period() {
+        let clock = MockClock::new();
+        let idle = IdleTimeout::new(Duration::from_secs(1), Some(Arc::new(clock.now_fn())));
+
+        clock.advance(Duration::from_millis(800));
+        assert!(!idle.is_expired());
+        idle.touch();
+
+        clock.advance(Duration::from_millis(800));
+        assert!(!idle.is_expired());
+        assert_eq!(Duration::from_millis(200), idle.remaining());
+
+        clock.advance(Duration::from_millis(200));
+        assert!(idle.is_expired());
+    }
+}
@@ -0,0 +1,207 @@
+use std::{fmt, pin::Pin};
+
+use super::{NotSendMessage, SendSyncMessage};
+use crate::stream::{PollRecv, Stream};
+use static_assertions::assert_impl_all;
+
+type MappedSource<U> = Box<dyn Source<U> + Send + Sync>;
+
+// A type-erased receiver, which maps its messages as they are received
+trait Source<U> {
+    fn poll_recv_mapped(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<U>;
+
+    fn size_hint(&self) -> (usize, Option<usize>);
+
+    fn clone_source(&self) -> MappedSource<U>;
+}
+
+struct Mapped<R, U>
+where
+    R: Stream,
+{
+    receiver: R,
+    map: fn(&R::Item) -> U,
+}
+
+impl<R, U> Source<U> for Mapped<R, U>
+where
+    R: Stream + Clone + Unpin + Send + Sync + 'static,
+    U: 'static,
+{
+    fn poll_recv_mapped(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<U> {
+        match Pin::new(&mut self.receiver).poll_recv(cx) {
+            PollRecv::Ready(value) => PollRecv::Ready((self.map)(&value)),
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.receiver.size_hint()
+    }
+
+    fn clone_source(&self) -> MappedSource<U> {
+        Box::new(Self {
+            receiver: self.receiver.clone(),
+            map: self.map,
+        })
+    }
+}
+
+/// A receiver which maps the messages of a broadcast or watch channel, returned by `map_subscribe`.
+///
+/// The type of the channel is erased, so mapped receivers from different channels (and message types) can be
+/// stored together, such as in a `Vec<MappedReceiver<Event>>`.  Messages are mapped by each receiver as they
+/// are received, and the channel is unaffected.
+///
+/// When cloned, the new receiver will begin processing messages at the same location as the original.
+pub struct MappedReceiver<U> {
+    source: MappedSource<U>,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
+}
+
+assert_impl_all!(MappedReceiver<NotSendMessage>: Send, Sync, Clone, fmt::Debug);
+assert_impl_all!(MappedReceiver<SendSyncMessage>: Send, Sync, Clone, fmt::Debug);
+
+impl<U> MappedReceiver<U> {
+    pub(in crate::channels) fn new<R>(receiver: R, map: fn(&R::Item) -> U) -> Self
+    where
+        R: Stream + Clone + Unpin + Send + Sync + 'static,
+        U: 'static,
+    {
+        Self::from_source(Box::new(Mapped { receiver, map }))
+    }
+
+    fn from_source(source: MappedSource<U>) -> Self {
+        Self {
+            source,
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
+        }
+    }
+}
+
+impl<U> Stream for MappedReceiver<U> {
+    type Item = U;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut crate::Context<'_>) -> PollRecv<Self::Item> {
+        self.get_mut().source.poll_recv_mapped(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.source.size_hint()
+    }
+}
+
+impl<U> Clone for MappedReceiver<U> {
+    fn clone(&self) -> Self {
+        Self::from_source(self.source.clone_source())
+    }
+}
+
+impl<U> fmt::Debug for MappedReceiver<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedReceiver").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{
+        broadcast,
+        sink::Sink,
+        stream::{PollRecv, Stream, TryRecvError},
+        test::noop_context,
+        watch,
+    };
+
+    use super::MappedReceiver;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    enum Event {
+        Count(usize),
+        Message(String),
+        Online(bool),
+    }
+
+    #[test]
+    fn mixed_receivers() {
+        let mut cx = noop_context();
+        let (mut counts, counts_rx) = broadcast::channel::<usize>(4);
+        let (mut messages, messages_rx) = broadcast::channel::<String>(4);
+        let (mut online, online_rx) = watch::channel_with(false);
+
+        let mut receivers: Vec<MappedReceiver<Event>> = vec![
+            counts_rx.map_subscribe(|count| Event::Count(*count)),
+            messages_rx.map_subscribe(|message| Event::Message(message.clone())),
+            online_rx.map_subscribe(|online| Event::Online(*online)),
+        ];
+
+        counts.try_send(1).unwrap();
+        messages.try_send("hello".to_string()).unwrap();
+        *online.borrow_mut() = true;
+
+        let events: Vec<_> = receivers
+            .iter_mut()
+            .map(|rx| Pin::new(rx).poll_recv(&mut cx))
+            .collect();
+
+        assert_eq!(
+            vec![
+                PollRecv::Ready(Event::Count(1)),
+                PollRecv::Ready(Event::Message("hello".to_string())),
+                PollRecv::Ready(Event::Online(true)),
+            ],
+            events
+        );
+
+        drop((counts, messages, online));
+        for rx in receivers.iter_mut() {
+            assert_eq!(PollRecv::Closed, Pin::new(rx).poll_recv(&mut cx));
+        }
+    }
+
+    #[test]
+    fn channel_is_unaffected() {
+        let (mut tx, mut rx) = broadcast::channel::<usize>(4);
+        let mut mapped = rx.map_subscribe(|i| i.to_string());
+
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+
+        assert_eq!(Ok("1".to_string()), mapped.try_recv());
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok("2".to_string()), mapped.try_recv());
+        assert_eq!(Err(TryRecvError::Pending), mapped.try_recv());
+    }
+
+    #[test]
+    fn clone_begins_at_same_location() {
+        let (mut tx, rx) = broadcast::channel::<usize>(4);
+        let mut mapped = rx.map_subscribe(|i| i * 10);
+        drop(rx);
+
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        assert_eq!(Ok(10), mapped.try_recv());
+
+        let mut clone = mapped.clone();
+        assert_eq!(Ok(20), clone.try_recv());
+        assert_eq!(Ok(20), mapped.try_recv());
+    }
+
+    #[tokio::test]
+    async fn watch_receives_current_value() {
+        let (mut tx, rx) = watch::channel_with(1usize);
+        let mut mapped = rx.map_subscribe(|i| i + 1);
+
+        assert_eq!(Some(2), mapped.recv().await);
+
+        tx.send(5).await.expect("the receivers are alive");
+        assert_eq!(Some(6), mapped.recv().await);
+    }
+}
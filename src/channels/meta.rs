@@ -0,0 +1,160 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    sink::{PollSend, SendError},
+    stream::PollRecv,
+    Context,
+};
+
+/// The metadata of a received message.
+///
+/// Channels only record metadata if they are built with `message_meta`.  Otherwise, the fields are None.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct MessageMeta {
+    /// The time the message was sent.  Prefilled messages are stamped when the channel is built.
+    pub enqueued_at: Option<Instant>,
+    /// The deadline the message was sent with, if any.
+    pub deadline: Option<Instant>,
+}
+
+impl MessageMeta {
+    /// Returns the time left before the deadline, or zero if the deadline has passed.
+    ///
+    /// Returns None if the message has no deadline.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Returns true if the message has a deadline, and it has passed.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.deadline.is_some_and(|deadline| deadline <= now)
+    }
+}
+
+// A sender which can attach a deadline to a message
+pub(crate) trait PollSendDeadline {
+    type Item;
+
+    fn poll_send_deadline(
+        &mut self,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+        deadline: Instant,
+    ) -> PollSend<Self::Item>;
+}
+
+// A receiver which can return the metadata of a message
+pub(crate) trait PollRecvMeta {
+    type Item;
+
+    fn poll_recv_meta(&mut self, cx: &mut Context<'_>) -> PollRecv<(Self::Item, MessageMeta)>;
+}
+
+/// A future returned by `send_with_deadline`, which sends a message with a deadline.
+#[must_use = "futures do nothing unless polled"]
+pub struct SendDeadlineFuture<'s, S, T> {
+    sender: &'s mut S,
+    value: Option<(T, Instant)>,
+}
+
+impl<'s, S, T> SendDeadlineFuture<'s, S, T> {
+    pub(crate) fn new(sender: &'s mut S, value: T, deadline: Instant) -> Self {
+        Self {
+            sender,
+            value: Some((value, deadline)),
+        }
+    }
+}
+
+// the message is never pinned
+impl<'s, S, T> Unpin for SendDeadlineFuture<'s, S, T> {}
+
+impl<'s, S, T> Future for SendDeadlineFuture<'s, S, T>
+where
+    S: PollSendDeadline<Item = T>,
+{
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let (value, deadline) = match this.value.take() {
+            Some(value) => value,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let mut cx: Context<'_> = cx.into();
+        match this.sender.poll_send_deadline(&mut cx, value, deadline) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(value) => {
+                this.value = Some((value, deadline));
+                Poll::Pending
+            }
+            PollSend::Rejected(value) => Poll::Ready(Err(SendError(value))),
+        }
+    }
+}
+
+/// A future returned by `recv_with_meta`, which receives a message and its metadata.
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvMetaFuture<'s, S, T> {
+    receiver: &'s mut S,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<'s, S, T> RecvMetaFuture<'s, S, T> {
+    pub(crate) fn new(receiver: &'s mut S) -> Self {
+        Self {
+            receiver,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<'s, S, T> Future for RecvMetaFuture<'s, S, T>
+where
+    S: PollRecvMeta<Item = T>,
+{
+    type Output = Option<(T, MessageMeta)>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut cx: Context<'_> = cx.into();
+        match self.receiver.poll_recv_meta(&mut cx) {
+            PollRecv::Ready(value) => Poll::Ready(Some(value)),
+            PollRecv::Pending => Poll::Pending,
+            PollRecv::Closed => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::MessageMeta;
+
+    #[test]
+    fn remaining() {
+        let now = Instant::now();
+        let meta = MessageMeta {
+            enqueued_at: Some(now),
+            deadline: Some(now + Duration::from_secs(1)),
+        };
+
+        assert_eq!(Some(Duration::from_secs(1)), meta.remaining(now));
+        assert!(!meta.is_expired(now));
+
+        let later = now + Duration::from_secs(2);
+        assert_eq!(Some(Duration::ZERO), meta.remaining(later));
+        assert!(meta.is_expired(later));
+
+        assert_eq!(None, MessageMeta::default().remaining(now));
+        assert!(!MessageMeta::default().is_expired(later));
+    }
+}
@@ -1,25 +1,89 @@
 //! A fixed-capacity multi-producer, single-consumer channel.  
 //!
 //! The producer can be cloned, and the sender task is suspended if the channel becomes full.
+//!
+//! A channel which starts with messages in the buffer, limits the number of live senders, or drops messages
+//! which have waited too long, can be constructed with `builder`.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
 
-use std::fmt;
-
-use super::SendMessage;
+use super::{
+    capacity::{Capacity, InvalidCapacity, QueueSlot},
+    expiry::{ExpiringQueue, Ttl},
+    idle::{IdleSweep, IdleTimeout},
+    meta::{PollRecvMeta, PollSendDeadline},
+    NotSendMessage, SendMessage,
+};
 use crate::{
-    sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
+    coop::Budget,
+    sink::{PollSend, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
     sync::{shared, ReceiverShared, SenderShared},
 };
-use crossbeam_queue::ArrayQueue;
-use static_assertions::{assert_impl_all, assert_not_impl_all};
+use parking_lot::Mutex;
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+mod builder;
+mod static_channel;
+
+pub use super::meta::{MessageMeta, RecvMetaFuture, SendDeadlineFuture};
+pub use builder::{builder, try_builder, Builder, LayeredBuilder};
+pub use static_channel::{static_channel, StaticReceiver, StaticSender};
 
+/// Constructs a pair of mpsc endpoints, with a fixed-size buffer of the given capacity.
+///
+/// If the capacity is known at compile time, `static_channel` stores the buffer inline.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+/// `try_channel` returns the error instead.
+#[track_caller]
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = Capacity::expect::<QueueSlot<T>>(capacity);
+    new_channel(StateExtension::new(capacity.get(), None))
+}
+
+/// Constructs a pair of mpsc endpoints, with a fixed-size buffer of the given capacity.
+///
+/// Returns an error if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+pub fn try_channel<T>(capacity: usize) -> Result<(Sender<T>, Receiver<T>), InvalidCapacity> {
+    let capacity = Capacity::for_slots::<QueueSlot<T>>(capacity)?;
+    Ok(new_channel(StateExtension::new(capacity.get(), None)))
+}
+
+fn new_channel<T>(extension: StateExtension<T>) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
-    log::error!("Creating mpsc channel with capacity {}", capacity);
-    let (tx_shared, rx_shared) = shared(StateExtension::new(capacity));
-    let sender = Sender { shared: tx_shared };
+    log::error!(
+        "Creating mpsc channel with capacity {}",
+        extension.queue.capacity()
+    );
+    let (tx_shared, rx_shared) = shared(extension);
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        "mpsc::channel",
+        std::any::type_name::<T>(),
+        Some(tx_shared.extension().queue.capacity()),
+        |extension| extension.len(),
+    );
+
+    let sender = Sender {
+        shared: tx_shared,
+        spins: 0,
+    };
 
-    let receiver = Receiver { shared: rx_shared };
+    let receiver = Receiver {
+        shared: rx_shared,
+        retained: Mutex::new(VecDeque::new()),
+        idle_sweep: Mutex::new(None),
+        spins: 0,
+        budget: Budget::new(),
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
 
     (sender, receiver)
 }
@@ -29,35 +93,105 @@ pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
 /// Can be cloned.
 pub struct Sender<T> {
     pub(in crate::channels::mpsc) shared: SenderShared<StateExtension<T>>,
+    spins: usize,
 }
 
-assert_impl_all!(Sender<String>: Clone, Send, Sync, fmt::Debug);
+assert_impl_all!(Sender<SendMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(Sender<NotSendMessage>: Send, Sync);
 
-impl<T> Clone for Sender<T> {
-    fn clone(&self) -> Self {
-        Self {
-            shared: self.shared.clone(),
-        }
+impl<T> Sender<T> {
+    /// Returns the number of messages in the channel
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
     }
-}
 
-impl<T> Sink for Sender<T> {
-    type Item = T;
+    /// Returns true if the channel contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-    fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().queue.capacity()
+    }
+
+    /// Returns the number of live senders, including this one.
+    pub fn sender_count(&self) -> usize {
+        self.shared.sender_count()
+    }
+
+    /// Clones the sender, or returns None if the channel has reached its sender limit.
+    ///
+    /// The limit is configured with [Builder::max_senders](./struct.Builder.html#method.max_senders).
+    /// Dropping a sender frees its slot.
+    pub fn try_clone(&self) -> Option<Self> {
+        let max = self.shared.extension().max_senders.unwrap_or(usize::MAX);
+
+        self.shared.try_clone(max).map(|shared| Self {
+            shared,
+            spins: self.spins,
+        })
+    }
+
+    /// Configures the number of times a send to a full channel is retried, before the task is suspended.
+    ///
+    /// This can reduce latency when the receiver frees capacity quickly, at the cost of busy-waiting.
+    /// The default is zero.  Clones of the sender inherit the setting.
+    pub fn spin_sends(mut self, spins: usize) -> Self {
+        self.spins = spins;
+        self
+    }
+
+    /// Sends a message with a deadline, which the receiver observes with
+    /// [Receiver::recv_with_meta](./struct.Receiver.html#method.recv_with_meta).
+    ///
+    /// The deadline is only stored if the channel was built with
+    /// [Builder::message_meta](./struct.Builder.html#method.message_meta).  If the channel also has a
+    /// [message_ttl](./struct.Builder.html#method.message_ttl), the message expires once the deadline has passed.
+    pub fn send_with_deadline(
+        &mut self,
+        value: T,
+        deadline: Instant,
+    ) -> SendDeadlineFuture<'_, Self, T> {
+        SendDeadlineFuture::new(self, value, deadline)
+    }
+
+    /// Attempts to send a message with a deadline, without blocking.  See [send_with_deadline](#method.send_with_deadline).
+    pub fn try_send_with_deadline(
+        &mut self,
+        value: T,
+        deadline: Instant,
+    ) -> Result<(), TrySendError<T>> {
+        match self.poll_push(&mut crate::Context::empty(), value, Some(deadline)) {
+            PollSend::Ready => Ok(()),
+            PollSend::Pending(value) => Err(TrySendError::Pending(value)),
+            PollSend::Rejected(value) => Err(TrySendError::Rejected(value)),
+        }
+    }
+
+    fn poll_push(
+        &mut self,
         cx: &mut crate::Context<'_>,
-        mut value: Self::Item,
-    ) -> PollSend<Self::Item> {
+        mut value: T,
+        deadline: Option<Instant>,
+    ) -> PollSend<T> {
+        if self.shared.extension().is_idle() {
+            self.shared.close();
+        }
+
         loop {
             if self.shared.is_closed() {
                 return PollSend::Rejected(value);
             }
 
             let guard = self.shared.recv_guard();
-            let queue = &self.shared.extension().queue;
-            match queue.push(value) {
+            match self
+                .shared
+                .extension()
+                .push_spin(value, deadline, self.spins)
+            {
                 Ok(_) => {
+                    self.shared.extension().touch();
                     self.shared.notify_receivers();
                     return PollSend::Ready;
                 }
@@ -76,6 +210,62 @@ impl<T> Sink for Sender<T> {
     }
 }
 
+impl<T> PollSendDeadline for Sender<T> {
+    type Item = T;
+
+    fn poll_send_deadline(
+        &mut self,
+        cx: &mut crate::Context<'_>,
+        value: T,
+        deadline: Instant,
+    ) -> PollSend<T> {
+        self.poll_push(cx, value, Some(deadline))
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    /// Clones the sender.
+    ///
+    /// Panics if the channel has reached its sender limit, unless the builder was configured with
+    /// `saturate_senders`.  In that case, the clone exceeds the limit.
+    #[track_caller]
+    fn clone(&self) -> Self {
+        if let Some(sender) = self.try_clone() {
+            return sender;
+        }
+
+        let extension = self.shared.extension();
+        if !extension.saturate_senders {
+            panic!(
+                "mpsc channel reached its limit of {} senders",
+                extension.max_senders.unwrap_or(usize::MAX)
+            );
+        }
+
+        Self {
+            shared: self.shared.clone(),
+            spins: self.spins,
+        }
+    }
+}
+
+impl<T> Sink for Sender<T> {
+    type Item = T;
+
+    fn poll_send(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        self.get_mut().poll_push(cx, value, None)
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
+    }
+}
+
 impl<T> fmt::Debug for Sender<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Sender").finish()
@@ -94,17 +284,21 @@ mod impl_futures {
             self: std::pin::Pin<&mut Self>,
             cx: &mut std::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
+            if self.shared.extension().is_idle() {
+                self.shared.close();
+            }
+
             loop {
                 if self.shared.is_closed() {
                     return Poll::Ready(Ok(()));
                 }
 
-                let queue = &self.shared.extension().queue;
+                let extension = self.shared.extension();
                 let guard = self.shared.recv_guard();
 
-                if queue.is_full() {
-                    let mut cx = cx.into();
-                    self.shared.subscribe_recv(&mut cx);
+                if extension.len() >= extension.queue.capacity() {
+                    let cx = cx.into();
+                    self.shared.subscribe_recv(&cx);
 
                     if guard.is_expired() {
                         continue;
@@ -118,6 +312,10 @@ mod impl_futures {
         }
 
         fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+            if self.shared.extension().is_idle() {
+                self.shared.close();
+            }
+
             if self.shared.is_closed() {
                 return Err(SendError(item));
             }
@@ -125,11 +323,11 @@ mod impl_futures {
             let result = self
                 .shared
                 .extension()
-                .queue
-                .push(item)
+                .push(item, None)
                 .map_err(|item| SendError(item));
 
             if result.is_ok() {
+                self.shared.extension().touch();
                 self.shared.notify_receivers();
             }
 
@@ -157,26 +355,220 @@ mod impl_futures {
 /// Can receive messages with the postage::Stream trait.
 pub struct Receiver<T> {
     pub(in crate::channels::mpsc) shared: ReceiverShared<StateExtension<T>>,
+    // messages kept by `retain`, which are received before the queue, and still occupy their slots.  only accessed
+    // with `get_mut`, so the receive path never locks.  the mutex keeps the receiver Sync if messages are only Send
+    retained: Mutex<VecDeque<T>>,
+    // wakes the parked receiver when the channel becomes idle, if the channel closes after idling
+    pub(in crate::channels::mpsc) idle_sweep: Mutex<Option<IdleSweep>>,
+    spins: usize,
+    budget: Budget,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
 }
 
 assert_impl_all!(Receiver<SendMessage>: Send, Sync, fmt::Debug);
 assert_not_impl_all!(Receiver<SendMessage>: Clone);
+assert_not_impl_any!(Receiver<NotSendMessage>: Send, Sync);
+
+// retained messages are never pinned
+impl<T> Unpin for Receiver<T> {}
+
+impl<T> Receiver<T> {
+    /// Returns true if a sender was dropped while its thread was panicking.
+    ///
+    /// Channels are only poisoned if they were built with `poison_on_panic`.  Buffered messages can still be
+    /// received, and once the channel is closed, this distinguishes a crashed producer from a graceful shutdown.
+    pub fn is_poisoned(&self) -> bool {
+        self.shared.is_poisoned()
+    }
 
-impl<T> Stream for Receiver<T> {
-    type Item = T;
+    /// Returns the number of messages in the channel
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// Returns true if the channel contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().queue.capacity()
+    }
+
+    /// Returns the number of messages which were dropped because they expired before they could be received.
+    ///
+    /// Messages only expire if the channel was built with [Builder::message_ttl](./struct.Builder.html#method.message_ttl).
+    pub fn expired_count(&self) -> usize {
+        self.shared.extension().queue.expired()
+    }
+
+    /// Configures the number of times a receive from an empty channel is retried, before the task is suspended.
+    ///
+    /// This can reduce latency when senders produce messages quickly, at the cost of busy-waiting.
+    /// The default is zero.
+    pub fn spin_recvs(mut self, spins: usize) -> Self {
+        self.spins = spins;
+        self
+    }
+
+    /// Returns the number of messages that can be received without waiting,
+    /// including messages kept by [retain](#method.retain).
+    pub fn buffered_len(&self) -> usize {
+        self.len()
+    }
+
+    /// Removes buffered messages for which `keep` returns false, preserving the order of the others.
+    ///
+    /// Kept messages are held by the receiver, and are received before any message sent during or after the call.
+    /// They still count against the capacity of the channel, and are included in `len`.  If messages are removed
+    /// from the channel, blocked senders are woken.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let retained = self.retained.get_mut();
+        let held = retained.len();
+        retained.retain(|item| keep(item));
+        let mut removed = held - retained.len();
+
+        // messages sent concurrently are left in the queue, behind the retained messages
+        let extension = self.shared.extension();
+        let buffered = extension.queue.len();
+        let mut freed = 0;
+
+        for _ in 0..buffered {
+            match extension.queue.pop(&mut freed) {
+                Some(item) => {
+                    if keep(&item) {
+                        // the kept message keeps its slot
+                        freed -= 1;
+                        retained.push_back(item);
+                    }
+                }
+                None => break,
+            }
+        }
+
+        removed += freed;
+        if removed > 0 {
+            extension.release(removed);
+            self.shared.notify_senders();
+        }
+    }
+
+    /// Removes and returns the buffered messages, in the order they would be received.  The channel stays open.
+    ///
+    /// Messages kept by [retain](#method.retain) are returned first.  Messages sent during the call may be
+    /// included, or left in the channel, but any that are left are received after the returned messages.
+    /// If messages are removed from the channel, blocked senders are woken.
+    ///
+    /// The snapshot can be restored into a new channel with [builder](./fn.builder.html), which inserts the messages
+    /// before any other sender exists:
+    /// ```rust
+    /// use postage::{mpsc, sink::Sink};
+    ///
+    /// let (mut tx, mut rx) = mpsc::channel(4);
+    /// tx.try_send(1).unwrap();
+    /// tx.try_send(2).unwrap();
+    ///
+    /// let snapshot = rx.snapshot();
+    /// assert_eq!(vec![1, 2], snapshot);
+    ///
+    /// let (_tx, rx) = mpsc::builder(4).prefill(snapshot).grow_to_fit(true).build();
+    /// assert_eq!(2, rx.len());
+    /// ```
+    pub fn snapshot(&mut self) -> Vec<T> {
+        let mut snapshot: Vec<T> = self.retained.get_mut().drain(..).collect();
+        let mut freed = snapshot.len();
+
+        // the number of messages is bounded, so concurrent senders can't extend the snapshot indefinitely
+        let extension = self.shared.extension();
+        let buffered = extension.queue.len();
+        snapshot.reserve(buffered);
+
+        for _ in 0..buffered {
+            match extension.queue.pop(&mut freed) {
+                Some(item) => snapshot.push(item),
+                None => break,
+            }
+        }
+
+        if freed > 0 {
+            extension.release(freed);
+            self.shared.notify_senders();
+        }
+
+        snapshot
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives a message, and the [metadata](./struct.MessageMeta.html) it was sent with.
+    ///
+    /// The metadata is only recorded if the channel was built with
+    /// [Builder::message_meta](./struct.Builder.html#method.message_meta).  Otherwise, and for messages kept by
+    /// [retain](#method.retain), the metadata is empty.
+    ///
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use postage::mpsc;
+    ///
+    /// let (mut tx, mut rx) = mpsc::builder(4).message_meta(true).build();
+    /// let deadline = Instant::now() + Duration::from_secs(1);
+    /// tx.try_send_with_deadline("request", deadline).unwrap();
+    ///
+    /// let (request, meta) = rx.try_recv_with_meta().unwrap();
+    /// assert_eq!("request", request);
+    /// assert_eq!(Some(deadline), meta.deadline);
+    /// ```
+    pub fn recv_with_meta(&mut self) -> RecvMetaFuture<'_, Self, T> {
+        RecvMetaFuture::new(self)
+    }
+
+    /// Attempts to receive a message and its metadata, without blocking.  See [recv_with_meta](#method.recv_with_meta).
+    pub fn try_recv_with_meta(&mut self) -> Result<(T, MessageMeta), TryRecvError> {
+        match self.poll_pop(&mut crate::Context::empty()) {
+            PollRecv::Ready(value) => Ok(value),
+            PollRecv::Pending => Err(TryRecvError::Pending),
+            PollRecv::Closed => Err(TryRecvError::Closed),
+        }
+    }
+
+    fn poll_pop(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<(T, MessageMeta)> {
+        if self.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
+        if self.shared.extension().is_idle() {
+            self.shared.close();
+        }
+
+        if let Some(v) = self.retained.get_mut().pop_front() {
+            self.shared.extension().release(1);
+            self.shared.extension().touch();
+            self.shared.notify_senders();
+            self.budget.consume();
+            return PollRecv::Ready((v, MessageMeta::default()));
+        }
 
-    fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut crate::Context<'_>,
-    ) -> PollRecv<Self::Item> {
         loop {
             let guard = self.shared.send_guard();
-            match self.shared.extension().queue.pop() {
+            let mut freed = false;
+            match self.shared.extension().pop_spin(self.spins, &mut freed) {
                 Some(v) => {
+                    self.shared.extension().touch();
                     self.shared.notify_senders();
+                    self.budget.consume();
                     return PollRecv::Ready(v);
                 }
                 None => {
+                    // expired messages free capacity, even if none could be received
+                    if freed {
+                        self.shared.notify_senders();
+                    }
+
                     if self.shared.is_closed() {
                         return PollRecv::Closed;
                     }
@@ -187,11 +579,59 @@ impl<T> Stream for Receiver<T> {
                         continue;
                     }
 
+                    if self.poll_idle_sweep(cx).is_ready() {
+                        self.shared.close();
+                        continue;
+                    }
+
+                    self.budget.reset();
                     return PollRecv::Pending;
                 }
             }
         }
     }
+
+    // resolves when the channel becomes idle.  pending if the channel does not close after idling
+    fn poll_idle_sweep(&mut self, cx: &crate::Context<'_>) -> std::task::Poll<()> {
+        match (
+            &self.shared.extension().idle,
+            self.idle_sweep.get_mut().as_mut(),
+        ) {
+            (Some(idle), Some(sweep)) => sweep.poll_idle(idle, cx),
+            _ => std::task::Poll::Pending,
+        }
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        match self.get_mut().poll_pop(cx) {
+            PollRecv::Ready((value, _meta)) => PollRecv::Ready(value),
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // once the channel is closed, no more messages can be buffered
+        let closed = self.shared.is_closed();
+        let len = self.len();
+
+        (len, if closed { Some(len) } else { None })
+    }
+}
+
+impl<T> PollRecvMeta for Receiver<T> {
+    type Item = T;
+
+    fn poll_recv_meta(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<(T, MessageMeta)> {
+        self.poll_pop(cx)
+    }
 }
 
 impl<T> fmt::Debug for Receiver<T> {
@@ -201,266 +641,807 @@ impl<T> fmt::Debug for Receiver<T> {
 }
 
 struct StateExtension<T> {
-    queue: ArrayQueue<T>,
+    queue: ExpiringQueue<T>,
+    // the number of occupied slots, including messages held by the receiver after `retain`.  senders reserve a slot
+    // before pushing to the queue, so held messages count against the capacity
+    occupied: AtomicUsize,
+    // the sender limit, enforced when senders are cloned
+    max_senders: Option<usize>,
+    // if set, `Sender::clone` exceeds the limit instead of panicking
+    saturate_senders: bool,
+    // if set, the channel is closed when no message is sent or received within the idle period
+    idle: Option<IdleTimeout>,
 }
 
 impl<T> StateExtension<T> {
-    pub fn new(capacity: usize) -> Self {
+    pub fn new(capacity: usize, ttl: Option<Ttl>) -> Self {
         Self {
-            queue: ArrayQueue::new(capacity),
+            queue: ExpiringQueue::new(capacity, ttl),
+            occupied: AtomicUsize::new(0),
+            max_senders: None,
+            saturate_senders: false,
+            idle: None,
+        }
+    }
+
+    // true if the channel closes after idling, and the idle period has elapsed
+    fn is_idle(&self) -> bool {
+        self.idle.as_ref().is_some_and(IdleTimeout::is_expired)
+    }
+
+    // records a send or receive, which restarts the idle period
+    fn touch(&self) {
+        if let Some(idle) = &self.idle {
+            idle.touch();
+        }
+    }
+
+    // the number of messages in the channel, including messages held by the receiver
+    fn len(&self) -> usize {
+        self.occupied.load(Ordering::Acquire)
+    }
+
+    // reserves a slot for a message, or returns false if the channel is full
+    fn reserve(&self) -> bool {
+        let capacity = self.queue.capacity();
+        let mut occupied = self.occupied.load(Ordering::Acquire);
+
+        loop {
+            if occupied >= capacity {
+                return false;
+            }
+
+            match self.occupied.compare_exchange_weak(
+                occupied,
+                occupied + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => occupied = actual,
+            }
+        }
+    }
+
+    // frees slots, once their messages have left the channel
+    fn release(&self, slots: usize) {
+        self.occupied.fetch_sub(slots, Ordering::AcqRel);
+    }
+
+    fn push(&self, value: T, deadline: Option<Instant>) -> Result<(), T> {
+        if !self.reserve() {
+            return Err(value);
+        }
+
+        // slots are released after messages are popped, so the queue has room for every reserved slot
+        self.queue
+            .push_with_deadline(value, deadline)
+            .inspect_err(|_| self.release(1))
+    }
+
+    // retries the push up to `spins` times while the channel is full
+    fn push_spin(&self, mut value: T, deadline: Option<Instant>, spins: usize) -> Result<(), T> {
+        for _ in 0..spins {
+            match self.push(value, deadline) {
+                Ok(()) => return Ok(()),
+                Err(v) => value = v,
+            }
+
+            std::hint::spin_loop();
+        }
+
+        self.push(value, deadline)
+    }
+
+    // retries the pop up to `spins` times while the queue is empty.  `freed` is set if any slot was freed, including
+    // the slots of expired messages
+    fn pop_spin(&self, spins: usize, freed: &mut bool) -> Option<(T, MessageMeta)> {
+        let mut slots = 0;
+        let mut popped = self.queue.pop_with_meta(&mut slots);
+
+        for _ in 0..spins {
+            if popped.is_some() {
+                break;
+            }
+
+            std::hint::spin_loop();
+            popped = self.queue.pop_with_meta(&mut slots);
+        }
+
+        if slots > 0 {
+            self.release(slots);
+            *freed = true;
         }
+
+        popped
     }
 }
 
+// The behavioral suite shared by the dynamic and static channels.
+// The `$chan` macro constructs a channel, given the message type and the capacity.
 #[cfg(test)]
-mod tests {
-    use std::{pin::Pin, task::Context};
+macro_rules! channel_tests {
+    ($chan:ident) => {
+        use std::{pin::Pin, task::Context};
+
+        use crate::{
+            sink::{PollSend, Sink},
+            stream::{PollRecv, Stream},
+            test::{noop_context, panic_context},
+        };
+        use futures_test::task::new_count_waker;
+
+        fn pin<Tx: Unpin, Rx: Unpin>(chan: &mut (Tx, Rx)) -> (Pin<&mut Tx>, Pin<&mut Rx>) {
+            let tx = Pin::new(&mut chan.0);
+            let rx = Pin::new(&mut chan.1);
+
+            (tx, rx)
+        }
 
-    use crate::{
-        sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
-        test::{noop_context, panic_context},
-    };
-    use futures_test::task::new_count_waker;
+        #[derive(Debug, PartialEq, Eq)]
+        struct Message(usize);
 
-    use super::{channel, Receiver, Sender};
+        #[test]
+        fn send_accepted() {
+            let mut cx = panic_context();
+            let mut chan = $chan!(Message, 2);
+            let (tx, _) = pin(&mut chan);
 
-    fn pin<'a, 'b>(
-        chan: &mut (Sender<Message>, Receiver<Message>),
-    ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
-        let tx = Pin::new(&mut chan.0);
-        let rx = Pin::new(&mut chan.1);
+            assert_eq!(PollSend::Ready, tx.poll_send(&mut cx, Message(1)));
+        }
 
-        (tx, rx)
-    }
+        #[test]
+        fn send_blocks() {
+            let mut cx = panic_context();
+            let (mut tx, _rx) = $chan!(Message, 2);
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+        }
 
-    #[derive(Debug, PartialEq, Eq)]
-    struct Message(usize);
+        #[test]
+        fn send_recv() {
+            let mut cx = panic_context();
+            let (mut tx, mut rx) = $chan!(Message, 2);
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+            );
+            assert_eq!(
+                PollSend::Pending(Message(3)),
+                Pin::new(&mut tx).poll_send(&mut noop_context(), Message(3))
+            );
+
+            assert_eq!(
+                PollRecv::Ready(Message(1)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+
+            assert_eq!(
+                PollRecv::Ready(Message(2)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+
+            assert_eq!(
+                PollRecv::Pending,
+                Pin::new(&mut rx).poll_recv(&mut noop_context())
+            );
+        }
 
-    #[test]
-    fn send_accepted() {
-        let mut cx = panic_context();
-        let mut chan = channel(2);
-        let (tx, _) = pin(&mut chan);
+        #[test]
+        fn sender_disconnect() {
+            let mut cx = panic_context();
+            let (mut tx, mut rx) = $chan!(Message, 100);
+            let mut tx2 = tx.clone();
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+            );
+
+            drop(tx);
+            drop(tx2);
+
+            assert_eq!(
+                PollRecv::Ready(Message(1)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+
+            assert_eq!(
+                PollRecv::Ready(Message(2)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+
+            assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        }
+
+        #[test]
+        fn receiver_disconnect() {
+            let mut cx = panic_context();
+            let (mut tx, rx) = $chan!(Message, 100);
+            let mut tx2 = tx.clone();
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+            );
+
+            drop(rx);
+
+            assert_eq!(
+                PollSend::Rejected(Message(3)),
+                Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+            );
+
+            assert_eq!(
+                PollSend::Rejected(Message(4)),
+                Pin::new(&mut tx2).poll_send(&mut cx, Message(4))
+            );
+        }
+
+        #[test]
+        fn wake_sender() {
+            let mut cx = panic_context();
+            let (mut tx, mut rx) = $chan!(Message, 1);
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+
+            let (w2, w2_count) = new_count_waker();
+            let w2_context = Context::from_waker(&w2);
+            assert_eq!(
+                PollSend::Pending(Message(2)),
+                Pin::new(&mut tx).poll_send(&mut w2_context.into(), Message(2))
+            );
+
+            assert_eq!(0, w2_count.get());
+
+            assert_eq!(
+                PollRecv::Ready(Message(1)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+
+            assert_eq!(1, w2_count.get());
+            assert_eq!(
+                PollRecv::Pending,
+                Pin::new(&mut rx).poll_recv(&mut noop_context())
+            );
+
+            assert_eq!(1, w2_count.get());
+        }
+
+        #[test]
+        fn wake_receiver() {
+            let mut cx = panic_context();
+            let (mut tx, mut rx) = $chan!(Message, 100);
+
+            let probe = crate::poll_pending_then_wake!(
+                |w_cx| Pin::new(&mut rx).poll_recv(w_cx),
+                assert_eq!(
+                    PollSend::Ready,
+                    Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+                )
+            );
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+            );
+
+            probe.assert_woken(1);
+        }
+
+        #[test]
+        fn close_drains_then_closes() {
+            let mut cx = noop_context();
+            let (mut tx, mut rx) = $chan!(Message, 2);
+            let mut tx2 = tx.clone();
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            );
+            assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_close(&mut cx));
+            assert_eq!(
+                PollSend::Rejected(Message(2)),
+                Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+            );
+
+            assert_eq!(
+                PollRecv::Ready(Message(1)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+            assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        }
+
+        #[test]
+        fn wake_receiver_on_close() {
+            let (mut tx, mut rx) = $chan!(Message, 2);
+
+            let (w1, w1_count) = new_count_waker();
+            let w1_context = Context::from_waker(&w1);
+
+            assert_eq!(
+                PollRecv::Pending,
+                Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            );
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_close(&mut noop_context())
+            );
+            assert_eq!(1, w1_count.get());
+        }
+
+        #[test]
+        fn wake_sender_on_disconnect() {
+            let (mut tx, rx) = $chan!(Message, 1);
+
+            let (w1, w1_count) = new_count_waker();
+            let w1_context = Context::from_waker(&w1);
+            let mut w1_context: crate::Context<'_> = w1_context.into();
+
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut w1_context, Message(1))
+            );
 
-        assert_eq!(PollSend::Ready, tx.poll_send(&mut cx, Message(1)));
+            assert_eq!(
+                PollSend::Pending(Message(2)),
+                Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+            );
+
+            assert_eq!(0, w1_count.get());
+
+            drop(rx);
+
+            assert_eq!(1, w1_count.get());
+        }
+
+        #[test]
+        fn wake_receiver_on_disconnect() {
+            let (tx, mut rx) = $chan!((), 100);
+
+            let (w1, w1_count) = new_count_waker();
+            let w1_context = Context::from_waker(&w1);
+
+            assert_eq!(
+                PollRecv::Pending,
+                Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            );
+
+            assert_eq!(0, w1_count.get());
+
+            drop(tx);
+
+            assert_eq!(1, w1_count.get());
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    macro_rules! dynamic_channel {
+        ($t:ty, $cap:literal) => {
+            super::channel::<$t>($cap)
+        };
     }
 
+    channel_tests!(dynamic_channel);
+
     #[test]
-    fn send_blocks() {
-        let mut cx = panic_context();
-        let (mut tx, _rx) = channel(2);
+    fn len_capacity() {
+        let (mut tx, rx) = super::channel::<usize>(4);
+        assert_eq!(4, tx.capacity());
+        assert_eq!(4, rx.capacity());
+        assert!(tx.is_empty());
+
+        crate::sink::Sink::try_send(&mut tx, 1).unwrap();
+        assert_eq!(1, tx.len());
+        assert_eq!(1, rx.len());
+        assert!(!rx.is_empty());
+    }
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+    #[test]
+    #[should_panic(expected = "the capacity must be greater than zero")]
+    fn zero_capacity() {
+        let _ = super::channel::<usize>(0);
     }
 
     #[test]
-    fn send_recv() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(2);
+    fn try_channel_capacity() {
+        use crate::{sink::Sink, InvalidCapacity};
 
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+            Some(InvalidCapacity::Zero),
+            super::try_channel::<usize>(0).err()
         );
         assert_eq!(
-            PollSend::Pending(Message(3)),
-            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(3))
-        );
-
-        assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            Some(InvalidCapacity::TooLarge(usize::MAX)),
+            super::try_channel::<usize>(usize::MAX).err()
         );
 
+        // the buffer of a valid capacity must fit in memory
         assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            Some(InvalidCapacity::TooLarge(crate::Capacity::MAX)),
+            super::try_channel::<u64>(crate::Capacity::MAX).err()
         );
 
-        assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut noop_context())
-        );
+        let (mut tx, rx) = super::try_channel::<usize>(1).unwrap();
+        assert_eq!(1, rx.capacity());
+        assert!(tx.try_send(1).is_ok());
+        assert!(tx.try_send(2).is_err());
     }
 
     #[test]
-    fn sender_disconnect() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(100);
-        let mut tx2 = tx.clone();
+    fn capacity_one_polls_per_message() {
+        use crate::{sink::Sink, stream::Stream};
+        use futures_test::task::new_count_waker;
+        use std::{future::Future, task::Context};
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+        const MESSAGES: usize = 1000;
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
-        );
+        let (mut tx, mut rx) = super::channel::<usize>(1);
+        let mut producer = Box::pin(async move {
+            for i in 0..MESSAGES {
+                tx.send(i).await.unwrap();
+            }
+        });
 
-        drop(tx);
-        drop(tx2);
+        let mut consumer = Box::pin(async move {
+            let mut received = 0;
+            while let Some(i) = rx.recv().await {
+                assert_eq!(received, i);
+                received += 1;
+            }
 
-        assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
-        );
+            received
+        });
 
-        assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
-        );
+        // each task is polled once, and then only when it has been woken
+        let (producer_waker, producer_wakes) = new_count_waker();
+        let (consumer_waker, consumer_wakes) = new_count_waker();
+        let (mut producer_polls, mut consumer_polls) = (0, 0);
+        let (mut producer_seen, mut consumer_seen) = (usize::MAX, usize::MAX);
+        let mut producer_done = false;
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
-    }
+        let received = loop {
+            let mut polled = false;
 
-    #[test]
-    fn receiver_disconnect() {
-        let mut cx = panic_context();
-        let (mut tx, rx) = channel(100);
-        let mut tx2 = tx.clone();
+            if !producer_done && producer_wakes.get() != producer_seen {
+                producer_seen = producer_wakes.get();
+                producer_polls += 1;
+                polled = true;
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+                let mut cx = Context::from_waker(&producer_waker);
+                producer_done = producer.as_mut().poll(&mut cx).is_ready();
+            }
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
-        );
+            if consumer_wakes.get() != consumer_seen {
+                consumer_seen = consumer_wakes.get();
+                consumer_polls += 1;
+                polled = true;
 
-        drop(rx);
+                let mut cx = Context::from_waker(&consumer_waker);
+                if let std::task::Poll::Ready(received) = consumer.as_mut().poll(&mut cx) {
+                    break received;
+                }
+            }
 
-        assert_eq!(
-            PollSend::Rejected(Message(3)),
-            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
-        );
+            assert!(polled, "neither task was woken");
+        };
 
-        assert_eq!(
-            PollSend::Rejected(Message(4)),
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(4))
+        assert_eq!(MESSAGES, received);
+        assert!(
+            producer_polls <= MESSAGES + 1,
+            "the producer was polled {} times",
+            producer_polls
+        );
+        assert!(
+            consumer_polls <= MESSAGES + 2,
+            "the consumer was polled {} times",
+            consumer_polls
         );
     }
 
     #[test]
-    fn wake_sender() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(1);
+    fn full_channel_yields() {
+        use crate::{
+            coop::BUDGET,
+            sink::{PollSend, Sink},
+            stream::{PollRecv, Stream},
+        };
+        use futures_test::task::new_count_waker;
+        use std::pin::Pin;
+
+        let (mut tx, mut rx) = super::channel::<usize>(4);
+        let (waker, count) = new_count_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+
+        let mut tx_cx = crate::test::noop_context();
+
+        // the sender refills the channel after every message, so it is never empty
+        let mut ready = 0;
+        for round in 0..3 {
+            loop {
+                while let PollSend::Ready = Pin::new(&mut tx).poll_send(&mut tx_cx, ready) {}
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+                match Pin::new(&mut rx).poll_recv(&mut cx) {
+                    PollRecv::Ready(_) => ready += 1,
+                    PollRecv::Pending => break,
+                    PollRecv::Closed => panic!("channel closed"),
+                }
+            }
 
-        let (w2, w2_count) = new_count_waker();
-        let w2_context = Context::from_waker(&w2);
+            assert_eq!((round + 1) * BUDGET, ready);
+            assert_eq!(round + 1, count.get());
+        }
+    }
+
+    #[test]
+    fn spin_send_registers_waker() {
+        use crate::{
+            sink::{PollSend, Sink},
+            stream::{PollRecv, Stream},
+            test_util::WakeProbe,
+        };
+        use std::pin::Pin;
+
+        let (tx, mut rx) = super::channel::<usize>(1);
+        let mut tx = tx.spin_sends(64);
+        tx.try_send(1).unwrap();
+
+        let probe = WakeProbe::new();
         assert_eq!(
-            PollSend::Pending(Message(2)),
-            Pin::new(&mut tx).poll_send(&mut w2_context.into(), Message(2))
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
         );
-
-        assert_eq!(0, w2_count.get());
+        probe.assert_not_woken();
 
         assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            PollRecv::Ready(1),
+            Pin::new(&mut rx).poll_recv(&mut crate::test::noop_context())
         );
+        probe.assert_woken(1);
+    }
 
-        assert_eq!(1, w2_count.get());
+    #[test]
+    fn spin_recv_registers_waker() {
+        use crate::{
+            sink::Sink,
+            stream::{PollRecv, Stream},
+            test_util::WakeProbe,
+        };
+        use std::pin::Pin;
+
+        let (mut tx, rx) = super::channel::<usize>(1);
+        let mut rx = rx.spin_recvs(64);
+
+        let probe = WakeProbe::new();
         assert_eq!(
             PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut noop_context())
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
         );
+        probe.assert_not_woken();
 
-        assert_eq!(1, w2_count.get());
+        tx.try_send(1).unwrap();
+        probe.assert_woken(1);
+        assert_eq!(Ok(1), rx.try_recv());
     }
 
     #[test]
-    fn wake_receiver() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(100);
+    fn retain_preserves_order() {
+        use crate::{sink::Sink, stream::Stream};
 
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
+        let (mut tx, mut rx) = super::channel::<usize>(4);
+        for i in 0..4 {
+            tx.try_send(i).unwrap();
+        }
 
-        assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
-        );
+        rx.retain(|i| i % 2 == 0);
+        assert_eq!(2, rx.buffered_len());
 
-        assert_eq!(0, w1_count.get());
+        tx.try_send(4).unwrap();
+        tx.try_send(5).unwrap();
+        assert_eq!(4, rx.buffered_len());
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+        rx.retain(|i| *i != 2);
+        assert_eq!(3, rx.buffered_len());
 
-        assert_eq!(1, w1_count.get());
+        assert_eq!(Ok(0), rx.try_recv());
+        assert_eq!(Ok(4), rx.try_recv());
+        assert_eq!(Ok(5), rx.try_recv());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(0, rx.buffered_len());
+    }
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
-        );
+    #[test]
+    fn snapshot_round_trip() {
+        use crate::{sink::Sink, stream::Stream};
 
-        assert_eq!(1, w1_count.get());
+        let (mut tx, mut rx) = super::channel::<usize>(4);
+        for i in 0..4 {
+            tx.try_send(i).unwrap();
+        }
+
+        // retained messages are returned first
+        rx.retain(|i| *i != 1);
+        tx.try_send(4).unwrap();
+
+        let snapshot = rx.snapshot();
+        assert_eq!(vec![0, 2, 3, 4], snapshot);
+        assert_eq!(0, rx.buffered_len());
+
+        // the channel stays open
+        tx.try_send(5).unwrap();
+        assert_eq!(Ok(5), rx.try_recv());
+
+        let (_tx, mut restored) = super::builder(2)
+            .prefill(snapshot)
+            .grow_to_fit(true)
+            .build();
+        for i in [0, 2, 3, 4].iter() {
+            assert_eq!(Ok(*i), restored.try_recv());
+        }
     }
 
     #[test]
-    fn wake_sender_on_disconnect() {
-        let (mut tx, rx) = channel(1);
+    fn snapshot_wakes_sender() {
+        use crate::{
+            sink::{PollSend, Sink},
+            test_util::WakeProbe,
+        };
+        use std::pin::Pin;
 
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
-        let mut w1_context: crate::Context<'_> = w1_context.into();
+        let (mut tx, mut rx) = super::channel::<usize>(1);
+        tx.try_send(1).unwrap();
 
+        let probe = WakeProbe::new();
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut w1_context, Message(1))
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
         );
 
+        assert_eq!(vec![1], rx.snapshot());
+        probe.assert_woken(1);
+
+        // an empty snapshot doesn't wake senders
+        assert!(rx.snapshot().is_empty());
+        probe.assert_woken(1);
+    }
+
+    #[test]
+    fn retain_wakes_sender() {
+        use crate::{
+            sink::{PollSend, Sink},
+            stream::{PollRecv, Stream},
+        };
+        use futures_test::task::new_count_waker;
+        use std::{pin::Pin, task::Context};
+
+        let (mut tx, mut rx) = super::channel::<usize>(2);
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
         assert_eq!(
-            PollSend::Pending(Message(2)),
-            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+            PollSend::Pending(3),
+            Pin::new(&mut tx).poll_send(&mut w1_context, 3)
         );
 
+        // kept messages still occupy their slots, so the sender is not woken
+        rx.retain(|_| true);
         assert_eq!(0, w1_count.get());
 
-        drop(rx);
-
+        rx.retain(|i| *i != 2);
         assert_eq!(1, w1_count.get());
+
+        drop(tx);
+        assert_eq!(1, rx.buffered_len());
+        let mut cx = crate::test::noop_context();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
     }
 
     #[test]
-    fn wake_receiver_on_disconnect() {
-        let (tx, mut rx) = channel::<()>(100);
-
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
+    fn retain_counts_against_capacity() {
+        use crate::{
+            sink::{Sink, TrySendError},
+            stream::Stream,
+        };
+
+        let (mut tx, mut rx) = super::channel::<usize>(2);
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+
+        rx.retain(|_| true);
+        assert_eq!(2, rx.len());
+        assert_eq!(2, tx.len());
+        assert!(!rx.is_empty());
+        assert_eq!(Err(TrySendError::Pending(3)), tx.try_send(3));
+
+        // receiving a retained message frees its slot
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(1, rx.len());
+        tx.try_send(3).unwrap();
+        assert_eq!(Err(TrySendError::Pending(4)), tx.try_send(4));
+
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(3), rx.try_recv());
+        assert!(rx.is_empty());
+    }
 
+    #[test]
+    fn retain_wakes_sender_on_recv() {
+        use crate::{
+            sink::{PollSend, Sink},
+            stream::Stream,
+            test_util::WakeProbe,
+        };
+        use std::pin::Pin;
+
+        let (mut tx, mut rx) = super::channel::<usize>(1);
+        tx.try_send(1).unwrap();
+        rx.retain(|_| true);
+
+        let probe = WakeProbe::new();
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
         );
+        probe.assert_not_woken();
 
-        assert_eq!(0, w1_count.get());
+        assert_eq!(Ok(1), rx.try_recv());
+        probe.assert_woken(1);
+        assert_eq!(Ok(()), tx.try_send(2));
+    }
+}
 
-        drop(tx);
+#[cfg(test)]
+mod static_tests {
+    macro_rules! static_channel {
+        ($t:ty, $cap:literal) => {
+            super::static_channel::<$t, $cap>()
+        };
+    }
 
-        assert_eq!(1, w1_count.get());
+    channel_tests!(static_channel);
+
+    #[test]
+    fn len_capacity() {
+        let (mut tx, rx) = super::static_channel::<usize, 4>();
+        assert_eq!(4, tx.capacity());
+        assert_eq!(4, rx.capacity());
+        assert!(tx.is_empty());
+
+        crate::sink::Sink::try_send(&mut tx, 1).unwrap();
+        assert_eq!(1, tx.len());
+        assert_eq!(1, rx.len());
+        assert!(!rx.is_empty());
     }
 }
 
@@ -556,7 +1537,7 @@ mod tokio_tests {
 
             spawn(async move {
                 loop {
-                    if let Ok(_) = sender_quit.try_recv() {
+                    if sender_quit.try_recv().is_ok() {
                         break;
                     }
 
@@ -582,6 +1563,46 @@ mod tokio_tests {
                 .expect("join failed");
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn retain_concurrent_send() {
+        for cap in capacity_iter() {
+            let (mut tx, mut rx) = super::channel(cap);
+
+            spawn(async move {
+                for i in 0..10_000usize {
+                    tx.send(i).await.expect("send failed");
+                }
+            });
+
+            let rx_handle = spawn(async move {
+                let mut expected = 0;
+                loop {
+                    // discard every third message, both before and after it is received
+                    rx.retain(|i| i % 3 != 0);
+
+                    match rx.recv().await {
+                        Some(i) => {
+                            while expected % 3 == 0 && expected < i {
+                                expected += 1;
+                            }
+                            assert_eq!(expected, i);
+                            expected += 1;
+                        }
+                        None => break,
+                    }
+                }
+
+                // the final message is a multiple of three, and may have been discarded
+                assert!(expected >= 9_999);
+            });
+
+            timeout(TEST_TIMEOUT, rx_handle)
+                .await
+                .expect("test timeout")
+                .expect("join failed");
+        }
+    }
 }
 
 #[cfg(test)]
@@ -671,7 +1692,7 @@ mod async_std_tests {
 
             spawn(async move {
                 loop {
-                    if let Ok(_) = sender_quit.try_recv() {
+                    if sender_quit.try_recv().is_ok() {
                         break;
                     }
 
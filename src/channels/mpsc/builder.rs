@@ -0,0 +1,739 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    channels::{
+        capacity::{Capacity, InvalidCapacity},
+        expiry::{Clock, ExpiringSlot, Ttl},
+        idle::{IdleSweep, IdleTimeout},
+        prefill::Prefill,
+    },
+    layer::{Identity, SinkLayer, Stack, StreamLayer},
+    time::Sleep,
+};
+
+use super::{new_channel, Receiver, Sender, StateExtension};
+
+/// Constructs a builder for an mpsc channel with the given capacity.
+///
+/// The channel can be prefilled with messages, which the receiver observes before any messages sent later.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+/// `try_builder` returns the error instead.
+#[track_caller]
+pub fn builder<T>(capacity: usize) -> Builder<T> {
+    new_builder(Capacity::expect::<ExpiringSlot<T>>(capacity))
+}
+
+/// Constructs a builder for a mpsc channel with the given capacity.
+///
+/// Returns an error if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+pub fn try_builder<T>(capacity: usize) -> Result<Builder<T>, InvalidCapacity> {
+    Capacity::for_slots::<ExpiringSlot<T>>(capacity).map(new_builder)
+}
+
+fn new_builder<T>(capacity: Capacity) -> Builder<T> {
+    Builder {
+        capacity,
+        prefill: Prefill::new(),
+        poison_on_panic: false,
+        max_senders: None,
+        saturate_senders: false,
+        ttl: None,
+        idle: None,
+        idle_sweep: None,
+        message_meta: false,
+        clock: None,
+    }
+}
+
+/// A builder for an mpsc channel, created by [builder](./fn.builder.html).
+pub struct Builder<T> {
+    capacity: Capacity,
+    prefill: Prefill<T>,
+    poison_on_panic: bool,
+    max_senders: Option<usize>,
+    saturate_senders: bool,
+    ttl: Option<Duration>,
+    idle: Option<Duration>,
+    idle_sweep: Option<IdleSweep>,
+    message_meta: bool,
+    clock: Option<Clock>,
+}
+
+impl<T> Builder<T> {
+    /// Seeds the channel with the messages, in order.  The messages count against the capacity.
+    ///
+    /// If called more than once, the messages are appended.
+    pub fn prefill<I>(mut self, messages: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.prefill.extend(messages);
+        self
+    }
+
+    /// Configures whether the capacity grows to fit the prefilled messages.
+    ///
+    /// If disabled (the default), `build` panics if the messages exceed the capacity.
+    pub fn grow_to_fit(mut self, grow: bool) -> Self {
+        self.prefill.set_grow_to_fit(grow);
+        self
+    }
+
+    /// Limits the number of live senders, including the sender returned by `build`.
+    ///
+    /// `Sender::try_clone` returns None once the limit is reached, and `Sender::clone` panics.
+    /// Dropping a sender frees its slot.  Panics if the limit is zero.
+    #[track_caller]
+    pub fn max_senders(mut self, max: usize) -> Self {
+        assert!(max > 0, "the sender limit must be at least one");
+        self.max_senders = Some(max);
+        self
+    }
+
+    /// Configures whether `Sender::clone` exceeds the sender limit, rather than panicking.
+    ///
+    /// If enabled, the limit is only enforced by `Sender::try_clone`, which returns None until enough senders
+    /// are dropped.  The default is disabled.
+    pub fn saturate_senders(mut self, saturate: bool) -> Self {
+        self.saturate_senders = saturate;
+        self
+    }
+
+    /// Drops messages which have been queued for longer than `ttl`, rather than delivering them.
+    ///
+    /// Expiry is evaluated when messages are received, so no timer or runtime is needed.  Expired messages are
+    /// skipped, and counted by `Receiver::expired_count`.  Prefilled messages are stamped when the channel is built.
+    pub fn message_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Closes the channel if no message is sent or received within `duration`.  Senders are then rejected,
+    /// and the receiver drains the buffered messages before it observes the closure.
+    ///
+    /// The idle period is checked by each send and receive, so no timer or runtime is needed.  With the `tokio` or
+    /// `async-std` feature, a parked receiver is also woken by the runtime timer when the channel becomes idle.
+    /// The timer can be replaced with [idle_timer](#method.idle_timer).
+    pub fn close_after_idle(mut self, duration: Duration) -> Self {
+        self.idle = Some(duration);
+        self
+    }
+
+    /// Replaces the [timer](../time/trait.Sleep.html) which wakes a parked receiver when the channel becomes idle.
+    ///
+    /// Only used if the channel is built with [close_after_idle](#method.close_after_idle).
+    pub fn idle_timer<S>(mut self, timer: S) -> Self
+    where
+        S: Sleep + Send + Sync + 'static,
+        S::Future: Send + 'static,
+    {
+        self.idle_sweep = Some(IdleSweep::new(timer));
+        self
+    }
+
+    /// Configures whether messages keep the time they were sent, and the deadline they were sent with.
+    ///
+    /// The metadata is returned by `Receiver::recv_with_meta`, and deadlines are attached with
+    /// `Sender::send_with_deadline`.  If the channel also has a [message_ttl](#method.message_ttl), messages
+    /// expire once their deadline has passed.  The default is disabled, and deadlines are discarded.
+    pub fn message_meta(mut self, enabled: bool) -> Self {
+        self.message_meta = enabled;
+        self
+    }
+
+    /// Replaces the monotonic clock which measures the age of messages, their send time, and the idle period.
+    /// The default is `Instant::now`.
+    pub fn clock<F>(mut self, now: F) -> Self
+    where
+        F: Fn() -> Instant + Send + Sync + 'static,
+    {
+        self.clock = Some(Arc::new(now));
+        self
+    }
+
+    /// Configures whether the channel is poisoned when a sender is dropped while its thread is panicking.
+    ///
+    /// Receivers can then distinguish a crashed producer from a graceful shutdown with `Receiver::is_poisoned`.
+    /// The default is disabled.
+    pub fn poison_on_panic(mut self, poison: bool) -> Self {
+        self.poison_on_panic = poison;
+        self
+    }
+
+    /// Wraps the receiver with a [layer](../layer/index.html).  Layers are applied in the order they are added.
+    ///
+    /// The layers are applied by `build`, so the channel must be configured before layers are added.
+    pub fn layer<L>(self, layer: L) -> LayeredBuilder<T, Identity, Stack<Identity, L>> {
+        LayeredBuilder {
+            builder: self,
+            sender_layer: Identity,
+            receiver_layer: Stack::new(Identity, layer),
+        }
+    }
+
+    /// Wraps the sender with a [layer](../layer/index.html).  Layers are applied in the order they are added.
+    ///
+    /// The layers are applied by `build`, so the channel must be configured before layers are added.
+    pub fn sender_layer<L>(self, layer: L) -> LayeredBuilder<T, Stack<Identity, L>, Identity> {
+        LayeredBuilder {
+            builder: self,
+            sender_layer: Stack::new(Identity, layer),
+            receiver_layer: Identity,
+        }
+    }
+
+    /// Constructs the channel, and sends the prefilled messages.
+    #[track_caller]
+    pub fn build(self) -> (Sender<T>, Receiver<T>) {
+        let clock = self.clock;
+        let ttl = self.ttl.map(|ttl| Ttl::new(ttl, clock.clone()));
+        let mut extension = StateExtension::new(self.prefill.capacity(self.capacity), ttl);
+        if self.message_meta {
+            extension.queue.record_meta(clock.clone());
+        }
+        extension.max_senders = self.max_senders;
+        extension.saturate_senders = self.saturate_senders;
+        extension.idle = self.idle.map(|idle| IdleTimeout::new(idle, clock));
+
+        let idle_sweep = match self.idle {
+            Some(_) => self.idle_sweep.or_else(IdleSweep::runtime),
+            None => None,
+        };
+
+        let (mut tx, rx) = new_channel(extension);
+        *rx.idle_sweep.lock() = idle_sweep;
+        tx.shared.set_poison_on_panic(self.poison_on_panic);
+        self.prefill.send_into(&mut tx);
+
+        (tx, rx)
+    }
+}
+
+impl<T> fmt::Debug for Builder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("capacity", &self.capacity.get())
+            .field("prefill", &self.prefill.len())
+            .field("poison_on_panic", &self.poison_on_panic)
+            .field("max_senders", &self.max_senders)
+            .field("saturate_senders", &self.saturate_senders)
+            .field("ttl", &self.ttl)
+            .field("idle", &self.idle)
+            .field("message_meta", &self.message_meta)
+            .finish()
+    }
+}
+
+/// A builder for an mpsc channel with layered endpoints, created by [Builder::layer](./struct.Builder.html#method.layer).
+pub struct LayeredBuilder<T, Tx, Rx> {
+    builder: Builder<T>,
+    sender_layer: Tx,
+    receiver_layer: Rx,
+}
+
+impl<T, Tx, Rx> LayeredBuilder<T, Tx, Rx> {
+    /// Wraps the receiver with a layer, which is applied after the previous receiver layers.
+    pub fn layer<L>(self, layer: L) -> LayeredBuilder<T, Tx, Stack<Rx, L>> {
+        LayeredBuilder {
+            builder: self.builder,
+            sender_layer: self.sender_layer,
+            receiver_layer: Stack::new(self.receiver_layer, layer),
+        }
+    }
+
+    /// Wraps the sender with a layer, which is applied after the previous sender layers.
+    pub fn sender_layer<L>(self, layer: L) -> LayeredBuilder<T, Stack<Tx, L>, Rx> {
+        LayeredBuilder {
+            builder: self.builder,
+            sender_layer: Stack::new(self.sender_layer, layer),
+            receiver_layer: self.receiver_layer,
+        }
+    }
+
+    /// Constructs the channel, sends the prefilled messages, and wraps the endpoints with the layers.
+    ///
+    /// The prefilled messages are sent before the sender is wrapped.
+    #[track_caller]
+    pub fn build(self) -> (Tx::Output, Rx::Output)
+    where
+        Tx: SinkLayer<Sender<T>>,
+        Rx: StreamLayer<Receiver<T>>,
+    {
+        let (tx, rx) = self.builder.build();
+        (self.sender_layer.wrap(tx), self.receiver_layer.wrap(rx))
+    }
+}
+
+impl<T, Tx, Rx> fmt::Debug for LayeredBuilder<T, Tx, Rx>
+where
+    Tx: fmt::Debug,
+    Rx: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LayeredBuilder")
+            .field("builder", &self.builder)
+            .field("sender_layer", &self.sender_layer)
+            .field("receiver_layer", &self.receiver_layer)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        layer::{DedupLayer, GateLayer},
+        mpsc::MessageMeta,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::{noop_context, time::MockClock},
+        test_util::WakeProbe,
+        watch,
+    };
+
+    use super::builder;
+
+    #[test]
+    fn prefill_before_sends() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(4).prefill(vec![1, 2]).build();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 4));
+
+        // the prefilled messages count against the capacity
+        assert_eq!(
+            PollSend::Pending(5),
+            Pin::new(&mut tx).poll_send(&mut cx, 5)
+        );
+
+        for i in 1..=4 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut rx).poll_recv(&mut cx));
+        }
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn grow_to_fit() {
+        let (tx, rx) = builder(2).prefill(0..3).grow_to_fit(true).build();
+
+        assert_eq!(3, tx.capacity());
+        assert_eq!(3, rx.len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn exceeds_capacity() {
+        let _ = builder(2).prefill(0..3).build();
+    }
+
+    #[test]
+    #[should_panic(expected = "the capacity must be greater than zero")]
+    fn zero_capacity() {
+        let _ = builder::<usize>(0);
+    }
+
+    #[test]
+    fn try_builder_capacity() {
+        use crate::InvalidCapacity;
+
+        assert_eq!(
+            Some(InvalidCapacity::Zero),
+            super::try_builder::<usize>(0).err()
+        );
+        assert_eq!(
+            Some(InvalidCapacity::TooLarge(usize::MAX)),
+            super::try_builder::<usize>(usize::MAX).err()
+        );
+
+        let (_tx, mut rx) = super::try_builder(1).unwrap().prefill(vec![1]).build();
+        assert_eq!(Ok(1), rx.try_recv());
+    }
+
+    #[test]
+    fn max_senders() {
+        let (tx, _rx) = builder::<usize>(1).max_senders(3).build();
+        assert_eq!(1, tx.sender_count());
+
+        let tx2 = tx.try_clone().expect("below the limit");
+        let tx3 = tx2.clone();
+        assert_eq!(3, tx.sender_count());
+        assert!(tx.try_clone().is_none());
+        assert!(tx3.try_clone().is_none());
+
+        // dropping a sender frees its slot
+        drop(tx2);
+        assert_eq!(2, tx.sender_count());
+        let _tx4 = tx.try_clone().expect("a slot was freed");
+        assert!(tx.try_clone().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn clone_beyond_max_senders() {
+        let (tx, _rx) = builder::<usize>(1).max_senders(1).build();
+        let _ = tx.clone();
+    }
+
+    #[test]
+    fn saturate_senders() {
+        let (tx, _rx) = builder::<usize>(1)
+            .max_senders(1)
+            .saturate_senders(true)
+            .build();
+
+        let tx2 = tx.clone();
+        assert_eq!(2, tx.sender_count());
+        assert!(tx.try_clone().is_none());
+
+        drop(tx2);
+        assert!(tx.try_clone().is_none());
+    }
+
+    #[test]
+    fn max_senders_closure() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = builder::<usize>(2).max_senders(2).build();
+        let mut tx2 = tx.try_clone().unwrap();
+        assert!(tx.try_clone().is_none());
+
+        drop(tx);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx2).poll_send(&mut cx, 1));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        // the rejected clone did not leak a sender, so the channel closes
+        drop(tx2);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn message_ttl() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let (mut tx, mut rx) = builder(4)
+            .prefill(vec![1])
+            .message_ttl(Duration::from_secs(1))
+            .clock(clock.now_fn())
+            .build();
+
+        clock.advance(Duration::from_millis(600));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        clock.advance(Duration::from_millis(600));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(1, rx.expired_count());
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(2, rx.expired_count());
+        assert!(rx.is_empty());
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn expired_messages_wake_sender() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let (mut tx, mut rx) = builder(1)
+            .message_ttl(Duration::from_secs(1))
+            .clock(clock.now_fn())
+            .build();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        let probe = WakeProbe::new();
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
+        );
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(probe.count() > 0);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+    }
+
+    #[test]
+    fn message_meta() {
+        let clock = MockClock::new();
+        let now = clock.now_fn();
+        let (mut tx, mut rx) = builder(4)
+            .prefill(vec![1])
+            .message_meta(true)
+            .clock(clock.now_fn())
+            .build();
+
+        let built = now();
+        clock.advance(Duration::from_secs(1));
+        let deadline = now() + Duration::from_secs(2);
+        tx.try_send_with_deadline(2, deadline).unwrap();
+        tx.try_send(3).unwrap();
+
+        let (value, meta) = rx.try_recv_with_meta().unwrap();
+        assert_eq!(1, value);
+        assert_eq!(Some(built), meta.enqueued_at);
+        assert_eq!(None, meta.deadline);
+
+        clock.advance(Duration::from_secs(3));
+        let (value, meta) = rx.try_recv_with_meta().unwrap();
+        assert_eq!(2, value);
+        assert_eq!(Some(built + Duration::from_secs(1)), meta.enqueued_at);
+        assert_eq!(Some(deadline), meta.deadline);
+
+        // without a TTL, the missed deadline is reported to the receiver
+        assert!(meta.is_expired(now()));
+
+        // plain receives discard the metadata
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+
+    #[test]
+    fn deadline_without_meta() {
+        let clock = MockClock::new();
+        let now = clock.now_fn();
+        let (mut tx, mut rx) = builder(4)
+            .message_ttl(Duration::from_secs(10))
+            .clock(clock.now_fn())
+            .build();
+
+        tx.try_send_with_deadline(1, now()).unwrap();
+
+        // the deadline is discarded, so only the TTL applies
+        clock.advance(Duration::from_secs(1));
+        let (value, meta) = rx.try_recv_with_meta().unwrap();
+        assert_eq!(1, value);
+        assert_eq!(MessageMeta::default(), meta);
+        assert_eq!(0, rx.expired_count());
+    }
+
+    #[test]
+    fn message_ttl_and_deadline() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let now = clock.now_fn();
+        let (mut tx, mut rx) = builder(4)
+            .message_ttl(Duration::from_secs(10))
+            .message_meta(true)
+            .clock(clock.now_fn())
+            .build();
+
+        tx.try_send_with_deadline(1, now() + Duration::from_secs(1))
+            .unwrap();
+        tx.try_send_with_deadline(2, now() + Duration::from_secs(5))
+            .unwrap();
+        tx.try_send(3).unwrap();
+        clock.advance(Duration::from_secs(2));
+
+        // the first message missed its deadline, so the plain receive skips it
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(1, rx.expired_count());
+
+        let (value, meta) = rx.try_recv_with_meta().unwrap();
+        assert_eq!(3, value);
+        assert_eq!(None, meta.deadline);
+        assert_eq!(None, meta.remaining(now()));
+    }
+
+    #[tokio::test]
+    async fn send_with_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let (mut tx, mut rx) = builder(1).message_meta(true).build();
+
+        tx.send_with_deadline("job", deadline).await.unwrap();
+        let (value, meta) = rx.recv_with_meta().await.unwrap();
+        assert_eq!("job", value);
+        assert_eq!(Some(deadline), meta.deadline);
+        assert!(meta.remaining(Instant::now()).unwrap() <= Duration::from_secs(60));
+
+        drop(tx);
+        assert_eq!(None, rx.recv_with_meta().await);
+    }
+
+    #[test]
+    fn close_after_idle() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let (mut tx, mut rx) = builder(4)
+            .close_after_idle(Duration::from_secs(1))
+            .clock(clock.now_fn())
+            .build();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        clock.advance(Duration::from_secs(1));
+
+        // senders are rejected, and the receiver drains the buffer before it observes the closure
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut tx).poll_send(&mut cx, 2)
+        );
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn activity_resets_idle_timer() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let (mut tx, mut rx) = builder(4)
+            .close_after_idle(Duration::from_secs(1))
+            .clock(clock.now_fn())
+            .build();
+
+        clock.advance(Duration::from_millis(800));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+        clock.advance(Duration::from_millis(800));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(800));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        // a pending receive is not activity
+        clock.advance(Duration::from_millis(200));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut tx).poll_send(&mut cx, 2)
+        );
+    }
+
+    #[test]
+    fn idle_sweep_wakes_parked_receiver() {
+        let clock = MockClock::new();
+        let (tx, mut rx) = builder::<usize>(4)
+            .close_after_idle(Duration::from_secs(1))
+            .clock(clock.now_fn())
+            .idle_timer(clock.clone())
+            .build();
+
+        let probe = WakeProbe::new();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
+        );
+
+        clock.advance(Duration::from_millis(999));
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
+        );
+
+        let wakes = probe.count();
+        clock.advance(Duration::from_millis(1));
+        assert!(probe.count() > wakes);
+        assert_eq!(
+            PollRecv::Closed,
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
+        );
+
+        drop(tx);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn idle_runtime_sweep() {
+        let start = tokio::time::Instant::now();
+        let (_tx, mut rx) = builder::<usize>(4)
+            .close_after_idle(Duration::from_secs(1))
+            .build();
+
+        assert_eq!(None, rx.recv().await);
+        assert_eq!(Duration::from_secs(1), start.elapsed());
+    }
+
+    #[test]
+    fn poison_on_panic_thread() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = builder(4).poison_on_panic(true).build();
+        let tx2 = tx.clone();
+
+        let producer = std::thread::spawn(move || {
+            tx.try_send(1).expect("the channel has capacity");
+            panic!("the producer failed");
+        });
+        assert!(producer.join().is_err());
+        assert!(rx.is_poisoned());
+
+        // buffered messages are still delivered, and the channel closes once the other sender is dropped
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        drop(tx2);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(rx.is_poisoned());
+    }
+
+    #[tokio::test]
+    async fn poison_on_panic_task() {
+        let (mut tx, mut rx) = builder(4).poison_on_panic(true).build();
+
+        let producer = tokio::spawn(async move {
+            tx.send(1usize).await.expect("the receiver is alive");
+            panic!("the producer failed");
+        });
+        assert!(producer.await.unwrap_err().is_panic());
+
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+        assert!(rx.is_poisoned());
+    }
+
+    #[test]
+    fn graceful_drop_does_not_poison() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = builder::<usize>(4).poison_on_panic(true).build();
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(!rx.is_poisoned());
+    }
+
+    #[test]
+    fn panic_without_poisoning() {
+        let (tx, rx) = builder::<usize>(4).build();
+
+        let producer = std::thread::spawn(move || {
+            let _tx = tx;
+            panic!("the producer failed");
+        });
+        assert!(producer.join().is_err());
+        assert!(!rx.is_poisoned());
+    }
+
+    #[test]
+    fn layered_endpoints() {
+        let mut cx = noop_context();
+        let (gate_tx, gate_rx) = watch::channel_with(false);
+        let (mut tx, mut rx) = builder(4)
+            .prefill(vec![1])
+            .layer(GateLayer::new(gate_rx))
+            .sender_layer(DedupLayer::new())
+            .build();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        drop(gate_tx);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_max_senders() {
+        let _ = builder::<usize>(1).max_senders(0);
+    }
+}
@@ -0,0 +1,308 @@
+//! An mpsc channel with a compile-time capacity.
+//!
+//! The buffer is a fixed-size array, stored inline in the shared channel state.
+
+use std::fmt;
+
+use crate::{
+    channels::{NotSendMessage, SendMessage},
+    coop::Budget,
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+    sync::{shared, static_queue::StaticQueue, ReceiverShared, SenderShared},
+};
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+/// Constructs a pair of mpsc endpoints, with a fixed-size buffer of capacity `N`.
+///
+/// The capacity must be nonzero, which is checked at compile time.
+pub fn static_channel<T, const N: usize>() -> (StaticSender<T, N>, StaticReceiver<T, N>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating static mpsc channel with capacity {}", N);
+    let (tx_shared, rx_shared) = shared(StaticQueue::new());
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        "mpsc::static_channel",
+        std::any::type_name::<T>(),
+        Some(N),
+        StaticQueue::len,
+    );
+
+    let sender = StaticSender { shared: tx_shared };
+
+    let receiver = StaticReceiver {
+        shared: rx_shared,
+        budget: Budget::new(),
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
+
+    (sender, receiver)
+}
+
+/// The sender half of a static mpsc channel.  Can send messages with the postage::Sink trait.
+///
+/// Can be cloned.
+pub struct StaticSender<T, const N: usize> {
+    shared: SenderShared<StaticQueue<T, N>>,
+}
+
+assert_impl_all!(StaticSender<SendMessage, 4>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(StaticSender<NotSendMessage, 4>: Send, Sync);
+
+impl<T, const N: usize> StaticSender<T, N> {
+    /// Returns the number of messages in the channel
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// Returns true if the channel contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.shared.extension().is_empty()
+    }
+
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Clone for StaticSender<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> Sink for StaticSender<T, N> {
+    type Item = T;
+
+    fn poll_send(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        mut value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        loop {
+            if self.shared.is_closed() {
+                return PollSend::Rejected(value);
+            }
+
+            let guard = self.shared.recv_guard();
+            match self.shared.extension().push(value) {
+                Ok(_) => {
+                    self.shared.notify_receivers();
+                    return PollSend::Ready;
+                }
+                Err(v) => {
+                    self.shared.subscribe_recv(cx);
+
+                    if guard.is_expired() {
+                        value = v;
+                        continue;
+                    }
+
+                    return PollSend::Pending(v);
+                }
+            }
+        }
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
+        PollSend::Ready
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for StaticSender<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSender").finish()
+    }
+}
+
+#[cfg(feature = "futures-traits")]
+mod impl_futures {
+    use crate::sink::SendError;
+    use std::task::Poll;
+
+    impl<T, const N: usize> futures::sink::Sink<T> for super::StaticSender<T, N> {
+        type Error = SendError<T>;
+
+        fn poll_ready(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            loop {
+                if self.shared.is_closed() {
+                    return Poll::Ready(Ok(()));
+                }
+
+                let guard = self.shared.recv_guard();
+
+                if self.shared.extension().len() == N {
+                    let cx = cx.into();
+                    self.shared.subscribe_recv(&cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    return Poll::Pending;
+                } else {
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
+
+        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+            if self.shared.is_closed() {
+                return Err(SendError(item));
+            }
+
+            let result = self.shared.extension().push(item).map_err(SendError);
+
+            if result.is_ok() {
+                self.shared.notify_receivers();
+            }
+
+            result
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+/// The receiver half of a static mpsc channel.  Cannot be cloned.
+///
+/// Can receive messages with the postage::Stream trait.
+pub struct StaticReceiver<T, const N: usize> {
+    shared: ReceiverShared<StaticQueue<T, N>>,
+    budget: Budget,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
+}
+
+assert_impl_all!(StaticReceiver<SendMessage, 4>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(StaticReceiver<SendMessage, 4>: Clone);
+assert_not_impl_any!(StaticReceiver<NotSendMessage, 4>: Send, Sync);
+
+impl<T, const N: usize> StaticReceiver<T, N> {
+    /// Returns the number of messages in the channel
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// Returns true if the channel contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.shared.extension().is_empty()
+    }
+
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Stream for StaticReceiver<T, N> {
+    type Item = T;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        if this.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
+        loop {
+            let guard = this.shared.send_guard();
+            match this.shared.extension().pop() {
+                Some(v) => {
+                    this.shared.notify_senders();
+                    this.budget.consume();
+                    return PollRecv::Ready(v);
+                }
+                None => {
+                    if this.shared.is_closed() {
+                        return PollRecv::Closed;
+                    }
+
+                    this.shared.subscribe_send(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    this.budget.reset();
+                    return PollRecv::Pending;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let closed = self.shared.is_closed();
+        let len = self.len();
+
+        (len, if closed { Some(len) } else { None })
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for StaticReceiver<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticReceiver").finish()
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::{task::spawn, time::timeout};
+
+    use crate::{
+        sink::Sink,
+        stream::Stream,
+        test::{Channels, Message, CHANNEL_TEST_SENDERS, TEST_TIMEOUT},
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn multi_sender() {
+        let (tx, mut rx) = super::static_channel::<Message, 4>();
+
+        for i in 0..CHANNEL_TEST_SENDERS {
+            let mut tx2 = tx.clone();
+            spawn(async move {
+                for message in Message::new_multi_sender(i) {
+                    tx2.send(message).await.expect("send failed");
+                }
+            });
+        }
+
+        drop(tx);
+
+        let rx_handle = spawn(async move {
+            let mut channel = Channels::new(CHANNEL_TEST_SENDERS);
+            while let Some(message) = rx.recv().await {
+                channel.assert_message(&message);
+            }
+        });
+
+        timeout(TEST_TIMEOUT, rx_handle)
+            .await
+            .expect("test timeout")
+            .expect("join error");
+    }
+}
@@ -1,16 +1,24 @@
 //! Oneshot channels transmit a single value between a sender and a reciever.  
 //!
 //! Neither can be cloned.  If the sender drops, the receiver recieves a `None` value.
+//!
+//! After the receiver has received the value, it is closed, and subsequent polls return `PollRecv::Closed`.
+//!
+//! If many tasks await the same value, `shared_channel` constructs a receiver which can be cloned.
 use std::fmt;
 use std::sync::Arc;
 
-use super::SendMessage;
+use super::{NotSendMessage, SendMessage};
 use crate::{
     sink::{PollSend, Sink},
     stream::{PollRecv, Stream},
     sync::transfer::Transfer,
 };
-use static_assertions::{assert_impl_all, assert_not_impl_all};
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+mod shared;
+
+pub use shared::{shared_channel, SharedReceiver, SharedSender};
 
 /// Constructs a pair of oneshot endpoints
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
@@ -22,7 +30,11 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
         shared: shared.clone(),
     };
 
-    let receiver = Receiver { shared };
+    let receiver = Receiver {
+        shared,
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
 
     (sender, receiver)
 }
@@ -34,6 +46,7 @@ pub struct Sender<T> {
 
 assert_impl_all!(Sender<SendMessage>: Send, Sync, fmt::Debug);
 assert_not_impl_all!(Sender<SendMessage>: Clone);
+assert_not_impl_any!(Sender<NotSendMessage>: Send, Sync);
 
 impl<T> Sink for Sender<T> {
     type Item = T;
@@ -48,6 +61,11 @@ impl<T> Sink for Sender<T> {
             Err(v) => PollSend::Rejected(v),
         }
     }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.sender_disconnect();
+        PollSend::Ready
+    }
 }
 
 impl<T> fmt::Debug for Sender<T> {
@@ -98,12 +116,17 @@ impl<T> Drop for Sender<T> {
 }
 
 /// The receiver half of a oneshot channel.  Can recieve a single message (or none if the sender drops) with the postage::Stream trait.
+///
+/// Once the message has been received, the receiver returns `PollRecv::Closed`.
 pub struct Receiver<T> {
     pub(in crate::channels::oneshot) shared: Arc<Transfer<T>>,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
 }
 
-assert_impl_all!(Sender<SendMessage>: Send, Sync, fmt::Debug);
-assert_not_impl_all!(Sender<SendMessage>: Clone);
+assert_impl_all!(Receiver<SendMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(Receiver<SendMessage>: Clone);
+assert_not_impl_any!(Receiver<NotSendMessage>: Send, Sync);
 
 impl<T> Stream for Receiver<T> {
     type Item = T;
@@ -114,6 +137,19 @@ impl<T> Stream for Receiver<T> {
     ) -> PollRecv<Self::Item> {
         self.shared.recv(cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.shared.size_hint()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Returns true if the receiver has received the value.
+    ///
+    /// Returns false if the sender was dropped without sending a value.
+    pub fn is_consumed(&self) -> bool {
+        self.shared.is_consumed()
+    }
 }
 
 impl<T> Drop for Receiver<T> {
@@ -130,7 +166,14 @@ impl<T> fmt::Debug for Receiver<T> {
 
 #[cfg(test)]
 mod tests {
-    use std::pin::Pin;
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
 
     use crate::{
         sink::{PollSend, Sink},
@@ -187,6 +230,19 @@ mod tests {
         assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
     }
 
+    #[test]
+    fn sender_close() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_close(&mut cx));
+        assert_eq!(
+            PollSend::Rejected(Message(1)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
     #[test]
     fn sender_disconnect_after_poll() {
         let mut cx = noop_context();
@@ -284,6 +340,143 @@ mod tests {
             Pin::new(&mut rx).poll_recv(&mut w1_context)
         );
     }
+
+    #[test]
+    fn poll_after_consumed() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+        assert!(!rx.is_consumed());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert!(!rx.is_consumed());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert!(rx.is_consumed());
+
+        // the sender is still alive, but the receiver is closed
+        for _ in 0..3 {
+            assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        }
+        assert!(rx.is_consumed());
+    }
+
+    #[test]
+    fn sender_disconnect_not_consumed() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel::<Message>();
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(!rx.is_consumed());
+    }
+
+    #[test]
+    fn size_hint() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+        assert_eq!((0, Some(1)), rx.size_hint());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!((1, Some(1)), rx.size_hint());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!((0, Some(0)), rx.size_hint());
+    }
+
+    #[test]
+    fn size_hint_sender_disconnect() {
+        let (tx, rx) = channel::<Message>();
+
+        drop(tx);
+        assert_eq!((0, Some(0)), rx.size_hint());
+    }
+
+    struct DropCounter(Arc<AtomicUsize>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn value_dropped_once_after_recv() {
+        let mut cx = noop_context();
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut tx, mut rx) = channel();
+
+        assert!(matches!(
+            Pin::new(&mut tx).poll_send(&mut cx, DropCounter(drops.clone())),
+            PollSend::Ready
+        ));
+
+        let value = match Pin::new(&mut rx).poll_recv(&mut cx) {
+            PollRecv::Ready(value) => value,
+            _ => panic!("expected a value"),
+        };
+        assert_eq!(0, drops.load(Ordering::SeqCst));
+
+        drop(value);
+        drop(tx);
+        drop(rx);
+        assert_eq!(1, drops.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn value_dropped_once_without_recv() {
+        let mut cx = noop_context();
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut tx, rx) = channel();
+
+        assert!(matches!(
+            Pin::new(&mut tx).poll_send(&mut cx, DropCounter(drops.clone())),
+            PollSend::Ready
+        ));
+
+        drop(rx);
+        assert_eq!(0, drops.load(Ordering::SeqCst));
+
+        drop(tx);
+        assert_eq!(1, drops.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn value_dropped_once_during_recv_future() {
+        let mut cx = noop_context();
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (mut tx, mut rx) = channel();
+
+        let mut recv = Box::pin(rx.recv());
+        assert!(recv
+            .as_mut()
+            .poll(&mut futures_test::task::noop_context())
+            .is_pending());
+
+        assert!(matches!(
+            Pin::new(&mut tx).poll_send(&mut cx, DropCounter(drops.clone())),
+            PollSend::Ready
+        ));
+
+        // the future and receiver are dropped before the value is received
+        drop(recv);
+        drop(rx);
+        assert_eq!(0, drops.load(Ordering::SeqCst));
+
+        drop(tx);
+        assert_eq!(1, drops.load(Ordering::SeqCst));
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,312 @@
+use std::{fmt, sync::Arc};
+
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+use crate::{
+    channels::{NotSendMessage, SendMessage, SendSyncMessage},
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+    sync::transfer::RetainingTransfer,
+};
+
+/// Constructs a pair of oneshot endpoints, with a receiver which can be cloned.
+///
+/// Every receiver observes a clone of the sent value exactly once, including receivers cloned after the value was
+/// sent.  If the sender is dropped without sending a value, the receivers are closed.
+/// ```rust
+/// use postage::{oneshot, prelude::*};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut tx, rx) = oneshot::shared_channel();
+///
+///     let mut waiters = Vec::new();
+///     for _ in 0..4 {
+///         let mut rx = rx.clone();
+///         waiters.push(tokio::spawn(async move { rx.recv().await }));
+///     }
+///
+///     tx.send("config").await.ok();
+///     for waiter in waiters {
+///         assert_eq!(Some("config"), waiter.await.unwrap());
+///     }
+/// }
+/// ```
+pub fn shared_channel<T: Clone>() -> (SharedSender<T>, SharedReceiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating shared oneshot channel");
+
+    let shared = Arc::new(RetainingTransfer::new());
+    let sender = SharedSender {
+        shared: shared.clone(),
+    };
+
+    let receiver = SharedReceiver {
+        shared,
+        received: false,
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
+
+    (sender, receiver)
+}
+
+/// The sender half of a shared oneshot channel.  Can transmit a single message with the postage::Sink trait.
+pub struct SharedSender<T> {
+    shared: Arc<RetainingTransfer<T>>,
+}
+
+assert_impl_all!(SharedSender<SendSyncMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(SharedSender<SendSyncMessage>: Clone);
+assert_not_impl_any!(SharedSender<SendMessage>: Send, Sync);
+
+impl<T> Sink for SharedSender<T> {
+    type Item = T;
+
+    fn poll_send(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        match self.shared.send(value) {
+            Ok(_) => PollSend::Ready,
+            Err(v) => PollSend::Rejected(v),
+        }
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.sender_disconnect();
+        PollSend::Ready
+    }
+}
+
+impl<T> Drop for SharedSender<T> {
+    fn drop(&mut self) {
+        self.shared.sender_disconnect();
+    }
+}
+
+impl<T> fmt::Debug for SharedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedSender").finish()
+    }
+}
+
+#[cfg(feature = "futures-traits")]
+mod impl_futures {
+    use crate::sink::SendError;
+    use std::task::Poll;
+
+    impl<T> futures::sink::Sink<T> for super::SharedSender<T> {
+        type Error = SendError<T>;
+
+        fn poll_ready(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+            self.shared.send(item).map_err(SendError)
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+/// The receiver half of a shared oneshot channel.  Can be cloned.
+///
+/// Receives a clone of the value with the postage::Stream trait, and then returns `PollRecv::Closed`.
+/// Clones of the receiver receive the value independently.
+pub struct SharedReceiver<T> {
+    shared: Arc<RetainingTransfer<T>>,
+    received: bool,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
+}
+
+assert_impl_all!(SharedReceiver<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(SharedReceiver<SendMessage>: Send, Sync);
+assert_not_impl_any!(SharedReceiver<NotSendMessage>: Send, Sync);
+
+impl<T> SharedReceiver<T> {
+    /// Returns true if this receiver has received the value.
+    pub fn is_consumed(&self) -> bool {
+        self.received
+    }
+}
+
+impl<T: Clone> Stream for SharedReceiver<T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+        if this.received {
+            return PollRecv::Closed;
+        }
+
+        match this.shared.recv(cx) {
+            PollRecv::Ready(value) => {
+                this.received = true;
+                PollRecv::Ready(value.clone())
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.received {
+            return (0, Some(0));
+        }
+
+        // the sender stores the value before disconnecting, so the liveness is loaded first
+        let alive = self.shared.is_sender_alive();
+        if self.shared.is_ready() {
+            (1, Some(1))
+        } else if alive {
+            (0, Some(1))
+        } else {
+            (0, Some(0))
+        }
+    }
+}
+
+impl<T> Clone for SharedReceiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.receiver_connect();
+
+        Self {
+            shared: self.shared.clone(),
+            received: false,
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
+        }
+    }
+}
+
+impl<T> Drop for SharedReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_disconnect();
+    }
+}
+
+impl<T> fmt::Debug for SharedReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedReceiver").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+        test_util::WakeProbe,
+    };
+
+    use super::shared_channel;
+
+    #[test]
+    fn waiters_and_late_clones() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = shared_channel();
+
+        let probes: Vec<_> = (0..3).map(|_| WakeProbe::new()).collect();
+        let mut waiters: Vec<_> = probes.iter().map(|_| rx.clone()).collect();
+        for (waiter, probe) in waiters.iter_mut().zip(&probes) {
+            assert_eq!(
+                PollRecv::Pending,
+                Pin::new(waiter).poll_recv(&mut probe.context())
+            );
+        }
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert!(probes.iter().all(|probe| probe.count() > 0));
+
+        // clones created after the send observe the value, including clones of receivers which observed it
+        let mut first = waiters.remove(0);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut first).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut first).poll_recv(&mut cx));
+        waiters.push(first.clone());
+        waiters.extend((0..2).map(|_| rx.clone()));
+        waiters.push(rx);
+
+        for mut waiter in waiters {
+            assert_eq!(PollRecv::Ready(1), Pin::new(&mut waiter).poll_recv(&mut cx));
+            assert!(waiter.is_consumed());
+            assert_eq!(PollRecv::Closed, Pin::new(&mut waiter).poll_recv(&mut cx));
+        }
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = shared_channel::<usize>();
+        let mut rx2 = rx.clone();
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx2).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn send_then_disconnect() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = shared_channel();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut rx.clone()).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn send_once() {
+        let mut cx = noop_context();
+        let (mut tx, _rx) = shared_channel();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut tx).poll_send(&mut cx, 2)
+        );
+    }
+
+    #[test]
+    fn receivers_disconnect() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = shared_channel();
+        let rx2 = rx.clone();
+
+        drop(rx);
+        drop(rx2);
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut tx).poll_send(&mut cx, 1)
+        );
+    }
+}
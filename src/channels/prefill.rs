@@ -0,0 +1,63 @@
+use crate::sink::Sink;
+
+use super::capacity::Capacity;
+
+// Messages which seed a channel when it is built.  They count against the capacity of the channel.
+pub(crate) struct Prefill<T> {
+    messages: Vec<T>,
+    grow_to_fit: bool,
+}
+
+impl<T> Prefill<T> {
+    pub fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            grow_to_fit: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn extend<I>(&mut self, messages: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.messages.extend(messages);
+    }
+
+    pub fn set_grow_to_fit(&mut self, grow_to_fit: bool) {
+        self.grow_to_fit = grow_to_fit;
+    }
+
+    // Returns the capacity of the channel, which fits the messages if grow_to_fit is set
+    #[track_caller]
+    pub fn capacity(&self, capacity: Capacity) -> usize {
+        let capacity = capacity.get();
+        let len = self.messages.len();
+
+        if len <= capacity {
+            capacity
+        } else if self.grow_to_fit {
+            len
+        } else {
+            panic!(
+                "the channel was prefilled with {} messages, which exceeds the capacity of {}",
+                len, capacity
+            );
+        }
+    }
+
+    // Sends the messages in order.  The sender must have capacity for all of them
+    pub fn send_into<S>(self, sender: &mut S)
+    where
+        S: Sink<Item = T> + Unpin,
+    {
+        for message in self.messages {
+            if sender.try_send(message).is_err() {
+                unreachable!("the channel has capacity for the prefilled messages");
+            }
+        }
+    }
+}
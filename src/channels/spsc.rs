@@ -0,0 +1,574 @@
+//! A single-producer, single-consumer ring buffer channel, with wait-free `try_send` and `try_recv`.
+//!
+//! The channel is intended for realtime threads, such as audio callbacks, which exchange messages with async tasks.
+//! Neither endpoint can be cloned.
+//!
+//! ## Realtime guarantees
+//! - `Sender::try_send` and `Receiver::try_recv` are wait-free.  They complete in a bounded number of atomic
+//!   operations, and never lock, spin, allocate, or make syscalls.  The buffer is allocated when the channel is
+//!   constructed.
+//! - If the other endpoint is parked in an async operation (`recv().await` or `send().await`), a successful
+//!   `try_send` or `try_recv` wakes it.  This happens at most once per park, and is detected with a single atomic
+//!   load, but the wake runs the executor's waker, which may lock or make syscalls.  If the other endpoint only uses
+//!   the `try_` methods, no waker is ever called.
+//! - The async paths (`send`, `recv` and `poll_send`/`poll_recv` with a waker) store a waker, and are not wait-free.
+//! - Dropping an endpoint wakes the other endpoint, if it is parked.  Dropping the last endpoint frees the buffer.
+//!
+//! ```rust
+//! use postage::{prelude::*, spsc};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut tx, mut rx) = spsc::channel(64);
+//!
+//!     // the realtime thread never blocks, and drops samples if the buffer is full
+//!     let audio = std::thread::spawn(move || {
+//!         for sample in 0..16u32 {
+//!             while tx.try_send(sample).is_err() {
+//!                 std::thread::yield_now();
+//!             }
+//!         }
+//!     });
+//!
+//!     let mut received = Vec::new();
+//!     while let Some(sample) = rx.recv().await {
+//!         received.push(sample);
+//!     }
+//!
+//!     audio.join().unwrap();
+//!     assert_eq!((0..16).collect::<Vec<_>>(), received);
+//! }
+//! ```
+
+use std::{fmt, sync::atomic::Ordering, sync::Arc};
+
+use static_assertions::{assert_impl_all, assert_not_impl_all, assert_not_impl_any};
+
+use crate::{
+    channels::{capacity::Capacity, NotSendMessage, SendMessage},
+    coop::Budget,
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+};
+
+mod ring;
+
+use ring::Ring;
+
+/// Constructs a pair of spsc endpoints, with a ring buffer of the given capacity.
+///
+/// Panics if the capacity is zero, or is [too large](../struct.Capacity.html#method.max_for) for the buffer.
+#[track_caller]
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = Capacity::expect::<T>(capacity).get();
+
+    #[cfg(feature = "debug")]
+    log::error!("Creating spsc channel with capacity {}", capacity);
+
+    let ring = Arc::new(Ring::new(capacity));
+    #[cfg(feature = "registry")]
+    ring.register("spsc::channel", std::any::type_name::<T>());
+
+    let sender = Sender { ring: ring.clone() };
+    let receiver = Receiver {
+        ring,
+        budget: Budget::new(),
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
+
+    (sender, receiver)
+}
+
+/// The sender half of an spsc channel.  Cannot be cloned.
+///
+/// Sends messages with the postage::Sink trait.  `try_send` is wait-free.
+pub struct Sender<T> {
+    ring: Arc<Ring<T>>,
+}
+
+assert_impl_all!(Sender<SendMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(Sender<SendMessage>: Clone);
+assert_not_impl_any!(Sender<NotSendMessage>: Send, Sync);
+
+impl<T> Sender<T> {
+    /// Returns the number of messages in the channel
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns true if the channel contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.ring.rx_closed.load(Ordering::Acquire) || self.ring.tx_closed.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Sink for Sender<T> {
+    type Item = T;
+
+    fn poll_send(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        if self.is_closed() {
+            return PollSend::Rejected(value);
+        }
+
+        let value = match self.ring.push(value) {
+            Ok(()) => {
+                self.ring.rx_parker.unpark();
+                return PollSend::Ready;
+            }
+            Err(value) => value,
+        };
+
+        // try_send polls without a waker, and never parks
+        let waker = match cx.waker() {
+            Some(waker) => waker,
+            None => return PollSend::Pending(value),
+        };
+
+        self.ring.tx_parker.park(waker);
+
+        // the receiver may have released a slot before the sender was parked
+        match self.ring.push(value) {
+            Ok(()) => {
+                self.ring.rx_parker.unpark();
+                PollSend::Ready
+            }
+            Err(value) if self.is_closed() => PollSend::Rejected(value),
+            Err(value) => PollSend::Pending(value),
+        }
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.ring.tx_closed.store(true, Ordering::SeqCst);
+        self.ring.rx_parker.unpark();
+        PollSend::Ready
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "registry")]
+        self.ring.counters.senders.decrement();
+
+        self.ring.tx_closed.store(true, Ordering::SeqCst);
+        self.ring.rx_parker.unpark();
+    }
+}
+
+#[cfg(feature = "futures-traits")]
+mod impl_futures {
+    use crate::sink::SendError;
+    use std::task::Poll;
+
+    impl<T> futures::sink::Sink<T> for super::Sender<T> {
+        type Error = SendError<T>;
+
+        fn poll_ready(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            if self.is_closed() || !self.ring.is_full() {
+                return Poll::Ready(Ok(()));
+            }
+
+            self.ring.tx_parker.park(cx.waker());
+
+            if self.is_closed() || !self.ring.is_full() {
+                return Poll::Ready(Ok(()));
+            }
+
+            Poll::Pending
+        }
+
+        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+            if self.is_closed() {
+                return Err(SendError(item));
+            }
+
+            self.ring.push(item).map_err(SendError)?;
+            self.ring.rx_parker.unpark();
+
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+/// The receiver half of an spsc channel.  Cannot be cloned.
+///
+/// Receives messages with the postage::Stream trait.  `try_recv` is wait-free.
+pub struct Receiver<T> {
+    ring: Arc<Ring<T>>,
+    budget: Budget,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
+}
+
+assert_impl_all!(Receiver<SendMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(Receiver<SendMessage>: Clone);
+assert_not_impl_any!(Receiver<NotSendMessage>: Send, Sync);
+
+impl<T> Receiver<T> {
+    /// Returns the number of messages in the channel
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns true if the channel contains no messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let value = self.ring.pop()?;
+        self.ring.tx_parker.unpark();
+        self.budget.consume();
+
+        Some(value)
+    }
+
+    // the sender closes the channel after its last push, so the ring is checked again
+    fn poll_closed(&mut self) -> Option<PollRecv<T>> {
+        if !self.ring.tx_closed.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        Some(match self.pop() {
+            Some(value) => PollRecv::Ready(value),
+            None => PollRecv::Closed,
+        })
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        if this.budget.poll_proceed(cx).is_pending() {
+            return PollRecv::Pending;
+        }
+
+        if let Some(value) = this.pop() {
+            return PollRecv::Ready(value);
+        }
+
+        if let Some(poll) = this.poll_closed() {
+            return poll;
+        }
+
+        // try_recv polls without a waker, and never parks
+        let waker = match cx.waker() {
+            Some(waker) => waker,
+            None => return PollRecv::Pending,
+        };
+
+        this.ring.rx_parker.park(waker);
+
+        // the sender may have published a message before the receiver was parked
+        if let Some(value) = this.pop() {
+            return PollRecv::Ready(value);
+        }
+
+        if let Some(poll) = this.poll_closed() {
+            return poll;
+        }
+
+        this.budget.reset();
+        PollRecv::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // the closed flag is loaded first, so all messages sent before the close are counted
+        let closed = self.ring.tx_closed.load(Ordering::SeqCst);
+        let len = self.len();
+
+        (len, if closed { Some(len) } else { None })
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "registry")]
+        self.ring.counters.receivers.decrement();
+
+        self.ring.rx_closed.store(true, Ordering::SeqCst);
+        self.ring.tx_parker.unpark();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, sync::Arc};
+
+    use crate::{
+        sink::{PollSend, Sink, TrySendError},
+        stream::{PollRecv, Stream, TryRecvError},
+        test::{noop_context, CHANNEL_TEST_ITERATIONS},
+        test_util::WakeProbe,
+    };
+
+    use super::channel;
+
+    #[test]
+    #[should_panic(expected = "the capacity must be greater than zero")]
+    fn zero_capacity() {
+        let _ = channel::<usize>(0);
+    }
+
+    #[test]
+    fn send_recv() {
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(Err(TrySendError::Pending(3)), tx.try_send(3));
+        assert_eq!(2, rx.len());
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+    }
+
+    #[test]
+    fn sender_drop_delivers_remaining() {
+        let (mut tx, mut rx) = channel(4);
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        drop(tx);
+
+        assert_eq!((2, Some(2)), rx.size_hint());
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+    }
+
+    #[test]
+    fn sender_close() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+        tx.try_send(1).unwrap();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_close(&mut cx));
+        assert_eq!(Err(TrySendError::Rejected(2)), tx.try_send(2));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+    }
+
+    #[test]
+    fn receiver_drop_rejects() {
+        let (mut tx, rx) = channel(4);
+        drop(rx);
+
+        assert_eq!(Err(TrySendError::Rejected(1)), tx.try_send(1));
+    }
+
+    #[test]
+    fn drops_undelivered_messages() {
+        let message = Arc::new(());
+        let (mut tx, rx) = channel(4);
+        tx.try_send(message.clone()).unwrap();
+        tx.try_send(message.clone()).unwrap();
+
+        drop(rx);
+        assert_eq!(3, Arc::strong_count(&message));
+        drop(tx);
+        assert_eq!(1, Arc::strong_count(&message));
+    }
+
+    #[test]
+    fn try_send_wakes_parked_receiver() {
+        let (mut tx, mut rx) = channel(4);
+        let probe = WakeProbe::new();
+
+        // the receiver is not parked, so the sender does not call a waker
+        tx.try_send(1).unwrap();
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
+        );
+        probe.assert_not_woken();
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
+        );
+        tx.try_send(2).unwrap();
+        tx.try_send(3).unwrap();
+        probe.assert_woken(1);
+
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+
+    #[test]
+    fn try_recv_wakes_parked_sender() {
+        let (mut tx, mut rx) = channel(1);
+        let probe = WakeProbe::new();
+
+        tx.try_send(1).unwrap();
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
+        );
+
+        assert_eq!(Ok(1), rx.try_recv());
+        probe.assert_woken(1);
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
+        );
+    }
+
+    #[test]
+    fn drop_wakes_parked_endpoint() {
+        let (tx, mut rx) = channel::<usize>(1);
+        let probe = WakeProbe::new();
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut probe.context())
+        );
+        drop(tx);
+        probe.assert_woken(1);
+        assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+
+        let (mut tx, rx) = channel(1);
+        tx.try_send(1).unwrap();
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut probe.context(), 2)
+        );
+        drop(rx);
+        probe.assert_woken(2);
+        assert_eq!(Err(TrySendError::Rejected(2)), tx.try_send(2));
+    }
+
+    // the debug feature formats log messages, which allocates
+    #[test]
+    #[cfg(not(feature = "debug"))]
+    fn try_paths_do_not_allocate() {
+        let (mut tx, mut rx) = channel::<usize>(4);
+
+        let allocations = crate::test::alloc::count_allocations(|| {
+            for i in 0..1000 {
+                tx.try_send(i).unwrap();
+                tx.try_send(i).unwrap();
+                assert_eq!(Ok(i), rx.try_recv());
+                assert_eq!(Ok(i), rx.try_recv());
+                assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+            }
+
+            for i in 0..4 {
+                tx.try_send(i).unwrap();
+            }
+            assert_eq!(Err(TrySendError::Pending(4)), tx.try_send(4));
+        });
+        assert_eq!(0, allocations);
+    }
+
+    #[tokio::test]
+    async fn realtime_producer() {
+        const MESSAGES: usize = 10_000;
+        let (mut tx, mut rx) = channel(16);
+
+        // the producer only uses try_send, as a realtime thread would
+        let producer = std::thread::spawn(move || {
+            for i in 0..MESSAGES {
+                let mut value = i;
+                loop {
+                    match tx.try_send(value) {
+                        Ok(()) => break,
+                        Err(TrySendError::Pending(v)) => {
+                            value = v;
+                            std::hint::spin_loop();
+                        }
+                        Err(TrySendError::Rejected(_)) => panic!("the receiver is alive"),
+                    }
+                }
+            }
+        });
+
+        let mut received = 0;
+        while let Some(value) = rx.recv().await {
+            assert_eq!(received, value);
+            received += 1;
+        }
+
+        producer.join().expect("the producer panicked");
+        assert_eq!(MESSAGES, received);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn async_endpoints() {
+        for _ in 0..CHANNEL_TEST_ITERATIONS / 10 {
+            let (mut tx, mut rx) = channel(2);
+
+            let sender = tokio::spawn(async move {
+                for i in 0..64usize {
+                    tx.send(i).await.expect("the receiver is alive");
+                }
+            });
+
+            for i in 0..64usize {
+                assert_eq!(Some(i), rx.recv().await);
+            }
+            assert_eq!(None, rx.recv().await);
+
+            sender.await.unwrap();
+        }
+    }
+}
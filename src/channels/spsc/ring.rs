@@ -0,0 +1,259 @@
+//! The wait-free ring buffer and parking flags of the spsc channel.
+
+use std::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+    task::Waker,
+};
+
+// A fixed-capacity ring, with exactly one producer and one consumer.
+//
+// `head` is only written by the consumer, and `tail` is only written by the producer, so `push` and `pop` complete
+// in a bounded number of atomic operations, without locks or retries.  The indices increase monotonically,
+// and wrap around the slots.
+pub(super) struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // the index of the next slot to read
+    head: AtomicUsize,
+    // the index of the next slot to write
+    tail: AtomicUsize,
+    // set when the producer is dropped or closed
+    pub tx_closed: AtomicBool,
+    // set when the consumer is dropped
+    pub rx_closed: AtomicBool,
+    // the endpoint counts and length, which are read by the registry
+    #[cfg(feature = "registry")]
+    pub counters: std::sync::Arc<crate::sync::ChannelCounters>,
+    // the registry id, or zero if the channel is not registered
+    #[cfg(feature = "registry")]
+    registration: std::sync::atomic::AtomicU64,
+    // the consumer parks here while the ring is empty
+    pub rx_parker: Parker,
+    // the producer parks here while the ring is full
+    pub tx_parker: Parker,
+}
+
+// slots are only accessed by the side which owns them, which is determined by the indices
+unsafe impl<T: Send> Send for Ring<T> {}
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Ring<T> {
+    pub fn new(capacity: usize) -> Self {
+        let slots = (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect();
+
+        Self {
+            slots,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            tx_closed: AtomicBool::new(false),
+            rx_closed: AtomicBool::new(false),
+            #[cfg(feature = "registry")]
+            counters: std::sync::Arc::new(crate::sync::ChannelCounters::new()),
+            #[cfg(feature = "registry")]
+            registration: std::sync::atomic::AtomicU64::new(0),
+            rx_parker: Parker::new(),
+            tx_parker: Parker::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    #[cfg(feature = "futures-traits")]
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+
+    // must only be called by the producer
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == self.capacity() {
+            return Err(value);
+        }
+
+        let slot = &self.slots[tail % self.capacity()];
+        unsafe { (*slot.get()).as_mut_ptr().write(value) };
+
+        // seqcst orders the publish before the producer checks whether the consumer is parked
+        self.tail.store(tail.wrapping_add(1), Ordering::SeqCst);
+        #[cfg(feature = "registry")]
+        self.counters.publish_len(self.len());
+        Ok(())
+    }
+
+    // must only be called by the consumer
+    pub fn pop(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::SeqCst);
+        if head == tail {
+            return None;
+        }
+
+        let slot = &self.slots[head % self.capacity()];
+        let value = unsafe { (*slot.get()).as_ptr().read() };
+
+        // seqcst orders the release before the consumer checks whether the producer is parked
+        self.head.store(head.wrapping_add(1), Ordering::SeqCst);
+        #[cfg(feature = "registry")]
+        self.counters.publish_len(self.len());
+        Some(value)
+    }
+
+    // registers the ring in the global registry
+    #[cfg(feature = "registry")]
+    pub fn register(&self, name: &'static str, item_type: &'static str) {
+        let counters = self.counters.clone();
+        let id = crate::registry::register(name, item_type, Some(self.capacity()), counters);
+        self.registration.store(id, Ordering::Release);
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "registry")]
+        {
+            let id = *self.registration.get_mut();
+            if id != 0 {
+                crate::registry::unregister(id);
+            }
+        }
+
+        while self.pop().is_some() {}
+    }
+}
+
+const IDLE: u8 = 0;
+const PARKED: u8 = 1;
+const WAKING: u8 = 2;
+
+// A waker slot for one side of the ring, which the other side wakes.
+//
+// `unpark` is wait-free: a single load if the side is not parked, and otherwise a single compare-exchange
+// before the waker is taken.  `park` may briefly spin while the other side takes the previous waker.
+pub(super) struct Parker {
+    state: AtomicU8,
+    // owned by the parking side while the state is IDLE, and by the waking side while the state is WAKING
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+impl Parker {
+    pub fn new() -> Self {
+        Self {
+            state: AtomicU8::new(IDLE),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    // stores the waker.  the caller must check its condition again afterwards, as the other side may have acted
+    // before the parked flag was set
+    pub fn park(&self, waker: &Waker) {
+        loop {
+            match self.state.compare_exchange_weak(
+                PARKED,
+                IDLE,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) | Err(IDLE) => break,
+                Err(_) => std::hint::spin_loop(),
+            }
+        }
+
+        let slot = unsafe { &mut *self.waker.get() };
+        match slot {
+            Some(stored) if stored.will_wake(waker) => {}
+            _ => *slot = Some(waker.clone()),
+        }
+
+        self.state.store(PARKED, Ordering::SeqCst);
+    }
+
+    pub fn unpark(&self) {
+        if self.state.load(Ordering::SeqCst) != PARKED {
+            return;
+        }
+
+        if self
+            .state
+            .compare_exchange(PARKED, WAKING, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let waker = unsafe { (*self.waker.get()).take() };
+            self.state.store(IDLE, Ordering::Release);
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub fn is_parked(&self) -> bool {
+        self.state.load(Ordering::Acquire) == PARKED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::test_util::WakeProbe;
+
+    use super::{Parker, Ring};
+
+    #[test]
+    fn push_pop_wraps() {
+        let ring = Ring::new(2);
+
+        for i in 0..10 {
+            assert_eq!(Ok(()), ring.push(i));
+            assert_eq!(Ok(()), ring.push(i + 100));
+            assert_eq!(Err(0), ring.push(0));
+            assert_eq!(2, ring.len());
+
+            assert_eq!(Some(i), ring.pop());
+            assert_eq!(Some(i + 100), ring.pop());
+            assert_eq!(None, ring.pop());
+        }
+    }
+
+    #[test]
+    fn drops_remaining() {
+        let value = Arc::new(());
+        let ring = Ring::new(4);
+        ring.push(value.clone()).unwrap();
+        ring.push(value.clone()).unwrap();
+        assert_eq!(Some(&value), ring.pop().as_ref());
+
+        drop(ring);
+        assert_eq!(1, Arc::strong_count(&value));
+    }
+
+    #[test]
+    fn unpark_wakes_once() {
+        let parker = Parker::new();
+        let probe = WakeProbe::new();
+
+        // without a parked waker, unpark does nothing
+        parker.unpark();
+
+        parker.park(probe.context().waker().unwrap());
+        assert!(parker.is_parked());
+        parker.unpark();
+        parker.unpark();
+
+        assert_eq!(1, probe.count());
+        assert!(!parker.is_parked());
+    }
+}
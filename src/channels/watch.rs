@@ -1,18 +1,24 @@
 //! A state distribution channel.  The internal state can be borrowed or cloned, but receivers do not observe every value.
 //!  
 //! When the channel is created, the receiver will immediately observe `T::default()`.  Cloned receivers will immediately observe the latest stored value.
+//! Channels created with `pending_channel` have no initial value, and receivers wait for the first message.
 //!
 //! Senders can mutably borrow the contained value (which notifies receivers on release).  Receivers can immutably borrow the contained value.
+//!
+//! Senders can be cloned.  Each send atomically replaces the stored value, so the last writer wins.
+//! The channel is closed when all senders have been dropped.
+//!
+//! Channels created with `acked_channel` do not overwrite unseen values.  Sends wait until a receiver has observed the stored value.
 
-use super::SendSyncMessage;
+use super::{SendMessage, SendSyncMessage};
 use std::{
     fmt,
     ops::{Deref, DerefMut},
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use static_assertions::{assert_impl_all, assert_not_impl_all};
+use parking_lot::{MappedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use static_assertions::{assert_impl_all, assert_not_impl_any};
 
 use crate::{
     sink::{PollSend, Sink},
@@ -20,6 +26,13 @@ use crate::{
     sync::{shared, ReceiverShared, SenderShared},
 };
 
+mod changes;
+mod pending;
+
+pub use super::mapped::MappedReceiver;
+pub use changes::{Change, Changes, RecvWithPreviousFuture};
+pub use pending::{pending_channel, PendingReceiver, PendingSender};
+
 /// Constructs a new watch channel pair, filled with `T::default()`.
 pub fn channel<T: Clone + Default>() -> (Sender<T>, Receiver<T>) {
     channel_with(T::default())
@@ -30,12 +43,50 @@ pub fn channel_with<T: Clone>(value: T) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
     log::error!("Creating watch channel");
 
-    let (tx_shared, rx_shared) = shared(StateExtension::new(value));
+    let (tx_shared, rx_shared) = shared(StateExtension::new(value, false));
+    #[cfg(feature = "registry")]
+    tx_shared.register("watch::channel", std::any::type_name::<T>(), None, |_| 0);
+
     let sender = Sender { shared: tx_shared };
 
     let receiver = Receiver {
         shared: rx_shared,
         generation: AtomicUsize::new(0),
+        previous: None,
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
+    };
+
+    (sender, receiver)
+}
+
+/// Constructs a new watch channel pair, filled with the provided value, which does not overwrite unseen values.
+///
+/// Sends are pending until any receiver has observed the stored value, including the initial value.
+/// This is a lossless single-slot handoff, like a broadcast channel with a capacity of 1.
+///
+/// `Sender::borrow_mut` does not wait for the stored value to be observed.
+pub fn acked_channel<T: Clone>(initial: T) -> (Sender<T>, Receiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating acked watch channel");
+
+    let (tx_shared, rx_shared) = shared(StateExtension::new(initial, true));
+    #[cfg(feature = "registry")]
+    tx_shared.register(
+        "watch::acked_channel",
+        std::any::type_name::<T>(),
+        Some(1),
+        |ext| if ext.is_acked() { 0 } else { 1 },
+    );
+
+    let sender = Sender { shared: tx_shared };
+
+    let receiver = Receiver {
+        shared: rx_shared,
+        generation: AtomicUsize::new(0),
+        previous: None,
+        #[cfg(feature = "futures-traits")]
+        terminated: false,
     };
 
     (sender, receiver)
@@ -44,33 +95,65 @@ pub fn channel_with<T: Clone>(value: T) -> (Sender<T>, Receiver<T>) {
 /// Constructs a pair of channel endpoints that store Option<T>
 ///
 /// This is helpful if T does not implement Default, and you don't have an initial value.
+/// See also `pending_channel`, which waits for the first value without an `Option` in the message type.
 pub fn channel_with_option<T: Clone>() -> (Sender<Option<T>>, Receiver<Option<T>>) {
     channel::<Option<T>>()
 }
 
 /// The sender half of a watch channel.  The stored value can be updated with the postage::Sink trait.
+///
+/// The sender can be cloned, and the channel is closed when all senders are dropped.
 pub struct Sender<T> {
     pub(in crate::channels::watch) shared: SenderShared<StateExtension<T>>,
 }
 
-assert_impl_all!(Sender<SendSyncMessage>: Send, Sync, fmt::Debug);
-assert_not_impl_all!(Sender<SendSyncMessage>: Clone);
+// receivers borrow the value concurrently, so the channel requires T: Sync
+assert_impl_all!(Sender<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(Sender<SendMessage>: Send, Sync);
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
 
 impl<T> Sink for Sender<T> {
     type Item = T;
 
     fn poll_send(
         self: std::pin::Pin<&mut Self>,
-        _cx: &mut crate::Context<'_>,
-        value: Self::Item,
+        cx: &mut crate::Context<'_>,
+        mut value: Self::Item,
     ) -> PollSend<Self::Item> {
-        if self.shared.is_closed() {
-            return PollSend::Rejected(value);
-        }
+        loop {
+            if self.shared.is_closed() {
+                return PollSend::Rejected(value);
+            }
 
-        self.shared.extension().push(value);
-        self.shared.notify_receivers();
+            let guard = self.shared.recv_guard();
+            match self.shared.extension().try_push(value) {
+                Ok(()) => {
+                    self.shared.notify_receivers();
+                    return PollSend::Ready;
+                }
+                Err(returned) => {
+                    value = returned;
+                    self.shared.subscribe_recv(cx);
 
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    return PollSend::Pending(value);
+                }
+            }
+        }
+    }
+
+    fn poll_close(self: std::pin::Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollSend<()> {
+        self.shared.close();
         PollSend::Ready
     }
 }
@@ -95,13 +178,16 @@ impl<T> Sender<T> {
         Receiver {
             shared: self.shared.clone_receiver(),
             generation: AtomicUsize::new(0),
+            previous: None,
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
         }
     }
 
     /// Immutably borrows the contained value, blocking the channel while the borrow is held.
     pub fn borrow<'s>(&'s mut self) -> Ref<'s, T> {
         let extension = self.shared.extension();
-        let lock = extension.value.read();
+        let lock = RwLockReadGuard::map(extension.value.read(), |value| value);
 
         Ref { lock }
     }
@@ -124,9 +210,28 @@ mod impl_futures {
 
         fn poll_ready(
             self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            cx: &mut std::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
+            loop {
+                if self.shared.is_closed() {
+                    return Poll::Ready(Ok(()));
+                }
+
+                let guard = self.shared.recv_guard();
+
+                if self.shared.extension().is_acked() {
+                    return Poll::Ready(Ok(()));
+                }
+
+                let cx = cx.into();
+                self.shared.subscribe_recv(&cx);
+
+                if guard.is_expired() {
+                    continue;
+                }
+
+                return Poll::Pending;
+            }
         }
 
         fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
@@ -134,7 +239,10 @@ mod impl_futures {
                 return Err(SendError(item));
             }
 
-            self.shared.extension().push(item);
+            self.shared
+                .extension()
+                .try_push(item)
+                .map_err(|item| SendError(item))?;
             self.shared.notify_receivers();
 
             Ok(())
@@ -162,9 +270,17 @@ mod impl_futures {
 pub struct Receiver<T> {
     pub(in crate::channels::watch) shared: ReceiverShared<StateExtension<T>>,
     pub(in crate::channels::watch) generation: AtomicUsize,
+    // the last value observed by `recv_with_previous` or `changes`.  clones start without a previous value
+    pub(in crate::channels::watch) previous: Option<T>,
+    #[cfg(feature = "futures-traits")]
+    pub(crate) terminated: bool,
 }
 
 assert_impl_all!(Receiver<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(Receiver<SendMessage>: Send, Sync);
+
+// the previous value is never pinned
+impl<T> Unpin for Receiver<T> {}
 
 impl<T> Stream for Receiver<T>
 where
@@ -182,7 +298,11 @@ where
             match self.try_recv_internal() {
                 TryRecv::Pending => {
                     if self.shared.is_closed() {
-                        return PollRecv::Closed;
+                        // the sender may have published a value before closing the channel
+                        return match self.try_recv_internal() {
+                            TryRecv::Ready(v) => PollRecv::Ready(v),
+                            TryRecv::Pending => PollRecv::Closed,
+                        };
                     }
 
                     self.shared.subscribe_send(cx);
@@ -197,6 +317,25 @@ where
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // only the latest value is received, so at most one value is ready
+        let closed = self.shared.is_closed();
+        let ready = if self.is_unread() { 1 } else { 0 };
+
+        (ready, if closed { Some(ready) } else { None })
+    }
+}
+
+impl<T> Receiver<T> {
+    pub(in crate::channels::watch) fn is_unread(&self) -> bool {
+        self.generation.load(Ordering::Acquire)
+            <= self.shared.extension().generation(Ordering::Acquire)
+    }
+
+    pub(in crate::channels::watch) fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
 }
 
 impl<T> Receiver<T>
@@ -215,7 +354,16 @@ where
         let stored_generation = self.shared.extension().generation(Ordering::SeqCst);
         self.generation
             .store(stored_generation + 1, Ordering::Release);
-        TryRecv::Ready(borrow.clone())
+
+        let value = borrow.clone();
+        let acked = state.ack(stored_generation);
+        drop(borrow);
+
+        if acked {
+            self.shared.notify_senders();
+        }
+
+        TryRecv::Ready(value)
     }
 }
 
@@ -229,6 +377,9 @@ impl<T> Clone for Receiver<T> {
         Self {
             shared: self.shared.clone(),
             generation: AtomicUsize::new(0),
+            previous: None,
+            #[cfg(feature = "futures-traits")]
+            terminated: false,
         }
     }
 }
@@ -248,7 +399,7 @@ pub struct RefMut<'t, T> {
 
 impl<'t, T> DerefMut for RefMut<'t, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.lock
+        &mut self.lock
     }
 }
 
@@ -256,7 +407,7 @@ impl<'t, T> Deref for RefMut<'t, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.lock
+        &self.lock
     }
 }
 
@@ -269,44 +420,91 @@ impl<'t, T> Drop for RefMut<'t, T> {
 
 /// An immutable reference to the value contained in the channel.
 pub struct Ref<'t, T> {
-    lock: RwLockReadGuard<'t, T>,
+    lock: MappedRwLockReadGuard<'t, T>,
 }
 
 impl<'t, T> Deref for Ref<'t, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.lock
+        &self.lock
     }
 }
 
 impl<T> Receiver<T> {
     /// Borrows the value in the channel, blocking the channel while the value is held.
     pub fn borrow(&self) -> Ref<'_, T> {
-        let lock = self.shared.extension().value.read();
+        let lock = RwLockReadGuard::map(self.shared.extension().value.read(), |value| value);
         Ref { lock }
     }
 }
 
+impl<T> Receiver<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Subscribes a receiver which maps each value with `map`.  Like a clone, the mapped receiver first receives
+    /// the current value.
+    ///
+    /// The mapped receiver has a nameable type, which does not depend on `T`.  Values are mapped after they are
+    /// received, so the channel and other receivers are unaffected.
+    pub fn map_subscribe<U>(&self, map: fn(&T) -> U) -> MappedReceiver<U>
+    where
+        U: 'static,
+    {
+        MappedReceiver::new(self.clone(), map)
+    }
+}
+
 struct StateExtension<T> {
     generation: AtomicUsize,
     value: RwLock<T>,
+    // in acked channels, one past the latest generation observed by a receiver
+    acked: Option<AtomicUsize>,
 }
 
 impl<T> StateExtension<T> {
-    pub fn new(value: T) -> Self {
+    pub fn new(value: T, acked: bool) -> Self {
         Self {
             generation: AtomicUsize::new(0),
             value: RwLock::new(value),
+            acked: if acked {
+                Some(AtomicUsize::new(0))
+            } else {
+                None
+            },
         }
     }
 
-    pub fn push(&self, value: T) {
+    // stores the value, unless the channel is acked and the stored value has not been observed
+    pub fn try_push(&self, value: T) -> Result<(), T> {
         let mut lock = self.value.write();
+
+        if !self.is_acked() {
+            return Err(value);
+        }
+
         *lock = value;
 
         self.generation.fetch_add(1, Ordering::SeqCst);
         drop(lock);
+        Ok(())
+    }
+
+    pub fn is_acked(&self) -> bool {
+        match &self.acked {
+            Some(acked) => acked.load(Ordering::SeqCst) > self.generation.load(Ordering::SeqCst),
+            None => true,
+        }
+    }
+
+    // records that a receiver observed the generation.  must be called while the value is borrowed.
+    // returns true if senders may be waiting
+    pub fn ack(&self, generation: usize) -> bool {
+        match &self.acked {
+            Some(acked) => acked.fetch_max(generation + 1, Ordering::SeqCst) <= generation,
+            None => false,
+        }
     }
 
     pub fn increment(&self) {
@@ -322,21 +520,154 @@ impl<T> StateExtension<T> {
 mod tests {
     use std::{pin::Pin, task::Context};
 
-    use super::channel;
+    use super::{acked_channel, channel};
     use crate::{
         sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
-        test::{noop_context, panic_context},
+        stream::{PollRecv, Stream, TryRecvError},
+        test::{noop_context, panic_context, CHANNEL_TEST_ITERATIONS},
     };
     use futures_test::task::new_count_waker;
 
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
     struct State(usize);
 
-    impl Default for State {
-        fn default() -> Self {
-            State(0)
+    #[test]
+    fn acked_send_waits_for_receiver() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = acked_channel(State(0));
+
+        // the initial value has not been observed
+        assert_eq!(
+            PollSend::Pending(State(1)),
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+        assert_eq!(
+            PollSend::Pending(State(2)),
+            Pin::new(&mut tx).poll_send(&mut cx, State(2))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(2))
+        );
+    }
+
+    #[test]
+    fn acked_wakes_sender() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = acked_channel(State(0));
+        let mut rx2 = rx.clone();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(State(1)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), State(1))
+        );
+        assert_eq!(0, w1_count.get());
+
+        // any receiver unblocks the sender
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(1, w1_count.get());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn acked_receiver_disconnect_while_parked() {
+        let (mut tx, rx) = acked_channel(State(0));
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(State(1)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), State(1))
+        );
+
+        drop(rx);
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollSend::Rejected(State(1)),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), State(1))
+        );
+    }
+
+    #[test]
+    fn acked_close_while_parked() {
+        let (mut tx, mut rx) = acked_channel(State(0));
+        let mut tx2 = tx.clone();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(State(1)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), State(1))
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_close(&mut noop_context())
+        );
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollSend::Rejected(State(1)),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), State(1))
+        );
+
+        // the unseen value is still received
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn size_hint() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+
+        // the initial value is ready
+        assert_eq!((1, None), rx.size_hint());
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!((0, None), rx.size_hint());
+
+        // only the latest value is received
+        for i in 1..3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, State(i))
+            );
         }
+        assert_eq!((1, None), rx.size_hint());
+
+        drop(tx);
+        assert_eq!((1, Some(1)), rx.size_hint());
     }
 
     #[test]
@@ -461,6 +792,61 @@ mod tests {
         assert_eq!(PollRecv::Closed, Pin::new(&mut rx2).poll_recv(&mut cx));
     }
 
+    #[test]
+    fn clone_sender_last_writer_wins() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, State(2))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(State(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        *tx.borrow_mut() = State(3);
+        assert_eq!(
+            PollRecv::Ready(State(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn clone_sender_disconnect() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel::<State>();
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        drop(tx);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, State(1))
+        );
+        drop(tx2);
+
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
     #[test]
     fn receiver_disconnect() {
         let mut cx = noop_context();
@@ -549,6 +935,33 @@ mod tests {
         assert_eq!(1, w1_count.get());
     }
 
+    #[test]
+    fn final_value_before_close() {
+        const WRITES: usize = 4;
+
+        for _ in 0..CHANNEL_TEST_ITERATIONS {
+            let (mut tx, mut rx) = channel::<usize>();
+            let sender = std::thread::spawn(move || {
+                for i in 1..=WRITES {
+                    tx.try_send(i).expect("the channel is open");
+                }
+            });
+
+            // the receiver may skip values, but the close is ordered after the final value
+            let mut last = 0;
+            loop {
+                match rx.try_recv() {
+                    Ok(value) => last = value,
+                    Err(TryRecvError::Pending) => std::hint::spin_loop(),
+                    Err(TryRecvError::Closed) => break,
+                }
+            }
+
+            sender.join().expect("the sender panicked");
+            assert_eq!(WRITES, last);
+        }
+    }
+
     #[async_std::test]
     async fn subscribe_default() {
         let mut cx = panic_context();
@@ -624,6 +1037,30 @@ mod tokio_tests {
         .expect("test timeout");
     }
 
+    #[tokio::test]
+    async fn acked_lossless() {
+        let (mut tx, mut rx) = super::acked_channel(Message::default());
+
+        tokio::task::spawn(async move {
+            let mut iter = Message::new_iter(0);
+            // skip state 0
+            iter.next();
+            for message in iter {
+                tx.send(message).await.expect("send failed");
+            }
+        });
+
+        timeout(TEST_TIMEOUT, async move {
+            // every value is received
+            let mut channel = Channel::new(0);
+            while let Some(message) = rx.recv().await {
+                channel.assert_message(&message);
+            }
+        })
+        .await
+        .expect("test timeout");
+    }
+
     #[tokio::test]
     async fn send_borrow_mut() {
         let (mut tx, mut rx) = super::channel();
@@ -647,6 +1084,45 @@ mod tokio_tests {
         .expect("test timeout");
     }
 
+    #[tokio::test]
+    async fn multi_sender() {
+        const WRITERS: usize = 4;
+        const WRITES: usize = 1000;
+
+        // values are (writer, sequence, checksum), so a torn write would be detected
+        let (tx, mut rx) = super::channel::<(usize, usize, usize)>();
+
+        for writer in 0..WRITERS {
+            let mut tx = tx.clone();
+            spawn(async move {
+                for seq in 1..=WRITES {
+                    tx.send((writer, seq, writer ^ seq))
+                        .await
+                        .expect("send failed");
+                    tokio::task::yield_now().await;
+                }
+            });
+        }
+        drop(tx);
+
+        timeout(TEST_TIMEOUT, async move {
+            let mut last_seq = [0; WRITERS];
+            let mut last = None;
+            while let Some((writer, seq, checksum)) = rx.recv().await {
+                assert_eq!(writer ^ seq, checksum);
+                assert!(seq >= last_seq[writer], "writer {} went backwards", writer);
+                last_seq[writer] = seq;
+                last = Some((writer, seq));
+            }
+
+            // the stored value is the last write of one of the writers
+            let (_, seq) = last.expect("no value received");
+            assert_eq!(WRITES, seq);
+        })
+        .await
+        .expect("test timeout");
+    }
+
     #[tokio::test]
     async fn multi_receiver() {
         let (mut tx, rx) = super::channel();
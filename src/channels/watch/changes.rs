@@ -0,0 +1,202 @@
+//! Receives watch values along with the value the receiver observed before them.
+
+use std::{future::Future, pin::Pin, task::Poll};
+
+use super::Receiver;
+use crate::{
+    stream::{PollRecv, Stream},
+    Context,
+};
+
+/// A transition between two values observed by a watch receiver, yielded by [Changes](./struct.Changes.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change<T> {
+    /// The value previously observed by the receiver, or `None` on the first observation.
+    pub old: Option<T>,
+    /// The newly observed value.
+    pub new: T,
+}
+
+impl<T> Receiver<T>
+where
+    T: Clone,
+{
+    /// Receives the next value, along with the value this receiver observed before it.
+    ///
+    /// The previous value is `None` on the first observation.  Each receiver tracks its own previous value, and
+    /// values which were overwritten before this receiver observed them are skipped.  Only values received with
+    /// `recv_with_previous` or `changes` are recorded - values received with `recv` or `try_recv` are not.
+    ///
+    /// Resolves to `None` if the channel is closed.
+    pub fn recv_with_previous(&mut self) -> RecvWithPreviousFuture<'_, T> {
+        RecvWithPreviousFuture { receiver: self }
+    }
+
+    /// Converts the receiver into a stream of [Change](./struct.Change.html) values, which pair each new value with
+    /// the value this receiver observed before it.
+    pub fn changes(self) -> Changes<T> {
+        Changes { receiver: self }
+    }
+
+    fn poll_recv_with_previous(&mut self, cx: &mut Context<'_>) -> PollRecv<(Option<T>, T)> {
+        match Pin::new(&mut *self).poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                let previous = self.previous.replace(value.clone());
+                PollRecv::Ready((previous, value))
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+}
+
+/// A future returned by [recv_with_previous](./struct.Receiver.html#method.recv_with_previous).
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvWithPreviousFuture<'s, T> {
+    receiver: &'s mut Receiver<T>,
+}
+
+impl<'s, T> Future for RecvWithPreviousFuture<'s, T>
+where
+    T: Clone,
+{
+    type Output = Option<(Option<T>, T)>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut cx: Context<'_> = cx.into();
+        match self.receiver.poll_recv_with_previous(&mut cx) {
+            PollRecv::Ready(change) => Poll::Ready(Some(change)),
+            PollRecv::Pending => Poll::Pending,
+            PollRecv::Closed => Poll::Ready(None),
+        }
+    }
+}
+
+/// A stream returned by [changes](./struct.Receiver.html#method.changes), which yields each value observed by the
+/// receiver along with the value observed before it.
+#[derive(Debug)]
+pub struct Changes<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> Changes<T> {
+    /// Returns the inner receiver, which keeps the last observed value.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T> Stream for Changes<T>
+where
+    T: Clone,
+{
+    type Item = Change<T>;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        match self.get_mut().receiver.poll_recv_with_previous(cx) {
+            PollRecv::Ready((old, new)) => PollRecv::Ready(Change { old, new }),
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.receiver.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use super::Change;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::noop_context,
+        watch,
+    };
+
+    #[tokio::test]
+    async fn recv_with_previous() {
+        let (mut tx, mut rx) = watch::channel_with(1);
+
+        assert_eq!(Some((None, 1)), rx.recv_with_previous().await);
+
+        tx.send(2).await.unwrap();
+        assert_eq!(Some((Some(1), 2)), rx.recv_with_previous().await);
+
+        drop(tx);
+        assert_eq!(None, rx.recv_with_previous().await);
+    }
+
+    #[tokio::test]
+    async fn previous_tracks_per_receiver() {
+        let (mut tx, mut rx) = watch::channel_with(1);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(Some((None, 1)), rx.recv_with_previous().await);
+        tx.send(2).await.unwrap();
+        assert_eq!(Some((Some(1), 2)), rx.recv_with_previous().await);
+
+        // the clone has not observed a value, even though the original receiver has
+        assert_eq!(Some((None, 2)), rx2.recv_with_previous().await);
+
+        tx.send(3).await.unwrap();
+        assert_eq!(Some((Some(2), 3)), rx2.recv_with_previous().await);
+        assert_eq!(Some((Some(2), 3)), rx.recv_with_previous().await);
+    }
+
+    #[tokio::test]
+    async fn skipped_values_yield_last_observed() {
+        let (mut tx, mut rx) = watch::channel_with(1);
+
+        assert_eq!(Some((None, 1)), rx.recv_with_previous().await);
+
+        // 2 is overwritten before the receiver observes it
+        tx.send(2).await.unwrap();
+        tx.send(3).await.unwrap();
+        assert_eq!(Some((Some(1), 3)), rx.recv_with_previous().await);
+    }
+
+    #[test]
+    fn changes() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = watch::channel_with(1);
+        let mut changes = rx.changes();
+
+        assert_eq!(
+            PollRecv::Ready(Change { old: None, new: 1 }),
+            Pin::new(&mut changes).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut changes).poll_recv(&mut cx));
+
+        tx.try_send(2).unwrap();
+        tx.try_send(3).unwrap();
+        assert_eq!(
+            PollRecv::Ready(Change {
+                old: Some(1),
+                new: 3
+            }),
+            Pin::new(&mut changes).poll_recv(&mut cx)
+        );
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut changes).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn recv_does_not_record() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = watch::channel_with(1);
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        tx.try_send(2).unwrap();
+        let mut changes = rx.changes();
+        assert_eq!(
+            PollRecv::Ready(Change { old: None, new: 2 }),
+            Pin::new(&mut changes).poll_recv(&mut cx)
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! A watch channel without an initial value.
+//!
+//! Receivers wait for the first message.  After it is sent, the channel behaves like a normal watch channel.
+
+use std::{fmt, pin::Pin};
+
+use parking_lot::RwLockReadGuard;
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+use super::{channel_with, Receiver, Ref, Sender};
+use crate::{
+    channels::{SendMessage, SendSyncMessage},
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+};
+
+/// Constructs a new watch channel pair, without an initial value.
+///
+/// Receivers are pending until the first message is sent.  If the sender is dropped first, receivers are closed.
+pub fn pending_channel<T: Clone>() -> (PendingSender<T>, PendingReceiver<T>) {
+    let (inner_tx, inner_rx) = channel_with(None);
+
+    let sender = PendingSender { inner: inner_tx };
+    let receiver = PendingReceiver { inner: inner_rx };
+
+    (sender, receiver)
+}
+
+/// The sender half of a pending watch channel.  The stored value can be set with the postage::Sink trait.
+pub struct PendingSender<T> {
+    inner: Sender<Option<T>>,
+}
+
+assert_impl_all!(PendingSender<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(PendingSender<SendMessage>: Send, Sync);
+
+impl<T> Clone for PendingSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Sink for PendingSender<T> {
+    type Item = T;
+
+    fn poll_send(
+        mut self: Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        match Pin::new(&mut self.inner).poll_send(cx, Some(value)) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(value) => PollSend::Pending(value.unwrap()),
+            PollSend::Rejected(value) => PollSend::Rejected(value.unwrap()),
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut crate::Context<'_>) -> PollSend<()> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+impl<T> PendingSender<T> {
+    /// Creates a new Receiver that listens to this channel.
+    pub fn subscribe(&mut self) -> PendingReceiver<T> {
+        PendingReceiver {
+            inner: self.inner.subscribe(),
+        }
+    }
+
+    /// Immutably borrows the contained value, blocking the channel while the borrow is held.
+    ///
+    /// Returns `None` if no value has been sent.
+    pub fn borrow(&mut self) -> Option<Ref<'_, T>> {
+        borrow_some(&self.inner.shared.extension().value)
+    }
+}
+
+impl<T> fmt::Debug for PendingSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingSender").finish()
+    }
+}
+
+/// The receiver half of a pending watch channel.  Can recieve state updates with the postage::Stream trait.
+///
+/// The receiver is pending until the first value is sent.
+pub struct PendingReceiver<T> {
+    inner: Receiver<Option<T>>,
+}
+
+assert_impl_all!(PendingReceiver<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+assert_not_impl_any!(PendingReceiver<SendMessage>: Send, Sync);
+
+impl<T> Stream for PendingReceiver<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_recv(mut self: Pin<&mut Self>, cx: &mut crate::Context<'_>) -> PollRecv<Self::Item> {
+        loop {
+            match Pin::new(&mut self.inner).poll_recv(cx) {
+                PollRecv::Ready(Some(value)) => return PollRecv::Ready(value),
+                // the initial placeholder is skipped, and the next poll registers for the first message
+                PollRecv::Ready(None) => continue,
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // the placeholder is never received
+        let closed = self.inner.is_closed();
+        let ready = if self.inner.is_unread() && self.borrow().is_some() {
+            1
+        } else {
+            0
+        };
+
+        (ready, if closed { Some(ready) } else { None })
+    }
+}
+
+impl<T> PendingReceiver<T> {
+    /// Borrows the value in the channel, blocking the channel while the value is held.
+    ///
+    /// Returns `None` if no value has been sent.
+    pub fn borrow(&self) -> Option<Ref<'_, T>> {
+        borrow_some(&self.inner.shared.extension().value)
+    }
+}
+
+impl<T> Clone for PendingReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for PendingReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingReceiver").finish()
+    }
+}
+
+fn borrow_some<T>(value: &parking_lot::RwLock<Option<T>>) -> Option<Ref<'_, T>> {
+    RwLockReadGuard::try_map(value.read(), Option::as_ref)
+        .ok()
+        .map(|lock| Ref { lock })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context};
+
+    use super::pending_channel;
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+    };
+    use futures_test::task::new_count_waker;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct State(usize);
+
+    #[test]
+    fn recv_waits_for_first_send() {
+        let (mut tx, mut rx) = pending_channel();
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+        assert_eq!(0, w1_count.get());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut noop_context(), State(1))
+        );
+        assert_eq!(1, w1_count.get());
+
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn subscribe_waits_for_first_send() {
+        let (mut tx, _rx) = pending_channel();
+        let mut rx2 = tx.subscribe();
+
+        let mut cx = noop_context();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+
+        let mut rx3 = tx.subscribe();
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx3).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn sender_disconnect_before_send() {
+        let (tx, mut rx) = pending_channel::<State>();
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+
+        drop(tx);
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Closed,
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn borrow() {
+        let (mut tx, rx) = pending_channel();
+        assert!(rx.borrow().is_none());
+        assert!(tx.borrow().is_none());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut noop_context(), State(1))
+        );
+        assert_eq!(Some(&State(1)), rx.borrow().as_deref());
+        assert_eq!(Some(&State(1)), tx.borrow().as_deref());
+    }
+}
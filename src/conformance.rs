@@ -0,0 +1,307 @@
+//! Conformance checks for the polling contracts of [Sink](../sink/trait.Sink.html) and [Stream](../stream/trait.Stream.html).
+//!
+//! The checks drive a channel through pending and closed states, and panic if the implementation
+//! violates the contract:
+//! - When `poll_recv` returns `Pending`, the waker is called once a message is sent, or the stream is closed.
+//! - When `poll_send` returns `Pending(value)`, the value is returned, and the waker is called once the
+//!   message may be accepted, or the sink is closed.
+//! - `Rejected(value)` returns the exact value that was sent.
+//! - `Closed` and `Rejected` are sticky.  Once returned, they are returned by every later poll.
+//!
+//! These functions are intended for channel authors, and are available with the `conformance` feature.
+//!
+//! ```rust
+//! use postage::{conformance, mpsc};
+//!
+//! conformance::assert_stream_contract(|| mpsc::channel(4), 1usize);
+//! conformance::assert_sink_contract(|| mpsc::channel(4), 1usize);
+//! ```
+use std::{fmt::Debug, pin::Pin};
+
+use futures_test::task::{new_count_waker, noop_waker};
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+    Context,
+};
+
+// the maximum number of polls before a channel is expected to become pending, or closed
+const POLL_LIMIT: usize = 1024;
+
+/// Checks the `Stream` contract of the receiver returned by `make_channel`.
+///
+/// `value` is sent with the paired sender, which must accept it when the receiver is pending.
+pub fn assert_stream_contract<Tx, Rx, F>(mut make_channel: F, value: Tx::Item)
+where
+    F: FnMut() -> (Tx, Rx),
+    Tx: Sink + Unpin,
+    Tx::Item: Clone,
+    Rx: Stream + Unpin,
+{
+    // a pending receiver is woken by a send
+    let (mut tx, mut rx) = make_channel();
+    let (waker, count) = new_count_waker();
+    poll_until_pending(&mut rx, &waker);
+
+    assert!(
+        matches!(send_now(&mut tx, value.clone()), PollSend::Ready),
+        "the sender did not accept a message while the receiver was pending"
+    );
+    assert!(
+        count.get() > 0,
+        "poll_recv returned Pending, but the waker was not called when a message was sent"
+    );
+    assert!(
+        matches!(recv_now(&mut rx), PollRecv::Ready(_)),
+        "poll_recv did not return the sent message"
+    );
+
+    // a pending receiver is woken when the channel is closed, and remains closed
+    let (tx, mut rx) = make_channel();
+    let (waker, count) = new_count_waker();
+    poll_until_pending(&mut rx, &waker);
+
+    drop(tx);
+    assert!(
+        count.get() > 0,
+        "poll_recv returned Pending, but the waker was not called when the sender was dropped"
+    );
+    assert_closed_sticky(&mut rx);
+}
+
+/// Checks the `Sink` contract of the sender returned by `make_channel`.
+///
+/// `value` is repeatedly sent with the sender.  The paired receiver is polled to free capacity, and dropped to close the channel.
+pub fn assert_sink_contract<Tx, Rx, F>(mut make_channel: F, value: Tx::Item)
+where
+    F: FnMut() -> (Tx, Rx),
+    Tx: Sink + Unpin,
+    Tx::Item: Clone + PartialEq + Debug,
+    Rx: Stream + Unpin,
+{
+    // a closed sink rejects the exact value, and remains closed
+    let (mut tx, rx) = make_channel();
+    drop(rx);
+    assert_rejected_sticky(&mut tx, &value);
+
+    // a pending sender returns the value, and is woken when the receiver frees capacity
+    let (mut tx, mut rx) = make_channel();
+    let (waker, count) = new_count_waker();
+    if poll_until_send_pending(&mut tx, &value, &waker) {
+        assert!(
+            matches!(recv_now(&mut rx), PollRecv::Ready(_)),
+            "the receiver did not return a message while the sender was pending"
+        );
+        assert!(
+            count.get() > 0,
+            "poll_send returned Pending, but the waker was not called when a message was received"
+        );
+    }
+
+    // a pending sender is woken when the receiver is dropped, and rejects the value
+    let (mut tx, rx) = make_channel();
+    let (waker, count) = new_count_waker();
+    if poll_until_send_pending(&mut tx, &value, &waker) {
+        drop(rx);
+        assert!(
+            count.get() > 0,
+            "poll_send returned Pending, but the waker was not called when the receiver was dropped"
+        );
+        assert_rejected_sticky(&mut tx, &value);
+    }
+}
+
+fn poll_until_pending<S>(stream: &mut S, waker: &std::task::Waker)
+where
+    S: Stream + Unpin,
+{
+    let mut cx = Context::from_waker(waker);
+
+    for _ in 0..POLL_LIMIT {
+        match Pin::new(&mut *stream).poll_recv(&mut cx) {
+            PollRecv::Ready(_) => continue,
+            PollRecv::Pending => return,
+            PollRecv::Closed => panic!("poll_recv returned Closed while the sender was alive"),
+        }
+    }
+
+    panic!(
+        "poll_recv did not return Pending after {} polls",
+        POLL_LIMIT
+    );
+}
+
+// returns true if the sink became pending, or false if it accepted every message
+fn poll_until_send_pending<S>(sink: &mut S, value: &S::Item, waker: &std::task::Waker) -> bool
+where
+    S: Sink + Unpin,
+    S::Item: Clone + PartialEq + Debug,
+{
+    let mut cx = Context::from_waker(waker);
+
+    for _ in 0..POLL_LIMIT {
+        match Pin::new(&mut *sink).poll_send(&mut cx, value.clone()) {
+            PollSend::Ready => continue,
+            PollSend::Pending(returned) => {
+                assert_eq!(
+                    value, &returned,
+                    "poll_send returned Pending with a different value"
+                );
+                return true;
+            }
+            PollSend::Rejected(_) => return false,
+        }
+    }
+
+    false
+}
+
+fn assert_closed_sticky<S>(stream: &mut S)
+where
+    S: Stream + Unpin,
+{
+    let mut closed = false;
+    for _ in 0..POLL_LIMIT {
+        match recv_now(stream) {
+            PollRecv::Ready(_) => continue,
+            PollRecv::Pending => panic!("poll_recv returned Pending after the sender was dropped"),
+            PollRecv::Closed => {
+                closed = true;
+                break;
+            }
+        }
+    }
+
+    assert!(
+        closed,
+        "poll_recv did not return Closed after the sender was dropped"
+    );
+
+    for _ in 0..4 {
+        assert!(
+            matches!(recv_now(stream), PollRecv::Closed),
+            "poll_recv returned Closed, and then a different result"
+        );
+    }
+}
+
+fn assert_rejected_sticky<S>(sink: &mut S, value: &S::Item)
+where
+    S: Sink + Unpin,
+    S::Item: Clone + PartialEq + Debug,
+{
+    for _ in 0..4 {
+        match send_now(sink, value.clone()) {
+            PollSend::Rejected(returned) => assert_eq!(
+                value, &returned,
+                "poll_send returned Rejected with a different value"
+            ),
+            PollSend::Ready => panic!("poll_send returned Ready after the receiver was dropped"),
+            PollSend::Pending(_) => {
+                panic!("poll_send returned Pending after the receiver was dropped")
+            }
+        }
+    }
+}
+
+fn send_now<S>(sink: &mut S, value: S::Item) -> PollSend<S::Item>
+where
+    S: Sink + Unpin,
+{
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    Pin::new(sink).poll_send(&mut cx, value)
+}
+
+fn recv_now<S>(stream: &mut S) -> PollRecv<S::Item>
+where
+    S: Stream + Unpin,
+{
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    Pin::new(stream).poll_recv(&mut cx)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::RandomState;
+
+    use super::{assert_sink_contract, assert_stream_contract};
+    use crate::{broadcast, dispatch, mpsc, oneshot, spsc, watch};
+
+    // the barrier is not checked, as it is level-triggered.  the receiver returns `Ready(())` forever once
+    // the barrier is sent, and the sender does not track receivers, so it is never closed.
+
+    #[test]
+    fn broadcast() {
+        assert_stream_contract(|| broadcast::channel(4), 1usize);
+        assert_sink_contract(|| broadcast::channel(4), 1usize);
+    }
+
+    #[test]
+    fn broadcast_fanout() {
+        assert_stream_contract(|| broadcast::fanout_channel(4), 1usize);
+        assert_sink_contract(|| broadcast::fanout_channel(4), 1usize);
+    }
+
+    #[test]
+    fn broadcast_lossy_fanout() {
+        assert_stream_contract(|| broadcast::lossy_fanout_channel(4), 1usize);
+        assert_sink_contract(|| broadcast::lossy_fanout_channel(4), 1usize);
+    }
+
+    #[test]
+    fn dispatch() {
+        assert_stream_contract(|| dispatch::channel(4), 1usize);
+        assert_sink_contract(|| dispatch::channel(4), 1usize);
+    }
+
+    #[test]
+    fn dispatch_keyed() {
+        let keyed_channel = || dispatch::keyed_channel(4, RandomState::new());
+        assert_stream_contract(keyed_channel, (1usize, 1usize));
+        assert_sink_contract(keyed_channel, (1usize, 1usize));
+    }
+
+    #[test]
+    fn mpsc() {
+        assert_stream_contract(|| mpsc::channel(4), 1usize);
+        assert_sink_contract(|| mpsc::channel(4), 1usize);
+    }
+
+    #[test]
+    fn mpsc_spin() {
+        let spin_channel = || {
+            let (tx, rx) = mpsc::channel(4);
+            (tx.spin_sends(16), rx.spin_recvs(16))
+        };
+
+        assert_stream_contract(spin_channel, 1usize);
+        assert_sink_contract(spin_channel, 1usize);
+    }
+
+    #[test]
+    fn mpsc_static() {
+        assert_stream_contract(mpsc::static_channel::<usize, 4>, 1usize);
+        assert_sink_contract(mpsc::static_channel::<usize, 4>, 1usize);
+    }
+
+    #[test]
+    fn oneshot() {
+        assert_stream_contract(oneshot::channel, 1usize);
+        assert_sink_contract(oneshot::channel, 1usize);
+    }
+
+    #[test]
+    fn spsc() {
+        assert_stream_contract(|| spsc::channel(4), 1usize);
+        assert_sink_contract(|| spsc::channel(4), 1usize);
+    }
+
+    #[test]
+    fn watch() {
+        assert_stream_contract(watch::channel, 1usize);
+        assert_sink_contract(watch::channel, 1usize);
+    }
+}
@@ -1,4 +1,13 @@
-use std::task::Waker;
+//! The polling context of postage channels, and scoped senders.
+//!
+//! [scope](./fn.scope.html) provides a sender to a future, and [send](./fn.send.html) sends on it from within the
+//! future, without passing the sender through every function.
+use std::task::{RawWaker, RawWakerVTable, Waker};
+
+mod scope;
+
+pub use scope::{is_active, scope, send, Scope, ScopedSendFuture};
+
 /// The `Context` of an asynchronous task.
 ///
 /// Unlike std::task::Context, this context *optionally* contains a waker.
@@ -42,6 +51,31 @@ impl<'a> Context<'a> {
     pub fn waker(&self) -> Option<&'a Waker> {
         self.waker
     }
+
+    /// Calls `f` with a `std::task::Context` for this task.
+    ///
+    /// If this context is empty, a no-op waker is provided, so that futures can be polled from `try_recv`.
+    pub(crate) fn with_std<R>(&self, f: impl FnOnce(&mut std::task::Context<'_>) -> R) -> R {
+        match self.waker {
+            Some(waker) => f(&mut std::task::Context::from_waker(waker)),
+            None => {
+                let waker = noop_waker();
+                f(&mut std::task::Context::from_waker(&waker))
+            }
+        }
+    }
+}
+
+fn noop_waker() -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    fn clone(_data: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    fn noop(_data: *const ()) {}
+
+    unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
 }
 
 impl std::fmt::Debug for Context<'_> {
@@ -0,0 +1,383 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::Poll,
+};
+
+use pin_project::pin_project;
+use static_assertions::assert_impl_all;
+
+use crate::sink::{PollSend, SendError, Sink};
+
+type ScopedSink<T> = Box<dyn ScopedSender<T>>;
+
+// A type-erased sender, which can be cloned and polled through a box
+trait ScopedSender<T>: Send {
+    fn clone_sink(&self) -> ScopedSink<T>;
+
+    fn poll_send_scoped(&mut self, cx: &mut crate::Context<'_>, value: T) -> PollSend<T>;
+}
+
+impl<S> ScopedSender<S::Item> for S
+where
+    S: Sink + Clone + Unpin + Send + 'static,
+{
+    fn clone_sink(&self) -> ScopedSink<S::Item> {
+        Box::new(self.clone())
+    }
+
+    fn poll_send_scoped(
+        &mut self,
+        cx: &mut crate::Context<'_>,
+        value: S::Item,
+    ) -> PollSend<S::Item> {
+        Pin::new(self).poll_send(cx, value)
+    }
+}
+
+// An entry in the stack of active scopes.  The sender is a `ScopedSink<T>`, keyed by the item type
+struct Entry {
+    item: TypeId,
+    sender: Box<dyn Any + Send>,
+}
+
+thread_local! {
+    // the scopes which are being polled on this thread, innermost last
+    static SCOPES: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `future` with `sender` as the scoped sender for its item type.
+///
+/// Within the future, [send](./fn.send.html) sends a message on a clone of the sender, so the sender does not need
+/// to be passed through every function.  Scopes can be nested, and the innermost scope for an item type shadows the
+/// outer scopes.  Scopes for different item types are independent.
+///
+/// The sender is installed while the scope is polled, so the scope works with any executor.  It propagates to
+/// futures which are awaited (or joined) within the scope, but not to tasks which are spawned from it, as the
+/// executor polls them separately.  Spawned tasks can be wrapped in a new scope, with a clone of the sender:
+/// ```rust
+/// use postage::{context, mpsc, prelude::*};
+///
+/// async fn load(file: &str) {
+///     // no sender in the signature
+///     context::send(format!("loaded {}", file)).await.ok();
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = mpsc::channel::<String>(8);
+///
+///     let task = tokio::spawn(context::scope(tx.clone(), load("b.toml")));
+///     context::scope(tx, load("a.toml")).await;
+///     task.await.unwrap();
+///
+///     let mut messages = vec![rx.recv().await.unwrap(), rx.recv().await.unwrap()];
+///     messages.sort();
+///     assert_eq!(vec!["loaded a.toml", "loaded b.toml"], messages);
+/// }
+/// ```
+pub fn scope<S, F>(sender: S, future: F) -> Scope<F>
+where
+    S: Sink + Clone + Unpin + Send + 'static,
+    S::Item: 'static,
+    F: Future,
+{
+    let sender: ScopedSink<S::Item> = Box::new(sender);
+
+    Scope {
+        future,
+        entry: Some(Entry {
+            item: TypeId::of::<S::Item>(),
+            sender: Box::new(sender),
+        }),
+    }
+}
+
+/// Sends `value` on a clone of the innermost scoped sender for the item type.
+///
+/// Scopes are found by the exact item type, so `value` must have the item type of the scoped sender.
+/// Returns `Err(SendError(value))` if no scope for the item type is active, or if the channel is closed.
+/// The scope is resolved when `send` is called, so the future can be awaited outside the scope.
+pub fn send<T>(value: T) -> ScopedSendFuture<T>
+where
+    T: 'static,
+{
+    let sink = SCOPES.with(|scopes| {
+        scopes
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|entry| entry.item == TypeId::of::<T>())
+            .find_map(|entry| entry.sender.downcast_ref::<ScopedSink<T>>())
+            .map(|sender| sender.clone_sink())
+    });
+
+    ScopedSendFuture {
+        sink,
+        value: Some(value),
+    }
+}
+
+/// Returns true if a scope for the item type is active.
+pub fn is_active<T>() -> bool
+where
+    T: 'static,
+{
+    SCOPES.with(|scopes| {
+        scopes
+            .borrow()
+            .iter()
+            .any(|entry| entry.item == TypeId::of::<T>())
+    })
+}
+
+/// A future returned by [scope](./fn.scope.html).
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct Scope<F> {
+    #[pin]
+    future: F,
+    entry: Option<Entry>,
+}
+
+assert_impl_all!(Scope<std::future::Ready<usize>>: Send);
+
+impl<F> Future for Scope<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let _guard = ScopeGuard::enter(this.entry);
+        this.future.poll(cx)
+    }
+}
+
+impl<F> fmt::Debug for Scope<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope").finish()
+    }
+}
+
+// Installs the entry while the scope is polled, and returns it to the scope when dropped, including during a panic
+struct ScopeGuard<'a> {
+    entry: &'a mut Option<Entry>,
+}
+
+impl<'a> ScopeGuard<'a> {
+    fn enter(entry: &'a mut Option<Entry>) -> Self {
+        let installed = entry.take().expect("the scope is not being polled");
+        SCOPES.with(|scopes| scopes.borrow_mut().push(installed));
+
+        Self { entry }
+    }
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        // scopes are polled in a stack, so the innermost entry belongs to this scope
+        *self.entry = SCOPES.with(|scopes| scopes.borrow_mut().pop());
+    }
+}
+
+/// A future returned by [send](./fn.send.html).
+#[must_use = "futures do nothing unless polled"]
+pub struct ScopedSendFuture<T> {
+    sink: Option<ScopedSink<T>>,
+    value: Option<T>,
+}
+
+assert_impl_all!(ScopedSendFuture<usize>: Send, Unpin);
+
+// the value is moved into the sink, and is never pinned
+impl<T> Unpin for ScopedSendFuture<T> {}
+
+impl<T> Future for ScopedSendFuture<T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let value = match this.value.take() {
+            Some(value) => value,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let sink = match this.sink.as_mut() {
+            Some(sink) => sink,
+            None => return Poll::Ready(Err(SendError(value))),
+        };
+
+        let mut cx: crate::Context<'_> = cx.into();
+        match sink.poll_send_scoped(&mut cx, value) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(value) => {
+                this.value = Some(value);
+                Poll::Pending
+            }
+            PollSend::Rejected(value) => Poll::Ready(Err(SendError(value))),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ScopedSendFuture<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedSendFuture")
+            .field("scoped", &self.sink.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::{is_active, scope, send},
+        mpsc,
+        sink::SendError,
+        stream::{Stream, TryRecvError},
+    };
+
+    #[tokio::test]
+    async fn send_in_scope() {
+        let (tx, mut rx) = mpsc::channel::<usize>(4);
+
+        scope(tx, async {
+            assert!(is_active::<usize>());
+            send(1usize).await.expect("the scope is active");
+            send(2usize).await.expect("the scope is active");
+        })
+        .await;
+
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(Some(2), rx.recv().await);
+
+        // the scope dropped the sender when it completed
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn send_without_scope() {
+        assert!(!is_active::<usize>());
+        assert_eq!(Err(SendError(1usize)), send(1usize).await);
+    }
+
+    #[tokio::test]
+    async fn item_types_are_independent() {
+        let (tx, _rx) = mpsc::channel::<usize>(4);
+
+        scope(tx, async {
+            assert!(!is_active::<u8>());
+            assert_eq!(Err(SendError(1u8)), send(1u8).await);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn nested_scopes_shadow() {
+        let (outer_tx, mut outer_rx) = mpsc::channel::<usize>(4);
+        let (inner_tx, mut inner_rx) = mpsc::channel::<usize>(4);
+
+        scope(outer_tx, async {
+            send(1usize).await.unwrap();
+            scope(inner_tx, async { send(2usize).await.unwrap() }).await;
+
+            // the outer scope is restored
+            send(3usize).await.unwrap();
+        })
+        .await;
+
+        assert_eq!(Ok(1), outer_rx.try_recv());
+        assert_eq!(Ok(3), outer_rx.try_recv());
+        assert_eq!(Ok(2), inner_rx.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), inner_rx.try_recv());
+    }
+
+    #[tokio::test]
+    async fn pending_scope_is_removed() {
+        let (tx, mut rx) = mpsc::channel::<usize>(1);
+
+        // the scope is suspended while the channel is full, and is only active while it is polled
+        let task = tokio::spawn(scope(tx, async {
+            send(1usize).await.unwrap();
+            send(2usize).await.unwrap();
+        }));
+        tokio::task::yield_now().await;
+        assert!(!is_active::<usize>());
+
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(Some(2), rx.recv().await);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn joined_futures_propagate() {
+        let (tx, mut rx) = mpsc::channel::<usize>(4);
+
+        scope(tx, async {
+            let (a, b) = futures::join!(send(1usize), async { send(2usize).await });
+            a.unwrap();
+            b.unwrap();
+        })
+        .await;
+
+        let mut values = vec![rx.recv().await.unwrap(), rx.recv().await.unwrap()];
+        values.sort_unstable();
+        assert_eq!(vec![1, 2], values);
+    }
+
+    #[tokio::test]
+    async fn spawned_tasks_do_not_propagate() {
+        let (tx, mut rx) = mpsc::channel::<usize>(4);
+
+        scope(tx.clone(), async {
+            let unscoped = tokio::spawn(async { send(1usize).await });
+            assert_eq!(Err(SendError(1)), unscoped.await.unwrap());
+
+            // the task is scoped again with a clone of the sender
+            let scoped = tokio::spawn(scope(tx, async { send(2usize).await }));
+            assert_eq!(Ok(()), scoped.await.unwrap());
+        })
+        .await;
+
+        assert_eq!(Some(2), rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn blocking_tasks_do_not_propagate() {
+        let (tx, _rx) = mpsc::channel::<usize>(4);
+
+        scope(tx, async {
+            let active = tokio::task::spawn_blocking(is_active::<usize>);
+            assert!(!active.await.unwrap());
+        })
+        .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn send_future_outlives_scope() {
+        let (tx, mut rx) = mpsc::channel::<usize>(4);
+
+        // the sender is resolved when the future is created, so it can be sent to another task
+        let send = scope(tx, futures::future::lazy(|_| send(1usize))).await;
+        tokio::spawn(send).await.unwrap().unwrap();
+
+        assert_eq!(Some(1), rx.recv().await);
+    }
+
+    #[test]
+    fn scope_restored_after_panic() {
+        let (tx, _rx) = mpsc::channel::<usize>(4);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+            crate::test_util::drive(scope(tx, async { panic!("the scoped future failed") }))
+        }));
+
+        assert!(result.is_err());
+        assert!(!is_active::<usize>());
+    }
+}
@@ -0,0 +1,192 @@
+//! Cooperative scheduling for busy channels.
+//!
+//! A receiver which always has messages available would never return `Pending`, and a task draining it could
+//! hold the executor thread indefinitely.  The mpsc, broadcast and dispatch receivers yield after producing
+//! 128 consecutive messages: `poll_recv` wakes the task, and returns `Pending`.
+//!
+//! The budget only applies when the receiver is polled with a waker, so `try_recv` never yields.
+//! Futures can opt out with [unconstrained](./fn.unconstrained.html).
+//!
+//! ```rust
+//! use postage::{coop::unconstrained, mpsc, sink::Sink, stream::Stream};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut tx, mut rx) = mpsc::channel(256);
+//!     for i in 0..256usize {
+//!         tx.send(i).await.ok();
+//!     }
+//!     drop(tx);
+//!
+//!     let sum = unconstrained(async move {
+//!         let mut sum = 0;
+//!         while let Some(i) = rx.recv().await {
+//!             sum += i;
+//!         }
+//!         sum
+//!     })
+//!     .await;
+//!
+//!     assert_eq!(32640, sum);
+//! }
+//! ```
+use std::{cell::Cell, future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::Context;
+
+// the number of consecutive messages a receiver produces before it yields
+pub(crate) const BUDGET: usize = 128;
+
+thread_local! {
+    static UNCONSTRAINED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Polls the future without the cooperative budget, so receivers never yield while they have messages.
+pub fn unconstrained<F>(future: F) -> Unconstrained<F>
+where
+    F: Future,
+{
+    Unconstrained { future }
+}
+
+/// A future returned by [unconstrained](./fn.unconstrained.html).
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct Unconstrained<F> {
+    #[pin]
+    future: F,
+}
+
+impl<F> Future for Unconstrained<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let _guard = UnconstrainedGuard::enter();
+        self.project().future.poll(cx)
+    }
+}
+
+// restores the previous state when the poll completes, or panics
+struct UnconstrainedGuard {
+    previous: bool,
+}
+
+impl UnconstrainedGuard {
+    fn enter() -> Self {
+        let previous = UNCONSTRAINED.with(|unconstrained| unconstrained.replace(true));
+        Self { previous }
+    }
+}
+
+impl Drop for UnconstrainedGuard {
+    fn drop(&mut self) {
+        UNCONSTRAINED.with(|unconstrained| unconstrained.set(self.previous));
+    }
+}
+
+fn is_unconstrained() -> bool {
+    UNCONSTRAINED.with(|unconstrained| unconstrained.get())
+}
+
+// Counts the messages a receiver has produced since it last returned Pending
+pub(crate) struct Budget {
+    remaining: usize,
+}
+
+impl Budget {
+    pub fn new() -> Self {
+        Self { remaining: BUDGET }
+    }
+
+    // returns Pending, after waking the task, if the budget is exhausted
+    pub fn poll_proceed(&mut self, cx: &Context<'_>) -> Poll<()> {
+        if self.remaining > 0 {
+            return Poll::Ready(());
+        }
+
+        self.reset();
+
+        match cx.waker() {
+            Some(waker) if !is_unconstrained() => {
+                waker.wake_by_ref();
+                Poll::Pending
+            }
+            _ => Poll::Ready(()),
+        }
+    }
+
+    pub fn consume(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+    }
+
+    // called when the receiver returns Pending, and the task yields anyway
+    pub fn reset(&mut self) {
+        self.remaining = BUDGET;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::new_count_waker;
+
+    use crate::Context;
+
+    use super::{unconstrained, Budget, BUDGET};
+
+    #[test]
+    fn budget_yields() {
+        let (waker, count) = new_count_waker();
+        let cx = Context::from_waker(&waker);
+        let mut budget = Budget::new();
+
+        for _ in 0..BUDGET {
+            assert_eq!(Poll::Ready(()), budget.poll_proceed(&cx));
+            budget.consume();
+        }
+
+        assert_eq!(Poll::Pending, budget.poll_proceed(&cx));
+        assert_eq!(1, count.get());
+
+        // the budget is restored after yielding
+        assert_eq!(Poll::Ready(()), budget.poll_proceed(&cx));
+    }
+
+    #[test]
+    fn empty_context_does_not_yield() {
+        let mut budget = Budget::new();
+
+        for _ in 0..BUDGET {
+            budget.consume();
+        }
+
+        assert_eq!(Poll::Ready(()), budget.poll_proceed(&Context::empty()));
+    }
+
+    #[test]
+    fn unconstrained_does_not_yield() {
+        let (waker, count) = new_count_waker();
+        let mut std_cx = std::task::Context::from_waker(&waker);
+
+        let mut future = unconstrained(futures::future::poll_fn(|cx| {
+            let cx: Context<'_> = cx.into();
+            let mut budget = Budget::new();
+
+            for _ in 0..BUDGET {
+                budget.consume();
+            }
+
+            budget.poll_proceed(&cx)
+        }));
+
+        assert_eq!(Poll::Ready(()), Pin::new(&mut future).poll(&mut std_cx));
+        assert_eq!(0, count.get());
+        assert!(!super::is_unconstrained());
+    }
+}
@@ -1,3 +1,39 @@
+//! Implementations of the futures `Sink`, `Stream`, `FusedStream` and `FusedFuture` traits.
+//!
+//! Receivers remember returning `None`, and `RecvFuture`/`SendFuture` remember completing, so they can be used in
+//! `futures::select!` loops without `fuse()` adapters:
+//!
+//! ```rust
+//! use futures::{select, StreamExt};
+//! use postage::{mpsc, oneshot, sink::Sink};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut tx, mut rx) = mpsc::channel(4);
+//!     let (mut shutdown_tx, mut shutdown_rx) = oneshot::channel();
+//!
+//!     tx.send(1usize).await.ok();
+//!     tx.send(2usize).await.ok();
+//!     shutdown_tx.send(()).await.ok();
+//!     drop(tx);
+//!
+//!     let mut sum = 0;
+//!     let mut shutdown = false;
+//!     loop {
+//!         select! {
+//!             message = rx.next() => match message {
+//!                 Some(message) => sum += message,
+//!                 None => {},
+//!             },
+//!             _ = shutdown_rx.next() => shutdown = true,
+//!             complete => break,
+//!         }
+//!     }
+//!
+//!     assert_eq!(3, sum);
+//!     assert!(shutdown);
+//! }
+//! ```
 use std::task::Poll;
 
 macro_rules! poll {
@@ -5,11 +41,15 @@ macro_rules! poll {
         use crate::prelude::Stream;
 
         let mut cx = $cx.into();
+        let this = $self.get_mut();
 
-        return match $self.poll_recv(&mut cx) {
+        return match std::pin::Pin::new(&mut *this).poll_recv(&mut cx) {
             crate::stream::PollRecv::Ready(v) => Poll::Ready(Some(v)),
             crate::stream::PollRecv::Pending => Poll::Pending,
-            crate::stream::PollRecv::Closed => Poll::Ready(None),
+            crate::stream::PollRecv::Closed => {
+                this.terminated = true;
+                Poll::Ready(None)
+            }
         };
     }};
 }
@@ -23,6 +63,10 @@ impl futures::stream::Stream for crate::barrier::Receiver {
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
 }
 
 impl<T: Clone> futures::stream::Stream for crate::broadcast::Receiver<T> {
@@ -34,6 +78,28 @@ impl<T: Clone> futures::stream::Stream for crate::broadcast::Receiver<T> {
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
+}
+
+impl<T> futures::stream::Stream for crate::broadcast::FanoutReceiver<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        poll!(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
 }
 
 impl<T> futures::stream::Stream for crate::dispatch::Receiver<T> {
@@ -45,6 +111,40 @@ impl<T> futures::stream::Stream for crate::dispatch::Receiver<T> {
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
+}
+
+impl<T, const N: usize> futures::stream::Stream for crate::mpsc::StaticReceiver<T, N> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        poll!(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
+}
+
+impl<U> futures::stream::Stream for crate::broadcast::MappedReceiver<U> {
+    type Item = U;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        poll!(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
 }
 
 impl<T> futures::stream::Stream for crate::mpsc::Receiver<T> {
@@ -56,6 +156,10 @@ impl<T> futures::stream::Stream for crate::mpsc::Receiver<T> {
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
 }
 
 impl<T> futures::stream::Stream for crate::oneshot::Receiver<T> {
@@ -67,6 +171,40 @@ impl<T> futures::stream::Stream for crate::oneshot::Receiver<T> {
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
+}
+
+impl<T: Clone> futures::stream::Stream for crate::oneshot::SharedReceiver<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        poll!(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
+}
+
+impl<T> futures::stream::Stream for crate::spsc::Receiver<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        poll!(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
 }
 
 impl<T: Clone> futures::stream::Stream for crate::watch::Receiver<T> {
@@ -78,6 +216,77 @@ impl<T: Clone> futures::stream::Stream for crate::watch::Receiver<T> {
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        crate::stream::Stream::size_hint(self)
+    }
+}
+
+// receivers remember returning Closed, so select! can skip them
+impl futures::stream::FusedStream for crate::barrier::Receiver {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Clone> futures::stream::FusedStream for crate::broadcast::Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Clone> futures::stream::FusedStream for crate::broadcast::FanoutReceiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> futures::stream::FusedStream for crate::dispatch::Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T, const N: usize> futures::stream::FusedStream for crate::mpsc::StaticReceiver<T, N> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<U> futures::stream::FusedStream for crate::broadcast::MappedReceiver<U> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> futures::stream::FusedStream for crate::mpsc::Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> futures::stream::FusedStream for crate::oneshot::Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Clone> futures::stream::FusedStream for crate::oneshot::SharedReceiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> futures::stream::FusedStream for crate::spsc::Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Clone> futures::stream::FusedStream for crate::watch::Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +373,11 @@ mod sink_tests {
         test_sink!(mpsc::channel(1), 1usize);
     }
 
+    #[test]
+    fn mpsc_static() {
+        test_sink!(mpsc::static_channel::<_, 1>(), 1usize);
+    }
+
     #[test]
     fn oneshot() {
         let mut std_cx = futures_test::task::noop_context();
@@ -206,7 +420,7 @@ mod stream_tests {
         sink::{PollSend, Sink},
         watch,
     };
-    use futures::Stream;
+    use futures::{stream::FusedStream, Stream};
 
     macro_rules! test_stream {
         ($chan:expr, $val:expr) => {
@@ -225,7 +439,9 @@ mod stream_tests {
 
             drop(tx);
 
+            assert!(!rx.is_terminated());
             assert_eq!(Poll::Ready(None), Pin::new(&mut rx).poll_next(&mut std_cx));
+            assert!(rx.is_terminated());
         };
     }
 
@@ -257,6 +473,11 @@ mod stream_tests {
         test_stream!(broadcast::channel(4), 1usize);
     }
 
+    #[test]
+    fn broadcast_fanout() {
+        test_stream!(broadcast::fanout_channel(4), 1usize);
+    }
+
     #[test]
     fn dispatch() {
         test_stream!(dispatch::channel(4), 1usize);
@@ -267,11 +488,41 @@ mod stream_tests {
         test_stream!(mpsc::channel(4), 1usize);
     }
 
+    #[test]
+    fn mpsc_static() {
+        test_stream!(mpsc::static_channel::<_, 4>(), 1usize);
+    }
+
     #[test]
     fn oneshot() {
         test_stream!(oneshot::channel(), 1usize);
     }
 
+    #[test]
+    fn size_hint() {
+        use futures::StreamExt;
+
+        let mut cx = crate::test::noop_context();
+
+        let (mut tx, rx) = mpsc::channel(4);
+        assert_eq!((0, None), rx.size_hint());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, 2usize)
+        );
+        assert_eq!((2, None), rx.size_hint());
+
+        // the hint is available to futures combinators
+        drop(tx);
+        let stream = rx.map(|i| i * 2).enumerate();
+        assert_eq!((2, Some(2)), stream.size_hint());
+    }
+
     #[test]
     fn watch() {
         let mut std_cx = futures_test::task::noop_context();
@@ -296,5 +547,55 @@ mod stream_tests {
         drop(tx);
 
         assert_eq!(Poll::Ready(None), Pin::new(&mut rx).poll_next(&mut std_cx));
+        assert!(rx.is_terminated());
+    }
+}
+
+#[cfg(test)]
+mod future_tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures::future::FusedFuture;
+
+    use crate::{
+        mpsc,
+        sink::{PollSend, SendError, Sink},
+        stream::Stream,
+    };
+
+    #[test]
+    fn recv_future() {
+        let mut std_cx = futures_test::task::noop_context();
+        let (tx, mut rx) = mpsc::channel::<usize>(1);
+
+        let mut recv = Box::pin(rx.recv());
+        assert!(!recv.is_terminated());
+        assert_eq!(Poll::Pending, recv.as_mut().poll(&mut std_cx));
+        assert!(!recv.is_terminated());
+
+        drop(tx);
+        assert_eq!(Poll::Ready(None), recv.as_mut().poll(&mut std_cx));
+        assert!(recv.is_terminated());
+    }
+
+    #[test]
+    fn send_future() {
+        let mut std_cx = futures_test::task::noop_context();
+        let (mut tx, rx) = mpsc::channel(1);
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut crate::test::noop_context(), 1usize)
+        );
+
+        let mut send = Box::pin(tx.send(2usize));
+        assert_eq!(Poll::Pending, send.as_mut().poll(&mut std_cx));
+        assert!(!send.is_terminated());
+
+        drop(rx);
+        assert_eq!(
+            Poll::Ready(Err(SendError(2usize))),
+            send.as_mut().poll(&mut std_cx)
+        );
+        assert!(send.is_terminated());
     }
 }
@@ -0,0 +1,557 @@
+//! Layers, which wrap channel endpoints with reusable behavior, such as gating, deduplication, or logging.
+//!
+//! A [StreamLayer](./trait.StreamLayer.html) wraps a stream, and a [SinkLayer](./trait.SinkLayer.html) wraps a sink.
+//! Layers can be stacked with [Layers](./struct.Layers.html), or the [layers!](../macro.layers.html) macro.
+//! The first layer is applied first, so it is the innermost wrapper, and each later layer wraps the previous one.
+//! Received items pass through the layers in order, and sent messages pass through the layers in reverse order.
+//! Stacked layers are not boxed, so the wrapped endpoint has a concrete type.
+//!
+//! The mpsc [Builder](../mpsc/struct.Builder.html#method.layer) applies layers to the endpoints of the channel:
+//! ```rust
+//! use postage::{layer::{layer_fn, DedupLayer, GateLayer}, mpsc, prelude::*, stream::GateStream, watch};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (_gate_tx, gate_rx) = watch::channel_with(true);
+//!
+//!     let (mut tx, mut rx) = mpsc::builder(16)
+//!         .layer(GateLayer::new(gate_rx))
+//!         .layer(layer_fn(|rx: GateStream<mpsc::Receiver<usize>>| rx.map(|i| i * 2)))
+//!         .sender_layer(DedupLayer::new())
+//!         .build();
+//!
+//!     tx.send(1).await.ok();
+//!     tx.send(1).await.ok();
+//!     tx.send(2).await.ok();
+//!     drop(tx);
+//!
+//!     assert_eq!(Some(2), rx.recv().await);
+//!     assert_eq!(Some(4), rx.recv().await);
+//!     assert_eq!(None, rx.recv().await);
+//! }
+//! ```
+use crate::{
+    sink::{dedup::DedupSink, Sink},
+    stream::{GateStream, Stream},
+    watch,
+};
+
+/// Wraps a stream, producing a stream with added behavior.
+pub trait StreamLayer<S>
+where
+    S: Stream,
+{
+    /// The wrapped stream.
+    type Output: Stream;
+
+    /// Wraps the `inner` stream.
+    fn wrap(self, inner: S) -> Self::Output;
+}
+
+/// Wraps a sink, producing a sink with added behavior.
+pub trait SinkLayer<S>
+where
+    S: Sink,
+{
+    /// The wrapped sink.
+    type Output: Sink;
+
+    /// Wraps the `inner` sink.
+    fn wrap(self, inner: S) -> Self::Output;
+}
+
+/// A layer which returns the endpoint unchanged.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Identity;
+
+impl<S> StreamLayer<S> for Identity
+where
+    S: Stream,
+{
+    type Output = S;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        inner
+    }
+}
+
+impl<S> SinkLayer<S> for Identity
+where
+    S: Sink,
+{
+    type Output = S;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        inner
+    }
+}
+
+/// Two stacked layers.  The `inner` layer is applied first, and the `outer` layer wraps the result.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Stack<Inner, Outer> {
+    inner: Inner,
+    outer: Outer,
+}
+
+impl<Inner, Outer> Stack<Inner, Outer> {
+    pub fn new(inner: Inner, outer: Outer) -> Self {
+        Self { inner, outer }
+    }
+}
+
+impl<S, Inner, Outer> StreamLayer<S> for Stack<Inner, Outer>
+where
+    S: Stream,
+    Inner: StreamLayer<S>,
+    Outer: StreamLayer<Inner::Output>,
+{
+    type Output = Outer::Output;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        self.outer.wrap(self.inner.wrap(inner))
+    }
+}
+
+impl<S, Inner, Outer> SinkLayer<S> for Stack<Inner, Outer>
+where
+    S: Sink,
+    Inner: SinkLayer<S>,
+    Outer: SinkLayer<Inner::Output>,
+{
+    type Output = Outer::Output;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        self.outer.wrap(self.inner.wrap(inner))
+    }
+}
+
+/// A stack of layers, which is built by adding layers in the order they are applied.
+///
+/// Can be constructed with the [layers!](../macro.layers.html) macro.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Layers<L> {
+    layer: L,
+}
+
+impl Layers<Identity> {
+    pub fn new() -> Self {
+        Self { layer: Identity }
+    }
+}
+
+impl<L> Layers<L> {
+    /// Adds a layer, which wraps the endpoint produced by the previous layers.
+    pub fn layer<Outer>(self, layer: Outer) -> Layers<Stack<L, Outer>> {
+        Layers {
+            layer: Stack::new(self.layer, layer),
+        }
+    }
+
+    /// Wraps the stream with each layer, in order.
+    pub fn wrap_stream<S>(self, stream: S) -> L::Output
+    where
+        S: Stream,
+        L: StreamLayer<S>,
+    {
+        self.layer.wrap(stream)
+    }
+
+    /// Wraps the sink with each layer, in order.
+    pub fn wrap_sink<S>(self, sink: S) -> <L as SinkLayer<S>>::Output
+    where
+        S: Sink,
+        L: SinkLayer<S>,
+    {
+        self.layer.wrap(sink)
+    }
+
+    /// Returns the stacked layer.
+    pub fn into_inner(self) -> L {
+        self.layer
+    }
+}
+
+impl<S, L> StreamLayer<S> for Layers<L>
+where
+    S: Stream,
+    L: StreamLayer<S>,
+{
+    type Output = L::Output;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        self.layer.wrap(inner)
+    }
+}
+
+impl<S, L> SinkLayer<S> for Layers<L>
+where
+    S: Sink,
+    L: SinkLayer<S>,
+{
+    type Output = L::Output;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        self.layer.wrap(inner)
+    }
+}
+
+/// Constructs [Layers](./layer/struct.Layers.html) from a list of layers, in the order they are applied.
+///
+/// ```rust
+/// use postage::{layer::{layer_fn, GateLayer}, layers, mpsc, prelude::*, stream::GateStream, watch};
+///
+/// let (_gate_tx, gate_rx) = watch::channel_with(true);
+/// let layers = layers![
+///     GateLayer::new(gate_rx),
+///     layer_fn(|rx: GateStream<mpsc::Receiver<usize>>| rx.map(|i| i * 10)),
+/// ];
+///
+/// let (mut tx, rx) = mpsc::channel(4);
+/// let mut rx = layers.wrap_stream(rx);
+///
+/// tx.try_send(2).unwrap();
+/// assert_eq!(Ok(20), rx.try_recv());
+/// ```
+#[macro_export]
+macro_rules! layers {
+    ($($layer:expr),* $(,)?) => {
+        $crate::layer::Layers::new()$(.layer($layer))*
+    };
+}
+
+/// Returns a layer which wraps the endpoint with a function, such as a combinator.
+pub fn layer_fn<F>(f: F) -> LayerFn<F> {
+    LayerFn { f }
+}
+
+/// A layer created by [layer_fn](./fn.layer_fn.html).
+#[derive(Copy, Clone, Debug)]
+pub struct LayerFn<F> {
+    f: F,
+}
+
+impl<S, F, O> StreamLayer<S> for LayerFn<F>
+where
+    S: Stream,
+    F: FnOnce(S) -> O,
+    O: Stream,
+{
+    type Output = O;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        (self.f)(inner)
+    }
+}
+
+impl<S, F, O> SinkLayer<S> for LayerFn<F>
+where
+    S: Sink,
+    F: FnOnce(S) -> O,
+    O: Sink,
+{
+    type Output = O;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        (self.f)(inner)
+    }
+}
+
+/// A stream layer which forwards messages only while the watched gate value is `true`.
+///
+/// Behaves like [Stream::gate](../stream/trait.Stream.html#method.gate).
+#[derive(Clone, Debug)]
+pub struct GateLayer {
+    gate: watch::Receiver<bool>,
+    close_with_gate: bool,
+}
+
+impl GateLayer {
+    pub fn new(gate: watch::Receiver<bool>) -> Self {
+        Self {
+            gate,
+            close_with_gate: false,
+        }
+    }
+
+    /// Configures whether the stream is closed when the gate channel is closed.
+    ///
+    /// By default, the gate remains permanently open.
+    pub fn close_with_gate(mut self, close: bool) -> Self {
+        self.close_with_gate = close;
+        self
+    }
+}
+
+impl<S> StreamLayer<S> for GateLayer
+where
+    S: Stream,
+{
+    type Output = GateStream<S>;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        GateStream::new(inner, self.gate).close_with_gate(self.close_with_gate)
+    }
+}
+
+/// A sink layer which drops messages that are equal to the last message accepted by the sink.
+///
+/// Behaves like [Sink::sink_dedup](../sink/trait.Sink.html#method.sink_dedup).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DedupLayer;
+
+impl DedupLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> SinkLayer<S> for DedupLayer
+where
+    S: Sink,
+    S::Item: PartialEq + Clone,
+{
+    type Output = DedupSink<S>;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        DedupSink::new(inner)
+    }
+}
+
+/// A layer which logs messages using the Debug trait, at the provided log level.
+///
+/// Behaves like [Stream::log](../stream/trait.Stream.html#method.log) and
+/// [Sink::log](../sink/trait.Sink.html#method.log).  Requires the `logging` feature.
+#[cfg(feature = "logging")]
+#[derive(Copy, Clone, Debug)]
+pub struct LogLayer {
+    level: log::Level,
+}
+
+#[cfg(feature = "logging")]
+impl LogLayer {
+    pub fn new(level: log::Level) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "logging")]
+impl<S> StreamLayer<S> for LogLayer
+where
+    S: Stream,
+    S::Item: std::fmt::Debug,
+{
+    type Output = crate::stream::stream_log::StreamLog<S>;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        crate::stream::stream_log::StreamLog::new(inner, self.level)
+    }
+}
+
+#[cfg(feature = "logging")]
+impl<S> SinkLayer<S> for LogLayer
+where
+    S: Sink,
+    S::Item: std::fmt::Debug,
+{
+    type Output = crate::sink::sink_log::SinkLog<S>;
+
+    fn wrap(self, inner: S) -> Self::Output {
+        crate::sink::sink_log::SinkLog::new(inner, self.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, sync::Arc};
+
+    use parking_lot::Mutex;
+    use pin_project::pin_project;
+
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::{noop_context, stream::from_iter},
+        watch, Context,
+    };
+
+    use super::{layer_fn, DedupLayer, GateLayer, Identity, Layers, SinkLayer, StreamLayer};
+
+    type Trace = Arc<Mutex<Vec<(&'static str, usize)>>>;
+
+    // a layer which records each message it observes, so the order of stacked layers can be checked
+    #[derive(Clone)]
+    struct TraceLayer {
+        name: &'static str,
+        trace: Trace,
+    }
+
+    impl TraceLayer {
+        fn new(name: &'static str, trace: &Trace) -> Self {
+            Self {
+                name,
+                trace: trace.clone(),
+            }
+        }
+    }
+
+    #[pin_project]
+    struct Traced<S> {
+        #[pin]
+        inner: S,
+        layer: TraceLayer,
+    }
+
+    impl<S> StreamLayer<S> for TraceLayer
+    where
+        S: Stream<Item = usize>,
+    {
+        type Output = Traced<S>;
+
+        fn wrap(self, inner: S) -> Self::Output {
+            Traced { inner, layer: self }
+        }
+    }
+
+    impl<S> SinkLayer<S> for TraceLayer
+    where
+        S: Sink<Item = usize>,
+    {
+        type Output = Traced<S>;
+
+        fn wrap(self, inner: S) -> Self::Output {
+            Traced { inner, layer: self }
+        }
+    }
+
+    impl<S> Stream for Traced<S>
+    where
+        S: Stream<Item = usize>,
+    {
+        type Item = usize;
+
+        fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+            let this = self.project();
+            let poll = this.inner.poll_recv(cx);
+            if let PollRecv::Ready(value) = poll {
+                this.layer.trace.lock().push((this.layer.name, value));
+            }
+
+            poll
+        }
+    }
+
+    impl<S> Sink for Traced<S>
+    where
+        S: Sink<Item = usize>,
+    {
+        type Item = usize;
+
+        fn poll_send(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            value: Self::Item,
+        ) -> PollSend<Self::Item> {
+            let this = self.project();
+            this.layer.trace.lock().push((this.layer.name, value));
+            this.inner.poll_send(cx, value)
+        }
+    }
+
+    #[test]
+    fn identity() {
+        let mut cx = noop_context();
+        let mut stream = StreamLayer::wrap(Identity, from_iter(vec![1]));
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn stream_layers_apply_in_order() {
+        let mut cx = noop_context();
+        let trace = Trace::default();
+        let layers = layers![TraceLayer::new("a", &trace), TraceLayer::new("b", &trace)];
+
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = layers.wrap_stream(rx);
+
+        // items pass from the first layer to the last
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(vec![("a", 1), ("b", 1)], *trace.lock());
+    }
+
+    #[test]
+    fn sink_layers_apply_in_order() {
+        let mut cx = noop_context();
+        let trace = Trace::default();
+        let layers = Layers::new()
+            .layer(TraceLayer::new("a", &trace))
+            .layer(DedupLayer::new())
+            .layer(TraceLayer::new("c", &trace));
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut tx = layers.wrap_sink(tx);
+
+        // messages pass from the last layer to the first, so `c` observes the duplicate, and `a` does not
+        for i in [1, 1, 2] {
+            assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, i));
+        }
+        assert_eq!(
+            vec![("c", 1), ("a", 1), ("c", 1), ("c", 2), ("a", 2)],
+            *trace.lock()
+        );
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn gate_layer_order() {
+        let mut cx = noop_context();
+        let trace = Trace::default();
+        let (mut gate_tx, gate_rx) = watch::channel_with(false);
+
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = layers![
+            TraceLayer::new("inner", &trace),
+            GateLayer::new(gate_rx),
+            TraceLayer::new("outer", &trace),
+        ]
+        .wrap_stream(rx);
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert!(trace.lock().is_empty());
+
+        gate_tx.try_send(true).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(vec![("inner", 1), ("outer", 1)], *trace.lock());
+    }
+
+    #[test]
+    fn gate_closes_with_gate() {
+        let mut cx = noop_context();
+        let (gate_tx, gate_rx) = watch::channel_with(true);
+        let mut stream = GateLayer::new(gate_rx)
+            .close_with_gate(true)
+            .wrap(from_iter(vec![1, 2]));
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        drop(gate_tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn layer_fn_combinator() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = layer_fn(|rx: mpsc::Receiver<usize>| rx.map(|i| i * 2)).wrap(rx);
+
+        tx.try_send(2).unwrap();
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+}
@@ -7,33 +7,59 @@
 //!   - [dispatch](./dispatch/index.html), a multi-producer, multi-consumer queue.
 //!   - [mpsc](./mpsc/index.html), a multi-producer, single-consumer channel.
 //!   - [oneshot](./oneshot/index.html), a oneshot transfer channel.
+//!   - [spsc](./spsc/index.html), a wait-free single-producer, single-consumer ring buffer, for realtime threads.
 //!   - [watch](./watch/index.html), a state distribution channel with a value that can be borrowed.
 //! - Works with **any executor.**
 //!   - Currently regressions are written for `tokio` and `async-std`.
 //!   - With the `futures-traits` feature, channels implement the futures `Sink/Stream` traits.
 //! - **Throughly tested.**  
 //!   - Channels have full unit test coverage, and integration test coverage with multiple async executors.
+//! - Receivers **yield cooperatively** when they always have messages available.  See [coop](./coop/index.html).
 //! - Comes with **built-in [Sink](./sink/trait.Sink.html) and [Stream](./stream/trait.Stream.html) combinators.**
 //!   - Sinks can be chained, and filtered.
 //!   - Streams can be chained, filtered, mapped, and merged.
 //!   - With the `logging` feature, Sinks and streams can log their values.  This is really helpful when debugging applications.
+//!   - Combinators can be stacked onto channel endpoints as [layers](./layer/index.html).
 //!
 //! See [the readme](https://github.com/austinjones/postage-rs#benchmarks) for benchmarks.
 //!
 //! ## Cargo features:
-//! - `blocking (default)` - enables [Sink::blocking_send](./sink/trait.Sink.html#method.blocking_send) and [Stream::blocking_recv](./stream/trait.Stream.html#method.blocking_recv)
+//! - `blocking (default)` - enables [Sink::blocking_send](./sink/trait.Sink.html#method.blocking_send), [Stream::blocking_recv](./stream/trait.Stream.html#method.blocking_recv), and [Stream::into_blocking_iter](./stream/trait.Stream.html#method.into_blocking_iter)
+//! - `conformance` - enables the [conformance](./conformance/index.html) checks, for authors of custom channels.
 //! - `debug` - enables _extremely verbose_ internal log statements.
-//! - `futures-traits` - enables `futures::Sink` and `futures::Stream` implementations for the postage channels.  Compatible with `v0.3`.
+//! - `futures-traits` - enables `futures::Sink` and `futures::Stream` implementations for the postage channels.  Receivers also implement `FusedStream`, for use with `futures::select!`.  Compatible with `v0.3`.
+//! - `strict` - in debug builds, panics when a [BufferSink](./sink/struct.BufferSink.html) is dropped with unsent items, rather than logging a warning.
+//! - `registry` - enables the global [registry](./registry/index.html) of live channels, for diagnostics.
 //! - `logging (default)` - enables the enables [Sink::log(Level)](./sink/trait.Sink.html#method.log) and [Stream::log(Level)](./stream/trait.Stream.html#method.log) combinators.
+//! - `test-util` - enables the [test_util](./test_util/index.html) helpers, which assert how tasks are woken, and drive futures without a runtime.
+//! - `tokio` - enables time-based combinators, such as [Stream::timeout](./stream/trait.Stream.html#method.timeout), using the [tokio timer](./time/struct.Tokio.html), [pipelines](./pipeline/index.html) and [buffered streams](./stream/trait.Stream.html#method.buffered) with tasks spawned on tokio, and [sink::with_final_flush](./sink/fn.with_final_flush.html).
+//! - `async-std` - enables time-based combinators, using the [async-std timer](./time/struct.AsyncStd.html), [pipelines](./pipeline/index.html) and [buffered streams](./stream/trait.Stream.html#method.buffered) with tasks spawned on async-std.
+//!
+//! Without a runtime feature, time-based combinators accept a custom [Sleep](./time/trait.Sleep.html) implementation.
 
 mod channels;
-mod context;
+pub mod context;
+pub mod coop;
+pub mod layer;
 mod logging;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub mod pipeline;
 pub mod prelude;
 pub mod sink;
 pub mod stream;
 mod sync;
 
+pub mod time;
+
+#[cfg(any(test, feature = "conformance"))]
+pub mod conformance;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 #[cfg(feature = "futures-traits")]
 mod futures;
 
@@ -42,7 +68,9 @@ pub use channels::broadcast;
 pub use channels::dispatch;
 pub use channels::mpsc;
 pub use channels::oneshot;
+pub use channels::spsc;
 pub use channels::watch;
+pub use channels::{Capacity, InvalidCapacity};
 
 pub use context::Context;
 
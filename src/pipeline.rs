@@ -0,0 +1,451 @@
+//! Multi-stage pipelines, which own the tasks and channels of each stage.
+//!
+//! A [Pipeline](./struct.Pipeline.html) connects async stages with mpsc channels.  Each stage receives
+//! messages from the previous stage, and sends messages to the next stage.  When the pipeline is built,
+//! the stages are spawned on the runtime, and the caller receives the head sender and the tail receiver.
+//!
+//! [PipelineHandle::shutdown](./struct.PipelineHandle.html#method.shutdown) closes the head channel, and waits for
+//! every stage to complete.  Stages complete when their input channel is closed and drained, so messages which were
+//! sent before the shutdown are delivered to the tail.  If a stage panicked, the panic is propagated by the shutdown.
+//!
+//! Requires the `tokio` or `async-std` feature.  If both features are enabled, stages are spawned on tokio.
+//!
+//! ```rust
+//! use postage::{pipeline::Pipeline, prelude::*};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut tx, mut rx, handle) = Pipeline::new()
+//!         .stage(
+//!             |rx, mut tx| async move {
+//!                 let mut doubled = rx.map(|i: usize| i * 2);
+//!                 while let Some(i) = doubled.recv().await {
+//!                     tx.send(i).await.ok();
+//!                 }
+//!             },
+//!             4,
+//!         )
+//!         .stage(
+//!             |mut rx, mut tx| async move {
+//!                 while let Some(i) = rx.recv().await {
+//!                     tx.send(format!("#{}", i)).await.ok();
+//!                 }
+//!             },
+//!             4,
+//!         )
+//!         .build();
+//!
+//!     tx.send(1).await.ok();
+//!     tx.send(2).await.ok();
+//!     drop(tx);
+//!
+//!     let received = tokio::spawn(async move {
+//!         let mut received = Vec::new();
+//!         while let Some(message) = rx.recv().await {
+//!             received.push(message);
+//!         }
+//!         received
+//!     });
+//!
+//!     handle.shutdown().await;
+//!     assert_eq!(vec!["#2", "#4"], received.await.unwrap());
+//! }
+//! ```
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    pin::Pin,
+    task::Poll,
+};
+
+use static_assertions::assert_impl_all;
+
+use crate::{
+    mpsc, oneshot,
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+};
+
+type StageFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+type StageResult = Result<(), Box<dyn Any + Send>>;
+type Connect<In, Out> =
+    Box<dyn FnOnce(usize, &mut Vec<StageFuture>) -> (mpsc::Sender<In>, mpsc::Receiver<Out>) + Send>;
+
+/// A builder for a pipeline of async stages, connected by mpsc channels.
+///
+/// `In` is the message type of the head channel, and `Out` is the message type of the last stage.
+pub struct Pipeline<In, Out> {
+    // creates the channels and stage futures, given the capacity of the head channel
+    connect: Connect<In, Out>,
+    head_capacity: Option<usize>,
+}
+
+assert_impl_all!(Pipeline<usize, String>: Send);
+
+impl<T> Pipeline<T, T>
+where
+    T: Send + 'static,
+{
+    /// Constructs a pipeline without stages.
+    pub fn new() -> Self {
+        Self {
+            connect: Box::new(|capacity, _stages| mpsc::channel(capacity)),
+            head_capacity: None,
+        }
+    }
+}
+
+impl<T> Default for Pipeline<T, T>
+where
+    T: Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<In, Out> Pipeline<In, Out>
+where
+    In: Send + 'static,
+    Out: Send + 'static,
+{
+    /// Adds a stage, which receives the messages of the previous stage, and sends messages to the next stage.
+    ///
+    /// `capacity` is the capacity of the channel the stage sends to.  The head channel has the capacity of the
+    /// first stage.  The stage is spawned when the pipeline is built, and should complete when its receiver
+    /// is closed, so that the pipeline can be shut down.
+    pub fn stage<Next, F, Fut>(self, stage: F, capacity: usize) -> Pipeline<In, Next>
+    where
+        Next: Send + 'static,
+        F: FnOnce(mpsc::Receiver<Out>, mpsc::Sender<Next>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let connect = self.connect;
+
+        Pipeline {
+            connect: Box::new(move |head_capacity, stages| {
+                let (head, rx) = connect(head_capacity, stages);
+                let (tx, tail) = mpsc::channel(capacity);
+                stages.push(Box::pin(stage(rx, tx)));
+
+                (head, tail)
+            }),
+            head_capacity: self.head_capacity.or(Some(capacity)),
+        }
+    }
+
+    /// Creates the channels, and spawns the stages.
+    ///
+    /// Returns the head sender, the tail receiver, and a handle which can shut down the pipeline.
+    /// If the handle is dropped, the stages continue to run in the background.
+    ///
+    /// Panics if the pipeline has no stages.
+    #[track_caller]
+    pub fn build(self) -> (mpsc::Sender<In>, mpsc::Receiver<Out>, PipelineHandle<In>) {
+        let head_capacity = self
+            .head_capacity
+            .expect("a pipeline must have at least one stage");
+
+        let mut stages = Vec::new();
+        let (head, tail) = (self.connect)(head_capacity, &mut stages);
+
+        let results = stages
+            .into_iter()
+            .map(|stage| {
+                let (mut tx, rx) = oneshot::channel();
+                spawn(async move {
+                    let result = CatchUnwind { future: stage }.await;
+                    tx.try_send(result).ok();
+                });
+
+                rx
+            })
+            .collect();
+
+        let handle = PipelineHandle {
+            head: head.clone(),
+            results,
+        };
+
+        (head, tail, handle)
+    }
+}
+
+impl<In, Out> fmt::Debug for Pipeline<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline").finish()
+    }
+}
+
+/// A handle to the stages of a running pipeline, returned by [Pipeline::build](./struct.Pipeline.html#method.build).
+pub struct PipelineHandle<In> {
+    head: mpsc::Sender<In>,
+    results: VecDeque<oneshot::Receiver<StageResult>>,
+}
+
+assert_impl_all!(PipelineHandle<usize>: Send, Sync);
+
+impl<In> PipelineHandle<In> {
+    /// Closes the head channel, and waits for the stages to drain their messages and complete.
+    ///
+    /// The tail receiver must be received concurrently (or dropped), or the last stage may wait for capacity,
+    /// and the shutdown will not complete.  If a stage panicked, the shutdown panics with the first stage panic,
+    /// after all of the other stages have completed.
+    pub fn shutdown(self) -> ShutdownFuture<In> {
+        ShutdownFuture {
+            head: Some(self.head),
+            results: self.results,
+            panic: None,
+        }
+    }
+}
+
+impl<In> fmt::Debug for PipelineHandle<In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineHandle")
+            .field("stages", &self.results.len())
+            .finish()
+    }
+}
+
+/// A future returned by [PipelineHandle::shutdown](./struct.PipelineHandle.html#method.shutdown).
+#[must_use = "futures do nothing unless polled"]
+pub struct ShutdownFuture<In> {
+    head: Option<mpsc::Sender<In>>,
+    // the results of the stages which have not completed, in pipeline order
+    results: VecDeque<oneshot::Receiver<StageResult>>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+assert_impl_all!(ShutdownFuture<usize>: Send, Unpin);
+
+impl<In> Future for ShutdownFuture<In> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut cx: crate::Context<'_> = cx.into();
+
+        if let Some(mut head) = this.head.take() {
+            // mpsc senders close immediately, and the channel stays closed after the sender is dropped
+            if let PollSend::Pending(()) = Pin::new(&mut head).poll_close(&mut cx) {
+                this.head = Some(head);
+                return Poll::Pending;
+            }
+        }
+
+        while let Some(result) = this.results.front_mut() {
+            let panic = match Pin::new(result).poll_recv(&mut cx) {
+                PollRecv::Ready(Ok(())) => None,
+                PollRecv::Ready(Err(panic)) => Some(panic),
+                PollRecv::Closed => Some(Box::new("a pipeline stage was cancelled by the runtime")
+                    as Box<dyn Any + Send>),
+                PollRecv::Pending => return Poll::Pending,
+            };
+
+            if this.panic.is_none() {
+                this.panic = panic;
+            }
+
+            this.results.pop_front();
+        }
+
+        if let Some(panic) = this.panic.take() {
+            resume_unwind(panic);
+        }
+
+        Poll::Ready(())
+    }
+}
+
+impl<In> fmt::Debug for ShutdownFuture<In> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownFuture")
+            .field("stages", &self.results.len())
+            .finish()
+    }
+}
+
+// Catches a panic in a stage, so the shutdown can propagate it on any runtime
+struct CatchUnwind {
+    future: StageFuture,
+}
+
+impl Future for CatchUnwind {
+    type Output = StageResult;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        match catch_unwind(AssertUnwindSafe(|| self.future.as_mut().poll(cx))) {
+            Ok(Poll::Ready(())) => Poll::Ready(Ok(())),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
+
+// spawns a task on the enabled runtime, which is also used by Stream::buffered
+#[cfg(feature = "tokio")]
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(future);
+}
+
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    async_std::task::spawn(future);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        mpsc,
+        prelude::{Sink, Stream},
+        time::{RuntimeSleep, Sleep},
+    };
+
+    use super::Pipeline;
+
+    // forwards messages until the input is closed
+    async fn forward(mut rx: mpsc::Receiver<usize>, mut tx: mpsc::Sender<usize>) {
+        while let Some(i) = rx.recv().await {
+            if tx.send(i).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    fn collect(mut rx: mpsc::Receiver<usize>) -> tokio::task::JoinHandle<Vec<usize>> {
+        tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(i) = rx.recv().await {
+                received.push(i);
+            }
+            received
+        })
+    }
+
+    #[tokio::test]
+    async fn completes_normally() {
+        let (mut tx, rx, handle) = Pipeline::new()
+            .stage(
+                |rx: mpsc::Receiver<usize>, mut tx| async move {
+                    let mut odd = rx.filter(|i| i % 2 == 1);
+                    while let Some(i) = odd.recv().await {
+                        tx.send(i * 10).await.ok();
+                    }
+                },
+                2,
+            )
+            .stage(forward, 2)
+            .build();
+
+        let received = collect(rx);
+        for i in 0..10 {
+            tx.send(i).await.expect("the pipeline is running");
+        }
+        drop(tx);
+
+        handle.shutdown().await;
+        assert_eq!(vec![10, 30, 50, 70, 90], received.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_busy_stages() {
+        let (mut tx, rx, handle) = Pipeline::new()
+            .stage(
+                |mut rx: mpsc::Receiver<usize>, mut tx| async move {
+                    while let Some(i) = rx.recv().await {
+                        RuntimeSleep::default()
+                            .sleep(Duration::from_millis(1))
+                            .await;
+                        tx.send(i).await.ok();
+                    }
+                },
+                4,
+            )
+            .stage(forward, 4)
+            .build();
+
+        let received = collect(rx);
+        for i in 0..16 {
+            tx.send(i).await.expect("the pipeline is running");
+        }
+
+        // the sender is still alive, and the first stage is still processing messages
+        handle.shutdown().await;
+        assert_eq!(
+            Err(crate::sink::SendError(16)),
+            tx.send(16).await,
+            "the head is closed"
+        );
+        assert_eq!((0..16).collect::<Vec<_>>(), received.await.unwrap());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "the stage failed")]
+    async fn stage_panic_propagates() {
+        let (mut tx, rx, handle) = Pipeline::new()
+            .stage(forward, 2)
+            .stage(
+                |mut rx: mpsc::Receiver<usize>, mut tx: mpsc::Sender<usize>| async move {
+                    while let Some(i) = rx.recv().await {
+                        if i == 3 {
+                            panic!("the stage failed");
+                        }
+                        tx.send(i).await.ok();
+                    }
+                },
+                2,
+            )
+            .build();
+
+        let received = collect(rx);
+        for i in 0..8 {
+            if tx.send(i).await.is_err() {
+                break;
+            }
+        }
+
+        // the stages after the failure are closed, and the messages before it are delivered
+        assert_eq!(vec![0, 1, 2], received.await.unwrap());
+        handle.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn early_stage_failure_closes_pipeline() {
+        let (mut tx, mut rx, handle) = Pipeline::new()
+            .stage(
+                |_rx: mpsc::Receiver<usize>, _tx: mpsc::Sender<usize>| async move {
+                    panic!("the stage failed");
+                },
+                2,
+            )
+            .stage(forward, 2)
+            .build();
+
+        // the failed stage dropped its receiver and sender
+        assert_eq!(None, rx.recv().await);
+        tokio::task::yield_now().await;
+        assert!(tx.send(1).await.is_err());
+
+        let shutdown = tokio::spawn(handle.shutdown());
+        assert!(shutdown.await.unwrap_err().is_panic());
+    }
+
+    #[test]
+    #[should_panic(expected = "a pipeline must have at least one stage")]
+    fn requires_stage() {
+        Pipeline::<usize, usize>::new().build();
+    }
+}
@@ -0,0 +1,241 @@
+//! A global registry of live channels, for diagnostics.
+//!
+//! Channels are registered when they are created, and removed when the last sender and receiver are dropped.
+//! A [snapshot](./struct.ChannelRegistry.html#method.snapshot) describes each live channel, and can be used by
+//! exporters and debug endpoints.
+//!
+//! The mpsc, spsc, broadcast, dispatch and watch channels are registered.  Oneshot and barrier channels are not.
+//!
+//! Requires the `registry` feature.  The registry only reads counters which do not depend on the message type,
+//! so channels of any message type are registered.  Channels publish their length when their endpoints are
+//! notified, so the length in a snapshot may be briefly stale.
+//!
+//! ```rust
+//! use postage::{mpsc, registry::ChannelRegistry, sink::Sink};
+//!
+//! let (mut tx, _rx) = mpsc::channel::<usize>(4);
+//! tx.try_send(1).unwrap();
+//!
+//! let info = ChannelRegistry::snapshot()
+//!     .into_iter()
+//!     .find(|info| info.name == "mpsc::channel")
+//!     .unwrap();
+//!
+//! assert_eq!(1, info.len);
+//! assert_eq!(Some(4), info.capacity);
+//! ```
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::sync::ChannelCounters;
+
+/// A description of a live channel, produced by `ChannelRegistry::snapshot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelInfo {
+    /// A unique id, assigned when the channel was created.
+    pub id: u64,
+    /// The constructor which created the channel, such as `mpsc::channel`.
+    /// Variants share the name of the constructor, so `watch::channel_with` is registered as `watch::channel`.
+    pub name: &'static str,
+    /// The type name of the channel message.
+    pub item_type: &'static str,
+    /// The buffer capacity, or `None` if the channel does not buffer messages.
+    pub capacity: Option<usize>,
+    /// The number of buffered messages.  For broadcast channels, this is the lag of the slowest receiver.
+    pub len: usize,
+    /// The number of live senders.
+    pub senders: usize,
+    /// The number of live receivers.
+    pub receivers: usize,
+}
+
+/// The global registry of live channels.
+pub struct ChannelRegistry;
+
+impl ChannelRegistry {
+    /// Returns a description of each live channel, in order of creation.
+    pub fn snapshot() -> Vec<ChannelInfo> {
+        CHANNELS
+            .lock()
+            .iter()
+            .map(|(id, registration)| ChannelInfo {
+                id: *id,
+                name: registration.name,
+                item_type: registration.item_type,
+                capacity: registration.capacity,
+                len: registration.counters.len(),
+                senders: registration.counters.senders(),
+                receivers: registration.counters.receivers(),
+            })
+            .collect()
+    }
+}
+
+struct Registration {
+    name: &'static str,
+    item_type: &'static str,
+    capacity: Option<usize>,
+    counters: Arc<ChannelCounters>,
+}
+
+static CHANNELS: Mutex<BTreeMap<u64, Registration>> = const_mutex(BTreeMap::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+pub(crate) fn register(
+    name: &'static str,
+    item_type: &'static str,
+    capacity: Option<usize>,
+    counters: Arc<ChannelCounters>,
+) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    let registration = Registration {
+        name,
+        item_type,
+        capacity,
+        counters,
+    };
+
+    CHANNELS.lock().insert(id, registration);
+    id
+}
+
+pub(crate) fn unregister(id: u64) {
+    CHANNELS.lock().remove(&id);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{broadcast, dispatch, mpsc, sink::Sink, spsc, watch};
+
+    use super::{ChannelInfo, ChannelRegistry};
+
+    // the registry is global, so each test uses its own message type
+    fn find<T>() -> Vec<ChannelInfo> {
+        let item_type = std::any::type_name::<T>();
+
+        ChannelRegistry::snapshot()
+            .into_iter()
+            .filter(|info| info.item_type == item_type)
+            .collect()
+    }
+
+    #[test]
+    fn tracks_channel() {
+        struct Message;
+
+        let (mut tx, rx) = mpsc::channel(4);
+        assert!(tx.try_send(Message).is_ok());
+        assert!(tx.try_send(Message).is_ok());
+
+        let info = find::<Message>();
+        assert_eq!(1, info.len());
+        assert_eq!("mpsc::channel", info[0].name);
+        assert_eq!(Some(4), info[0].capacity);
+        assert_eq!(2, info[0].len);
+        assert_eq!(1, info[0].senders);
+        assert_eq!(1, info[0].receivers);
+
+        let tx2 = tx.clone();
+        assert_eq!(2, find::<Message>()[0].senders);
+
+        drop(tx);
+        drop(tx2);
+        assert_eq!(1, find::<Message>().len());
+
+        drop(rx);
+        assert!(find::<Message>().is_empty());
+    }
+
+    #[test]
+    fn tracks_channel_kinds() {
+        #[derive(Clone, Default)]
+        struct Message;
+
+        let channels = (
+            mpsc::channel::<Message>(4),
+            mpsc::static_channel::<Message, 4>(),
+            broadcast::channel::<Message>(4),
+            broadcast::fanout_channel::<Message>(4),
+            dispatch::channel::<Message>(4),
+            spsc::channel::<Message>(4),
+            watch::channel::<Message>(),
+        );
+
+        let mut names: Vec<_> = find::<Message>()
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        names.sort_unstable();
+        assert_eq!(
+            vec![
+                "broadcast::channel",
+                "broadcast::fanout_channel",
+                "dispatch::channel",
+                "mpsc::channel",
+                "mpsc::static_channel",
+                "spsc::channel",
+                "watch::channel"
+            ],
+            names
+        );
+
+        drop(channels);
+        assert!(find::<Message>().is_empty());
+    }
+
+    #[test]
+    fn tracks_spsc_channel() {
+        struct Message;
+
+        let (mut tx, rx) = spsc::channel(4);
+        assert!(tx.try_send(Message).is_ok());
+
+        let info = find::<Message>();
+        assert_eq!(1, info.len());
+        assert_eq!(Some(4), info[0].capacity);
+        assert_eq!(1, info[0].len);
+        assert_eq!(1, info[0].senders);
+        assert_eq!(1, info[0].receivers);
+
+        drop(tx);
+        assert_eq!(0, find::<Message>()[0].senders);
+
+        drop(rx);
+        assert!(find::<Message>().is_empty());
+    }
+
+    #[test]
+    fn tracks_non_send_messages() {
+        #[allow(dead_code)]
+        struct Message(std::rc::Rc<u8>);
+
+        let (mut tx, rx) = mpsc::channel(4);
+        assert!(tx.try_send(Message(std::rc::Rc::new(1))).is_ok());
+
+        let info = find::<Message>();
+        assert_eq!(1, info.len());
+        assert_eq!(1, info[0].len);
+
+        drop((tx, rx));
+        assert!(find::<Message>().is_empty());
+    }
+
+    #[test]
+    fn no_leaks() {
+        struct Message;
+
+        let channels: Vec<_> = (0..16).map(|_| mpsc::channel::<Message>(4)).collect();
+        assert_eq!(16, find::<Message>().len());
+
+        drop(channels);
+        assert!(find::<Message>().is_empty());
+    }
+}
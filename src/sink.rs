@@ -56,14 +56,25 @@ use std::{future::Future, ops::DerefMut, pin::Pin, task::Poll};
 use crate::Context;
 use pin_project::pin_project;
 
+mod buffer;
 mod chain;
+pub(crate) mod dedup;
 mod errors;
+mod fanout;
 mod filter;
+#[cfg(feature = "tokio")]
+mod final_flush;
+mod retry;
+mod with;
 
 #[cfg(feature = "logging")]
-mod sink_log;
+pub(crate) mod sink_log;
 
+pub use buffer::{BufferFlushFuture, BufferSink, FlushFuture};
 pub use errors::*;
+#[cfg(feature = "tokio")]
+pub use final_flush::{with_final_flush, FinalFlush};
+pub use retry::{RetryPolicy, RetrySink};
 
 /// A sink which can asynchronously accept messages, and at some point may refuse to accept any further messages.
 ///
@@ -110,7 +121,7 @@ pub use errors::*;
 ///
 ///     combo.send(1usize).await.ok();
 ///     combo.send(2usize).await.ok();
-
+///
 ///     assert_eq!(Some(2usize), rx.recv().await);
 ///     drop(rx);
 ///
@@ -132,18 +143,33 @@ pub trait Sink {
     /// - `PollSend::Ready` if the value was sent
     /// - `PollSend::Pending(value)` if the channel is full.  The channel will call the waker in `cx` when the item may be accepted in the future.
     /// - `PollSend::Rejected(value)` if the channel is closed, and will never accept the item.
+    ///
+    /// The value is returned unchanged by `Pending` and `Rejected`.  Once `Rejected` is returned,
+    /// every later call must also return `Rejected`.
     fn poll_send(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         value: Self::Item,
     ) -> PollSend<Self::Item>;
 
+    /// Attempts to close the sink, signaling that no further messages will be sent.
+    ///
+    /// Returns:
+    /// - `PollSend::Ready` if the sink is closed.  Further messages are rejected.
+    /// - `PollSend::Pending(())` if the sink must flush buffered messages before it can close.
+    /// - `PollSend::Rejected(())` if buffered messages could not be flushed, as the target was closed.
+    ///
+    /// The default implementation does nothing, and returns `Ready`.
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> PollSend<()> {
+        PollSend::Ready
+    }
+
     /// Attempts to send a message into the sink.  
     ///
     /// Returns:
     /// - `Ok(())` if the value was accepted.
     /// - `Err(SendError(value))` if the sink rejected the message.
-    fn send(&mut self, value: Self::Item) -> SendFuture<Self> {
+    fn send(&mut self, value: Self::Item) -> SendFuture<'_, Self> {
         SendFuture::new(self, value)
     }
 
@@ -177,6 +203,17 @@ pub trait Sink {
         pollster::block_on(self.send(value))
     }
 
+    /// Closes the sink, signaling that no further messages will be sent.
+    ///
+    /// Channel senders close the channel for all senders.  Receivers observe the closure once buffered messages are received.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the sink was closed.
+    /// - `Err(SendError(()))` if buffered messages could not be flushed, as the target was closed.
+    fn close(&mut self) -> CloseFuture<'_, Self> {
+        CloseFuture::new(self)
+    }
+
     /// Chains two sink implementations.  Messages will be transmitted to the argument until it rejects a message.
     /// Then messages will be transmitted to self.
     fn after<Before>(self, before: Before) -> chain::ChainSink<Before, Self>
@@ -196,6 +233,99 @@ pub trait Sink {
         filter::FilterSink::new(filter, self)
     }
 
+    /// Maps messages with `map`, and forwards them to the sink.
+    ///
+    /// If the sink returns a message as pending or rejected, the original message is returned, so it isn't lost.  As
+    /// the mapped message can't be converted back, `map` receives a clone, and is called again when a pending message
+    /// is sent again.
+    fn with<F, From>(self, map: F) -> with::WithSink<Self, F, From>
+    where
+        F: FnMut(From) -> Self::Item,
+        From: Clone,
+        Self: Sized,
+    {
+        with::WithSink::new(self, map)
+    }
+
+    /// Maps messages with `map`, and forwards them to the sink if `map` returns `Some`.  Otherwise, the message is
+    /// dropped, and `Ready` is returned.
+    ///
+    /// Pending and rejected messages are returned unmapped, as with [with](#method.with).
+    fn with_filter_map<F, From>(self, map: F) -> with::WithFilterMapSink<Self, F, From>
+    where
+        F: FnMut(From) -> Option<Self::Item>,
+        From: Clone,
+        Self: Sized,
+    {
+        with::WithFilterMapSink::new(self, map)
+    }
+
+    /// Sends each message to this sink, and to `other`.  The message is accepted once both sinks accept it.
+    ///
+    /// If this sink accepts the message, and `other` is pending, the message is not sent to this sink again.  The
+    /// same message must be sent again, which `send` does.  If either sink rejects a message, it is returned as
+    /// `Rejected`, and the fanout rejects all later messages.
+    fn fanout<Other>(self, other: Other) -> fanout::FanoutSink<Self, Other>
+    where
+        Other: Sink<Item = Self::Item>,
+        Self::Item: Clone,
+        Self: Sized,
+    {
+        fanout::FanoutSink::new(self, other)
+    }
+
+    /// Drops messages that are equal to the last message accepted by the sink.
+    ///
+    /// A message only becomes the last message once the sink accepts it, so a message returned as pending
+    /// will be forwarded when it is sent again.
+    fn sink_dedup(self) -> dedup::DedupSink<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq + Clone,
+    {
+        dedup::DedupSink::new(self)
+    }
+
+    /// Accepts up to `capacity` messages while the sink is pending, and sends them in order as the sink frees capacity.
+    ///
+    /// Buffered messages are only sent when the buffer is polled, so the buffer should be flushed with
+    /// [BufferSink::flush](./struct.BufferSink.html#method.flush), or closed with
+    /// [BufferSink::close](./struct.BufferSink.html#method.close), which returns any messages that could not be sent.
+    /// Panics if the capacity is zero.
+    #[track_caller]
+    fn buffer(self, capacity: usize) -> BufferSink<Self>
+    where
+        Self: Sized,
+    {
+        BufferSink::new(self, capacity)
+    }
+
+    /// Retries messages rejected by the sink, waiting with an exponential backoff between attempts.
+    ///
+    /// The rejection of the inner sink is treated as transient.  While a message waits for a retry, the sink returns
+    /// `Pending`, and the same message must be sent again (which `send` does).  Once a message is rejected
+    /// `max_attempts` times in a row, it is returned as `Rejected`, and the sink rejects all later messages.
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be polled within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn retry(self, policy: RetryPolicy) -> RetrySink<Self, crate::time::RuntimeSleep>
+    where
+        Self: Sized,
+    {
+        RetrySink::new(self, policy, crate::time::RuntimeSleep::default())
+    }
+
+    /// Retries messages rejected by the sink, using the provided [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [retry](#method.retry), but does not require an async runtime feature.
+    fn retry_with<T>(self, policy: RetryPolicy, timer: T) -> RetrySink<Self, T>
+    where
+        Self: Sized,
+        T: crate::time::Sleep,
+    {
+        RetrySink::new(self, policy, timer)
+    }
+
     /// Logs messages that are accepted by the sink using the Debug trait, at the provided log level.
     ///
     /// Requires the `logging` feature
@@ -222,6 +352,10 @@ where
     ) -> PollSend<Self::Item> {
         S::poll_send(Pin::new(&mut **self), cx, value)
     }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        S::poll_close(Pin::new(&mut **self), cx)
+    }
 }
 
 impl<P, S> Sink for Pin<P>
@@ -238,6 +372,10 @@ where
     ) -> PollSend<Self::Item> {
         Pin::get_mut(self).as_mut().poll_send(cx, value)
     }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        Pin::get_mut(self).as_mut().poll_close(cx)
+    }
 }
 
 /// An enum of poll responses that are produced by Sink implementations.
@@ -270,7 +408,7 @@ impl<'s, S> SendFuture<'s, S>
 where
     S: Sink + ?Sized,
 {
-    pub fn new(send: &'s mut S, value: S::Item) -> SendFuture<S> {
+    pub fn new(send: &'s mut S, value: S::Item) -> SendFuture<'s, S> {
         Self {
             send,
             value: Some(value),
@@ -304,8 +442,70 @@ where
     }
 }
 
+#[cfg(feature = "futures-traits")]
+mod impl_futures {
+    use super::{SendFuture, Sink};
+
+    impl<'s, S> futures::future::FusedFuture for SendFuture<'s, S>
+    where
+        S: Sink + Unpin + ?Sized,
+    {
+        fn is_terminated(&self) -> bool {
+            self.value.is_none()
+        }
+    }
+}
+
+/// A future returned by `Sink::close`, which closes the sink.
+#[must_use = "futures do nothing unless polled"]
+pub struct CloseFuture<'s, S>
+where
+    S: Sink + ?Sized,
+{
+    sink: &'s mut S,
+}
+
+impl<'s, S> CloseFuture<'s, S>
+where
+    S: Sink + ?Sized,
+{
+    pub fn new(sink: &'s mut S) -> CloseFuture<'s, S> {
+        Self { sink }
+    }
+}
+
+impl<'s, S> Future for CloseFuture<'s, S>
+where
+    S: Sink + Unpin + ?Sized,
+{
+    type Output = Result<(), SendError<()>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut cx: crate::Context<'_> = cx.into();
+        match Pin::new(&mut *self.sink).poll_close(&mut cx) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(()) => Poll::Pending,
+            PollSend::Rejected(()) => Poll::Ready(Err(SendError(()))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    #[tokio::test]
+    async fn close() {
+        use super::{SendError, Sink};
+        use crate::{mpsc, stream::Stream};
+
+        let (mut tx, mut rx) = mpsc::channel(2);
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(Ok(()), tx.close().await);
+        assert_eq!(Err(SendError(2)), tx.send(2).await);
+
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+
     #[cfg(feature = "blocking")]
     #[test]
     fn test_blocking() {
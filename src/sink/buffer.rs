@@ -0,0 +1,438 @@
+use std::{collections::VecDeque, future::Future, pin::Pin, task::Poll};
+
+use crate::sink::{FlushError, PollSend, SendError, Sink};
+use crate::Context;
+use pin_project::{pin_project, pinned_drop};
+
+/// A sink returned by [Sink::buffer](./trait.Sink.html#method.buffer), which accepts items while the inner sink
+/// is pending.
+///
+/// Rust has no async Drop, so when the sink is dropped, buffered items are only sent if the inner sink accepts them
+/// without waiting, and the rest are lost.  [flush](#method.flush) sends the buffered items, and
+/// [close](#method.close) also returns the items which could not be sent.  In debug builds, dropping the sink with
+/// unsent items logs a warning, or panics with the `strict` feature.
+#[pin_project(PinnedDrop)]
+#[must_use = "buffered items are lost unless the sink is closed"]
+pub struct BufferSink<S>
+where
+    S: Sink,
+{
+    #[pin]
+    sink: S,
+    buffer: VecDeque<S::Item>,
+    capacity: usize,
+}
+
+impl<S> BufferSink<S>
+where
+    S: Sink,
+{
+    #[track_caller]
+    pub fn new(sink: S, capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "the buffer capacity must be greater than zero"
+        );
+
+        Self {
+            sink,
+            buffer: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the number of items which have been accepted, but not sent to the inner sink.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Sends the buffered items, and closes the inner sink.
+    ///
+    /// Resolves to `Err(FlushError(items))` with the unsent items, if the inner sink rejects an item.
+    pub fn close(self) -> FlushFuture<S> {
+        FlushFuture { sink: Some(self) }
+    }
+
+    /// Returns a future which sends the buffered items, without closing the inner sink.
+    ///
+    /// Resolves to `Err(SendError(()))` if the inner sink is closed.  The unsent items remain buffered, and are
+    /// returned by [close](#method.close).
+    pub fn flush(&mut self) -> BufferFlushFuture<'_, S>
+    where
+        S: Unpin,
+    {
+        BufferFlushFuture { sink: self }
+    }
+
+    /// Sends buffered items until the buffer is empty, or the inner sink is pending or closed.
+    ///
+    /// Returns:
+    /// - `PollSend::Ready` if the buffer is empty.
+    /// - `PollSend::Pending(())` if the inner sink is pending.  The inner sink will call the waker in `cx`.
+    /// - `PollSend::Rejected(())` if the inner sink is closed.  The unsent items remain buffered.
+    pub fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        let mut this = self.project();
+
+        while let Some(value) = this.buffer.pop_front() {
+            match this.sink.as_mut().poll_send(cx, value) {
+                PollSend::Ready => {}
+                PollSend::Pending(value) => {
+                    this.buffer.push_front(value);
+                    return PollSend::Pending(());
+                }
+                PollSend::Rejected(value) => {
+                    this.buffer.push_front(value);
+                    return PollSend::Rejected(());
+                }
+            }
+        }
+
+        PollSend::Ready
+    }
+}
+
+impl<S> Sink for BufferSink<S>
+where
+    S: Sink,
+{
+    type Item = S::Item;
+
+    fn poll_send(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        if let PollSend::Rejected(()) = self.as_mut().poll_flush(cx) {
+            return PollSend::Rejected(value);
+        }
+
+        // the flush registered the waker, if the buffer is still full
+        if self.buffer.len() >= self.capacity {
+            return PollSend::Pending(value);
+        }
+
+        self.as_mut().project().buffer.push_back(value);
+
+        // the inner sink may be able to send the item right away.  if the inner sink is closed, the item is
+        // rejected, and the older items are returned by `close`
+        if let PollSend::Rejected(()) = self.as_mut().poll_flush(cx) {
+            let value = self.project().buffer.pop_back().unwrap();
+            return PollSend::Rejected(value);
+        }
+
+        PollSend::Ready
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        match self.as_mut().poll_flush(cx) {
+            PollSend::Ready => self.project().sink.poll_close(cx),
+            poll => poll,
+        }
+    }
+}
+
+#[pinned_drop]
+impl<S> PinnedDrop for BufferSink<S>
+where
+    S: Sink,
+{
+    fn drop(mut self: Pin<&mut Self>) {
+        // a best-effort flush, which sends the items the inner sink accepts without waiting
+        let _ = self.as_mut().poll_flush(&mut Context::empty());
+
+        #[cfg(debug_assertions)]
+        if !self.buffer.is_empty() && !std::thread::panicking() {
+            #[cfg(feature = "strict")]
+            panic!(
+                "BufferSink dropped with {} unsent items.  Close the sink to flush them.",
+                self.buffer.len()
+            );
+
+            #[cfg(all(feature = "logging", not(feature = "strict")))]
+            log::warn!(
+                "BufferSink dropped with {} unsent items.  Close the sink to flush them.",
+                self.buffer.len()
+            );
+        }
+    }
+}
+
+/// A future returned by [BufferSink::flush](./struct.BufferSink.html#method.flush), which sends the buffered items.
+#[must_use = "futures do nothing unless polled"]
+pub struct BufferFlushFuture<'s, S>
+where
+    S: Sink,
+{
+    sink: &'s mut BufferSink<S>,
+}
+
+impl<'s, S> Future for BufferFlushFuture<'s, S>
+where
+    S: Sink + Unpin,
+{
+    type Output = Result<(), SendError<()>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut cx: Context<'_> = cx.into();
+        match Pin::new(&mut *self.sink).poll_flush(&mut cx) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(()) => Poll::Pending,
+            PollSend::Rejected(()) => Poll::Ready(Err(SendError(()))),
+        }
+    }
+}
+
+/// A future returned by [BufferSink::close](./struct.BufferSink.html#method.close), which flushes the buffer, and
+/// closes the inner sink.
+///
+/// Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct FlushFuture<S>
+where
+    S: Sink,
+{
+    #[pin]
+    sink: Option<BufferSink<S>>,
+}
+
+impl<S> Future for FlushFuture<S>
+where
+    S: Sink,
+{
+    type Output = Result<(), FlushError<Vec<S::Item>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let mut sink = this
+            .sink
+            .as_mut()
+            .as_pin_mut()
+            .expect("FlushFuture polled after completion");
+
+        let mut cx: Context<'_> = cx.into();
+        let result = match sink.as_mut().poll_close(&mut cx) {
+            PollSend::Ready => Ok(()),
+            PollSend::Pending(()) => return Poll::Pending,
+            PollSend::Rejected(()) => {
+                let unsent = sink.project().buffer.drain(..).collect();
+                Err(FlushError(unsent))
+            }
+        };
+
+        this.sink.set(None);
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use crate::{
+        mpsc,
+        sink::{FlushError, PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::{noop_context, sink::test_sink},
+    };
+
+    use super::BufferSink;
+
+    #[test]
+    fn buffers_while_pending() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = BufferSink::new(tx, 2);
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 3));
+        assert_eq!(2, sink.buffered_len());
+        assert_eq!(
+            PollSend::Pending(4),
+            Pin::new(&mut sink).poll_send(&mut cx, 4)
+        );
+
+        // the buffer is flushed in order as the channel frees capacity
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 4));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(
+            PollSend::Pending(()),
+            Pin::new(&mut sink).poll_close(&mut cx)
+        );
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_close(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn rejected_items_are_returned() {
+        let inner = test_sink(vec![
+            PollSend::Ready,
+            PollSend::Pending(2),
+            PollSend::Pending(2),
+            PollSend::Pending(2),
+        ]);
+        let mut sink = BufferSink::new(inner, 4);
+
+        let mut cx = noop_context();
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 3));
+
+        // the inner sink rejects the items once its responses are exhausted
+        let mut close = sink.close();
+        assert_eq!(
+            Poll::Ready(Err(FlushError(vec![2, 3]))),
+            Pin::new(&mut close).poll(&mut futures_test::task::noop_context())
+        );
+    }
+
+    #[test]
+    fn rejects_after_inner_closed() {
+        let mut cx = noop_context();
+        let (tx, rx) = mpsc::channel(1);
+        let mut sink = BufferSink::new(tx, 2);
+        drop(rx);
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+        assert_eq!(0, sink.buffered_len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        let (tx, _rx) = mpsc::channel::<usize>(1);
+        let _ = BufferSink::new(tx, 0);
+    }
+
+    #[test]
+    #[cfg_attr(all(debug_assertions, feature = "strict"), should_panic)]
+    fn drop_with_buffered_items() {
+        let mut cx = noop_context();
+        let (tx, _rx) = mpsc::channel(1);
+        let mut sink = BufferSink::new(tx, 2);
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        assert_eq!(1, sink.buffered_len());
+
+        // logs a warning, or panics with the `strict` feature
+        drop(sink);
+    }
+
+    #[test]
+    fn poll_flush() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = BufferSink::new(tx, 4);
+
+        // interleaved sends and receives keep the buffer short
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 3));
+        assert_eq!(1, sink.buffered_len());
+
+        assert_eq!(
+            PollSend::Pending(()),
+            Pin::new(&mut sink).poll_flush(&mut cx)
+        );
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_flush(&mut cx));
+        assert_eq!(0, sink.buffered_len());
+
+        // the inner sink is not closed by the flush
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 4));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn poll_flush_rejected() {
+        let mut cx = noop_context();
+        let (tx, rx) = mpsc::channel(1);
+        let mut sink = BufferSink::new(tx, 4);
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        drop(rx);
+
+        assert_eq!(
+            PollSend::Rejected(()),
+            Pin::new(&mut sink).poll_flush(&mut cx)
+        );
+        assert_eq!(1, sink.buffered_len());
+
+        let mut close = sink.close();
+        assert_eq!(
+            Poll::Ready(Err(FlushError(vec![2]))),
+            Pin::new(&mut close).poll(&mut futures_test::task::noop_context())
+        );
+    }
+
+    #[test]
+    fn drop_flushes_ready_items() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = BufferSink::new(tx, 2);
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        // the channel has capacity, so the buffered item is sent when the sink is dropped
+        drop(sink);
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn flush() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = tx.buffer(4);
+        for i in 1..=3 {
+            sink.send(i).await.unwrap();
+        }
+
+        let receive = tokio::spawn(async move {
+            let mut received = Vec::new();
+            for _ in 1..=3 {
+                received.push(rx.recv().await.unwrap());
+            }
+            (received, rx)
+        });
+
+        assert_eq!(Ok(()), sink.flush().await);
+        assert_eq!(0, sink.buffered_len());
+
+        let (received, mut rx) = receive.await.unwrap();
+        assert_eq!(vec![1, 2, 3], received);
+
+        assert_eq!(Ok(()), sink.close().await);
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn close() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = tx.buffer(4);
+        for i in 1..=3 {
+            sink.send(i).await.unwrap();
+        }
+
+        let close = tokio::spawn(sink.close());
+        for i in 1..=3 {
+            assert_eq!(Some(i), rx.recv().await);
+        }
+
+        assert_eq!(Ok(()), close.await.unwrap());
+        assert_eq!(None, rx.recv().await);
+    }
+}
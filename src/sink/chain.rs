@@ -80,6 +80,33 @@ where
 
         unreachable!();
     }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        let this = self.project();
+        let mut state = this.state.load(Ordering::Acquire);
+
+        // both sinks are closed, and the chain rejects further messages
+        if let State::WritingLeft = state {
+            if let PollSend::Pending(()) = this.left.poll_close(cx) {
+                return PollSend::Pending(());
+            }
+
+            this.state.store(State::WritingRight, Ordering::Release);
+            state = State::WritingRight;
+        }
+
+        if let State::WritingRight = state {
+            let poll = this.right.poll_close(cx);
+            if let PollSend::Pending(()) = poll {
+                return PollSend::Pending(());
+            }
+
+            this.state.store(State::Closed, Ordering::Release);
+            return poll;
+        }
+
+        PollSend::Ready
+    }
 }
 
 #[cfg(test)]
@@ -112,12 +139,41 @@ mod tests {
             Pin::new(&mut chain).poll_send(&mut cx, 3)
         );
 
-        drop(chain);
-
         assert_eq!(&[1], left.values());
         assert_eq!(&[2], right.values());
     }
 
+    #[test]
+    fn close_closes_both() {
+        use crate::{
+            mpsc,
+            stream::{PollRecv, Stream},
+        };
+
+        let (left, mut left_rx) = mpsc::channel(2);
+        let (right, mut right_rx) = mpsc::channel(2);
+        let mut chain = ChainSink::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut chain).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(PollSend::Ready, Pin::new(&mut chain).poll_close(&mut cx));
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut chain).poll_send(&mut cx, 2)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut left_rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut left_rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut right_rx).poll_recv(&mut cx));
+    }
+
     #[test]
     fn waits_for_right() {
         let mut left = test_sink(vec![PollSend::Pending(1)]);
@@ -136,8 +192,6 @@ mod tests {
             Pin::new(&mut chain).poll_send(&mut cx, 3)
         );
 
-        drop(chain);
-
         assert_eq!(Vec::<usize>::new(), left.values());
         assert_eq!(&[2], right.values());
     }
@@ -167,8 +221,6 @@ mod tests {
             Pin::new(&mut chain).poll_send(&mut cx, 4)
         );
 
-        drop(chain);
-
         assert_eq!(Vec::<usize>::new(), left.values());
         assert_eq!(Vec::<usize>::new(), right.values());
     }
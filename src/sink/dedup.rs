@@ -0,0 +1,144 @@
+use std::pin::Pin;
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct DedupSink<S>
+where
+    S: Sink,
+{
+    last: Option<S::Item>,
+    #[pin]
+    into: S,
+}
+
+impl<S> DedupSink<S>
+where
+    S: Sink,
+    S::Item: PartialEq + Clone,
+{
+    pub fn new(into: S) -> Self {
+        Self { last: None, into }
+    }
+}
+
+impl<S> Sink for DedupSink<S>
+where
+    S: Sink,
+    S::Item: PartialEq + Clone,
+{
+    type Item = S::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+        if this.last.as_ref() == Some(&value) {
+            return PollSend::Ready;
+        }
+
+        // the last value is only updated once the inner sink accepts the message
+        let saved = value.clone();
+        let poll = this.into.poll_send(cx, value);
+        if let PollSend::Ready = poll {
+            *this.last = Some(saved);
+        }
+
+        poll
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        self.project().into.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+        Context,
+    };
+
+    use super::DedupSink;
+
+    #[test]
+    fn simple() {
+        let mut test_sink = test_sink(vec![PollSend::Ready, PollSend::Ready, PollSend::Ready]);
+        let mut dedup = DedupSink::new(&mut test_sink);
+
+        let mut cx = Context::empty();
+
+        for value in [1usize, 1, 2, 2, 2, 1] {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut dedup).poll_send(&mut cx, value)
+            );
+        }
+
+        assert_eq!(&[1, 2, 1], test_sink.values());
+    }
+
+    #[test]
+    fn forward_rejected() {
+        let source = rejected::<usize>();
+        let mut dedup = DedupSink::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut dedup).poll_send(&mut cx, 1)
+        );
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut dedup).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn pending_does_not_update_last() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut dedup = DedupSink::new(tx);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut dedup).poll_send(&mut cx, 1usize)
+        );
+
+        // the channel is full, so 2 is not forwarded, and must not be remembered
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut dedup).poll_send(&mut cx, 2)
+        );
+
+        // a duplicate of the last accepted value is dropped, even while the channel is full
+        assert_eq!(PollSend::Ready, Pin::new(&mut dedup).poll_send(&mut cx, 1));
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut dedup).poll_send(&mut cx, 2));
+        assert_eq!(
+            PollSend::Pending(3),
+            Pin::new(&mut dedup).poll_send(&mut cx, 3)
+        );
+        assert_eq!(PollSend::Ready, Pin::new(&mut dedup).poll_send(&mut cx, 2));
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut dedup).poll_send(&mut cx, 3));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+}
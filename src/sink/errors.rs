@@ -36,3 +36,22 @@ where
 }
 
 impl<T> std::error::Error for SendError<T> where T: std::fmt::Debug {}
+
+/// An error type returned by `BufferSink::close`, if the inner sink is closed before the buffered items are sent.
+///
+/// Contains the items which were not sent.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FlushError<T>(pub T);
+
+impl<T> std::fmt::Display for FlushError<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{:?}", &self))?;
+
+        Ok(())
+    }
+}
+
+impl<T> std::error::Error for FlushError<T> where T: std::fmt::Debug {}
@@ -0,0 +1,315 @@
+use std::pin::Pin;
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FanoutSink<Left, Right> {
+    #[pin]
+    left: Left,
+    #[pin]
+    right: Right,
+    // set when the left sink accepted the message, and the right sink is pending
+    left_sent: bool,
+    // set once either sink rejects a message
+    rejected: bool,
+    left_closed: bool,
+    right_closed: bool,
+}
+
+impl<Left, Right> FanoutSink<Left, Right>
+where
+    Left: Sink,
+    Right: Sink<Item = Left::Item>,
+    Left::Item: Clone,
+{
+    pub fn new(left: Left, right: Right) -> Self {
+        Self {
+            left,
+            right,
+            left_sent: false,
+            rejected: false,
+            left_closed: false,
+            right_closed: false,
+        }
+    }
+}
+
+impl<Left, Right> Sink for FanoutSink<Left, Right>
+where
+    Left: Sink,
+    Right: Sink<Item = Left::Item>,
+    Left::Item: Clone,
+{
+    type Item = Left::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        if *this.rejected {
+            return PollSend::Rejected(value);
+        }
+
+        // if the right sink was pending, the left sink has already accepted the message
+        if !*this.left_sent {
+            match this.left.poll_send(cx, value.clone()) {
+                PollSend::Ready => *this.left_sent = true,
+                PollSend::Pending(_value) => return PollSend::Pending(value),
+                PollSend::Rejected(_value) => {
+                    *this.rejected = true;
+                    return PollSend::Rejected(value);
+                }
+            }
+        }
+
+        match this.right.poll_send(cx, value) {
+            PollSend::Ready => {
+                *this.left_sent = false;
+                PollSend::Ready
+            }
+            PollSend::Pending(value) => PollSend::Pending(value),
+            PollSend::Rejected(value) => {
+                *this.left_sent = false;
+                *this.rejected = true;
+                PollSend::Rejected(value)
+            }
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        let this = self.project();
+
+        // both sinks are closed concurrently.  rejections are reported once both are done
+        if !*this.left_closed {
+            match this.left.poll_close(cx) {
+                PollSend::Ready => *this.left_closed = true,
+                PollSend::Pending(()) => {}
+                PollSend::Rejected(()) => {
+                    *this.left_closed = true;
+                    *this.rejected = true;
+                }
+            }
+        }
+
+        if !*this.right_closed {
+            match this.right.poll_close(cx) {
+                PollSend::Ready => *this.right_closed = true,
+                PollSend::Pending(()) => {}
+                PollSend::Rejected(()) => {
+                    *this.right_closed = true;
+                    *this.rejected = true;
+                }
+            }
+        }
+
+        if !*this.left_closed || !*this.right_closed {
+            return PollSend::Pending(());
+        }
+
+        if *this.rejected {
+            return PollSend::Rejected(());
+        }
+
+        // further messages are rejected
+        *this.rejected = true;
+        PollSend::Ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::{sink::*, stream::recv};
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::FanoutSink;
+
+    #[test]
+    fn fanout() {
+        let mut left = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut right = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut fanout = FanoutSink::new(&mut left, &mut right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 2));
+
+        assert_eq!(&[1, 2], left.values());
+        assert_eq!(&[1, 2], right.values());
+    }
+
+    #[test]
+    fn left_pending() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, mut right_rx) = mpsc::channel(1);
+        let mut fanout = FanoutSink::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 1));
+        assert_eq!(PollRecv::Ready(1), recv(&mut right_rx));
+
+        // neither sink receives the message
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut fanout).poll_send(&mut cx, 2)
+        );
+        assert_eq!(PollRecv::Pending, recv(&mut right_rx));
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 2));
+        assert_eq!(PollRecv::Ready(2), recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut right_rx));
+    }
+
+    #[test]
+    fn right_pending() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, mut right_rx) = mpsc::channel(1);
+        let mut fanout = FanoutSink::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 1));
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // the left sink accepts the message, and the right sink is pending
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut fanout).poll_send(&mut cx, 2)
+        );
+        assert_eq!(PollRecv::Ready(1), recv(&mut right_rx));
+        assert_eq!(1, count.get());
+
+        // the message is not sent to the left sink again
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 2));
+        assert_eq!(PollRecv::Ready(2), recv(&mut left_rx));
+        assert_eq!(PollRecv::Pending, recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut right_rx));
+
+        // the next message is sent to both sinks
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 3));
+        assert_eq!(PollRecv::Ready(3), recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready(3), recv(&mut right_rx));
+    }
+
+    #[test]
+    fn left_rejected() {
+        let mut right = test_sink(vec![PollSend::Ready]);
+        let mut fanout = FanoutSink::new(rejected::<usize>(), &mut right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1)
+        );
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut fanout).poll_send(&mut cx, 2)
+        );
+
+        assert!(right.values().is_empty());
+    }
+
+    #[test]
+    fn right_rejected() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, right_rx) = mpsc::channel(1);
+        let mut fanout = FanoutSink::new(left, right);
+        drop(right_rx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1)
+        );
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+
+        // the fanout rejects later messages, even though the left sink is open
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut fanout).poll_send(&mut cx, 2)
+        );
+        assert_eq!(PollRecv::Pending, recv(&mut left_rx));
+    }
+
+    #[test]
+    fn right_rejected_after_pending() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, right_rx) = mpsc::channel(1);
+        let mut fanout = FanoutSink::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_send(&mut cx, 1));
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut fanout).poll_send(&mut cx, 2)
+        );
+
+        drop(right_rx);
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut fanout).poll_send(&mut cx, 2)
+        );
+        assert_eq!(PollRecv::Ready(2), recv(&mut left_rx));
+        assert_eq!(PollRecv::Pending, recv(&mut left_rx));
+    }
+
+    #[test]
+    fn close() {
+        let (left, mut left_rx) = mpsc::channel::<usize>(1);
+        let (right, mut right_rx) = mpsc::channel::<usize>(1);
+        let mut fanout = FanoutSink::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut fanout).poll_close(&mut cx));
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1)
+        );
+        assert_eq!(PollRecv::Closed, recv(&mut left_rx));
+        assert_eq!(PollRecv::Closed, recv(&mut right_rx));
+    }
+
+    #[tokio::test]
+    async fn mirror() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, mut right_rx) = crate::broadcast::channel(4);
+        let mut fanout = left.fanout(right);
+
+        let send = tokio::spawn(async move {
+            for i in 0..4usize {
+                fanout.send(i).await.unwrap();
+            }
+        });
+
+        for i in 0..4 {
+            assert_eq!(Some(i), left_rx.recv().await);
+            assert_eq!(Some(i), right_rx.recv().await);
+        }
+
+        send.await.unwrap();
+    }
+}
@@ -41,6 +41,10 @@ where
 
         this.into.poll_send(cx, value)
     }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        self.project().into.poll_close(cx)
+    }
 }
 
 #[cfg(test)]
@@ -79,8 +83,6 @@ mod tests {
             Pin::new(&mut filter).poll_send(&mut cx, 4usize)
         );
 
-        drop(filter);
-
         assert_eq!(&[2, 4], test_sink.values());
     }
 
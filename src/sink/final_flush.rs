@@ -0,0 +1,146 @@
+use std::pin::Pin;
+
+use tokio::runtime::Handle;
+
+use crate::sink::{PollSend, Sink};
+use crate::Context;
+
+/// Wraps a sink which buffers items, such as [BufferSink](./struct.BufferSink.html), so that it is closed by a task
+/// spawned on `handle` when the wrapper is dropped.
+///
+/// This is a best-effort escape hatch for code which can't await `close`.  The task flushes the buffered items, and
+/// then closes the inner sink.  For channel senders, this closes the channel for all senders.  Items which can't be
+/// flushed are dropped.  Closing the wrapper explicitly, or taking the sink with `into_inner`, skips the spawn.
+///
+/// Requires the `tokio` feature.
+pub fn with_final_flush<S>(sink: S, handle: Handle) -> FinalFlush<S>
+where
+    S: Sink + Unpin + Send + 'static,
+{
+    FinalFlush {
+        sink: Some(sink),
+        handle,
+        closed: false,
+    }
+}
+
+/// A sink returned by [with_final_flush](./fn.with_final_flush.html), which closes the inner sink on a spawned task
+/// when it is dropped.
+pub struct FinalFlush<S>
+where
+    S: Sink + Unpin + Send + 'static,
+{
+    // only taken by `into_inner`, or the drop
+    sink: Option<S>,
+    handle: Handle,
+    closed: bool,
+}
+
+impl<S> FinalFlush<S>
+where
+    S: Sink + Unpin + Send + 'static,
+{
+    /// Returns the inner sink, which is not closed when the wrapper is dropped.
+    pub fn into_inner(mut self) -> S {
+        self.sink.take().unwrap()
+    }
+
+    fn sink(self: Pin<&mut Self>) -> Pin<&mut S> {
+        Pin::new(self.get_mut().sink.as_mut().unwrap())
+    }
+}
+
+impl<S> Sink for FinalFlush<S>
+where
+    S: Sink + Unpin + Send + 'static,
+{
+    type Item = S::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        self.sink().poll_send(cx, value)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        let poll = self.as_mut().sink().poll_close(cx);
+        if !matches!(poll, PollSend::Pending(())) {
+            self.closed = true;
+        }
+
+        poll
+    }
+}
+
+impl<S> Drop for FinalFlush<S>
+where
+    S: Sink + Unpin + Send + 'static,
+{
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+
+        if let Some(mut sink) = self.sink.take() {
+            self.handle.spawn(async move {
+                let _ = sink.close().await;
+            });
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for FinalFlush<S>
+where
+    S: Sink + Unpin + Send + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FinalFlush")
+            .field("closed", &self.closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Handle;
+
+    use crate::{
+        mpsc,
+        sink::{BufferSink, Sink},
+        stream::Stream,
+    };
+
+    use super::with_final_flush;
+
+    #[tokio::test]
+    async fn spawns_flush_on_drop() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = with_final_flush(BufferSink::new(tx, 4), Handle::current());
+        for i in 1..=3 {
+            sink.send(i).await.unwrap();
+        }
+
+        // two items are buffered, and are sent by the spawned task as the receiver frees capacity
+        drop(sink);
+        for i in 1..=3 {
+            assert_eq!(Some(i), rx.recv().await);
+        }
+
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn into_inner_skips_flush() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let sink = with_final_flush(BufferSink::new(tx, 4), Handle::current());
+
+        let mut inner = sink.into_inner();
+        inner.send(1).await.unwrap();
+        inner.close().await.unwrap();
+
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -0,0 +1,346 @@
+use std::{convert::TryFrom, pin::Pin, task::Poll, time::Duration};
+
+use crate::sink::{PollSend, Sink};
+use crate::time::{Delay, Sleep};
+use crate::Context;
+use pin_project::pin_project;
+
+/// Configures the attempts and exponential backoff of [Sink::retry](./trait.Sink.html#method.retry).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_delay: Duration,
+    factor: u32,
+    max_delay: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Constructs a policy which offers each item at most `max_attempts` times (including the first attempt).
+    ///
+    /// The first retry waits `initial_delay`, and each later retry doubles the delay.  Panics if `max_attempts` is zero.
+    #[track_caller]
+    pub fn new(max_attempts: usize, initial_delay: Duration) -> Self {
+        assert!(max_attempts > 0, "max_attempts must be greater than zero");
+
+        Self {
+            max_attempts,
+            initial_delay,
+            factor: 2,
+            max_delay: None,
+        }
+    }
+
+    /// Configures the factor by which the delay grows after each retry.  The default is 2.
+    pub fn factor(mut self, factor: u32) -> Self {
+        self.factor = factor;
+        self
+    }
+
+    /// Configures an upper bound for the delay between retries.  The default is unbounded.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    // the delay which precedes the given retry, starting at 1
+    fn delay(&self, retry: usize) -> Duration {
+        let exponent = u32::try_from(retry - 1).unwrap_or(u32::MAX);
+        let delay = self
+            .factor
+            .checked_pow(exponent)
+            .and_then(|factor| self.initial_delay.checked_mul(factor))
+            .unwrap_or(Duration::MAX);
+
+        match self.max_delay {
+            Some(max_delay) => delay.min(max_delay),
+            None => delay,
+        }
+    }
+}
+
+#[pin_project]
+pub struct RetrySink<S, T: Sleep> {
+    #[pin]
+    sink: S,
+    policy: RetryPolicy,
+    delay: Delay<T>,
+    // the number of times the current item has been rejected
+    rejections: usize,
+    waiting: bool,
+    exhausted: bool,
+}
+
+impl<S, T> RetrySink<S, T>
+where
+    S: Sink,
+    T: Sleep,
+{
+    pub fn new(sink: S, policy: RetryPolicy, timer: T) -> Self {
+        Self {
+            sink,
+            policy,
+            delay: Delay::new(timer, Duration::ZERO),
+            rejections: 0,
+            waiting: false,
+            exhausted: false,
+        }
+    }
+}
+
+impl<S, T> Sink for RetrySink<S, T>
+where
+    S: Sink,
+    T: Sleep,
+{
+    type Item = S::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        mut value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let mut this = self.project();
+        if *this.exhausted {
+            return PollSend::Rejected(value);
+        }
+
+        loop {
+            // the item in the retry loop is returned as pending until the delay elapses
+            if *this.waiting {
+                match this.delay.poll_elapsed(cx) {
+                    Poll::Ready(()) => *this.waiting = false,
+                    Poll::Pending => return PollSend::Pending(value),
+                }
+            }
+
+            match this.sink.as_mut().poll_send(cx, value) {
+                PollSend::Ready => {
+                    *this.rejections = 0;
+                    return PollSend::Ready;
+                }
+                PollSend::Pending(v) => return PollSend::Pending(v),
+                PollSend::Rejected(v) => {
+                    *this.rejections += 1;
+                    if *this.rejections >= this.policy.max_attempts {
+                        *this.exhausted = true;
+                        return PollSend::Rejected(v);
+                    }
+
+                    this.delay.reset(this.policy.delay(*this.rejections));
+                    *this.waiting = true;
+                    value = v;
+                }
+            }
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        self.project().sink.poll_close(cx)
+    }
+}
+
+impl<S, T> std::fmt::Debug for RetrySink<S, T>
+where
+    S: std::fmt::Debug,
+    T: Sleep,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetrySink")
+            .field("sink", &self.sink)
+            .field("policy", &self.policy)
+            .field("rejections", &self.rejections)
+            .field("waiting", &self.waiting)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::{
+        sink::{PollSend, Sink},
+        test::{noop_context, sink::test_sink, time::MockClock},
+        test_util::WakeProbe,
+    };
+
+    use super::{RetryPolicy, RetrySink};
+
+    #[test]
+    fn delays() {
+        let policy =
+            RetryPolicy::new(8, Duration::from_millis(100)).max_delay(Duration::from_secs(1));
+
+        assert_eq!(Duration::from_millis(100), policy.delay(1));
+        assert_eq!(Duration::from_millis(200), policy.delay(2));
+        assert_eq!(Duration::from_millis(800), policy.delay(4));
+        assert_eq!(Duration::from_secs(1), policy.delay(5));
+        assert_eq!(Duration::from_secs(1), policy.delay(usize::MAX));
+    }
+
+    #[test]
+    fn debug() {
+        let (tx, _rx) = crate::mpsc::channel::<usize>(4);
+        let policy = RetryPolicy::new(3, Duration::from_secs(1));
+        let sink = RetrySink::new(tx, policy, MockClock::new());
+
+        let debug = format!("{:?}", sink);
+        assert!(debug.starts_with("RetrySink { sink: Sender"));
+        assert!(debug.contains("rejections: 0"));
+    }
+
+    #[test]
+    fn retries_on_mock_clock() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let mut inner = test_sink(vec![
+            PollSend::Rejected(1),
+            PollSend::Rejected(1),
+            PollSend::Ready,
+        ]);
+        let policy = RetryPolicy::new(3, Duration::from_secs(1));
+        let mut sink = RetrySink::new(&mut inner, policy, clock.clone());
+
+        let probe = WakeProbe::new();
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut probe.context(), 1)
+        );
+
+        // a second item is not accepted while the first is waiting for a retry
+        clock.advance(Duration::from_millis(999));
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut sink).poll_send(&mut cx, 2)
+        );
+
+        clock.advance(Duration::from_millis(1));
+        assert!(probe.count() > 0);
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+
+        drop(sink);
+        assert_eq!(&[1], inner.values());
+    }
+
+    #[test]
+    fn rejected_when_exhausted() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let inner = test_sink(vec![
+            PollSend::Rejected(1),
+            PollSend::Rejected(1),
+            PollSend::Ready,
+        ]);
+        let policy = RetryPolicy::new(2, Duration::from_secs(1));
+        let mut sink = RetrySink::new(inner, policy, clock.clone());
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+
+        // the sink stays closed, although the inner sink would accept the item
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut sink).poll_send(&mut cx, 2)
+        );
+    }
+
+    #[test]
+    fn rejections_reset_after_ready() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let inner = test_sink(vec![
+            PollSend::Rejected(1),
+            PollSend::Ready,
+            PollSend::Rejected(2),
+            PollSend::Ready,
+        ]);
+        let policy = RetryPolicy::new(2, Duration::from_secs(1));
+        let mut sink = RetrySink::new(inner, policy, clock.clone());
+
+        for i in 1..=2 {
+            assert_eq!(
+                PollSend::Pending(i),
+                Pin::new(&mut sink).poll_send(&mut cx, i)
+            );
+            clock.advance(Duration::from_secs(1));
+            assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, i));
+        }
+    }
+
+    #[test]
+    fn zero_delay() {
+        let mut cx = noop_context();
+        let inner = test_sink(vec![PollSend::Rejected(1), PollSend::Ready]);
+        let policy = RetryPolicy::new(2, Duration::ZERO);
+        let mut sink = RetrySink::new(inner, policy, MockClock::new());
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use crate::{
+        sink::{PollSend, RetryPolicy, SendError, Sink},
+        test::sink::test_sink,
+    };
+
+    #[test]
+    fn constructed_outside_runtime() {
+        let inner = test_sink(vec![PollSend::Rejected(1usize), PollSend::Ready]);
+        let mut sink = inner.retry(RetryPolicy::new(2, Duration::from_millis(10)));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("failed to build the runtime");
+        runtime.block_on(async move {
+            assert_eq!(Ok(()), sink.send(1).await);
+        });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejects_twice_then_accepts() {
+        let mut inner = test_sink(vec![
+            PollSend::Rejected(1usize),
+            PollSend::Rejected(1usize),
+            PollSend::Ready,
+        ]);
+        let mut sink = (&mut inner).retry(RetryPolicy::new(3, Duration::from_millis(100)));
+
+        let start = Instant::now();
+        assert_eq!(Ok(()), sink.send(1).await);
+        assert_eq!(Duration::from_millis(300), start.elapsed());
+
+        drop(sink);
+        assert_eq!(&[1], inner.values());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn send_error_when_exhausted() {
+        let inner = test_sink(Vec::<PollSend<usize>>::new());
+        let mut sink = inner.retry(RetryPolicy::new(3, Duration::from_millis(100)));
+
+        let start = Instant::now();
+        assert_eq!(Err(SendError(1)), sink.send(1).await);
+        assert_eq!(Duration::from_millis(300), start.elapsed());
+    }
+}
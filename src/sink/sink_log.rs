@@ -58,6 +58,10 @@ where
             PollSend::Rejected(v) => PollSend::Rejected(v),
         }
     }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        self.project().sink.poll_close(cx)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,249 @@
+use std::{marker::PhantomData, pin::Pin};
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct WithSink<S, F, From> {
+    #[pin]
+    into: S,
+    map: F,
+    from: PhantomData<fn(From)>,
+}
+
+impl<S, F, From> WithSink<S, F, From>
+where
+    S: Sink,
+    F: FnMut(From) -> S::Item,
+    From: Clone,
+{
+    pub fn new(into: S, map: F) -> Self {
+        Self {
+            into,
+            map,
+            from: PhantomData,
+        }
+    }
+}
+
+impl<S, F, From> Sink for WithSink<S, F, From>
+where
+    S: Sink,
+    F: FnMut(From) -> S::Item,
+    From: Clone,
+{
+    type Item = From;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        // the mapped value can't be converted back, so a clone is mapped, and the original is returned
+        let mapped = (this.map)(value.clone());
+        match this.into.poll_send(cx, mapped) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(_mapped) => PollSend::Pending(value),
+            PollSend::Rejected(_mapped) => PollSend::Rejected(value),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        self.project().into.poll_close(cx)
+    }
+}
+
+#[pin_project]
+pub struct WithFilterMapSink<S, F, From> {
+    #[pin]
+    into: S,
+    map: F,
+    from: PhantomData<fn(From)>,
+}
+
+impl<S, F, From> WithFilterMapSink<S, F, From>
+where
+    S: Sink,
+    F: FnMut(From) -> Option<S::Item>,
+    From: Clone,
+{
+    pub fn new(into: S, map: F) -> Self {
+        Self {
+            into,
+            map,
+            from: PhantomData,
+        }
+    }
+}
+
+impl<S, F, From> Sink for WithFilterMapSink<S, F, From>
+where
+    S: Sink,
+    F: FnMut(From) -> Option<S::Item>,
+    From: Clone,
+{
+    type Item = From;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        let mapped = match (this.map)(value.clone()) {
+            Some(mapped) => mapped,
+            None => return PollSend::Ready,
+        };
+
+        match this.into.poll_send(cx, mapped) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(_mapped) => PollSend::Pending(value),
+            PollSend::Rejected(_mapped) => PollSend::Rejected(value),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollSend<()> {
+        self.project().into.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::{WithFilterMapSink, WithSink};
+
+    #[test]
+    fn with() {
+        let mut test_sink = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut with = WithSink::new(&mut test_sink, |i: usize| i.to_string());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, 2));
+
+        assert_eq!(&["1".to_string(), "2".to_string()], test_sink.values());
+    }
+
+    #[test]
+    fn with_pending() {
+        let mut with = WithSink::new(pending::<String>(), |i: usize| i.to_string());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut with).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn with_rejected() {
+        let mut with = WithSink::new(rejected::<String>(), |i: usize| i.to_string());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut with).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn with_channel() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut with = tx.with(|i: usize| i * 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, 1));
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut with).poll_send(&mut cx, 2)
+        );
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        drop(rx);
+        assert_eq!(
+            PollSend::Rejected(3),
+            Pin::new(&mut with).poll_send(&mut cx, 3)
+        );
+    }
+
+    #[test]
+    fn filter_map() {
+        let mut test_sink = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut with = WithFilterMapSink::new(&mut test_sink, |s: &str| s.parse::<usize>().ok());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, "1"));
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, "x"));
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, "3"));
+
+        assert_eq!(&[1, 3], test_sink.values());
+    }
+
+    #[test]
+    fn filter_map_pending() {
+        let mut with = WithFilterMapSink::new(pending::<usize>(), |s: &str| s.parse().ok());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending("1"),
+            Pin::new(&mut with).poll_send(&mut cx, "1")
+        );
+    }
+
+    #[test]
+    fn filter_map_rejected() {
+        let mut with = WithFilterMapSink::new(rejected::<usize>(), |s: &str| s.parse().ok());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected("1"),
+            Pin::new(&mut with).poll_send(&mut cx, "1")
+        );
+    }
+
+    #[test]
+    fn filter_map_dropped_on_rejected() {
+        let mut with = WithFilterMapSink::new(rejected::<usize>(), |s: &str| s.parse().ok());
+
+        let mut cx = Context::empty();
+
+        // dropped items are not sent to the inner sink, so they are accepted
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, "x"));
+    }
+
+    #[tokio::test]
+    async fn with_send() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut tx = tx.with_filter_map(|i: usize| (i > 2).then(|| i * 10));
+
+        for i in 1..=4 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(Some(30), rx.recv().await);
+        assert_eq!(Some(40), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+}
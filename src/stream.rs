@@ -42,23 +42,133 @@ use pin_project::pin_project;
 use std::task::Poll;
 
 use self::{
-    chain::ChainStream, filter::FilterStream, find::FindStream, map::MapStream, merge::MergeStream,
-    once::OnceStream, repeat::RepeatStream,
+    chain::ChainStream,
+    chunks::ChunksStream,
+    concat::ConcatStream,
+    cycle::CycleStream,
+    dedup::{DedupByKeyStream, DedupStream},
+    enumerate::EnumerateStream,
+    filter::FilterStream,
+    filter_async::FilterAsyncStream,
+    filter_map::FilterMapStream,
+    find::FindStream,
+    flat_map::FlatMapStream,
+    flatten::FlattenStream,
+    inspect::InspectStream,
+    map::MapStream,
+    map_while::MapWhileStream,
+    merge::MergeStream,
+    merge_sorted::MergeSortedStream,
+    once::OnceStream,
+    repeat::RepeatStream,
+    scan::ScanStream,
+    skip::SkipStream,
+    skip_while::SkipWhileStream,
+    step_by::StepByStream,
+    take::TakeStream,
+    take_while::TakeWhileStream,
+    then::ThenStream,
+    zip::ZipStream,
 };
+use crate::watch;
 
+mod any;
+#[cfg(feature = "blocking")]
+mod blocking_iter;
+mod boxed;
+mod bridge;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+mod buffered;
 mod chain;
+mod chunks;
+mod collect;
+mod concat;
+mod count;
+mod cycle;
+mod debounce;
+mod dedup;
+mod enumerate;
 mod errors;
 mod filter;
+mod filter_async;
+mod filter_map;
 mod find;
+mod find_map;
+mod first;
+mod flat_map;
+mod flatten;
+mod fold;
+mod for_each;
+mod forward;
+mod fuse;
+mod gate;
+mod gather;
+mod heartbeat;
+mod inspect;
 mod map;
+mod map_concurrent;
+mod map_while;
 mod merge;
+mod merge_sorted;
 mod once;
+mod peekable;
+mod record;
+mod reduce;
 mod repeat;
+mod replay;
+mod scan;
+mod select_all;
+mod skip;
+mod skip_while;
+mod step_by;
+mod take;
+mod take_until;
+mod take_while;
+mod tee;
+mod then;
+mod throttle;
+mod unclosable;
+mod unzip;
+mod zip;
 
 #[cfg(feature = "logging")]
-mod stream_log;
+pub(crate) mod stream_log;
 
+mod timeout;
+
+pub use any::{AllFuture, AnyFuture};
+#[cfg(feature = "blocking")]
+pub use blocking_iter::BlockingIter;
+pub use boxed::{BoxStream, LocalBoxStream};
+pub use bridge::{BridgeFuture, BridgeReport, Termination};
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub use buffered::BufferedStream;
+pub use collect::CollectFuture;
+pub use count::{CountFuture, LastFuture};
+pub use debounce::DebounceStream;
 pub use errors::*;
+pub use find_map::{FindMapFuture, FindMapStream};
+pub use first::FirstFuture;
+pub use fold::FoldFuture;
+pub use for_each::{ForEachAsyncFuture, ForEachFuture};
+pub use forward::ForwardFuture;
+pub use fuse::{FuseStream, FusedStream};
+pub use gate::GateStream;
+pub use gather::GatherFuture;
+pub use heartbeat::HeartbeatStream;
+pub use map_concurrent::{MapConcurrentStream, MapConcurrentUnorderedStream};
+pub use peekable::{PeekFuture, PeekableStream};
+pub use record::{Record, RecordingStream};
+pub use reduce::ReduceFuture;
+pub use replay::ReplayStream;
+pub use select_all::SelectAll;
+pub use take_until::TakeUntilStream;
+pub use tee::{TeePolicy, TeeStream};
+pub use throttle::ThrottleStream;
+
+pub use timeout::{RecvTimeoutFuture, TimeoutStream};
+pub use unclosable::{RecvAlwaysFuture, UnclosableStream};
+pub use unzip::UnzipFuture;
 
 /// An asynchronous stream, which produces a series of messages until closed.
 ///
@@ -126,8 +236,22 @@ pub trait Stream {
     /// - `PollRecv::Ready(value)` if a message is ready
     /// - `PollRecv::Pending` if the stream is open, but no message is currently available.
     /// - `PollRecv::Closed` if the stream is closed, and no messages are expected.
+    ///
+    /// When `Pending` is returned, the waker in `cx` must be called when a message becomes available,
+    /// or the stream is closed.  Once `Closed` is returned, every later call must also return `Closed`.
     fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item>;
 
+    /// Returns bounds on the remaining length of the stream, like `Iterator::size_hint`.
+    ///
+    /// The lower bound is the number of items which can be received without waiting, such as the messages buffered
+    /// by a channel.  The upper bound is `None` if more items may arrive, for example while the channel has senders.
+    ///
+    /// The hint is not guaranteed to be accurate, as other tasks may send or receive concurrently.
+    /// The default implementation returns `(0, None)`.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
     /// Retrieves a message from the stream.
     ///
     /// Returns:
@@ -140,6 +264,39 @@ pub trait Stream {
         RecvFuture::new(self)
     }
 
+    /// Retrieves a message from the stream, or times out after `duration`.
+    ///
+    /// Resolves to `Ok(Some(item))` if an item is received, `Ok(None)` if the stream is closed, and `Err(Elapsed)` if
+    /// no item is received within `duration`.  The stream is unaffected by the timeout, and can be received again.
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be called within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn recv_timeout(
+        &mut self,
+        duration: std::time::Duration,
+    ) -> RecvTimeoutFuture<'_, Self, crate::time::RuntimeSleep>
+    where
+        Self: Unpin,
+    {
+        RecvTimeoutFuture::new(self, duration, crate::time::RuntimeSleep::default())
+    }
+
+    /// Retrieves a message from the stream, or times out after `duration`, using the provided
+    /// [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [recv_timeout](#method.recv_timeout), but does not require an async runtime feature.
+    fn recv_timeout_with<T>(
+        &mut self,
+        duration: std::time::Duration,
+        timer: T,
+    ) -> RecvTimeoutFuture<'_, Self, T>
+    where
+        Self: Unpin,
+        T: crate::time::Sleep,
+    {
+        RecvTimeoutFuture::new(self, duration, timer)
+    }
+
     /// Attempts to retrive a message from the stream, without blocking.
     ///
     /// Returns:
@@ -172,6 +329,37 @@ pub trait Stream {
         pollster::block_on(self.recv())
     }
 
+    /// Converts the stream into an iterator, which blocks the current thread until each item is received.
+    ///
+    /// While the stream is pending, the thread is parked until the stream wakes it.  The iterator returns `None`
+    /// once the stream is closed.  This can be used to drain a receiver from synchronous code.
+    #[cfg(feature = "blocking")]
+    fn into_blocking_iter(self) -> BlockingIter<Self>
+    where
+        Self: Sized + Unpin,
+    {
+        BlockingIter::new(self)
+    }
+
+    /// Boxes the stream, erasing its type.
+    ///
+    /// Combinator chains produce large types.  The boxed stream can be stored in a struct field, and received with
+    /// `recv`, as the box keeps the stream pinned.
+    fn boxed<'a>(self) -> BoxStream<'a, Self::Item>
+    where
+        Self: Sized + Send + 'a,
+    {
+        BoxStream::new(self)
+    }
+
+    /// Boxes the stream, erasing its type.  Behaves like [boxed](#method.boxed), for streams which are not `Send`.
+    fn boxed_local<'a>(self) -> LocalBoxStream<'a, Self::Item>
+    where
+        Self: Sized + 'a,
+    {
+        LocalBoxStream::new(self)
+    }
+
     /// Transforms the stream with a map function.
     fn map<Map, Into>(self, map: Map) -> MapStream<Self, Map, Into>
     where
@@ -181,16 +369,195 @@ pub trait Stream {
         MapStream::new(self, map)
     }
 
+    /// Calls `f` with a reference to each item, and then forwards the item.  Useful for logging and debugging.
+    ///
+    /// The closure is called once for each ready item, including items received with `try_recv`, and is not called
+    /// when the stream is pending or closed.
+    fn inspect<F>(self, f: F) -> InspectStream<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item),
+    {
+        InspectStream::new(self, f)
+    }
+
+    /// Sends a clone of each item to `sink`, and then forwards the item.
+    ///
+    /// By default, the clone is held while the sink is full, and the next item is not received until it is sent.
+    /// The behavior can be configured with [TeeStream::policy](./struct.TeeStream.html#method.policy).  If the sink is
+    /// closed, items are forwarded without it.
+    fn tee<K>(self, sink: K) -> TeeStream<Self, K>
+    where
+        Self: Sized,
+        K: crate::sink::Sink<Item = Self::Item>,
+        Self::Item: Clone,
+    {
+        TeeStream::new(self, sink)
+    }
+
+    /// Maps each item to a future, and returns its output once it completes.
+    ///
+    /// No item is received while the future is in flight.  If the stream is closed, the in-flight future completes
+    /// before the closure is returned.  To run several futures at once, see [map_concurrent](#method.map_concurrent).
+    fn then<F, Fut>(self, then: F) -> ThenStream<Self, F, Fut>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: std::future::Future,
+        Self: Sized,
+    {
+        ThenStream::new(self, then)
+    }
+
+    /// Maps items to futures, and runs up to `n` of them at once.  Outputs are returned in the order of the items.
+    ///
+    /// The futures are polled by the stream, so no tasks are spawned.  Outputs which complete before earlier items
+    /// are buffered, and count against `n`, so no new items are received while the front future is in flight.
+    /// Once the stream is closed, the in-flight futures complete before the closure is returned.  Panics if `n` is zero.
+    #[track_caller]
+    fn map_concurrent<F, Fut>(self, n: usize, map: F) -> MapConcurrentStream<Self, F, Fut>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: std::future::Future,
+        Self: Sized,
+    {
+        MapConcurrentStream::new(self, n, map)
+    }
+
+    /// Maps items to futures, and runs up to `n` of them at once.  Outputs are returned as the futures complete.
+    ///
+    /// See [map_concurrent](#method.map_concurrent).  Panics if `n` is zero.
+    #[track_caller]
+    fn map_concurrent_unordered<F, Fut>(
+        self,
+        n: usize,
+        map: F,
+    ) -> MapConcurrentUnorderedStream<Self, F, Fut>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: std::future::Future,
+        Self: Sized,
+    {
+        MapConcurrentUnorderedStream::new(self, n, map)
+    }
+
     /// Filters messages returned by the stream, ignoring messages where `filter` returns false.
     fn filter<Filter>(self, filter: Filter) -> FilterStream<Self, Filter>
     where
-        Self: Sized + Unpin,
-        Filter: FnMut(&Self::Item) -> bool + Unpin,
+        Self: Sized,
+        Filter: FnMut(&Self::Item) -> bool,
     {
         FilterStream::new(self, filter)
     }
 
+    /// Filters messages returned by the stream with an asynchronous predicate, ignoring messages where the future
+    /// returned by `filter` resolves to false.
+    ///
+    /// The item is held while the predicate future is pending, and no other item is received.
+    fn filter_async<F, Fut>(self, filter: F) -> FilterAsyncStream<Self, F, Fut>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> Fut,
+        Fut: std::future::Future<Output = bool>,
+    {
+        FilterAsyncStream::new(self, filter)
+    }
+
+    /// Maps items with `map`, and forwards the values it returns, ignoring items for which it returns `None`.
+    ///
+    /// Ignored items are skipped within a single poll, so `Pending` is only returned if the inner stream is pending.
+    fn filter_map<Map, Into>(self, map: Map) -> FilterMapStream<Self, Map, Into>
+    where
+        Self: Sized,
+        Map: FnMut(Self::Item) -> Option<Into>,
+    {
+        FilterMapStream::new(self, map)
+    }
+
+    /// Maps items with `map`, and forwards the values it returns, until it returns `None`.
+    ///
+    /// Unlike [filter_map](#method.filter_map), the stream is closed the first time `map` returns `None`, and the
+    /// inner stream is not polled again.
+    fn map_while<Map, Into>(self, map: Map) -> MapWhileStream<Self, Map, Into>
+    where
+        Self: Sized,
+        Map: FnMut(Self::Item) -> Option<Into>,
+    {
+        MapWhileStream::new(self, map)
+    }
+
+    /// Flattens a stream of streams, forwarding each inner stream until it closes, and then receiving the next.
+    ///
+    /// The outer stream is not polled while an inner stream is open.  The flattened stream is closed once the outer
+    /// stream, and the last inner stream, are both closed.
+    fn flatten(self) -> FlattenStream<Self>
+    where
+        Self: Sized,
+        Self::Item: Stream,
+    {
+        FlattenStream::new(self)
+    }
+
+    /// Maps each item to a stream with `f`, and forwards the items of each stream until it closes.
+    ///
+    /// Behaves like `map` followed by [flatten](#method.flatten).  The outer stream is not polled while an inner
+    /// stream is open, and empty inner streams are skipped.
+    fn flat_map<F, Inner>(self, f: F) -> FlatMapStream<Self, F, Inner>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> Inner,
+        Inner: Stream,
+    {
+        FlatMapStream::new(self, f)
+    }
+
+    /// Collects ready items into batches of up to `capacity` items.
+    ///
+    /// Each poll receives as many ready items as fit in the batch.  If the stream is pending after at least one item
+    /// was received, the partial batch is returned.  When the stream closes, the final partial batch is returned
+    /// before the closure.  Panics if `capacity` is zero.
+    #[track_caller]
+    fn chunks(self, capacity: usize) -> ChunksStream<Self>
+    where
+        Self: Sized,
+    {
+        ChunksStream::new(self, capacity)
+    }
+
+    /// Folds each item into `state` with `scan`, and forwards the value it returns.
+    ///
+    /// If `scan` returns `None`, the stream is closed, and the inner stream is not polled again.
+    fn scan<State, F, Into>(self, initial: State, scan: F) -> ScanStream<Self, State, F, Into>
+    where
+        Self: Sized,
+        F: FnMut(&mut State, Self::Item) -> Option<Into>,
+    {
+        ScanStream::new(self, initial, scan)
+    }
+
+    /// Returns `PollRecv::Closed` forever once the stream is closed, without polling the inner stream again.
+    ///
+    /// Some streams may misbehave if polled after closure.  The fused stream implements
+    /// [FusedStream](./trait.FusedStream.html).
+    fn fuse(self) -> FuseStream<Self>
+    where
+        Self: Sized,
+    {
+        FuseStream::new(self)
+    }
+
+    /// Buffers at most one item, so the next item can be inspected with `poll_peek` or `peek` without consuming it.
+    fn peekable(self) -> PeekableStream<Self>
+    where
+        Self: Sized,
+    {
+        PeekableStream::new(self)
+    }
+
     /// Merges two streams, returning values from both at once, until both are closed.
+    ///
+    /// The merge is fair.  The streams take turns being polled first, and the stream which did not produce the last
+    /// item is polled first, so a stream which is always ready cannot starve the other.  While one stream is closed,
+    /// items are returned from the other.
     fn merge<Other>(self, other: Other) -> MergeStream<Self, Other>
     where
         Other: Stream<Item = Self::Item>,
@@ -199,6 +566,24 @@ pub trait Stream {
         MergeStream::new(self, other)
     }
 
+    /// Merges two streams which produce items in sorted order, returning items from both in sorted order, until both are closed.
+    ///
+    /// The stream waits while either side is pending, as it may produce the smaller item.  If both sides produce items with
+    /// equal keys, the item from `self` is returned first.  Once one side is closed, items from the other are returned directly.
+    fn merge_sorted_by_key<Other, Key, K>(
+        self,
+        other: Other,
+        key: Key,
+    ) -> MergeSortedStream<Self, Other, Key>
+    where
+        Other: Stream<Item = Self::Item>,
+        Key: FnMut(&Self::Item) -> K,
+        K: Ord,
+        Self: Sized,
+    {
+        MergeSortedStream::new(self, other, key)
+    }
+
     /// Chains two streams, returning values from `self` until it is closed, and then returning values from `other`.
     fn chain<Other>(self, other: Other) -> ChainStream<Self, Other>
     where
@@ -208,16 +593,489 @@ pub trait Stream {
         ChainStream::new(self, other)
     }
 
+    /// Chains a sequence of streams, returning values from `self` until it is closed, and then from each stream in `others`.
+    ///
+    /// Streams are taken from the iterator once the previous stream is closed.
+    fn chain_all<Others>(self, others: Others) -> ChainStream<Self, ConcatStream<Others::IntoIter>>
+    where
+        Others: IntoIterator,
+        Others::Item: Stream<Item = Self::Item>,
+        Self: Sized,
+    {
+        ChainStream::new(self, ConcatStream::new(others.into_iter()))
+    }
+
+    /// Pairs the items of two streams, returning a tuple of one item from each, until either stream is closed.
+    ///
+    /// If one side produces an item first, it is buffered until the other side produces an item.  The stream is
+    /// closed when either side is closed, and any buffered item is dropped.
+    fn zip<Other>(self, other: Other) -> ZipStream<Self, Other>
+    where
+        Other: Stream,
+        Self: Sized,
+    {
+        ZipStream::new(self, other)
+    }
+
+    /// Forwards at most `n` items, and then returns `Closed`.
+    ///
+    /// Once `n` items have been received, the inner stream is not polled again.  Streams can be borrowed with
+    /// `&mut`, and the remaining items can be received after the take stream is dropped.
+    fn take(self, n: usize) -> TakeStream<Self>
+    where
+        Self: Sized,
+    {
+        TakeStream::new(self, n)
+    }
+
+    /// Forwards items until `signal` produces an item, and then closes the stream.
+    ///
+    /// The signal is polled before the stream, so buffered items are discarded once the signal fires.  A
+    /// [barrier](../barrier/index.html) receiver can be used as a shutdown signal.  If the signal is closed without
+    /// an item, items are forwarded until the stream is closed.  This can be configured with
+    /// [TakeUntilStream::close_with_signal](./struct.TakeUntilStream.html#method.close_with_signal).
+    fn take_until<Signal>(self, signal: Signal) -> TakeUntilStream<Self, Signal>
+    where
+        Self: Sized,
+        Signal: Stream,
+    {
+        TakeUntilStream::new(self, signal)
+    }
+
+    /// Forwards items while `predicate` returns true.
+    ///
+    /// The first item for which the predicate returns false is dropped, and the stream is closed.  The inner stream is
+    /// not polled again.
+    fn take_while<F>(self, predicate: F) -> TakeWhileStream<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> bool,
+    {
+        TakeWhileStream::new(self, predicate)
+    }
+
+    /// Discards the first `n` items, and then forwards the rest.
+    ///
+    /// Items which are ready are discarded within a single poll, so `Pending` is only returned if the inner stream is
+    /// pending.
+    fn skip(self, n: usize) -> SkipStream<Self>
+    where
+        Self: Sized,
+    {
+        SkipStream::new(self, n)
+    }
+
+    /// Returns the first item, and then every `n`-th item after it, discarding the rest.
+    ///
+    /// Items which are ready are discarded within a single poll, so `Pending` is only returned if the inner stream is
+    /// pending.  Panics if `n` is zero.
+    #[track_caller]
+    fn step_by(self, n: usize) -> StepByStream<Self>
+    where
+        Self: Sized,
+    {
+        StepByStream::new(self, n)
+    }
+
+    /// Discards items while `predicate` returns true.  The first item for which it returns false, and all later
+    /// items, are forwarded without evaluating the predicate.
+    fn skip_while<F>(self, predicate: F) -> SkipWhileStream<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> bool,
+    {
+        SkipWhileStream::new(self, predicate)
+    }
+
+    /// Records the items of the stream, and once it is closed, replays them in order forever.
+    ///
+    /// If the stream closes without producing an item, the cycle is closed.
+    fn cycle(self) -> CycleStream<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        CycleStream::new(self)
+    }
+
+    /// Discards items which are equal to the previous item, so each run of equal items produces one item.
+    fn dedup(self) -> DedupStream<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq + Clone,
+    {
+        DedupStream::new(self)
+    }
+
+    /// Discards items whose key is equal to the key of the previous item.  The key is computed with `key`.
+    fn dedup_by_key<F, K>(self, key: F) -> DedupByKeyStream<Self, F, K>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> K,
+        K: PartialEq,
+    {
+        DedupByKeyStream::new(self, key)
+    }
+
+    /// Pairs each item with its index, counting from 0.  The index only advances when an item is ready.
+    ///
+    /// When the stream is a lossy receiver, gaps in the indices of the payloads can be used to detect dropped messages.
+    fn enumerate(self) -> EnumerateStream<Self>
+    where
+        Self: Sized,
+    {
+        EnumerateStream::new(self)
+    }
+
     /// Finds a message matching a condition.  When the condition is matched, a single value will be returned.
     /// Then the stream will be closed.
     fn find<Condition>(self, condition: Condition) -> FindStream<Self, Condition>
     where
-        Self: Sized + Unpin,
-        Condition: Fn(&Self::Item) -> bool + Unpin,
+        Self: Sized,
+        Condition: Fn(&Self::Item) -> bool,
     {
         FindStream::new(self, condition)
     }
 
+    /// Maps items with `map` until it returns `Some`.  The first value is returned, and then the stream is closed.
+    ///
+    /// The returned stream can be awaited, which resolves to the first value, or `None` if the stream closes without
+    /// a match.
+    fn find_map<Map, Into>(self, map: Map) -> FindMapStream<Self, Map, Into>
+    where
+        Self: Sized,
+        Map: FnMut(Self::Item) -> Option<Into>,
+    {
+        FindMapStream::new(self, map)
+    }
+
+    /// Returns a future which collects the items of the stream, and resolves to the collection once the stream is
+    /// closed.
+    ///
+    /// Streams can be borrowed with `&mut`, so a prefix of the items can be collected with `(&mut rx).take(n)`.
+    fn collect<C>(self) -> CollectFuture<Self, C>
+    where
+        Self: Sized,
+        C: Default + Extend<Self::Item>,
+    {
+        CollectFuture::new(self)
+    }
+
+    /// Returns a future which resolves to `true` if any item matches the predicate.
+    ///
+    /// The future resolves on the first matching item, and doesn't poll the stream again.  If the stream closes
+    /// without a match, it resolves to `false`.
+    fn any<F>(self, predicate: F) -> AnyFuture<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> bool,
+    {
+        AnyFuture::new(self, predicate)
+    }
+
+    /// Returns a future which resolves to `true` if every item matches the predicate.
+    ///
+    /// The future resolves to `false` on the first item which doesn't match, and doesn't poll the stream again.  If
+    /// the stream closes, it resolves to `true`.
+    fn all<F>(self, predicate: F) -> AllFuture<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> bool,
+    {
+        AllFuture::new(self, predicate)
+    }
+
+    /// Returns a future which resolves to the number of items received, once the stream is closed.
+    fn count(self) -> CountFuture<Self>
+    where
+        Self: Sized,
+    {
+        CountFuture::new(self)
+    }
+
+    /// Returns a future which resolves to the first item received, consuming the stream.
+    ///
+    /// Resolves to `None` if the stream closes without an item.  Unlike `recv`, this takes the stream by value, so it
+    /// can be called on a combinator chain, such as `rx.filter(f).first().await`.
+    fn first(self) -> FirstFuture<Self>
+    where
+        Self: Sized,
+    {
+        FirstFuture::new(self)
+    }
+
+    /// Returns a future which resolves to the last item received, once the stream is closed.
+    ///
+    /// Resolves to `None` if the stream closes without an item.
+    fn last(self) -> LastFuture<Self>
+    where
+        Self: Sized,
+    {
+        LastFuture::new(self)
+    }
+
+    /// Returns a future which sends each item to the sink, until the stream is closed.
+    ///
+    /// A new item is only received once the sink has accepted the previous item, so the sink applies backpressure
+    /// to the stream.  The future resolves to `Ok(())` when the stream is closed, or `Err(item)` if the sink rejects
+    /// an item.
+    fn forward<K>(self, sink: K) -> ForwardFuture<Self, K>
+    where
+        Self: Sized,
+        K: crate::sink::Sink<Item = Self::Item>,
+    {
+        ForwardFuture::new(self, sink)
+    }
+
+    /// Returns a future which sends the left half of each pair to `left`, and the right half to `right`.
+    ///
+    /// A new pair is only received once both halves have been accepted, so either sink applies backpressure to the
+    /// stream.  The future resolves to `Ok(())` when the stream is closed.  If either sink rejects a half, it
+    /// resolves to `Err((left, right))` with the halves which were not sent.
+    fn unzip<A, B, SA, SB>(self, left: SA, right: SB) -> UnzipFuture<Self, SA, SB>
+    where
+        Self: Stream<Item = (A, B)> + Sized,
+        SA: crate::sink::Sink<Item = A>,
+        SB: crate::sink::Sink<Item = B>,
+    {
+        UnzipFuture::new(self, left, right)
+    }
+
+    /// Returns a future which accumulates the items of the stream with `f`, starting with `init`.
+    ///
+    /// Resolves to the accumulator once the stream is closed.  Streams can be borrowed with `&mut`,
+    /// and can be used after the future completes.
+    fn fold<Acc, F>(self, init: Acc, f: F) -> FoldFuture<Self, Acc, F>
+    where
+        Self: Sized,
+        F: FnMut(Acc, Self::Item) -> Acc,
+    {
+        FoldFuture::new(self, init, f)
+    }
+
+    /// Returns a future which combines the items of the stream with `f`, using the first item as the accumulator.
+    ///
+    /// Resolves to the accumulator once the stream is closed, or `None` if the stream closes without an item.
+    fn reduce<F>(self, f: F) -> ReduceFuture<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item, Self::Item) -> Self::Item,
+    {
+        ReduceFuture::new(self, f)
+    }
+
+    /// Returns a future which calls `f` with each item of the stream, and resolves once the stream is closed.
+    ///
+    /// Ready items are visited within a single poll, so a burst of items is handled with one wakeup.  Streams can be
+    /// borrowed with `&mut`, and can be used after the future completes.
+    fn for_each<F>(self, f: F) -> ForEachFuture<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item),
+    {
+        ForEachFuture::new(self, f)
+    }
+
+    /// Returns a future which maps each item of the stream to a future with `f`, and awaits it.
+    ///
+    /// No item is received while a future is in flight.  Resolves once the stream is closed, and the last future
+    /// has completed.
+    fn for_each_async<F, Fut>(self, f: F) -> ForEachAsyncFuture<Self, F, Fut>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        ForEachAsyncFuture::new(self, f)
+    }
+
+    /// Forwards messages only while the watched `gate` value is `true`.
+    ///
+    /// While the gate is `false`, the inner stream is not polled, and the task is woken when the gate changes.
+    /// If the gate channel is closed, the gate remains open.  This can be configured with
+    /// [GateStream::close_with_gate](./struct.GateStream.html#method.close_with_gate).
+    fn gate(self, gate: watch::Receiver<bool>) -> GateStream<Self>
+    where
+        Self: Sized,
+    {
+        GateStream::new(self, gate)
+    }
+
+    /// Produces `Err(Elapsed)` each time the stream does not produce an item within `duration`.
+    /// Items are returned as `Ok(item)`, and the stream continues after a timeout.
+    ///
+    /// The timer restarts after each item, and after each `Elapsed` error.  This can be configured with
+    /// [TimeoutStream::reset_on_elapsed](./struct.TimeoutStream.html#method.reset_on_elapsed).
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be polled within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn timeout(
+        self,
+        duration: std::time::Duration,
+    ) -> TimeoutStream<Self, crate::time::RuntimeSleep>
+    where
+        Self: Sized,
+    {
+        TimeoutStream::new(self, duration, crate::time::RuntimeSleep::default())
+    }
+
+    /// Produces `Err(Elapsed)` each time the stream does not produce an item within `duration`,
+    /// using the provided [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [timeout](#method.timeout), but does not require an async runtime feature.
+    fn timeout_with<T>(self, duration: std::time::Duration, timer: T) -> TimeoutStream<Self, T>
+    where
+        Self: Sized,
+        T: crate::time::Sleep,
+    {
+        TimeoutStream::new(self, duration, timer)
+    }
+
+    /// Produces the most recent item once the stream has not produced an item for `quiet`.
+    ///
+    /// Items which are replaced by a newer item before the stream is quiet are discarded.  When the stream is
+    /// closed, the buffered item is produced immediately, before the closure.
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be polled within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn debounce(self, quiet: std::time::Duration) -> DebounceStream<Self, crate::time::RuntimeSleep>
+    where
+        Self: Sized,
+    {
+        DebounceStream::new(self, quiet, crate::time::RuntimeSleep::default())
+    }
+
+    /// Produces the most recent item once the stream has not produced an item for `quiet`, using the provided
+    /// [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [debounce](#method.debounce), but does not require an async runtime feature.
+    fn debounce_with<T>(self, quiet: std::time::Duration, timer: T) -> DebounceStream<Self, T>
+    where
+        Self: Sized,
+        T: crate::time::Sleep,
+    {
+        DebounceStream::new(self, quiet, timer)
+    }
+
+    /// Drives the stream on a spawned task, which receives up to `n` items ahead of the consumer.
+    ///
+    /// The items are buffered in an mpsc channel with capacity `n`, so the stages of the stream make progress while
+    /// the consumer is busy.  When the returned stream is dropped, the task stops, and the inner stream and buffered
+    /// items are dropped.  The task also stops when the inner stream is closed.  Panics if `n` is zero.
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be called within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    #[track_caller]
+    fn buffered(self, n: usize) -> BufferedStream<Self::Item>
+    where
+        Self: Sized + Send + 'static,
+        Self::Item: Send + 'static,
+    {
+        BufferedStream::new(self, n)
+    }
+
+    /// Produces at most one item per `interval`, delaying items which are received before the interval elapses.
+    ///
+    /// Unlike [debounce](#method.debounce), items are not discarded.  The timer is only started when an item is
+    /// waiting, and a closure is returned immediately, even before the interval elapses.
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be polled within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn throttle(
+        self,
+        interval: std::time::Duration,
+    ) -> ThrottleStream<Self, crate::time::RuntimeSleep>
+    where
+        Self: Sized,
+    {
+        ThrottleStream::new(self, interval, crate::time::RuntimeSleep::default())
+    }
+
+    /// Produces at most one item per `interval`, using the provided [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [throttle](#method.throttle), but does not require an async runtime feature.
+    fn throttle_with<T>(self, interval: std::time::Duration, timer: T) -> ThrottleStream<Self, T>
+    where
+        Self: Sized,
+        T: crate::time::Sleep,
+    {
+        ThrottleStream::new(self, interval, timer)
+    }
+
+    /// Produces an item from `heartbeat` each time the stream does not produce an item within `duration`.
+    ///
+    /// The timer restarts after each item, and after each heartbeat.  This can be configured with
+    /// [HeartbeatStream::reset_on_heartbeat](./struct.HeartbeatStream.html#method.reset_on_heartbeat).
+    /// The stream is closed when the source stream is closed.
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be polled within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn with_heartbeat<F>(
+        self,
+        duration: std::time::Duration,
+        heartbeat: F,
+    ) -> HeartbeatStream<Self, F, crate::time::RuntimeSleep>
+    where
+        Self: Sized,
+        F: FnMut() -> Self::Item,
+    {
+        HeartbeatStream::new(
+            self,
+            duration,
+            heartbeat,
+            crate::time::RuntimeSleep::default(),
+        )
+    }
+
+    /// Produces an item from `heartbeat` each time the stream does not produce an item within `duration`,
+    /// using the provided [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [with_heartbeat](#method.with_heartbeat), but does not require an async runtime feature.
+    fn with_heartbeat_timer<F, T>(
+        self,
+        duration: std::time::Duration,
+        heartbeat: F,
+        timer: T,
+    ) -> HeartbeatStream<Self, F, T>
+    where
+        Self: Sized,
+        F: FnMut() -> Self::Item,
+        T: crate::time::Sleep,
+    {
+        HeartbeatStream::new(self, duration, heartbeat, timer)
+    }
+
+    /// Records the items produced by the stream, sending a [Record](./struct.Record.html) of each item to `sink`,
+    /// with the time it was received.  Items are forwarded unchanged.
+    ///
+    /// The sink does not stall the stream.  Records are buffered while the sink is pending, and the recording stops
+    /// if the sink is closed.  When the stream is closed, the remaining records are flushed, and the sink is closed.
+    /// Records can be replayed with [replay](./fn.replay.html).
+    ///
+    /// Requires the `tokio` or `async-std` feature, and must be polled within the runtime.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    fn record<K>(self, sink: K) -> RecordingStream<Self, K, crate::time::RuntimeSleep>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+        K: crate::sink::Sink<Item = Record<Self::Item>>,
+    {
+        RecordingStream::new(self, sink, crate::time::RuntimeSleep::default())
+    }
+
+    /// Records the items produced by the stream, using the provided [timer](../time/trait.Sleep.html).
+    ///
+    /// Behaves like [record](#method.record), but does not require an async runtime feature.
+    fn record_with<K, T>(self, sink: K, timer: T) -> RecordingStream<Self, K, T>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+        K: crate::sink::Sink<Item = Record<Self::Item>>,
+        T: crate::time::Sleep,
+    {
+        RecordingStream::new(self, sink, timer)
+    }
+
     /// Logs messages that are produced by the stream using the Debug trait, at the provided log level.
     ///
     /// Requires the `logging` feature
@@ -240,18 +1098,173 @@ where
     fn poll_recv(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
         S::poll_recv(Pin::new(&mut **self), cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        S::size_hint(&**self)
+    }
 }
 
+// the target may be !Unpin, as the pointer keeps it pinned
 impl<P, S> Stream for Pin<P>
 where
     P: DerefMut<Target = S> + Unpin,
-    S: Stream + Unpin + ?Sized,
+    S: Stream + ?Sized,
 {
     type Item = <S as Stream>::Item;
 
     fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
         Pin::get_mut(self).as_mut().poll_recv(cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        S::size_hint(&**self)
+    }
+}
+
+/// Returns a future which forwards items from `rx` to `tx`, converting each item with `convert`.
+///
+/// Conversion failures are sent to `err_tx`.  Backpressure from either sink stalls the input.
+/// The future completes with a [report](./struct.BridgeReport.html) when the input is closed, or either sink is closed.
+///
+/// ```rust
+/// use postage::{mpsc, sink::Sink, stream::{bridge, Termination}};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut tx, rx) = mpsc::channel(4);
+///     let (out_tx, _out_rx) = mpsc::channel(4);
+///     let (err_tx, _err_rx) = mpsc::channel(4);
+///
+///     tx.send("1").await.ok();
+///     tx.send("x").await.ok();
+///     drop(tx);
+///
+///     let report = bridge(rx, out_tx, |v: &str| v.parse::<usize>(), err_tx).await;
+///     assert_eq!(1, report.forwarded);
+///     assert_eq!(1, report.failed);
+///     assert_eq!(Termination::InputClosed, report.terminated_by);
+/// }
+/// ```
+pub fn bridge<Rx, Tx, ErrTx, Convert>(
+    rx: Rx,
+    tx: Tx,
+    convert: Convert,
+    err_tx: ErrTx,
+) -> BridgeFuture<Rx, Tx, ErrTx, Convert>
+where
+    Rx: Stream,
+    Tx: crate::sink::Sink,
+    ErrTx: crate::sink::Sink,
+    Convert: FnMut(Rx::Item) -> Result<Tx::Item, ErrTx::Item>,
+{
+    BridgeFuture::new(rx, tx, convert, err_tx)
+}
+
+/// Returns a future which receives one item from each stream, polling the streams concurrently.
+///
+/// Resolves to the items in the order of the streams, once every stream has produced an item or closed.
+/// Closed streams produce `None`.  Streams can be borrowed with `&mut`, and receive later items after the future completes.
+///
+/// ```rust
+/// use postage::{mpsc, sink::Sink, stream::gather};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut tx1, mut rx1) = mpsc::channel(4);
+///     let (tx2, mut rx2) = mpsc::channel::<usize>(4);
+///
+///     tx1.send(1).await.ok();
+///     drop(tx2);
+///
+///     assert_eq!(vec![Some(1), None], gather(vec![&mut rx1, &mut rx2]).await);
+/// }
+/// ```
+pub fn gather<I>(streams: I) -> GatherFuture<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Stream + Unpin,
+{
+    GatherFuture::new(streams.into_iter().collect())
+}
+
+/// Returns a stream which receives items from each of the streams, until all of them are closed.
+///
+/// The streams are polled round-robin, and closed streams are removed from the set.  Streams can be added with
+/// [SelectAll::push](./struct.SelectAll.html#method.push), for example as clients connect.
+///
+/// ```rust
+/// use postage::mpsc;
+/// use postage::sink::Sink;
+/// use postage::stream::{select_all, Stream};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut tx1, rx1) = mpsc::channel(4);
+///     let mut select = select_all(vec![rx1]);
+///
+///     let (mut tx2, rx2) = mpsc::channel(4);
+///     select.push(rx2);
+///
+///     tx1.send(1usize).await.ok();
+///     tx2.send(2usize).await.ok();
+///     drop(tx1);
+///     drop(tx2);
+///
+///     assert_eq!(Some(1), select.recv().await);
+///     assert_eq!(Some(2), select.recv().await);
+///     assert_eq!(None, select.recv().await);
+/// }
+/// ```
+pub fn select_all<I>(streams: I) -> SelectAll<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Stream + Unpin,
+{
+    let mut select = SelectAll::new();
+    select.extend(streams);
+    select
+}
+
+/// Returns a stream which produces the values of each stream in the sequence, until the last stream is closed.
+///
+/// Streams are taken from the iterator once the previous stream is closed.
+pub fn concat<I>(streams: I) -> ConcatStream<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Stream,
+{
+    ConcatStream::new(streams.into_iter())
+}
+
+/// Returns a stream which replays recorded items, with the original gaps between them.
+///
+/// The gaps are divided by `speed`, so a speed of `2.0` replays twice as fast.  The replay starts when the stream is
+/// first polled, and the stream is closed after the last record.  Records are produced in order, and are expected to
+/// be sorted by time, as produced by [Stream::record](./trait.Stream.html#method.record).
+///
+/// Panics if `speed` is not positive and finite.  Requires the `tokio` or `async-std` feature, and must be polled
+/// within the runtime.
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub fn replay<I, T>(records: I, speed: f64) -> ReplayStream<I::IntoIter, crate::time::RuntimeSleep>
+where
+    I: IntoIterator<Item = Record<T>>,
+{
+    ReplayStream::new(
+        records.into_iter(),
+        speed,
+        crate::time::RuntimeSleep::default(),
+    )
+}
+
+/// Returns a stream which replays recorded items, using the provided [timer](../time/trait.Sleep.html).
+///
+/// Behaves like [replay](./fn.replay.html), but does not require an async runtime feature.
+pub fn replay_with<I, T, S>(records: I, speed: f64, timer: S) -> ReplayStream<I::IntoIter, S>
+where
+    I: IntoIterator<Item = Record<T>>,
+    S: crate::time::Sleep,
+{
+    ReplayStream::new(records.into_iter(), speed, timer)
 }
 
 /// Returns a stream which produces a single value, and then is closed.
@@ -286,6 +1299,7 @@ where
     S: Stream + ?Sized,
 {
     recv: &'s mut S,
+    complete: bool,
     #[pin]
     _pin: PhantomPinned,
 }
@@ -297,6 +1311,7 @@ where
     pub fn new(recv: &'s mut S) -> RecvFuture<'s, S> {
         Self {
             recv,
+            complete: false,
             _pin: PhantomPinned,
         }
     }
@@ -312,16 +1327,178 @@ where
         let this = self.project();
 
         let mut cx: crate::Context<'_> = cx.into();
-        match Pin::new(this.recv).poll_recv(&mut cx) {
+        let poll = match Pin::new(this.recv).poll_recv(&mut cx) {
             PollRecv::Ready(v) => Poll::Ready(Some(v)),
             PollRecv::Pending => Poll::Pending,
             PollRecv::Closed => Poll::Ready(None),
+        };
+
+        if poll.is_ready() {
+            *this.complete = true;
+        }
+
+        poll
+    }
+}
+
+#[cfg(feature = "futures-traits")]
+mod impl_futures {
+    use super::{RecvFuture, Stream};
+
+    impl<'s, S> futures::future::FusedFuture for RecvFuture<'s, S>
+    where
+        S: Stream + Unpin + ?Sized,
+    {
+        fn is_terminated(&self) -> bool {
+            self.complete
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{pin::Pin, rc::Rc};
+
+    use super::{once, BoxStream, LocalBoxStream, Stream, TryRecvError};
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::PollRecv,
+        test::{
+            noop_context,
+            stream::{from_iter, not_unpin},
+        },
+    };
+
+    #[test]
+    fn size_hint_default() {
+        struct Unknown;
+
+        impl Stream for Unknown {
+            type Item = ();
+
+            fn poll_recv(self: Pin<&mut Self>, _cx: &mut crate::Context<'_>) -> PollRecv<()> {
+                PollRecv::Pending
+            }
+        }
+
+        assert_eq!((0, None), Unknown.size_hint());
+        let mut unknown = Unknown;
+        let forward = &mut unknown;
+        assert_eq!((0, None), forward.size_hint());
+        assert_eq!((0, None), Box::pin(Unknown).size_hint());
+    }
+
+    struct Handler {
+        input: BoxStream<'static, usize>,
+    }
+
+    #[tokio::test]
+    async fn boxed() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut handler = Handler {
+            input: rx.map(|i: usize| i * 2).filter(|i| *i > 2).boxed(),
+        };
+
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        drop(tx);
+
+        assert_eq!((0, Some(2)), handler.input.size_hint());
+        assert_eq!(Some(4), handler.input.recv().await);
+        assert_eq!(None, handler.input.recv().await);
+
+        handler.input = once(3).boxed();
+        assert_eq!(Ok(3), handler.input.try_recv());
+    }
+
+    #[test]
+    fn boxed_not_unpin() {
+        let mut stream = not_unpin(from_iter(vec![1, 2])).boxed();
+
+        assert_eq!(Ok(1), stream.try_recv());
+        assert_eq!(Ok(2), stream.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), stream.try_recv());
+    }
+
+    #[test]
+    fn boxed_local() {
+        let mut stream: LocalBoxStream<'_, Rc<usize>> = once(Rc::new(1)).boxed_local();
+
+        assert_eq!(Ok(Rc::new(1)), stream.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), stream.try_recv());
+    }
+
+    // recv only requires `Unpin`, so it can be called through unsized references
+    async fn recv_unsized<S>(stream: &mut S) -> Option<S::Item>
+    where
+        S: Stream + Unpin + ?Sized,
+    {
+        stream.recv().await
+    }
+
+    #[tokio::test]
+    async fn recv_unsized_stream() {
+        let mut stream = once(1usize);
+        assert_eq!(Some(1), recv_unsized(&mut stream).await);
+        assert_eq!(None, recv_unsized(&mut stream).await);
+    }
+
+    #[test]
+    fn size_hint_map_filter() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = mpsc::channel(4);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+
+        let stream = rx.map(|v| v * 2);
+        assert_eq!((2, None), stream.size_hint());
+
+        drop(tx);
+        assert_eq!((2, Some(2)), stream.size_hint());
+
+        let mut stream = stream.filter(|v| *v > 2);
+        assert_eq!((0, Some(2)), stream.size_hint());
+
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), stream.size_hint());
+    }
+
+    #[test]
+    fn size_hint_chain() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = mpsc::channel(4);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 3));
+
+        let mut stream = from_iter(vec![1, 2]).chain(rx);
+        assert_eq!((2, None), stream.size_hint());
+
+        drop(tx);
+        assert_eq!((2, Some(3)), stream.size_hint());
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        // the left stream is exhausted, so the right stream is ready
+        assert_eq!((1, Some(1)), stream.size_hint());
+
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), stream.size_hint());
+    }
+
+    #[test]
+    fn size_hint_merge() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = mpsc::channel(4);
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+
+        let stream = rx.merge(once(2)).map(|v| v + 1);
+        assert_eq!((2, None), stream.size_hint());
+
+        drop(tx);
+        assert_eq!((2, Some(2)), stream.size_hint());
+    }
 
     #[cfg(feature = "blocking")]
     #[test]
@@ -0,0 +1,298 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::any](./trait.Stream.html#method.any), which checks whether any item matches a
+/// predicate.
+///
+/// Resolves to `true` on the first matching item, without polling the stream again, or `false` once the stream is
+/// closed.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct AnyFuture<S, F> {
+    #[pin]
+    stream: S,
+    predicate: F,
+    complete: bool,
+}
+
+impl<S, F> AnyFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> bool,
+{
+    pub fn new(stream: S, predicate: F) -> Self {
+        Self {
+            stream,
+            predicate,
+            complete: false,
+        }
+    }
+}
+
+impl<S, F> Future for AnyFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> bool,
+{
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "AnyFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => {
+                    if (this.predicate)(item) {
+                        *this.complete = true;
+                        return Poll::Ready(true);
+                    }
+                }
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(false);
+                }
+            }
+        }
+    }
+}
+
+/// A future returned by [Stream::all](./trait.Stream.html#method.all), which checks whether every item matches a
+/// predicate.
+///
+/// Resolves to `false` on the first item which doesn't match, without polling the stream again, or `true` once the
+/// stream is closed.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct AllFuture<S, F> {
+    #[pin]
+    stream: S,
+    predicate: F,
+    complete: bool,
+}
+
+impl<S, F> AllFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> bool,
+{
+    pub fn new(stream: S, predicate: F) -> Self {
+        Self {
+            stream,
+            predicate,
+            complete: false,
+        }
+    }
+}
+
+impl<S, F> Future for AllFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> bool,
+{
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "AllFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => {
+                    if !(this.predicate)(item) {
+                        *this.complete = true;
+                        return Poll::Ready(false);
+                    }
+                }
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(true);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::noop_context;
+
+    use crate::{
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter},
+        Context,
+    };
+
+    use super::{AllFuture, AnyFuture};
+
+    // counts the calls to poll_recv, so short-circuiting can be observed
+    struct CountPolls<'c, S> {
+        stream: S,
+        polls: &'c Cell<usize>,
+    }
+
+    impl<'c, S> Stream for CountPolls<'c, S>
+    where
+        S: Stream + Unpin,
+    {
+        type Item = S::Item;
+
+        fn poll_recv(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+            self.polls.set(self.polls.get() + 1);
+            Pin::new(&mut self.stream).poll_recv(cx)
+        }
+    }
+
+    fn count_polls<S>(stream: S, polls: &Cell<usize>) -> CountPolls<'_, S> {
+        CountPolls { stream, polls }
+    }
+
+    #[test]
+    fn any_empty() {
+        let mut any = AnyFuture::new(closed::<usize>(), |_| true);
+
+        assert_eq!(
+            Poll::Ready(false),
+            Pin::new(&mut any).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn any_no_match() {
+        let mut any = AnyFuture::new(from_iter(vec![1, 2, 3]), |i| i > 3);
+
+        assert_eq!(
+            Poll::Ready(false),
+            Pin::new(&mut any).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn any_short_circuits() {
+        let polls = Cell::new(0);
+        let stream = count_polls(from_iter(vec![1, 2, 3, 4]), &polls);
+        let mut any = AnyFuture::new(stream, |i| i == 2);
+
+        assert_eq!(
+            Poll::Ready(true),
+            Pin::new(&mut any).poll(&mut noop_context())
+        );
+        assert_eq!(2, polls.get());
+    }
+
+    #[test]
+    fn any_pending() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut any = AnyFuture::new(stream, |i| i == 2);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut any).poll(&mut noop_context()));
+        assert_eq!(
+            Poll::Ready(true),
+            Pin::new(&mut any).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn any_poll_after_completion() {
+        let mut any = AnyFuture::new(closed::<usize>(), |_| true);
+
+        assert_eq!(
+            Poll::Ready(false),
+            Pin::new(&mut any).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut any).poll(&mut noop_context());
+    }
+
+    #[test]
+    fn all_empty() {
+        let mut all = AllFuture::new(closed::<usize>(), |_| false);
+
+        assert_eq!(
+            Poll::Ready(true),
+            Pin::new(&mut all).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn all_match() {
+        let mut all = AllFuture::new(from_iter(vec![1, 2, 3]), |i| i <= 3);
+
+        assert_eq!(
+            Poll::Ready(true),
+            Pin::new(&mut all).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn all_short_circuits() {
+        let polls = Cell::new(0);
+        let stream = count_polls(from_iter(vec![1, 2, 3, 4]), &polls);
+        let mut all = AllFuture::new(stream, |i| i < 2);
+
+        assert_eq!(
+            Poll::Ready(false),
+            Pin::new(&mut all).poll(&mut noop_context())
+        );
+        assert_eq!(2, polls.get());
+    }
+
+    #[test]
+    fn all_pending() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut all = AllFuture::new(stream, |i| i < 3);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut all).poll(&mut noop_context()));
+        assert_eq!(
+            Poll::Ready(true),
+            Pin::new(&mut all).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn all_poll_after_completion() {
+        let mut all = AllFuture::new(closed::<usize>(), |_| false);
+
+        assert_eq!(
+            Poll::Ready(true),
+            Pin::new(&mut all).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut all).poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn any_all_broadcast() {
+        use crate::{broadcast, sink::Sink};
+
+        let (mut tx, rx) = broadcast::channel(4);
+        let rx2 = tx.subscribe();
+
+        for i in 1..=3 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        assert!(rx.any(|i| i == 3).await);
+        assert!(rx2.all(|i| i > 0).await);
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Wake, Waker},
+    thread::{self, Thread, ThreadId},
+};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+
+/// An iterator returned by [Stream::into_blocking_iter](./trait.Stream.html#method.into_blocking_iter), which
+/// blocks the current thread until each item is received.
+///
+/// While the stream is pending, the thread is parked, and it is unparked by the waker.  The iterator returns `None`
+/// once the stream is closed.
+pub struct BlockingIter<S> {
+    stream: S,
+    waker: Option<ThreadWaker>,
+    closed: bool,
+}
+
+// the waker of the thread which is iterating.  replaced if the iterator is moved to another thread
+struct ThreadWaker {
+    id: ThreadId,
+    state: Arc<ThreadWakerState>,
+    waker: Waker,
+}
+
+struct ThreadWakerState {
+    thread: Thread,
+    // set by the waker, so spurious unparks do not cause the stream to be polled
+    notified: AtomicBool,
+}
+
+impl Wake for ThreadWakerState {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.notified.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+impl ThreadWaker {
+    fn current() -> Self {
+        let thread = thread::current();
+        let id = thread.id();
+        let state = Arc::new(ThreadWakerState {
+            thread,
+            notified: AtomicBool::new(false),
+        });
+        let waker = Waker::from(state.clone());
+
+        Self { id, state, waker }
+    }
+
+    fn park(&self) {
+        while !self.state.notified.swap(false, Ordering::AcqRel) {
+            thread::park();
+        }
+    }
+}
+
+impl<S> BlockingIter<S>
+where
+    S: Stream + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            waker: None,
+            closed: false,
+        }
+    }
+
+    /// Returns the inner stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> Iterator for BlockingIter<S>
+where
+    S: Stream + Unpin,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.closed {
+            return None;
+        }
+
+        let current = thread::current().id();
+        if self.waker.as_ref().map(|waker| waker.id) != Some(current) {
+            self.waker = Some(ThreadWaker::current());
+        }
+
+        let waker = self.waker.as_ref().unwrap();
+        let mut cx = Context::from_waker(&waker.waker);
+
+        loop {
+            match Pin::new(&mut self.stream).poll_recv(&mut cx) {
+                PollRecv::Ready(item) => return Some(item),
+                PollRecv::Pending => waker.park(),
+                PollRecv::Closed => {
+                    self.closed = true;
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{from_iter, from_poll_iter},
+    };
+
+    use super::BlockingIter;
+
+    #[test]
+    fn ready_items() {
+        let iter = BlockingIter::new(from_iter(vec![1, 2, 3]));
+
+        assert_eq!(vec![1, 2, 3], iter.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn closed_is_fused() {
+        let mut iter = BlockingIter::new(from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Closed,
+            PollRecv::Ready(2),
+        ]));
+
+        assert_eq!(Some(1), iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!((0, Some(0)), iter.size_hint());
+    }
+
+    #[test]
+    fn producer_thread() {
+        let (mut tx, rx) = mpsc::channel(2);
+
+        let producer = thread::spawn(move || {
+            for i in 0..100usize {
+                tx.blocking_send(i).expect("send failed");
+
+                if i % 10 == 0 {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        });
+
+        let mut expected = 0;
+        for item in rx.into_blocking_iter() {
+            assert_eq!(expected, item);
+            expected += 1;
+        }
+
+        assert_eq!(100, expected);
+        producer.join().unwrap();
+    }
+
+    #[test]
+    fn parks_until_woken() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut iter = rx.into_blocking_iter();
+
+        let producer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            tx.blocking_send(1usize).expect("send failed");
+            thread::sleep(Duration::from_millis(50));
+        });
+
+        assert_eq!(Some(1), iter.next());
+        assert_eq!(None, iter.next());
+        producer.join().unwrap();
+    }
+}
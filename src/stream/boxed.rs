@@ -0,0 +1,90 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+
+// The object-safe subset of Stream.  Boxed streams store a trait object, and pin the stream internally,
+// so `Stream` keeps its `recv` bounds and does not need to be object-safe.
+trait DynStream {
+    type Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item>;
+
+    fn size_hint(&self) -> (usize, Option<usize>);
+}
+
+impl<S> DynStream for S
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        Stream::poll_recv(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Stream::size_hint(self)
+    }
+}
+
+/// A boxed stream, which erases the type of a stream, so it can be stored in a struct field.
+///
+/// Created with [Stream::boxed](./trait.Stream.html#method.boxed).  The box keeps the stream pinned, so the boxed
+/// stream is `Unpin`, and can be received with `recv`.
+pub struct BoxStream<'a, T> {
+    stream: Pin<Box<dyn DynStream<Item = T> + Send + 'a>>,
+}
+
+impl<'a, T> BoxStream<'a, T> {
+    pub(in crate::stream) fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = T> + Send + 'a,
+    {
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+impl<'a, T> Stream for BoxStream<'a, T> {
+    type Item = T;
+
+    fn poll_recv(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        self.stream.as_mut().poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+/// A boxed stream which is not `Send`.
+///
+/// Created with [Stream::boxed_local](./trait.Stream.html#method.boxed_local).
+pub struct LocalBoxStream<'a, T> {
+    stream: Pin<Box<dyn DynStream<Item = T> + 'a>>,
+}
+
+impl<'a, T> LocalBoxStream<'a, T> {
+    pub(in crate::stream) fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = T> + 'a,
+    {
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+impl<'a, T> Stream for LocalBoxStream<'a, T> {
+    type Item = T;
+
+    fn poll_recv(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        self.stream.as_mut().poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
@@ -0,0 +1,301 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+};
+
+/// The reason a [bridge](./fn.bridge.html) completed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Termination {
+    /// The input stream was closed.
+    InputClosed,
+    /// The output sink rejected a converted item.  The item was dropped.
+    OutputClosed,
+    /// The error sink rejected a conversion failure.  The error was dropped.
+    ErrorClosed,
+}
+
+/// The completion report of a [bridge](./fn.bridge.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BridgeReport {
+    /// The number of items which were converted and accepted by the output sink.
+    pub forwarded: usize,
+    /// The number of conversion failures which were accepted by the error sink.
+    pub failed: usize,
+    /// The reason the bridge completed.
+    pub terminated_by: Termination,
+}
+
+enum InFlight<B, E> {
+    Output(B),
+    Error(E),
+}
+
+/// A future returned by [bridge](./fn.bridge.html), which forwards items until the input or either sink is closed.
+///
+/// An item is only received once the previous item has been accepted.  If the future is dropped,
+/// the item in flight is dropped with it.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct BridgeFuture<Rx, Tx, ErrTx, Convert>
+where
+    Tx: Sink,
+    ErrTx: Sink,
+{
+    #[pin]
+    rx: Rx,
+    #[pin]
+    tx: Tx,
+    #[pin]
+    err_tx: ErrTx,
+    convert: Convert,
+    in_flight: Option<InFlight<Tx::Item, ErrTx::Item>>,
+    forwarded: usize,
+    failed: usize,
+    terminated_by: Option<Termination>,
+}
+
+impl<Rx, Tx, ErrTx, Convert> BridgeFuture<Rx, Tx, ErrTx, Convert>
+where
+    Rx: Stream,
+    Tx: Sink,
+    ErrTx: Sink,
+    Convert: FnMut(Rx::Item) -> Result<Tx::Item, ErrTx::Item>,
+{
+    pub fn new(rx: Rx, tx: Tx, convert: Convert, err_tx: ErrTx) -> Self {
+        Self {
+            rx,
+            tx,
+            err_tx,
+            convert,
+            in_flight: None,
+            forwarded: 0,
+            failed: 0,
+            terminated_by: None,
+        }
+    }
+}
+
+impl<Rx, Tx, ErrTx, Convert> Future for BridgeFuture<Rx, Tx, ErrTx, Convert>
+where
+    Rx: Stream,
+    Tx: Sink,
+    ErrTx: Sink,
+    Convert: FnMut(Rx::Item) -> Result<Tx::Item, ErrTx::Item>,
+{
+    type Output = BridgeReport;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            if let Some(terminated_by) = *this.terminated_by {
+                return Poll::Ready(BridgeReport {
+                    forwarded: *this.forwarded,
+                    failed: *this.failed,
+                    terminated_by,
+                });
+            }
+
+            // the item in flight is kept until the sink accepts it, so backpressure stalls the input
+            match this.in_flight.take() {
+                Some(InFlight::Output(value)) => match this.tx.as_mut().poll_send(&mut cx, value) {
+                    PollSend::Ready => *this.forwarded += 1,
+                    PollSend::Pending(value) => {
+                        *this.in_flight = Some(InFlight::Output(value));
+                        return Poll::Pending;
+                    }
+                    PollSend::Rejected(_value) => {
+                        *this.terminated_by = Some(Termination::OutputClosed);
+                        continue;
+                    }
+                },
+                Some(InFlight::Error(error)) => {
+                    match this.err_tx.as_mut().poll_send(&mut cx, error) {
+                        PollSend::Ready => *this.failed += 1,
+                        PollSend::Pending(error) => {
+                            *this.in_flight = Some(InFlight::Error(error));
+                            return Poll::Pending;
+                        }
+                        PollSend::Rejected(_error) => {
+                            *this.terminated_by = Some(Termination::ErrorClosed);
+                            continue;
+                        }
+                    }
+                }
+                None => {}
+            }
+
+            match this.rx.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(value) => {
+                    *this.in_flight = Some(match (this.convert)(value) {
+                        Ok(value) => InFlight::Output(value),
+                        Err(error) => InFlight::Error(error),
+                    });
+                }
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => *this.terminated_by = Some(Termination::InputClosed),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::noop_context;
+
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::PollRecv,
+        test::{noop_context as postage_context, stream::recv},
+    };
+
+    use super::{BridgeFuture, BridgeReport, Termination};
+
+    fn parse(value: &'static str) -> Result<usize, String> {
+        value.parse().map_err(|_| value.to_string())
+    }
+
+    fn send<S: Sink + Unpin>(tx: &mut S, value: S::Item) {
+        let mut cx = postage_context();
+        assert!(matches!(
+            Pin::new(tx).poll_send(&mut cx, value),
+            PollSend::Ready
+        ));
+    }
+
+    #[test]
+    fn forward_and_fail() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(4);
+        let (err_tx, mut err_rx) = mpsc::channel(4);
+
+        send(&mut in_tx, "1");
+        send(&mut in_tx, "x");
+        send(&mut in_tx, "2");
+        drop(in_tx);
+
+        let mut bridge = Box::pin(BridgeFuture::new(in_rx, out_tx, parse, err_tx));
+        let report = BridgeReport {
+            forwarded: 2,
+            failed: 1,
+            terminated_by: Termination::InputClosed,
+        };
+        assert_eq!(
+            Poll::Ready(report),
+            bridge.as_mut().poll(&mut noop_context())
+        );
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut out_rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut out_rx));
+        assert_eq!(PollRecv::Ready("x".to_string()), recv(&mut err_rx));
+
+        // the report is returned again after completion
+        assert_eq!(
+            Poll::Ready(report),
+            bridge.as_mut().poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn output_backpressure() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(1);
+        let (err_tx, _err_rx) = mpsc::channel(4);
+
+        send(&mut in_tx, "1");
+        send(&mut in_tx, "2");
+        send(&mut in_tx, "3");
+
+        let mut bridge = Box::pin(BridgeFuture::new(in_rx, out_tx, parse, err_tx));
+        assert_eq!(Poll::Pending, bridge.as_mut().poll(&mut noop_context()));
+
+        // 2 is in flight, and 3 has not been received
+        assert_eq!(PollRecv::Ready(1), recv(&mut out_rx));
+        assert_eq!(PollRecv::Pending, recv(&mut out_rx));
+
+        assert_eq!(Poll::Pending, bridge.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Ready(2), recv(&mut out_rx));
+
+        drop(in_tx);
+        assert_eq!(
+            Poll::Ready(BridgeReport {
+                forwarded: 3,
+                failed: 0,
+                terminated_by: Termination::InputClosed,
+            }),
+            bridge.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Ready(3), recv(&mut out_rx));
+    }
+
+    #[test]
+    fn error_backpressure() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(4);
+        let (err_tx, mut err_rx) = mpsc::channel(1);
+
+        send(&mut in_tx, "x");
+        send(&mut in_tx, "y");
+        send(&mut in_tx, "1");
+
+        let mut bridge = Box::pin(BridgeFuture::new(in_rx, out_tx, parse, err_tx));
+        assert_eq!(Poll::Pending, bridge.as_mut().poll(&mut noop_context()));
+
+        // the full error channel stalls the input
+        assert_eq!(PollRecv::Pending, recv(&mut out_rx));
+        assert_eq!(PollRecv::Ready("x".to_string()), recv(&mut err_rx));
+
+        assert_eq!(Poll::Pending, bridge.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Ready("y".to_string()), recv(&mut err_rx));
+        assert_eq!(PollRecv::Ready(1), recv(&mut out_rx));
+    }
+
+    #[test]
+    fn output_closed() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, out_rx) = mpsc::channel::<usize>(4);
+        let (err_tx, _err_rx) = mpsc::channel(4);
+
+        drop(out_rx);
+        send(&mut in_tx, "1");
+
+        let mut bridge = Box::pin(BridgeFuture::new(in_rx, out_tx, parse, err_tx));
+        assert_eq!(
+            Poll::Ready(BridgeReport {
+                forwarded: 0,
+                failed: 0,
+                terminated_by: Termination::OutputClosed,
+            }),
+            bridge.as_mut().poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn error_closed() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, _out_rx) = mpsc::channel(4);
+        let (err_tx, err_rx) = mpsc::channel::<String>(4);
+
+        drop(err_rx);
+        send(&mut in_tx, "1");
+        send(&mut in_tx, "x");
+
+        let mut bridge = Box::pin(BridgeFuture::new(in_rx, out_tx, parse, err_tx));
+        assert_eq!(
+            Poll::Ready(BridgeReport {
+                forwarded: 1,
+                failed: 0,
+                terminated_by: Termination::ErrorClosed,
+            }),
+            bridge.as_mut().poll(&mut noop_context())
+        );
+    }
+}
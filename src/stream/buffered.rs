@@ -0,0 +1,216 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::{
+    mpsc, oneshot,
+    stream::{ForwardFuture, PollRecv, Stream},
+    Context,
+};
+
+/// A stream returned by [Stream::buffered](./trait.Stream.html#method.buffered), which receives items from a task
+/// that eagerly drives the inner stream.
+///
+/// When the stream is dropped, the task stops, and the inner stream and buffered items are dropped.
+pub struct BufferedStream<T> {
+    rx: mpsc::Receiver<T>,
+    // dropped with the stream, which stops the driver task
+    _stop: oneshot::Sender<()>,
+}
+
+impl<T> BufferedStream<T>
+where
+    T: Send + 'static,
+{
+    #[track_caller]
+    pub fn new<S>(stream: S, capacity: usize) -> Self
+    where
+        S: Stream<Item = T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(capacity);
+        let (stop_tx, stop_rx) = oneshot::channel();
+
+        crate::pipeline::spawn(Driver {
+            forward: ForwardFuture::new(stream, tx),
+            stop: stop_rx,
+        });
+
+        Self { rx, _stop: stop_tx }
+    }
+}
+
+impl<T> Stream for BufferedStream<T> {
+    type Item = T;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        Pin::new(&mut self.get_mut().rx).poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rx.size_hint()
+    }
+}
+
+// forwards the inner stream into the channel, until the stream or channel is closed, or the stop signal is dropped
+#[pin_project]
+struct Driver<S>
+where
+    S: Stream,
+{
+    #[pin]
+    forward: ForwardFuture<S, mpsc::Sender<S::Item>>,
+    stop: oneshot::Receiver<()>,
+}
+
+impl<S> Future for Driver<S>
+where
+    S: Stream,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut stop_cx: Context<'_> = (&mut *cx).into();
+        if !matches!(
+            Pin::new(this.stop).poll_recv(&mut stop_cx),
+            PollRecv::Pending
+        ) {
+            return Poll::Ready(());
+        }
+
+        this.forward.poll(cx).map(|_| ())
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use tokio::{task::yield_now, time::timeout};
+
+    use crate::{
+        mpsc,
+        sink::{Sink, TrySendError},
+        stream::Stream,
+    };
+
+    #[tokio::test]
+    async fn produces_while_idle() {
+        let (mut tx, rx) = mpsc::channel(8);
+        let produced = Arc::new(AtomicUsize::new(0));
+
+        let counter = produced.clone();
+        let mut rx = rx
+            .inspect(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+            .buffered(4);
+
+        for i in 0..4usize {
+            tx.send(i).await.unwrap();
+        }
+
+        // the consumer has not polled, but the driver task receives the items
+        timeout(Duration::from_secs(1), async {
+            while produced.load(Ordering::SeqCst) < 4 {
+                yield_now().await;
+            }
+        })
+        .await
+        .expect("the items were not produced");
+
+        drop(tx);
+        for i in 0..4usize {
+            assert_eq!(Some(i), rx.recv().await);
+        }
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn drop_stops_driver() {
+        let (mut tx, rx) = mpsc::channel(8);
+        let item = Arc::new(());
+
+        let rx = rx.buffered(4);
+        tx.send(item.clone()).await.unwrap();
+
+        timeout(Duration::from_secs(1), async {
+            while rx.size_hint().0 < 1 {
+                yield_now().await;
+            }
+        })
+        .await
+        .expect("the item was not buffered");
+
+        drop(rx);
+
+        // the inner stream is dropped, which closes its channel, and the buffered item is dropped
+        timeout(Duration::from_secs(1), async {
+            loop {
+                match tx.try_send(item.clone()) {
+                    Err(TrySendError::Rejected(_)) => break,
+                    _ => yield_now().await,
+                }
+            }
+        })
+        .await
+        .expect("the driver task was not stopped");
+
+        // clones sent while the driver was stopping are held by the inner channel
+        drop(tx);
+        assert_eq!(1, Arc::strong_count(&item));
+    }
+}
+
+#[cfg(all(test, feature = "async-std", not(feature = "tokio")))]
+mod async_std_tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use async_std::{future::timeout, task::yield_now};
+
+    use crate::{mpsc, sink::Sink, stream::Stream};
+
+    #[async_std::test]
+    async fn produces_while_idle() {
+        let (mut tx, rx) = mpsc::channel(8);
+        let produced = Arc::new(AtomicUsize::new(0));
+
+        let counter = produced.clone();
+        let mut rx = rx
+            .inspect(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+            .buffered(4);
+
+        for i in 0..4usize {
+            tx.send(i).await.unwrap();
+        }
+
+        timeout(Duration::from_secs(1), async {
+            while produced.load(Ordering::SeqCst) < 4 {
+                yield_now().await;
+            }
+        })
+        .await
+        .expect("the items were not produced");
+
+        drop(tx);
+        for i in 0..4usize {
+            assert_eq!(Some(i), rx.recv().await);
+        }
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -75,6 +75,30 @@ where
 
         unreachable!();
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.state.load(Ordering::Acquire) {
+            State::Left => {
+                let (left_lower, left_upper) = self.left.size_hint();
+                let (right_lower, right_upper) = self.right.size_hint();
+
+                // the right stream is only ready once the left stream is closed
+                let lower = match left_upper {
+                    Some(0) => right_lower,
+                    _ => left_lower,
+                };
+
+                let upper = match (left_upper, right_upper) {
+                    (Some(left), Some(right)) => left.checked_add(right),
+                    _ => None,
+                };
+
+                (lower, upper)
+            }
+            State::Right => self.right.size_hint(),
+            State::Closed => (0, Some(0)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -128,4 +152,17 @@ mod tests {
         assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
     }
+
+    #[test]
+    fn not_unpin_inner() {
+        let left = not_unpin(from_iter(vec![1]));
+        let right = not_unpin(from_iter(vec![2]));
+        let mut chain = Box::pin(left.chain(right));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), chain.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), chain.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, chain.as_mut().poll_recv(&mut cx));
+    }
 }
@@ -0,0 +1,217 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ChunksStream<S> {
+    #[pin]
+    stream: S,
+    capacity: usize,
+    // set once the inner stream closes, after the final batch is returned.  the stream is not polled again
+    closed: bool,
+}
+
+impl<S> ChunksStream<S>
+where
+    S: Stream,
+{
+    #[track_caller]
+    pub fn new(stream: S, capacity: usize) -> Self {
+        assert!(capacity > 0, "the chunk capacity must be greater than zero");
+
+        Self {
+            stream,
+            capacity,
+            closed: false,
+        }
+    }
+}
+
+impl<S> Stream for ChunksStream<S>
+where
+    S: Stream,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        // the batch only holds items which are ready within this call, so nothing is buffered between calls
+        let mut batch = Vec::new();
+        while batch.len() < *this.capacity {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => batch.push(value),
+                PollRecv::Pending => break,
+                PollRecv::Closed => {
+                    *this.closed = true;
+                    break;
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            return PollRecv::Ready(batch);
+        }
+
+        if *this.closed {
+            PollRecv::Closed
+        } else {
+            PollRecv::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        let (lower, upper) = self.stream.size_hint();
+
+        // ready items may be split into more batches, if the stream is pending between them
+        (lower.div_ceil(self.capacity), upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::ChunksStream;
+
+    #[test]
+    fn chunks() {
+        let source = from_iter(vec![1, 2, 3, 4, 5]);
+        let mut chunks = ChunksStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((3, Some(5)), chunks.size_hint());
+        assert_eq!(
+            PollRecv::Ready(vec![1, 2]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(vec![3, 4]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(vec![5]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chunks).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn partial_batch_on_pending() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Ready(3),
+        ]);
+        let mut chunks = ChunksStream::new(source, 4);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(vec![1]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut chunks).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready(vec![2, 3]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chunks).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn flushes_partial_batch_on_close() {
+        let polls = Cell::new(0);
+        let source = from_iter(
+            vec![1, 2, 3]
+                .into_iter()
+                .inspect(|_| polls.set(polls.get() + 1)),
+        );
+        let mut chunks = ChunksStream::new(source, 4);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(vec![1, 2, 3]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+        assert_eq!((0, Some(0)), chunks.size_hint());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chunks).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chunks).poll_recv(&mut cx));
+        assert_eq!(3, polls.get());
+    }
+
+    #[test]
+    fn forward_closed() {
+        let mut chunks = ChunksStream::new(closed::<usize>(), 4);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chunks).poll_recv(&mut cx));
+    }
+
+    #[test]
+    #[should_panic(expected = "the chunk capacity must be greater than zero")]
+    fn zero_capacity() {
+        let _ = ChunksStream::new(closed::<usize>(), 0);
+    }
+
+    #[test]
+    fn registers_waker() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut chunks = ChunksStream::new(rx, 4);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut chunks).poll_recv(&mut cx));
+
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready(vec![1, 2]),
+            Pin::new(&mut chunks).poll_recv(&mut cx)
+        );
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chunks).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2, 3]));
+        let mut chunks = Box::pin(source.chunks(2));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(vec![1, 2]),
+            chunks.as_mut().poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Ready(vec![3]), chunks.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, chunks.as_mut().poll_recv(&mut cx));
+    }
+}
@@ -0,0 +1,268 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::collect](./trait.Stream.html#method.collect), which collects the items of a
+/// stream.
+///
+/// Resolves to the collection once the stream is closed.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct CollectFuture<S, C> {
+    #[pin]
+    stream: S,
+    // taken when the future completes
+    collection: Option<C>,
+}
+
+impl<S, C> CollectFuture<S, C>
+where
+    S: Stream,
+    C: Default + Extend<S::Item>,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            collection: Some(C::default()),
+        }
+    }
+}
+
+impl<S, C> Future for CollectFuture<S, C>
+where
+    S: Stream,
+    C: Default + Extend<S::Item>,
+{
+    type Output = C;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let collection = this
+            .collection
+            .as_mut()
+            .expect("CollectFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        // the ready items are extended at once, so the collection can reserve space using the size hint
+        let mut ready = ReadyItems {
+            stream: this.stream,
+            cx: &mut cx,
+            closed: None,
+        };
+        collection.extend(&mut ready);
+
+        // an Extend impl may stop before the iterator is exhausted.  the next item is passed on its own, so each
+        // iteration makes progress
+        while ready.closed.is_none() {
+            match ready.next() {
+                Some(item) => collection.extend(Some(item)),
+                None => break,
+            }
+
+            collection.extend(&mut ready);
+        }
+
+        match ready.closed {
+            Some(true) => Poll::Ready(this.collection.take().unwrap()),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+// an iterator over the items which are ready, which stops once the stream is pending or closed
+struct ReadyItems<'a, 'c, S> {
+    stream: Pin<&'a mut S>,
+    cx: &'a mut crate::Context<'c>,
+    // set to true if the stream was closed, or false if it was pending
+    closed: Option<bool>,
+}
+
+impl<'a, 'c, S> Iterator for ReadyItems<'a, 'c, S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.closed.is_some() {
+            return None;
+        }
+
+        match self.stream.as_mut().poll_recv(self.cx) {
+            PollRecv::Ready(item) => Some(item),
+            PollRecv::Pending => {
+                self.closed = Some(false);
+                None
+            }
+            PollRecv::Closed => {
+                self.closed = Some(true);
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed.is_some() {
+            return (0, Some(0));
+        }
+
+        // items beyond the lower bound may be pending
+        (self.stream.size_hint().0, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_poll_iter},
+    };
+
+    use super::CollectFuture;
+
+    #[test]
+    fn collect_vec() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+        ]);
+        let mut collect = CollectFuture::<_, Vec<_>>::new(stream);
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut collect).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Ready(vec![1, 2, 3]),
+            Pin::new(&mut collect).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn collect_hash_set() {
+        let (mut tx, rx) = mpsc::channel(4);
+        for i in [1, 2, 1, 3].iter().copied() {
+            tx.try_send(i).unwrap();
+        }
+        drop(tx);
+
+        let mut collect = CollectFuture::<_, HashSet<_>>::new(rx);
+        let expected: HashSet<_> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(
+            Poll::Ready(expected),
+            Pin::new(&mut collect).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn collect_buffered() {
+        let (mut tx, rx) = mpsc::channel(8);
+        for i in 0..5 {
+            tx.try_send(i).unwrap();
+        }
+        drop(tx);
+
+        let mut collect = CollectFuture::<_, Vec<_>>::new(rx);
+        match Pin::new(&mut collect).poll(&mut noop_context()) {
+            Poll::Ready(vec) => assert_eq!(vec![0, 1, 2, 3, 4], vec),
+            Poll::Pending => panic!("the channel is closed"),
+        }
+    }
+
+    // takes at most two items from each iterator it is extended with
+    #[derive(Default)]
+    struct Partial(Vec<usize>);
+
+    impl Extend<usize> for Partial {
+        fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+            self.0.extend(iter.into_iter().take(2));
+        }
+    }
+
+    #[test]
+    fn collect_partial_extend() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Ready(2),
+            PollRecv::Ready(3),
+            PollRecv::Ready(4),
+            PollRecv::Ready(5),
+            PollRecv::Pending,
+            PollRecv::Ready(6),
+        ]);
+        let mut collect = CollectFuture::<_, Partial>::new(stream);
+
+        assert!(Pin::new(&mut collect)
+            .poll(&mut noop_context())
+            .is_pending());
+        match Pin::new(&mut collect).poll(&mut noop_context()) {
+            Poll::Ready(partial) => assert_eq!(vec![1, 2, 3, 4, 5, 6], partial.0),
+            Poll::Pending => panic!("the stream is closed"),
+        }
+    }
+
+    #[test]
+    fn collect_closed() {
+        let mut collect = CollectFuture::<_, Vec<usize>>::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(vec![]),
+            Pin::new(&mut collect).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn registers_waker() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut collect = CollectFuture::<_, Vec<_>>::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut collect).poll(&mut cx));
+
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        drop(tx);
+        assert_eq!(Poll::Ready(vec![1]), Pin::new(&mut collect).poll(&mut cx));
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let mut collect = CollectFuture::<_, Vec<usize>>::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(vec![]),
+            Pin::new(&mut collect).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut collect).poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn collect_borrowed() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+        for i in 1..=3 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        // the receiver is borrowed, and the rest of the items can be collected later
+        let first: Vec<_> = (&mut rx).take(2).collect().await;
+        assert_eq!(vec![1, 2], first);
+
+        let rest: Vec<_> = rx.collect().await;
+        assert_eq!(vec![3], rest);
+    }
+}
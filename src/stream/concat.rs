@@ -0,0 +1,148 @@
+use std::{iter::Fuse, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ConcatStream<I>
+where
+    I: Iterator,
+{
+    iter: Fuse<I>,
+    #[pin]
+    current: Option<I::Item>,
+}
+
+impl<I> ConcatStream<I>
+where
+    I: Iterator,
+    I::Item: Stream,
+{
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter: iter.fuse(),
+            current: None,
+        }
+    }
+}
+
+impl<I> Stream for ConcatStream<I>
+where
+    I: Iterator,
+    I::Item: Stream,
+{
+    type Item = <I::Item as Stream>::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(stream) = this.current.as_mut().as_pin_mut() {
+                match stream.poll_recv(cx) {
+                    PollRecv::Ready(v) => return PollRecv::Ready(v),
+                    PollRecv::Pending => return PollRecv::Pending,
+                    PollRecv::Closed => this.current.set(None),
+                }
+            }
+
+            // the next stream is only constructed once the previous stream is closed
+            match this.iter.next() {
+                Some(stream) => this.current.set(Some(stream)),
+                None => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = match &self.current {
+            Some(stream) => stream.size_hint(),
+            None => (0, Some(0)),
+        };
+
+        // the remaining streams have not been constructed, so only an exhausted iterator bounds the length
+        match self.iter.size_hint().1 {
+            Some(0) => (lower, upper),
+            _ => (lower, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::ConcatStream;
+
+    #[test]
+    fn simple() {
+        let streams = vec![
+            from_iter(vec![1, 2]),
+            from_iter(vec![3]),
+            from_iter(vec![4, 5]),
+        ];
+        let mut concat = ConcatStream::new(streams.into_iter());
+
+        let mut cx = Context::empty();
+
+        for i in 1..=5 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut concat).poll_recv(&mut cx));
+        }
+        assert_eq!(PollRecv::Closed, Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut concat).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn skips_empty() {
+        let streams = vec![from_iter(vec![1]), from_iter(vec![]), from_iter(vec![2])];
+        let mut concat = ConcatStream::new(streams.into_iter());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut concat).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn waits_for_pending() {
+        let streams = vec![
+            from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]),
+            from_poll_iter(vec![PollRecv::Ready(2)]),
+        ];
+        let mut concat = ConcatStream::new(streams.into_iter());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut concat).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn lazy_construction() {
+        use std::cell::Cell;
+
+        let created = Cell::new(0);
+        let streams = (0..3).map(|i| {
+            created.set(created.get() + 1);
+            from_iter(vec![i])
+        });
+        let mut concat = ConcatStream::new(streams);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(0, created.get());
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(1, created.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut concat).poll_recv(&mut cx));
+        assert_eq!(2, created.get());
+    }
+}
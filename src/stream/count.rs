@@ -0,0 +1,237 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::count](./trait.Stream.html#method.count), which counts the items of a stream.
+///
+/// Resolves to the number of items once the stream is closed.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct CountFuture<S> {
+    #[pin]
+    stream: S,
+    // taken when the future completes
+    count: Option<usize>,
+}
+
+impl<S> CountFuture<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            count: Some(0),
+        }
+    }
+}
+
+impl<S> Future for CountFuture<S>
+where
+    S: Stream,
+{
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let count = this
+            .count
+            .as_mut()
+            .expect("CountFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(_) => *count += 1,
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => return Poll::Ready(this.count.take().unwrap()),
+            }
+        }
+    }
+}
+
+/// A future returned by [Stream::last](./trait.Stream.html#method.last), which receives the last item of a stream.
+///
+/// Resolves once the stream is closed, to the last item, or `None` if the stream had no items.  Panics if polled
+/// after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct LastFuture<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    last: Option<S::Item>,
+    complete: bool,
+}
+
+impl<S> LastFuture<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            last: None,
+            complete: false,
+        }
+    }
+}
+
+impl<S> Future for LastFuture<S>
+where
+    S: Stream,
+{
+    type Output = Option<S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "LastFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => *this.last = Some(item),
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(this.last.take());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::noop_context;
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter},
+    };
+
+    use super::{CountFuture, LastFuture};
+
+    #[test]
+    fn count_empty() {
+        let mut count = CountFuture::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(0),
+            Pin::new(&mut count).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn count_single() {
+        let mut count = CountFuture::new(from_iter(vec![1]));
+
+        assert_eq!(
+            Poll::Ready(1),
+            Pin::new(&mut count).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn count_multiple() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+        ]);
+        let mut count = CountFuture::new(stream);
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut count).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Ready(3),
+            Pin::new(&mut count).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn count_poll_after_completion() {
+        let mut count = CountFuture::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(0),
+            Pin::new(&mut count).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut count).poll(&mut noop_context());
+    }
+
+    #[test]
+    fn last_empty() {
+        let mut last = LastFuture::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut last).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn last_single() {
+        let mut last = LastFuture::new(from_iter(vec![1]));
+
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut last).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn last_multiple() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Ready(3),
+        ]);
+        let mut last = LastFuture::new(stream);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut last).poll(&mut noop_context()));
+        assert_eq!(
+            Poll::Ready(Some(3)),
+            Pin::new(&mut last).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn last_poll_after_completion() {
+        let mut last = LastFuture::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut last).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut last).poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn count_and_last_channel() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+        for i in 1..=4 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(2, (&mut rx).take(2).count().await);
+        assert_eq!(Some(4), rx.last().await);
+    }
+}
@@ -0,0 +1,180 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct CycleStream<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    recorded: Vec<S::Item>,
+    // the index of the next replayed item, set once the inner stream is closed
+    replay: Option<usize>,
+}
+
+impl<S> CycleStream<S>
+where
+    S: Stream,
+    S::Item: Clone,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            recorded: Vec::new(),
+            replay: None,
+        }
+    }
+}
+
+impl<S> Stream for CycleStream<S>
+where
+    S: Stream,
+    S::Item: Clone,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if this.replay.is_none() {
+            match this.stream.poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    this.recorded.push(item.clone());
+                    return PollRecv::Ready(item);
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => *this.replay = Some(0),
+            }
+        }
+
+        // a stream which closed without items is closed, rather than replaying an empty sequence
+        if this.recorded.is_empty() {
+            return PollRecv::Closed;
+        }
+
+        let index = this.replay.as_mut().unwrap();
+        let item = this.recorded[*index].clone();
+        *index = (*index + 1) % this.recorded.len();
+
+        PollRecv::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.replay.is_some() && self.recorded.is_empty() {
+            return (0, Some(0));
+        }
+
+        if !self.recorded.is_empty() || self.stream.size_hint().0 > 0 {
+            return (usize::MAX, None);
+        }
+
+        (0, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::CycleStream;
+
+    #[test]
+    fn cycle() {
+        let source = from_iter(vec![1, 2, 3]);
+        let mut cycle = CycleStream::new(source);
+
+        let mut cx = Context::empty();
+
+        for _ in 0..3 {
+            assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Ready(2), Pin::new(&mut cycle).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Ready(3), Pin::new(&mut cycle).poll_recv(&mut cx));
+        }
+
+        assert_eq!((usize::MAX, None), cycle.size_hint());
+    }
+
+    #[test]
+    fn single_item() {
+        let source = from_iter(vec![1]);
+        let mut cycle = CycleStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn empty_closed() {
+        let source = panic_after_close(closed::<usize>());
+        let mut cycle = CycleStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), cycle.size_hint());
+    }
+
+    #[test]
+    fn pending_while_recording() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut cycle = CycleStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut cycle).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_while_recording() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut cycle = CycleStream::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut cycle).poll_recv(&mut cx));
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut cycle).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut cycle = Box::pin(source.cycle());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), cycle.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), cycle.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), cycle.as_mut().poll_recv(&mut cx));
+    }
+}
@@ -0,0 +1,277 @@
+use std::{pin::Pin, task::Poll, time::Duration};
+
+use crate::coop::BUDGET;
+use crate::stream::{PollRecv, Stream};
+use crate::time::{Delay, Sleep};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct DebounceStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    #[pin]
+    stream: S,
+    quiet: Duration,
+    delay: Delay<T>,
+    // the most recent item, which is produced once the stream is quiet
+    latest: Option<S::Item>,
+    closed: bool,
+}
+
+impl<S, T> DebounceStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    pub fn new(stream: S, quiet: Duration, timer: T) -> Self {
+        Self {
+            stream,
+            quiet,
+            delay: Delay::new(timer, quiet),
+            latest: None,
+            closed: false,
+        }
+    }
+}
+
+impl<S, T> Stream for DebounceStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // the buffered item is flushed without waiting once the source is closed
+        if *this.closed {
+            return match this.latest.take() {
+                Some(item) => PollRecv::Ready(item),
+                None => PollRecv::Closed,
+            };
+        }
+
+        let mut drained = 0;
+        loop {
+            // a source which is always ready is drained in batches, and the task yields between them
+            if drained == BUDGET {
+                if let Some(waker) = cx.waker() {
+                    waker.wake_by_ref();
+                }
+
+                return PollRecv::Pending;
+            }
+
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    *this.latest = Some(item);
+                    this.delay.reset(*this.quiet);
+                    drained += 1;
+                }
+                PollRecv::Pending => break,
+                PollRecv::Closed => {
+                    *this.closed = true;
+
+                    return match this.latest.take() {
+                        Some(item) => PollRecv::Ready(item),
+                        None => PollRecv::Closed,
+                    };
+                }
+            }
+        }
+
+        if this.latest.is_none() {
+            return PollRecv::Pending;
+        }
+
+        match this.delay.poll_elapsed(cx) {
+            Poll::Ready(()) => PollRecv::Ready(this.latest.take().unwrap()),
+            Poll::Pending => PollRecv::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let latest = if self.latest.is_some() { 1 } else { 0 };
+        if self.closed {
+            return (latest, Some(latest));
+        }
+
+        // any number of items may be replaced before the stream is quiet
+        let lower = if latest > 0 || self.stream.size_hint().0 > 0 {
+            1
+        } else {
+            0
+        };
+
+        let upper = self
+            .stream
+            .size_hint()
+            .1
+            .and_then(|upper| upper.checked_add(latest));
+
+        (lower, upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::{
+            stream::{closed, from_iter, ready},
+            time::MockClock,
+        },
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::DebounceStream;
+
+    #[test]
+    fn yields_latest_when_quiet() {
+        let clock = MockClock::new();
+        let (mut tx, rx) = crate::mpsc::channel(8);
+        let mut stream = DebounceStream::new(rx, Duration::from_secs(1), clock.clone());
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(500));
+        tx.try_send(3).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        // the timer restarted when 3 replaced 2
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        let woken = count.get();
+        clock.advance(Duration::from_millis(500));
+        assert!(count.get() > woken);
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn flushes_on_close() {
+        let clock = MockClock::new();
+        let mut stream = DebounceStream::new(from_iter(vec![1, 2]), Duration::from_secs(1), clock);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_without_items() {
+        let clock = MockClock::new();
+        let mut stream = DebounceStream::new(closed::<usize>(), Duration::from_secs(1), clock);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pending_without_items() {
+        let clock = MockClock::new();
+        let (_tx, rx) = crate::mpsc::channel::<usize>(8);
+        let mut stream = DebounceStream::new(rx, Duration::from_secs(1), clock.clone());
+
+        let mut cx = Context::empty();
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn yields_to_ready_source() {
+        let clock = MockClock::new();
+        let mut stream = DebounceStream::new(ready(1), Duration::from_secs(1), clock.clone());
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(1, count.get());
+
+        // the source is never quiet, so the latest item is never produced
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(2, count.get());
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut Context::empty())
+        );
+    }
+
+    #[test]
+    fn size_hint() {
+        let clock = MockClock::new();
+        let stream = DebounceStream::new(from_iter(vec![1, 2]), Duration::from_secs(1), clock);
+
+        assert_eq!((1, Some(2)), stream.size_hint());
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::time::{pause, sleep, Instant};
+
+    use crate::{mpsc, sink::Sink, stream::Stream};
+
+    #[tokio::test]
+    async fn rapid_sends_deliver_last() {
+        pause();
+
+        let (mut tx, rx) = mpsc::channel(8);
+        let mut rx = rx.debounce(Duration::from_millis(100));
+
+        tokio::spawn(async move {
+            for i in 1..=5usize {
+                tx.send(i).await.expect("send failed");
+                sleep(Duration::from_millis(10)).await;
+            }
+
+            sleep(Duration::from_millis(500)).await;
+        });
+
+        let start = Instant::now();
+        assert_eq!(Some(5), rx.recv().await);
+        assert!(start.elapsed() >= Duration::from_millis(140));
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn flushes_on_close() {
+        pause();
+
+        let (mut tx, rx) = mpsc::channel(8);
+        let mut rx = rx.debounce(Duration::from_secs(1));
+
+        for i in 1..=5usize {
+            tx.send(i).await.expect("send failed");
+        }
+        drop(tx);
+
+        let start = Instant::now();
+        assert_eq!(Some(5), rx.recv().await);
+        assert_eq!(Duration::from_secs(0), start.elapsed());
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -0,0 +1,260 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct DedupStream<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    // a clone of the last item which was returned
+    last: Option<S::Item>,
+}
+
+impl<S> DedupStream<S>
+where
+    S: Stream,
+    S::Item: PartialEq + Clone,
+{
+    pub fn new(stream: S) -> Self {
+        Self { stream, last: None }
+    }
+}
+
+impl<S> Stream for DedupStream<S>
+where
+    S: Stream,
+    S::Item: PartialEq + Clone,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // duplicates are discarded within the call, so pending is only returned if the inner stream is pending
+        loop {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    if this.last.as_ref() == Some(&item) {
+                        continue;
+                    }
+
+                    *this.last = Some(item.clone());
+                    return PollRecv::Ready(item);
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        let lower = if self.last.is_none() { lower.min(1) } else { 0 };
+
+        (lower, upper)
+    }
+}
+
+#[pin_project]
+pub struct DedupByKeyStream<S, F, K> {
+    #[pin]
+    stream: S,
+    key: F,
+    // the key of the last item which was returned
+    last: Option<K>,
+}
+
+impl<S, F, K> DedupByKeyStream<S, F, K>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> K,
+    K: PartialEq,
+{
+    pub fn new(stream: S, key: F) -> Self {
+        Self {
+            stream,
+            key,
+            last: None,
+        }
+    }
+}
+
+impl<S, F, K> Stream for DedupByKeyStream<S, F, K>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> K,
+    K: PartialEq,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        loop {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    let key = (this.key)(&item);
+                    if this.last.as_ref() == Some(&key) {
+                        continue;
+                    }
+
+                    *this.last = Some(key);
+                    return PollRecv::Ready(item);
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        let lower = if self.last.is_none() { lower.min(1) } else { 0 };
+
+        (lower, upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{DedupByKeyStream, DedupStream};
+
+    #[test]
+    fn dedup() {
+        let source = from_iter(vec![1, 1, 2, 2, 2, 1, 3, 3]);
+        let mut dedup = DedupStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((1, Some(8)), dedup.size_hint());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut dedup).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn run_straddles_pending() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(1),
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Ready(3),
+        ]);
+        let mut dedup = DedupStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut dedup).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_during_run() {
+        let source = from_iter(vec![1, 1, 1]);
+        let mut dedup = DedupStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut dedup).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_during_run() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut dedup = DedupStream::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send(1).unwrap();
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut dedup).poll_recv(&mut cx));
+
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut dedup).poll_recv(&mut cx));
+
+        tx.try_send(2).unwrap();
+        assert_eq!(2, count.get());
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut dedup).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn dedup_by_key() {
+        let source = from_iter(vec![(1, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]);
+        let mut dedup = DedupByKeyStream::new(source, |(key, _)| *key);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready((1, 'a')),
+            Pin::new(&mut dedup).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((2, 'c')),
+            Pin::new(&mut dedup).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((1, 'd')),
+            Pin::new(&mut dedup).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut dedup).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn dedup_by_key_straddles_pending() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(10),
+            PollRecv::Pending,
+            PollRecv::Ready(11),
+            PollRecv::Ready(20),
+        ]);
+        let mut dedup = DedupByKeyStream::new(source, |i| *i / 10);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(10), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(20), Pin::new(&mut dedup).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut dedup).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 1, 2]));
+        let mut dedup = Box::pin(source.dedup());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), dedup.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), dedup.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, dedup.as_mut().poll_recv(&mut cx));
+    }
+}
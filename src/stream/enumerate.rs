@@ -0,0 +1,165 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct EnumerateStream<S> {
+    #[pin]
+    stream: S,
+    // the index of the next ready item
+    count: usize,
+}
+
+impl<S> EnumerateStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self { stream, count: 0 }
+    }
+}
+
+impl<S> Stream for EnumerateStream<S>
+where
+    S: Stream,
+{
+    type Item = (usize, S::Item);
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                let index = *this.count;
+                *this.count += 1;
+                PollRecv::Ready((index, value))
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::EnumerateStream;
+
+    #[test]
+    fn enumerate() {
+        let source = from_iter(vec!['a', 'b']);
+        let mut enumerate = EnumerateStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((2, Some(2)), enumerate.size_hint());
+        assert_eq!(
+            PollRecv::Ready((0, 'a')),
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((1, 'b')),
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed,
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn pending_does_not_advance() {
+        let source = from_poll_iter(vec![
+            PollRecv::Pending,
+            PollRecv::Ready('a'),
+            PollRecv::Pending,
+            PollRecv::Pending,
+            PollRecv::Ready('b'),
+        ]);
+        let mut enumerate = EnumerateStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((0, 'a')),
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((1, 'b')),
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed,
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut enumerate = EnumerateStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed,
+            Pin::new(&mut enumerate).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1]));
+        let mut enumerate = Box::pin(source.enumerate());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready((0, 1)),
+            enumerate.as_mut().poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, enumerate.as_mut().poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn detects_dropped_messages() {
+        use crate::{broadcast, sink::Sink};
+
+        let (mut tx, rx) = broadcast::lossy_fanout_channel(2);
+        for i in 0..3 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        // the oldest message was dropped, so the sequence number is ahead of the index
+        let mut rx = rx.enumerate();
+        assert_eq!(Some((0, 1)), rx.recv().await);
+        assert_eq!(Some((1, 2)), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -10,3 +10,8 @@ pub enum TryRecvError {
     #[error("TryRecvError::Closed")]
     Closed,
 }
+
+/// An error produced by `Stream::timeout`, when the stream did not produce an item within the duration.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("Elapsed")]
+pub struct Elapsed;
@@ -2,16 +2,19 @@ use std::pin::Pin;
 
 use crate::stream::{PollRecv, Stream};
 use crate::Context;
+use pin_project::pin_project;
 
+#[pin_project]
 pub struct FilterStream<From, Filter> {
+    #[pin]
     from: From,
     filter: Filter,
 }
 
 impl<From, Filter> FilterStream<From, Filter>
 where
-    From: Stream + Unpin,
-    Filter: FnMut(&From::Item) -> bool + Unpin,
+    From: Stream,
+    Filter: FnMut(&From::Item) -> bool,
 {
     pub fn new(from: From, filter: Filter) -> Self {
         Self { from, filter }
@@ -20,16 +23,15 @@ where
 
 impl<From, Filter> Stream for FilterStream<From, Filter>
 where
-    From: Stream + Unpin,
-    Filter: FnMut(&From::Item) -> bool + Unpin,
+    From: Stream,
+    Filter: FnMut(&From::Item) -> bool,
 {
     type Item = From::Item;
 
     fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
-        let this = self.get_mut();
+        let mut this = self.project();
         loop {
-            let from = Pin::new(&mut this.from);
-            match from.poll_recv(cx) {
+            match this.from.as_mut().poll_recv(cx) {
                 PollRecv::Ready(value) => {
                     if (this.filter)(&value) {
                         return PollRecv::Ready(value);
@@ -40,6 +42,11 @@ where
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // any item may be filtered out
+        (0, self.from.size_hint().1)
+    }
 }
 
 #[cfg(test)]
@@ -85,4 +92,16 @@ mod tests {
 
         assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
     }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2, 3, 4]));
+        let mut filter = Box::pin(source.filter(|i| i % 2 == 0));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, filter.as_mut().poll_recv(&mut cx));
+    }
 }
@@ -0,0 +1,208 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FilterAsyncStream<S, F, Fut>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    filter: F,
+    // the candidate item, and the predicate future for it.  no item is received while it is in flight
+    item: Option<S::Item>,
+    #[pin]
+    future: Option<Fut>,
+    closed: bool,
+}
+
+impl<S, F, Fut> FilterAsyncStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    pub fn new(stream: S, filter: F) -> Self {
+        Self {
+            stream,
+            filter,
+            item: None,
+            future: None,
+            closed: false,
+        }
+    }
+}
+
+impl<S, F, Fut> Stream for FilterAsyncStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                match cx.with_std(|cx| future.poll(cx)) {
+                    Poll::Ready(accept) => {
+                        this.future.set(None);
+                        let item = this.item.take().unwrap();
+
+                        if accept {
+                            return PollRecv::Ready(item);
+                        }
+
+                        // the rejected item is dropped, and the next item is received
+                        continue;
+                    }
+                    Poll::Pending => return PollRecv::Pending,
+                }
+            }
+
+            if *this.closed {
+                return PollRecv::Closed;
+            }
+
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    this.future.set(Some((this.filter)(&item)));
+                    *this.item = Some(item);
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => {
+                    *this.closed = true;
+                    return PollRecv::Closed;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let in_flight = if self.item.is_some() { 1 } else { 0 };
+        let upper = if self.closed {
+            Some(0)
+        } else {
+            self.stream.size_hint().1
+        };
+
+        // any item may be filtered out
+        (0, upper.and_then(|upper| upper.checked_add(in_flight)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, rc::Rc};
+
+    use crate::test::{future::pending_once, stream::*};
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::FilterAsyncStream;
+
+    #[test]
+    fn filter_async() {
+        let source = from_iter(vec![1, 2, 3, 4]);
+        let mut filter = FilterAsyncStream::new(source, |i| pending_once(*i % 2 == 0));
+
+        let mut cx = Context::empty();
+
+        // 1 is rejected after two polls, and the predicate for 2 is pending
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut filter).poll_recv(&mut cx));
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut filter).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn retains_item_while_pending() {
+        let item = Rc::new(1);
+        let source = from_iter(vec![item.clone()]);
+        let mut filter = FilterAsyncStream::new(source, |_| pending_once(true));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(2, Rc::strong_count(&item));
+        assert_eq!((0, Some(1)), filter.size_hint());
+
+        match Pin::new(&mut filter).poll_recv(&mut cx) {
+            PollRecv::Ready(received) => assert!(Rc::ptr_eq(&item, &received)),
+            poll => panic!("unexpected poll: {:?}", poll),
+        }
+    }
+
+    #[test]
+    fn rejected_item_is_dropped() {
+        let item = Rc::new(1);
+        let source = from_iter(vec![item.clone()]);
+        let mut filter = FilterAsyncStream::new(source, |_| pending_once(false));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(1, Rc::strong_count(&item));
+    }
+
+    #[test]
+    fn mixed_ready_predicates() {
+        let source = from_iter(vec![1, 2, 3, 4, 5]);
+        let mut filter = Box::pin(FilterAsyncStream::new(source, |i| {
+            let accept = *i != 2 && *i != 3;
+            async move { accept }
+        }));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(5), filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, filter.as_mut().poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn wakes_with_future() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut filter = FilterAsyncStream::new(rx, |i| pending_once(*i > 1));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        tx.try_send(2).unwrap();
+        assert_eq!(1, count.get());
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(2, count.get());
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut filter).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut filter = Box::pin(source.filter_async(|i| pending_once(*i == 2)));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), filter.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, filter.as_mut().poll_recv(&mut cx));
+    }
+}
@@ -0,0 +1,129 @@
+use std::{marker::PhantomData, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FilterMapStream<From, Map, Into> {
+    #[pin]
+    from: From,
+    map: Map,
+    into: PhantomData<Into>,
+}
+
+impl<From, Map, Into> FilterMapStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    pub fn new(from: From, map: Map) -> Self {
+        Self {
+            from,
+            map,
+            into: PhantomData,
+        }
+    }
+}
+
+impl<From, Map, Into> Stream for FilterMapStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    type Item = Into;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+        loop {
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    if let Some(mapped) = (this.map)(value) {
+                        return PollRecv::Ready(mapped);
+                    }
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // any item may be filtered out
+        (0, self.from.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::FilterMapStream;
+
+    #[test]
+    fn filter_map() {
+        let source = from_iter(vec![1, 2, 3, 4]);
+        let mut filter =
+            FilterMapStream::new(source, |i| if i % 2 == 0 { Some(i * 10) } else { None });
+
+        let mut cx = Context::empty();
+
+        assert_eq!((0, Some(4)), filter.size_hint());
+        assert_eq!(
+            PollRecv::Ready(20),
+            Pin::new(&mut filter).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(40),
+            Pin::new(&mut filter).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut filter).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn all_filtered() {
+        let source = from_iter(vec![1, 3, 5]);
+        let mut filter = FilterMapStream::new(source, |_: usize| None::<usize>);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut filter).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut filter = FilterMapStream::new(source, |i| if i == 2 { Some("two") } else { None });
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut filter).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready("two"),
+            Pin::new(&mut filter).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut filter).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn parse() {
+        let source = from_iter(vec!["1", "x", "3"]);
+        let mut parsed = source.filter_map(|s| s.parse::<usize>().ok());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut parsed).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut parsed).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut parsed).poll_recv(&mut cx));
+    }
+}
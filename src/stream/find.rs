@@ -2,6 +2,7 @@ use std::pin::Pin;
 
 use crate::Context;
 use atomic::{Atomic, Ordering};
+use pin_project::pin_project;
 
 use crate::stream::{PollRecv, Stream};
 
@@ -11,8 +12,10 @@ enum State {
     Closed,
 }
 
+#[pin_project]
 pub struct FindStream<From, Condition> {
     state: Atomic<State>,
+    #[pin]
     from: From,
     condition: Condition,
 }
@@ -33,21 +36,20 @@ where
 
 impl<From, Condition> Stream for FindStream<From, Condition>
 where
-    From: Stream + Unpin,
-    Condition: Fn(&From::Item) -> bool + Unpin,
+    From: Stream,
+    Condition: Fn(&From::Item) -> bool,
 {
     type Item = From::Item;
 
     fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
-        let this = self.get_mut();
+        let mut this = self.project();
 
         if let State::Closed = this.state.load(Ordering::Acquire) {
             return PollRecv::Closed;
         }
 
         loop {
-            let from = Pin::new(&mut this.from);
-            match from.poll_recv(cx) {
+            match this.from.as_mut().poll_recv(cx) {
                 PollRecv::Ready(value) => {
                     if (this.condition)(&value) {
                         this.state.store(State::Closed, Ordering::Release);
@@ -60,6 +62,15 @@ where
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if let State::Closed = self.state.load(Ordering::Acquire) {
+            return (0, Some(0));
+        }
+
+        let upper = self.from.size_hint().1;
+        (0, upper.map(|upper| upper.min(1)))
+    }
 }
 
 #[cfg(test)]
@@ -125,4 +136,15 @@ mod tests {
 
         assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
     }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2, 3]));
+        let mut find = Box::pin(source.find(|i| *i == 2));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), find.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, find.as_mut().poll_recv(&mut cx));
+    }
 }
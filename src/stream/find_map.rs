@@ -0,0 +1,280 @@
+use std::{
+    future::{Future, IntoFuture},
+    marker::PhantomData,
+    pin::Pin,
+    task::Poll,
+};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FindMapStream<From, Map, Into> {
+    #[pin]
+    from: From,
+    map: Map,
+    // set once an item is found, or the stream closes.  the stream is not polled again
+    closed: bool,
+    into: PhantomData<Into>,
+}
+
+impl<From, Map, Into> FindMapStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    pub fn new(from: From, map: Map) -> Self {
+        Self {
+            from,
+            map,
+            closed: false,
+            into: PhantomData,
+        }
+    }
+}
+
+impl<From, Map, Into> Stream for FindMapStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    type Item = Into;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        // items which are not matched are discarded within the call
+        loop {
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    if let Some(mapped) = (this.map)(value) {
+                        *this.closed = true;
+                        return PollRecv::Ready(mapped);
+                    }
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => {
+                    *this.closed = true;
+                    return PollRecv::Closed;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        let upper = self.from.size_hint().1;
+        (0, upper.map(|upper| upper.min(1)))
+    }
+}
+
+impl<From, Map, Into> IntoFuture for FindMapStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    type Output = Option<Into>;
+    type IntoFuture = FindMapFuture<From, Map, Into>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        FindMapFuture {
+            stream: self,
+            complete: false,
+        }
+    }
+}
+
+/// A future which resolves to the first item found by [Stream::find_map](./trait.Stream.html#method.find_map), or
+/// `None` if the stream closes without a match.
+///
+/// Created by awaiting the stream returned by `find_map`.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct FindMapFuture<From, Map, Into> {
+    #[pin]
+    stream: FindMapStream<From, Map, Into>,
+    complete: bool,
+}
+
+impl<From, Map, Into> Future for FindMapFuture<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    type Output = Option<Into>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        assert!(!*this.complete, "FindMapFuture polled after completion");
+
+        let mut cx: Context<'_> = cx.into();
+        let output = match this.stream.poll_recv(&mut cx) {
+            PollRecv::Ready(value) => Some(value),
+            PollRecv::Pending => return Poll::Pending,
+            PollRecv::Closed => None,
+        };
+
+        *this.complete = true;
+        Poll::Ready(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::{Future, IntoFuture},
+        pin::Pin,
+        task::Poll,
+    };
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use super::FindMapStream;
+
+    fn parse(s: &str) -> Option<usize> {
+        s.parse().ok()
+    }
+
+    #[test]
+    fn match_first_item() {
+        let source = panic_after_close(from_iter(vec!["1", "2"]));
+        let mut find = FindMapStream::new(source, parse);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), find.size_hint());
+    }
+
+    #[test]
+    fn match_after_non_matches() {
+        let source = from_iter(vec!["a", "b", "c", "4", "5"]);
+        let mut find = FindMapStream::new(source, parse);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((0, Some(1)), find.size_hint());
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_without_match() {
+        let source = panic_after_close(from_iter(vec!["a", "b"]));
+        let mut find = FindMapStream::new(source, parse);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pending_between_non_matches() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready("a"),
+            PollRecv::Pending,
+            PollRecv::Ready("b"),
+            PollRecv::Ready("3"),
+        ]);
+        let mut find = FindMapStream::new(source, parse);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut find).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn future() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready("a"),
+            PollRecv::Pending,
+            PollRecv::Ready("2"),
+        ]);
+        let mut future = FindMapStream::new(source, parse).into_future();
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut future).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Ready(Some(2)),
+            Pin::new(&mut future).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn future_without_match() {
+        let mut future = FindMapStream::new(from_iter(vec!["a"]), parse).into_future();
+
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut future).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn future_polled_after_completion() {
+        let mut future = FindMapStream::new(from_iter(vec!["1"]), parse).into_future();
+
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut future).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut future).poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn await_find_map() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+
+        tx.send("a").await.unwrap();
+        tx.send("2").await.unwrap();
+        assert_eq!(Some(2), rx.find_map(parse).await);
+    }
+
+    #[test]
+    fn registers_waker() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut find = FindMapStream::new(rx, parse);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send("a").unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut find).poll_recv(&mut cx));
+
+        tx.try_send("1").unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec!["a", "1"]));
+        let mut find = Box::pin(source.find_map(parse));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), find.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, find.as_mut().poll_recv(&mut cx));
+    }
+}
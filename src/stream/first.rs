@@ -0,0 +1,194 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::first](./trait.Stream.html#method.first), which receives the first item of a
+/// stream.
+///
+/// Resolves to the first item, or `None` if the stream closes without an item.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct FirstFuture<S> {
+    #[pin]
+    stream: S,
+    complete: bool,
+}
+
+impl<S> FirstFuture<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            complete: false,
+        }
+    }
+}
+
+impl<S> Future for FirstFuture<S>
+where
+    S: Stream,
+{
+    type Output = Option<S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        assert!(!*this.complete, "FirstFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        let output = match this.stream.poll_recv(&mut cx) {
+            PollRecv::Ready(item) => Some(item),
+            PollRecv::Pending => return Poll::Pending,
+            PollRecv::Closed => None,
+        };
+
+        *this.complete = true;
+        Poll::Ready(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter, not_unpin, pending},
+        watch,
+    };
+
+    use super::FirstFuture;
+
+    #[test]
+    fn first_empty() {
+        let mut first = FirstFuture::new(closed::<usize>());
+
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut first).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn first_multiple() {
+        let mut first = FirstFuture::new(from_iter(vec![1, 2, 3]));
+
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut first).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn first_pending() {
+        let stream = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut first = FirstFuture::new(stream);
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut first).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut first).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn first_never() {
+        let mut first = FirstFuture::new(pending::<usize>());
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut first).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn first_poll_after_completion() {
+        let mut first = FirstFuture::new(from_iter(vec![1]));
+
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut first).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut first).poll(&mut noop_context());
+    }
+
+    #[test]
+    fn registers_waker() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut first = FirstFuture::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut first).poll(&mut cx));
+        assert_eq!(0, count.get());
+
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut first).poll(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_closed() {
+        let (tx, rx) = mpsc::channel::<usize>(4);
+        let mut first = FirstFuture::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut first).poll(&mut cx));
+
+        drop(tx);
+        assert_eq!(1, count.get());
+        assert_eq!(Poll::Ready(None), Pin::new(&mut first).poll(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let mut first = Box::pin(not_unpin(from_iter(vec![1, 2])).first());
+
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            first.as_mut().poll(&mut noop_context())
+        );
+    }
+
+    #[tokio::test]
+    async fn first_map_filter() {
+        let stream = from_iter(vec![1, 2, 3, 4])
+            .filter(|i| i % 2 == 0)
+            .map(|i| i * 10);
+
+        assert_eq!(Some(20), stream.first().await);
+    }
+
+    #[tokio::test]
+    async fn first_filter_closed() {
+        let stream = from_iter(vec![1, 3]).filter(|i| i % 2 == 0);
+
+        assert_eq!(None, stream.first().await);
+    }
+
+    #[tokio::test]
+    async fn first_watch_filter() {
+        let (mut tx, rx) = watch::channel_with(1);
+
+        let join = tokio::spawn(async move { rx.filter(|i| *i >= 3).first().await });
+
+        tx.send(2).await.unwrap();
+        tx.send(3).await.unwrap();
+        assert_eq!(Some(3), join.await.unwrap());
+    }
+}
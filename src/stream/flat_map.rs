@@ -0,0 +1,244 @@
+use std::pin::Pin;
+
+use crate::coop::BUDGET;
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FlatMapStream<S, F, Inner> {
+    #[pin]
+    outer: S,
+    f: F,
+    // the inner stream which is forwarded until it closes.  the outer stream is not polled while it is set
+    #[pin]
+    inner: Option<Inner>,
+    outer_closed: bool,
+}
+
+impl<S, F, Inner> FlatMapStream<S, F, Inner>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Inner,
+    Inner: Stream,
+{
+    pub fn new(outer: S, f: F) -> Self {
+        Self {
+            outer,
+            f,
+            inner: None,
+            outer_closed: false,
+        }
+    }
+}
+
+impl<S, F, Inner> Stream for FlatMapStream<S, F, Inner>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Inner,
+    Inner: Stream,
+{
+    type Item = Inner::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        let mut opened = 0;
+        loop {
+            if let Some(inner) = this.inner.as_mut().as_pin_mut() {
+                match inner.poll_recv(cx) {
+                    PollRecv::Ready(value) => return PollRecv::Ready(value),
+                    PollRecv::Pending => return PollRecv::Pending,
+                    PollRecv::Closed => this.inner.set(None),
+                }
+            }
+
+            if *this.outer_closed {
+                return PollRecv::Closed;
+            }
+
+            // empty inner streams are skipped in batches, and the task yields between them
+            if opened == BUDGET {
+                if let Some(waker) = cx.waker() {
+                    waker.wake_by_ref();
+                }
+
+                return PollRecv::Pending;
+            }
+
+            match this.outer.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    this.inner.set(Some((this.f)(item)));
+                    opened += 1;
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => {
+                    *this.outer_closed = true;
+                    return PollRecv::Closed;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = match &self.inner {
+            Some(inner) => inner.size_hint(),
+            None => (0, Some(0)),
+        };
+
+        // later inner streams may yield any number of items
+        if self.outer_closed {
+            (lower, upper)
+        } else {
+            (lower, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::FlatMapStream;
+
+    #[test]
+    fn flat_map() {
+        let source = from_iter(vec![1, 2, 3]);
+        let mut flat_map = FlatMapStream::new(source, |i| from_iter(vec![i; i]));
+
+        let mut cx = Context::empty();
+
+        for expected in [1, 2, 2, 3, 3, 3] {
+            assert_eq!(
+                PollRecv::Ready(expected),
+                Pin::new(&mut flat_map).poll_recv(&mut cx)
+            );
+        }
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flat_map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flat_map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn empty_inner_is_skipped() {
+        let source = from_iter(vec![0, 0, 2, 0]);
+        let mut flat_map = FlatMapStream::new(source, |i| from_iter(vec![i; i]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flat_map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn all_inner_empty() {
+        let source = from_iter(vec![1, 2]);
+        let mut flat_map = FlatMapStream::new(source, |_| closed::<usize>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flat_map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pending_inner() {
+        let source = from_iter(vec![1, 2]);
+        let mut flat_map = FlatMapStream::new(source, |i| {
+            from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(i)])
+        });
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flat_map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_with_outer() {
+        let (mut outer_tx, outer_rx) = mpsc::channel(4);
+        let mut flat_map = FlatMapStream::new(outer_rx, |batch: Vec<usize>| from_iter(batch));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!((0, None), flat_map.size_hint());
+
+        outer_tx.try_send(vec![]).unwrap();
+        outer_tx.try_send(vec![1, 2]).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+
+        drop(outer_tx);
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut flat_map).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flat_map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1]));
+        let mut flat_map = Box::pin(source.flat_map(|i| not_unpin(from_iter(vec![i, i + 1]))));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), flat_map.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), flat_map.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, flat_map.as_mut().poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn yields_to_empty_inner_streams() {
+        let mut stream = FlatMapStream::new(ready(0), |_| closed::<usize>());
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(1, count.get());
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut Context::empty())
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::pin::Pin;
+
+use crate::coop::BUDGET;
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FlattenStream<S>
+where
+    S: Stream,
+{
+    #[pin]
+    outer: S,
+    // the inner stream which is forwarded until it closes.  the outer stream is not polled while it is set
+    #[pin]
+    inner: Option<S::Item>,
+    outer_closed: bool,
+}
+
+impl<S> FlattenStream<S>
+where
+    S: Stream,
+    S::Item: Stream,
+{
+    pub fn new(outer: S) -> Self {
+        Self {
+            outer,
+            inner: None,
+            outer_closed: false,
+        }
+    }
+}
+
+impl<S> Stream for FlattenStream<S>
+where
+    S: Stream,
+    S::Item: Stream,
+{
+    type Item = <S::Item as Stream>::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        let mut opened = 0;
+        loop {
+            if let Some(inner) = this.inner.as_mut().as_pin_mut() {
+                match inner.poll_recv(cx) {
+                    PollRecv::Ready(value) => return PollRecv::Ready(value),
+                    PollRecv::Pending => return PollRecv::Pending,
+                    PollRecv::Closed => this.inner.set(None),
+                }
+            }
+
+            if *this.outer_closed {
+                return PollRecv::Closed;
+            }
+
+            // empty inner streams are skipped in batches, and the task yields between them
+            if opened == BUDGET {
+                if let Some(waker) = cx.waker() {
+                    waker.wake_by_ref();
+                }
+
+                return PollRecv::Pending;
+            }
+
+            match this.outer.as_mut().poll_recv(cx) {
+                PollRecv::Ready(inner) => {
+                    this.inner.set(Some(inner));
+                    opened += 1;
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => {
+                    *this.outer_closed = true;
+                    return PollRecv::Closed;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = match &self.inner {
+            Some(inner) => inner.size_hint(),
+            None => (0, Some(0)),
+        };
+
+        // later inner streams may yield any number of items
+        if self.outer_closed {
+            (lower, upper)
+        } else {
+            (lower, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::FlattenStream;
+
+    #[test]
+    fn flatten_with_pending() {
+        let inner = |polls: Vec<PollRecv<usize>>| from_poll_iter(polls);
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(inner(vec![PollRecv::Ready(1), PollRecv::Pending])),
+            PollRecv::Pending,
+            PollRecv::Ready(inner(vec![])),
+            PollRecv::Ready(inner(vec![
+                PollRecv::Pending,
+                PollRecv::Ready(2),
+                PollRecv::Pending,
+                PollRecv::Ready(3),
+            ])),
+        ]);
+        let mut flatten = FlattenStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut flatten).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut flatten).poll_recv(&mut cx));
+        // the first inner stream closes, and the outer stream is pending
+        assert_eq!(PollRecv::Pending, Pin::new(&mut flatten).poll_recv(&mut cx));
+        // the second inner stream is empty, and the third is pending
+        assert_eq!(PollRecv::Pending, Pin::new(&mut flatten).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut flatten).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut flatten).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready(3),
+            Pin::new(&mut flatten).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flatten).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flatten).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn inner_outlives_outer() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let (mut outer_tx, outer_rx) = mpsc::channel(4);
+        outer_tx.try_send(rx).unwrap();
+        drop(outer_tx);
+
+        let mut flatten = FlattenStream::new(outer_rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut flatten).poll_recv(&mut cx));
+        assert_eq!((0, None), flatten.size_hint());
+
+        // the waker is registered with the inner stream
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut flatten).poll_recv(&mut cx)
+        );
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flatten).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_with_outer() {
+        let (mut outer_tx, outer_rx) = mpsc::channel(4);
+        let mut flatten = FlattenStream::new(outer_rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut flatten).poll_recv(&mut cx));
+
+        let (mut tx, rx) = mpsc::channel(4);
+        tx.try_send(1).unwrap();
+        outer_tx.try_send(rx).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut flatten).poll_recv(&mut cx)
+        );
+
+        drop(outer_tx);
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut flatten).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = from_iter(vec![not_unpin(from_iter(vec![1, 2]))]);
+        let mut flatten = Box::pin(source.flatten());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), flatten.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), flatten.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, flatten.as_mut().poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn yields_to_empty_inner_streams() {
+        let mut stream = FlattenStream::new(from_iter(std::iter::repeat_with(closed::<usize>)));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(1, count.get());
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut Context::empty())
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::fold](./trait.Stream.html#method.fold), which accumulates the items of a stream.
+///
+/// Resolves to the accumulator once the stream is closed.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct FoldFuture<S, Acc, F> {
+    #[pin]
+    stream: S,
+    // taken when the future completes
+    acc: Option<Acc>,
+    f: F,
+}
+
+impl<S, Acc, F> FoldFuture<S, Acc, F>
+where
+    S: Stream,
+    F: FnMut(Acc, S::Item) -> Acc,
+{
+    pub fn new(stream: S, init: Acc, f: F) -> Self {
+        Self {
+            stream,
+            acc: Some(init),
+            f,
+        }
+    }
+}
+
+impl<S, Acc, F> Future for FoldFuture<S, Acc, F>
+where
+    S: Stream,
+    F: FnMut(Acc, S::Item) -> Acc,
+{
+    type Output = Acc;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(this.acc.is_some(), "FoldFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => {
+                    let acc = this.acc.take().unwrap();
+                    *this.acc = Some((this.f)(acc, item));
+                }
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => return Poll::Ready(this.acc.take().unwrap()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::noop_context;
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_poll_iter},
+    };
+
+    use super::FoldFuture;
+
+    #[test]
+    fn fold_channel() {
+        let (mut tx, rx) = mpsc::channel(4);
+        for i in 1..=3 {
+            tx.try_send(i).unwrap();
+        }
+        drop(tx);
+
+        let mut fold = FoldFuture::new(rx, 0, |acc, i| acc + i);
+        assert_eq!(
+            Poll::Ready(6),
+            Pin::new(&mut fold).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn fold_closed() {
+        let mut fold = FoldFuture::new(closed::<usize>(), vec![7], |mut acc, i| {
+            acc.push(i);
+            acc
+        });
+
+        assert_eq!(
+            Poll::Ready(vec![7]),
+            Pin::new(&mut fold).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn keeps_acc_while_pending() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+        ]);
+        let mut fold = FoldFuture::new(stream, 0, |acc, i| acc * 10 + i);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut fold).poll(&mut noop_context()));
+        assert_eq!(Poll::Pending, Pin::new(&mut fold).poll(&mut noop_context()));
+        assert_eq!(
+            Poll::Ready(123),
+            Pin::new(&mut fold).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let mut fold = FoldFuture::new(closed::<usize>(), 0, |acc, i| acc + i);
+
+        assert_eq!(
+            Poll::Ready(0),
+            Pin::new(&mut fold).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut fold).poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn fold_borrowed() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+        tx.send(1usize).await.unwrap();
+        tx.send(2).await.unwrap();
+        drop(tx);
+
+        // the receiver is borrowed, and can be used after the fold completes
+        assert_eq!(3, (&mut rx).fold(0, |acc, i| acc + i).await);
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -0,0 +1,247 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::for_each](./trait.Stream.html#method.for_each), which calls a closure with each
+/// item of a stream.
+///
+/// Ready items are visited within a single poll.  Resolves once the stream is closed.  Panics if polled after
+/// completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct ForEachFuture<S, F> {
+    #[pin]
+    stream: S,
+    f: F,
+    complete: bool,
+}
+
+impl<S, F> ForEachFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item),
+{
+    pub fn new(stream: S, f: F) -> Self {
+        Self {
+            stream,
+            f,
+            complete: false,
+        }
+    }
+}
+
+impl<S, F> Future for ForEachFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item),
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "ForEachFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => (this.f)(item),
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(());
+                }
+            }
+        }
+    }
+}
+
+/// A future returned by [Stream::for_each_async](./trait.Stream.html#method.for_each_async), which maps each item
+/// of a stream to a future, and awaits it.
+///
+/// No item is received while a future is in flight.  Resolves once the stream is closed, and the last future has
+/// completed.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct ForEachAsyncFuture<S, F, Fut> {
+    #[pin]
+    stream: S,
+    f: F,
+    #[pin]
+    future: Option<Fut>,
+    complete: bool,
+}
+
+impl<S, F, Fut> ForEachAsyncFuture<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    pub fn new(stream: S, f: F) -> Self {
+        Self {
+            stream,
+            f,
+            future: None,
+            complete: false,
+        }
+    }
+}
+
+impl<S, F, Fut> Future for ForEachAsyncFuture<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(
+            !*this.complete,
+            "ForEachAsyncFuture polled after completion"
+        );
+
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                match future.poll(cx) {
+                    Poll::Ready(()) => this.future.set(None),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+
+            let mut stream_cx: crate::Context<'_> = cx.into();
+            match this.stream.as_mut().poll_recv(&mut stream_cx) {
+                PollRecv::Ready(item) => this.future.set(Some((this.f)(item))),
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::noop_context;
+
+    use crate::{
+        mpsc, oneshot,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter},
+    };
+
+    use super::{ForEachAsyncFuture, ForEachFuture};
+
+    #[test]
+    fn visits_queued_items_in_one_poll() {
+        let (mut tx, rx) = mpsc::channel(4);
+        for i in 1..=3 {
+            tx.try_send(i).unwrap();
+        }
+
+        let visited = RefCell::new(Vec::new());
+        let mut for_each = ForEachFuture::new(rx, |i| visited.borrow_mut().push(i));
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut for_each).poll(&mut noop_context())
+        );
+        assert_eq!(vec![1, 2, 3], *visited.borrow());
+
+        drop(tx);
+        assert_eq!(
+            Poll::Ready(()),
+            Pin::new(&mut for_each).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn completes_on_closed() {
+        let mut for_each = ForEachFuture::new(closed::<usize>(), |_| panic!("no items"));
+
+        assert_eq!(
+            Poll::Ready(()),
+            Pin::new(&mut for_each).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn resumes_after_pending() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let visited = RefCell::new(Vec::new());
+        let mut for_each = ForEachFuture::new(stream, |i| visited.borrow_mut().push(i));
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut for_each).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Ready(()),
+            Pin::new(&mut for_each).poll(&mut noop_context())
+        );
+        assert_eq!(vec![1, 2], *visited.borrow());
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let mut for_each = ForEachFuture::new(closed::<usize>(), |_| {});
+
+        assert_eq!(
+            Poll::Ready(()),
+            Pin::new(&mut for_each).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut for_each).poll(&mut noop_context());
+    }
+
+    #[test]
+    fn async_awaits_each_future() {
+        let (mut tx, rx) = oneshot::channel::<usize>();
+        let visited = RefCell::new(Vec::new());
+        let source = from_iter(vec![Some(rx), None]);
+        let mut for_each = Box::pin(ForEachAsyncFuture::new(source, |rx| {
+            let visited = &visited;
+            async move {
+                let value = match rx {
+                    Some(mut rx) => rx.recv().await.unwrap(),
+                    None => 0,
+                };
+                visited.borrow_mut().push(value);
+            }
+        }));
+
+        // the second item is not received while the first future is in flight
+        assert_eq!(Poll::Pending, for_each.as_mut().poll(&mut noop_context()));
+        assert!(visited.borrow().is_empty());
+
+        tx.try_send(1).unwrap();
+        assert_eq!(Poll::Ready(()), for_each.as_mut().poll(&mut noop_context()));
+        assert_eq!(vec![1, 0], *visited.borrow());
+    }
+
+    #[tokio::test]
+    async fn for_each_borrowed() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+        tx.send(1usize).await.unwrap();
+        tx.send(2).await.unwrap();
+        drop(tx);
+
+        let mut sum = 0;
+        (&mut rx).for_each(|i| sum += i).await;
+        assert_eq!(3, sum);
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -0,0 +1,225 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+};
+
+/// A future returned by [Stream::forward](./trait.Stream.html#method.forward), which sends each item of the stream
+/// to a sink.
+///
+/// Resolves to `Ok(())` once the stream is closed, or `Err(item)` if the sink rejects an item.  Panics if polled
+/// after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct ForwardFuture<S, K>
+where
+    K: Sink,
+{
+    #[pin]
+    stream: S,
+    #[pin]
+    sink: K,
+    // the item is kept until the sink accepts it.  a new item is only received once it is sent
+    in_flight: Option<K::Item>,
+    complete: bool,
+}
+
+impl<S, K> ForwardFuture<S, K>
+where
+    S: Stream<Item = K::Item>,
+    K: Sink,
+{
+    pub fn new(stream: S, sink: K) -> Self {
+        Self {
+            stream,
+            sink,
+            in_flight: None,
+            complete: false,
+        }
+    }
+}
+
+impl<S, K> Future for ForwardFuture<S, K>
+where
+    S: Stream<Item = K::Item>,
+    K: Sink,
+{
+    type Output = Result<(), S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "ForwardFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            if let Some(item) = this.in_flight.take() {
+                match this.sink.as_mut().poll_send(&mut cx, item) {
+                    PollSend::Ready => {}
+                    PollSend::Pending(item) => {
+                        *this.in_flight = Some(item);
+                        return Poll::Pending;
+                    }
+                    PollSend::Rejected(item) => {
+                        *this.complete = true;
+                        return Poll::Ready(Err(item));
+                    }
+                }
+            }
+
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => *this.in_flight = Some(item),
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, task::Poll};
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use crate::{
+        broadcast, mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter, recv},
+    };
+
+    use super::ForwardFuture;
+
+    #[test]
+    fn empty() {
+        let (tx, _rx) = mpsc::channel::<usize>(1);
+        let mut forward = Box::pin(ForwardFuture::new(closed(), tx));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            forward.as_mut().poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn stream_closed() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut forward = Box::pin(ForwardFuture::new(from_iter(vec![1, 2]), tx));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            forward.as_mut().poll(&mut noop_context())
+        );
+        drop(forward);
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut rx));
+        assert_eq!(PollRecv::Closed, recv(&mut rx));
+    }
+
+    #[test]
+    fn stream_pending() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut forward = Box::pin(ForwardFuture::new(stream, tx));
+
+        assert_eq!(Poll::Pending, forward.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            forward.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Ready(2), recv(&mut rx));
+    }
+
+    #[test]
+    fn sink_rejected() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut forward = Box::pin(ForwardFuture::new(from_iter(vec![1, 2, 3]), tx));
+
+        assert_eq!(Poll::Pending, forward.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        drop(rx);
+
+        assert_eq!(
+            Poll::Ready(Err(2)),
+            forward.as_mut().poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn sink_full() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (tx, mut rx) = mpsc::channel(1);
+
+        in_tx.try_send(1).unwrap();
+        in_tx.try_send(2).unwrap();
+        in_tx.try_send(3).unwrap();
+
+        let mut forward = Box::pin(ForwardFuture::new(in_rx, tx));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        // 2 is in flight, and 3 is not received until it is sent
+        assert_eq!(Poll::Pending, forward.as_mut().poll(&mut cx));
+        assert_eq!(0, count.get());
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Pending, recv(&mut rx));
+
+        assert_eq!(Poll::Pending, forward.as_mut().poll(&mut cx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut rx));
+        assert_eq!(2, count.get());
+
+        assert_eq!(Poll::Pending, forward.as_mut().poll(&mut cx));
+        drop(in_tx);
+        assert_eq!(3, count.get());
+
+        assert_eq!(Poll::Ready(Ok(())), forward.as_mut().poll(&mut cx));
+        assert_eq!(PollRecv::Ready(3), recv(&mut rx));
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let (tx, _rx) = mpsc::channel::<usize>(1);
+        let mut forward = Box::pin(ForwardFuture::new(closed(), tx));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            forward.as_mut().poll(&mut noop_context())
+        );
+        let _ = forward.as_mut().poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn broadcast_to_mpsc() {
+        let (mut tx, rx) = broadcast::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(1);
+
+        let join = tokio::spawn(rx.forward(out_tx));
+
+        for i in 0..8 {
+            tx.send(i).await.unwrap();
+            assert_eq!(Some(i), out_rx.recv().await);
+        }
+
+        drop(tx);
+        assert_eq!(Ok(()), join.await.unwrap());
+        assert_eq!(None, out_rx.recv().await);
+    }
+}
@@ -0,0 +1,247 @@
+use std::pin::Pin;
+
+use crate::stream::{chain::ChainStream, merge::MergeStream, PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+/// A marker for streams which continue to return `PollRecv::Closed` once closed, and can be safely polled after
+/// closure.
+///
+/// Any stream can be fused with [Stream::fuse](./trait.Stream.html#method.fuse).  The marker is implemented by
+/// `FuseStream`, by the `barrier`, `oneshot` and `watch` receivers, and by combinators which track the closure of
+/// their sources, such as `chain` and `merge`.
+pub trait FusedStream: Stream {}
+
+impl<S> FusedStream for &mut S where S: FusedStream + Unpin + ?Sized {}
+
+impl<P, S> FusedStream for Pin<P>
+where
+    P: std::ops::DerefMut<Target = S> + Unpin,
+    S: FusedStream + Unpin + ?Sized,
+{
+}
+
+/// A stream returned by [Stream::fuse](./trait.Stream.html#method.fuse), which returns `PollRecv::Closed` forever
+/// once the inner stream is closed.  The inner stream is not polled again after it returns `PollRecv::Closed`.
+#[pin_project]
+pub struct FuseStream<S> {
+    #[pin]
+    stream: S,
+    closed: bool,
+}
+
+impl<S> FuseStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            closed: false,
+        }
+    }
+
+    /// Returns true if the inner stream has returned `PollRecv::Closed`.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl<S> Stream for FuseStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        let poll = this.stream.poll_recv(cx);
+        if let PollRecv::Closed = poll {
+            *this.closed = true;
+        }
+
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        self.stream.size_hint()
+    }
+}
+
+impl<S> FusedStream for FuseStream<S> where S: Stream {}
+
+// chain stores the closed state, and merge fuses both sources
+impl<Left, Right> FusedStream for ChainStream<Left, Right>
+where
+    Left: Stream,
+    Right: Stream<Item = Left::Item>,
+{
+}
+
+impl<Left, Right> FusedStream for MergeStream<Left, Right>
+where
+    Left: Stream,
+    Right: Stream<Item = Left::Item>,
+{
+}
+
+// the barrier receiver is ready forever once released
+impl FusedStream for crate::barrier::Receiver {}
+
+// the oneshot receiver is closed once the value is taken, or the sender is dropped
+impl<T> FusedStream for crate::oneshot::Receiver<T> {}
+
+// the watch receiver is closed once the sender is dropped, and the last value is observed
+impl<T> FusedStream for crate::watch::Receiver<T> where T: Clone {}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{FuseStream, FusedStream};
+
+    fn assert_fused<S: FusedStream>(_stream: &S) {}
+
+    #[test]
+    fn fuse() {
+        let source = panic_after_close(from_iter(vec![1, 2]));
+        let mut fuse = FuseStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert!(!fuse.is_closed());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert!(fuse.is_closed());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), fuse.size_hint());
+    }
+
+    #[test]
+    #[should_panic(expected = "stream polled after returning Closed")]
+    fn mock_panics_without_fuse() {
+        let mut source = panic_after_close(closed::<usize>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut source).poll_recv(&mut cx));
+        let _ = Pin::new(&mut source).poll_recv(&mut cx);
+    }
+
+    #[test]
+    fn pending() {
+        let source = panic_after_close(from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]));
+        let mut fuse = FuseStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut fuse = FuseStream::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut fuse).poll_recv(&mut cx));
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut fuse).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut fuse).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn merge_skips_closed_sources() {
+        let left = panic_after_close(from_iter(vec![1]));
+        let right = panic_after_close(from_iter(vec![2, 3]));
+        let mut merge = left.merge(right);
+        assert_fused(&merge);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn chain_skips_closed_sources() {
+        let left = panic_after_close(from_iter(vec![1]));
+        let right = panic_after_close(from_iter(vec![2]));
+        let mut chain = left.chain(right);
+        assert_fused(&chain);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut chain).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut chain).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chain).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut chain).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn receivers_are_fused() {
+        let (_tx, rx) = crate::barrier::channel();
+        assert_fused(&rx);
+
+        let (_tx, rx) = crate::oneshot::channel::<usize>();
+        assert_fused(&rx);
+
+        let (_tx, rx) = crate::watch::channel::<usize>();
+        assert_fused(&rx);
+    }
+
+    #[test]
+    fn oneshot_closed_after_recv() {
+        let (mut tx, mut rx) = crate::oneshot::channel();
+
+        let mut cx = Context::empty();
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1]));
+        let mut fuse = Box::pin(source.fuse());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), fuse.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, fuse.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, fuse.as_mut().poll_recv(&mut cx));
+    }
+}
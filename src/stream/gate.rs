@@ -0,0 +1,181 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::watch;
+use crate::Context;
+use pin_project::pin_project;
+
+#[derive(Copy, Clone)]
+enum State {
+    Watching,
+    Open,
+}
+
+#[pin_project]
+pub struct GateStream<S> {
+    #[pin]
+    stream: S,
+    gate: watch::Receiver<bool>,
+    state: State,
+    open: bool,
+    close_with_gate: bool,
+}
+
+impl<S> GateStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S, gate: watch::Receiver<bool>) -> Self {
+        Self {
+            stream,
+            gate,
+            state: State::Watching,
+            open: false,
+            close_with_gate: false,
+        }
+    }
+
+    /// Configures the behavior when the gate channel is closed.
+    ///
+    /// By default, the gate remains permanently open.  If enabled, the stream is closed instead.
+    pub fn close_with_gate(mut self, close: bool) -> Self {
+        self.close_with_gate = close;
+        self
+    }
+}
+
+impl<S> Stream for GateStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        // the gate is polled until it returns pending, which registers for the next change
+        while let State::Watching = this.state {
+            match Pin::new(&mut *this.gate).poll_recv(cx) {
+                PollRecv::Ready(open) => *this.open = open,
+                PollRecv::Pending => break,
+                PollRecv::Closed => {
+                    if *this.close_with_gate {
+                        return PollRecv::Closed;
+                    }
+
+                    *this.state = State::Open;
+                    *this.open = true;
+                }
+            }
+        }
+
+        if !*this.open {
+            return PollRecv::Pending;
+        }
+
+        this.stream.poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+
+        if self.open {
+            (lower, upper)
+        } else {
+            (0, upper)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context};
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::noop_context,
+        watch,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::GateStream;
+
+    #[test]
+    fn closed_gate_does_not_poll() {
+        let (_tx, rx) = watch::channel_with(false);
+        let source = from_poll_iter(vec![PollRecv::Ready(1)]);
+        let mut gate = GateStream::new(source, rx);
+
+        let mut cx = noop_context();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut gate).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut gate).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn open_forwards() {
+        let (_tx, rx) = watch::channel_with(true);
+        let source = from_iter(vec![1, 2]);
+        let mut gate = GateStream::new(source, rx);
+
+        let mut cx = noop_context();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut gate).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut gate).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut gate).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pause_resume() {
+        let (mut tx, rx) = watch::channel_with(true);
+        let source = from_iter(vec![1, 2]);
+        let mut gate = GateStream::new(source, rx);
+
+        let mut cx = noop_context();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut gate).poll_recv(&mut cx));
+
+        tx.try_send(false).unwrap();
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut gate).poll_recv(&mut w1_context)
+        );
+        assert_eq!(0, w1_count.get());
+
+        tx.try_send(true).unwrap();
+        assert_eq!(1, w1_count.get());
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut gate).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn gate_closed_opens() {
+        let (tx, rx) = watch::channel_with(false);
+        let source = from_iter(vec![1]);
+        let mut gate = GateStream::new(source, rx);
+
+        let mut cx = noop_context();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut gate).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut gate).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut gate).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn gate_closed_closes() {
+        let (tx, rx) = watch::channel_with(true);
+        let source = from_iter(vec![1, 2]);
+        let mut gate = GateStream::new(source, rx).close_with_gate(true);
+
+        let mut cx = noop_context();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut gate).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut gate).poll_recv(&mut cx));
+    }
+}
@@ -0,0 +1,181 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [gather](./fn.gather.html), which receives one item from each stream.
+///
+/// Resolves once every stream has produced an item or closed.  Panics if polled after completion.
+#[must_use = "futures do nothing unless polled"]
+pub struct GatherFuture<S>
+where
+    S: Stream,
+{
+    streams: Vec<S>,
+    // the outer option is none while the stream is pending
+    results: Vec<Option<Option<S::Item>>>,
+    remaining: usize,
+    complete: bool,
+}
+
+impl<S> GatherFuture<S>
+where
+    S: Stream + Unpin,
+{
+    pub fn new(streams: Vec<S>) -> Self {
+        let results = streams.iter().map(|_| None).collect();
+        let remaining = streams.len();
+
+        Self {
+            streams,
+            results,
+            remaining,
+            complete: false,
+        }
+    }
+}
+
+// the streams are Unpin, and the results are never pinned
+impl<S> Unpin for GatherFuture<S> where S: Stream {}
+
+impl<S> Future for GatherFuture<S>
+where
+    S: Stream + Unpin,
+{
+    type Output = Vec<Option<S::Item>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        assert!(!this.complete, "GatherFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        // each pending stream registers the waker, so any of them can wake the task
+        for (stream, result) in this.streams.iter_mut().zip(this.results.iter_mut()) {
+            if result.is_some() {
+                continue;
+            }
+
+            match Pin::new(stream).poll_recv(&mut cx) {
+                PollRecv::Ready(value) => *result = Some(Some(value)),
+                PollRecv::Pending => continue,
+                PollRecv::Closed => *result = Some(None),
+            }
+
+            this.remaining -= 1;
+        }
+
+        if this.remaining > 0 {
+            return Poll::Pending;
+        }
+
+        this.complete = true;
+        let results = std::mem::take(&mut this.results)
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect();
+
+        Poll::Ready(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::PollRecv,
+        test::stream::{from_iter, from_poll_iter},
+    };
+
+    use super::GatherFuture;
+
+    #[test]
+    fn ready_and_closed() {
+        let streams = vec![from_iter(vec![1, 2]), from_iter(vec![]), from_iter(vec![3])];
+        let mut gather = GatherFuture::new(streams);
+
+        assert_eq!(
+            Poll::Ready(vec![Some(1), None, Some(3)]),
+            Pin::new(&mut gather).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut gather = GatherFuture::new(Vec::<mpsc::Receiver<usize>>::new());
+
+        assert_eq!(
+            Poll::Ready(vec![]),
+            Pin::new(&mut gather).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn pending_stream() {
+        let streams = vec![
+            from_poll_iter(vec![PollRecv::Ready(1)]),
+            from_poll_iter(vec![]),
+            from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(2)]),
+        ];
+        let mut gather = GatherFuture::new(streams);
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut gather).poll(&mut noop_context())
+        );
+
+        // completed streams are not polled again
+        assert_eq!(
+            Poll::Ready(vec![Some(1), None, Some(2)]),
+            Pin::new(&mut gather).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn one_item_per_stream() {
+        let mut cx = crate::test::noop_context();
+        let (mut tx1, mut rx1) = mpsc::channel(4);
+        let (mut tx2, mut rx2) = mpsc::channel(4);
+
+        let (waker, count) = new_count_waker();
+        let mut std_cx = std::task::Context::from_waker(&waker);
+
+        let mut gather = GatherFuture::new(vec![&mut rx1, &mut rx2]);
+        assert_eq!(Poll::Pending, Pin::new(&mut gather).poll(&mut std_cx));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx2).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx2).poll_send(&mut cx, 2));
+        assert_eq!(1, count.get());
+        assert_eq!(Poll::Pending, Pin::new(&mut gather).poll(&mut std_cx));
+
+        // the first stream is still registered with the waker
+        let woken = count.get();
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx1).poll_send(&mut cx, 3));
+        assert!(count.get() > woken);
+        assert_eq!(
+            Poll::Ready(vec![Some(3), Some(1)]),
+            Pin::new(&mut gather).poll(&mut std_cx)
+        );
+        drop(gather);
+
+        // the remaining item is left in the channel
+        assert_eq!(1, rx1.len() + rx2.len());
+        drop(tx1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let mut gather = GatherFuture::new(vec![from_iter(vec![1])]);
+
+        assert_eq!(
+            Poll::Ready(vec![Some(1)]),
+            Pin::new(&mut gather).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut gather).poll(&mut noop_context());
+    }
+}
@@ -0,0 +1,230 @@
+use std::{pin::Pin, task::Poll, time::Duration};
+
+use crate::stream::{PollRecv, Stream};
+use crate::time::{Delay, Sleep};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct HeartbeatStream<S, F, T: Sleep> {
+    #[pin]
+    stream: S,
+    heartbeat: F,
+    duration: Duration,
+    delay: Delay<T>,
+    armed: bool,
+    reset_on_heartbeat: bool,
+}
+
+impl<S, F, T> HeartbeatStream<S, F, T>
+where
+    S: Stream,
+    F: FnMut() -> S::Item,
+    T: Sleep,
+{
+    pub fn new(stream: S, duration: Duration, heartbeat: F, timer: T) -> Self {
+        Self {
+            stream,
+            heartbeat,
+            duration,
+            delay: Delay::new(timer, duration),
+            armed: true,
+            reset_on_heartbeat: true,
+        }
+    }
+
+    /// Configures whether the timer restarts after a heartbeat is produced (the default).
+    ///
+    /// If disabled, at most one heartbeat is produced for each gap between items.
+    pub fn reset_on_heartbeat(mut self, reset: bool) -> Self {
+        self.reset_on_heartbeat = reset;
+        self
+    }
+}
+
+impl<S, F, T> Stream for HeartbeatStream<S, F, T>
+where
+    S: Stream,
+    F: FnMut() -> S::Item,
+    T: Sleep,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        // the source is polled first, so the stream closes with the source, even if the timer has elapsed
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                this.delay.reset(*this.duration);
+                *this.armed = true;
+                return PollRecv::Ready(value);
+            }
+            PollRecv::Pending => {}
+            PollRecv::Closed => return PollRecv::Closed,
+        }
+
+        if !*this.armed {
+            return PollRecv::Pending;
+        }
+
+        match this.delay.poll_elapsed(cx) {
+            Poll::Ready(()) => {
+                if *this.reset_on_heartbeat {
+                    this.delay.reset(*this.duration);
+                } else {
+                    *this.armed = false;
+                }
+
+                PollRecv::Ready((this.heartbeat)())
+            }
+            Poll::Pending => PollRecv::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // any number of heartbeats may be produced while waiting, unless the source is exhausted
+        match self.stream.size_hint() {
+            (lower, Some(0)) => (lower, Some(0)),
+            (lower, _) => (lower, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context, time::Duration};
+
+    use crate::{
+        stream::{PollRecv, Stream},
+        test::{
+            stream::{from_poll_iter, pending},
+            time::MockClock,
+        },
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::HeartbeatStream;
+
+    #[test]
+    fn heartbeat_on_mock_clock() {
+        let clock = MockClock::new();
+        let mut stream = HeartbeatStream::new(
+            pending::<usize>(),
+            Duration::from_secs(1),
+            || 0,
+            clock.clone(),
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        clock.advance(Duration::from_millis(999));
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(2, w1_count.get());
+        assert_eq!(
+            PollRecv::Ready(0),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        // the timer restarts after the heartbeat
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            PollRecv::Ready(0),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn closed_source_wins() {
+        let clock = MockClock::new();
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Closed]);
+        let mut stream = HeartbeatStream::new(source, Duration::from_secs(1), || 0, clock.clone());
+
+        let mut cx = crate::test::noop_context();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::time::{sleep, timeout, Instant};
+
+    use crate::{mpsc, sink::Sink, stream::Stream};
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Item {
+        Value(usize),
+        Heartbeat,
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scripted_gaps() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx
+            .map(Item::Value)
+            .with_heartbeat(Duration::from_millis(100), || Item::Heartbeat);
+
+        let start = Instant::now();
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(250)).await;
+            tx.send(1).await.expect("send failed");
+            sleep(Duration::from_millis(50)).await;
+            tx.send(2).await.expect("send failed");
+            sleep(Duration::from_millis(120)).await;
+        });
+
+        let mut received = Vec::new();
+        while let Some(item) = rx.recv().await {
+            received.push((item, start.elapsed().as_millis()));
+        }
+
+        assert_eq!(
+            vec![
+                (Item::Heartbeat, 100),
+                (Item::Heartbeat, 200),
+                (Item::Value(1), 250),
+                (Item::Value(2), 300),
+                (Item::Heartbeat, 400),
+            ],
+            received
+        );
+        assert_eq!(Duration::from_millis(420), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn heartbeat_once_per_gap() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx
+            .with_heartbeat(Duration::from_millis(100), || 0usize)
+            .reset_on_heartbeat(false);
+
+        assert_eq!(Some(0), rx.recv().await);
+        assert!(timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .is_err());
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(Some(0), rx.recv().await);
+    }
+}
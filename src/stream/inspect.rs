@@ -0,0 +1,131 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct InspectStream<S, F> {
+    #[pin]
+    stream: S,
+    inspect: F,
+}
+
+impl<S, F> InspectStream<S, F>
+where
+    S: Stream,
+    F: FnMut(&S::Item),
+{
+    pub fn new(stream: S, inspect: F) -> Self {
+        Self { stream, inspect }
+    }
+}
+
+impl<S, F> Stream for InspectStream<S, F>
+where
+    S: Stream,
+    F: FnMut(&S::Item),
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                (this.inspect)(&value);
+                PollRecv::Ready(value)
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream, TryRecvError},
+        Context,
+    };
+
+    use super::InspectStream;
+
+    #[test]
+    fn inspect() {
+        let calls = Cell::new(0);
+        let source = from_iter(vec![1, 2]);
+        let mut inspect = InspectStream::new(source, |i| calls.set(calls.get() + *i));
+
+        let mut cx = Context::empty();
+
+        assert_eq!((2, Some(2)), inspect.size_hint());
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut inspect).poll_recv(&mut cx)
+        );
+        assert_eq!(1, calls.get());
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut inspect).poll_recv(&mut cx)
+        );
+        assert_eq!(3, calls.get());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut inspect).poll_recv(&mut cx));
+        assert_eq!(3, calls.get());
+    }
+
+    #[test]
+    fn not_called_when_pending() {
+        let calls = Cell::new(0);
+        let source = from_poll_iter(vec![
+            PollRecv::Pending,
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+        ]);
+        let mut inspect = InspectStream::new(source, |_| calls.set(calls.get() + 1));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut inspect).poll_recv(&mut cx));
+        assert_eq!(0, calls.get());
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut inspect).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut inspect).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut inspect).poll_recv(&mut cx));
+        assert_eq!(1, calls.get());
+    }
+
+    #[test]
+    fn called_by_try_recv() {
+        let calls = Cell::new(0);
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut inspect = InspectStream::new(source, |_| calls.set(calls.get() + 1));
+
+        assert_eq!(Err(TryRecvError::Pending), inspect.try_recv());
+        assert_eq!(Ok(1), inspect.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), inspect.try_recv());
+        assert_eq!(1, calls.get());
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let calls = Cell::new(0);
+        let source = not_unpin(from_iter(vec![1]));
+        let mut inspect = Box::pin(source.inspect(|_| calls.set(calls.get() + 1)));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), inspect.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, inspect.as_mut().poll_recv(&mut cx));
+        assert_eq!(1, calls.get());
+    }
+}
@@ -43,6 +43,10 @@ where
             PollRecv::Closed => PollRecv::Closed,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.from.size_hint()
+    }
 }
 
 #[cfg(test)]
@@ -90,4 +94,16 @@ mod tests {
 
         assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
     }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut map = Box::pin(source.map(|i| i + 1));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), map.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), map.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, map.as_mut().poll_recv(&mut cx));
+    }
 }
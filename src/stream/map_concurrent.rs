@@ -0,0 +1,364 @@
+use std::{collections::VecDeque, future::Future, pin::Pin, task::Poll};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+// An item in the ordered queue.  Futures are boxed so they stay pinned while the queue shifts.
+enum Slot<Fut: Future> {
+    InFlight(Pin<Box<Fut>>),
+    Done(Fut::Output),
+}
+
+/// A stream returned by [Stream::map_concurrent](./trait.Stream.html#method.map_concurrent), which runs up to `n`
+/// futures at once, and yields their outputs in the order of the items.
+#[pin_project]
+pub struct MapConcurrentStream<S, F, Fut>
+where
+    Fut: Future,
+{
+    #[pin]
+    stream: S,
+    map: F,
+    limit: usize,
+    // in-flight futures and completed outputs, in item order.  outputs which complete before the front are held
+    // here, and count against the limit
+    slots: VecDeque<Slot<Fut>>,
+    source_closed: bool,
+}
+
+impl<S, F, Fut> MapConcurrentStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+    #[track_caller]
+    pub fn new(stream: S, limit: usize, map: F) -> Self {
+        assert!(limit > 0, "the concurrency limit must be greater than zero");
+
+        Self {
+            stream,
+            map,
+            limit,
+            slots: VecDeque::with_capacity(limit),
+            source_closed: false,
+        }
+    }
+}
+
+impl<S, F, Fut> Stream for MapConcurrentStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+    type Item = Fut::Output;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // new items are not pulled while the reorder buffer is full
+        while !*this.source_closed && this.slots.len() < *this.limit {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    let future = Box::pin((this.map)(item));
+                    this.slots.push_back(Slot::InFlight(future));
+                }
+                PollRecv::Pending => break,
+                PollRecv::Closed => *this.source_closed = true,
+            }
+        }
+
+        for slot in this.slots.iter_mut() {
+            if let Slot::InFlight(future) = slot {
+                if let Poll::Ready(output) = cx.with_std(|cx| future.as_mut().poll(cx)) {
+                    *slot = Slot::Done(output);
+                }
+            }
+        }
+
+        if let Some(Slot::Done(_)) = this.slots.front() {
+            match this.slots.pop_front() {
+                Some(Slot::Done(output)) => return PollRecv::Ready(output),
+                _ => unreachable!(),
+            }
+        }
+
+        // in-flight futures are drained before the closure is reported
+        if *this.source_closed && this.slots.is_empty() {
+            return PollRecv::Closed;
+        }
+
+        PollRecv::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        hint_with_in_flight(&self.stream, self.source_closed, self.slots.len())
+    }
+}
+
+/// A stream returned by [Stream::map_concurrent_unordered](./trait.Stream.html#method.map_concurrent_unordered),
+/// which runs up to `n` futures at once, and yields their outputs as they complete.
+#[pin_project]
+pub struct MapConcurrentUnorderedStream<S, F, Fut> {
+    #[pin]
+    stream: S,
+    map: F,
+    limit: usize,
+    in_flight: Vec<Pin<Box<Fut>>>,
+    source_closed: bool,
+}
+
+impl<S, F, Fut> MapConcurrentUnorderedStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+    #[track_caller]
+    pub fn new(stream: S, limit: usize, map: F) -> Self {
+        assert!(limit > 0, "the concurrency limit must be greater than zero");
+
+        Self {
+            stream,
+            map,
+            limit,
+            in_flight: Vec::with_capacity(limit),
+            source_closed: false,
+        }
+    }
+}
+
+impl<S, F, Fut> Stream for MapConcurrentUnorderedStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+    type Item = Fut::Output;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        while !*this.source_closed && this.in_flight.len() < *this.limit {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => this.in_flight.push(Box::pin((this.map)(item))),
+                PollRecv::Pending => break,
+                PollRecv::Closed => *this.source_closed = true,
+            }
+        }
+
+        // futures which are not polled before an output is returned are polled on the next call
+        for i in 0..this.in_flight.len() {
+            if let Poll::Ready(output) = cx.with_std(|cx| this.in_flight[i].as_mut().poll(cx)) {
+                drop(this.in_flight.swap_remove(i));
+                return PollRecv::Ready(output);
+            }
+        }
+
+        if *this.source_closed && this.in_flight.is_empty() {
+            return PollRecv::Closed;
+        }
+
+        PollRecv::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        hint_with_in_flight(&self.stream, self.source_closed, self.in_flight.len())
+    }
+}
+
+// each in-flight future yields exactly one output
+fn hint_with_in_flight<S: Stream>(
+    stream: &S,
+    source_closed: bool,
+    in_flight: usize,
+) -> (usize, Option<usize>) {
+    let (lower, upper) = if source_closed {
+        (0, Some(0))
+    } else {
+        stream.size_hint()
+    };
+
+    (
+        lower.saturating_add(in_flight),
+        upper.and_then(|upper| upper.checked_add(in_flight)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        oneshot,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::{MapConcurrentStream, MapConcurrentUnorderedStream};
+
+    // futures which complete when the matching sender is used
+    fn controlled(n: usize) -> (Vec<oneshot::Sender<usize>>, Vec<oneshot::Receiver<usize>>) {
+        (0..n).map(|_| oneshot::channel()).unzip()
+    }
+
+    async fn resolve(mut rx: oneshot::Receiver<usize>) -> usize {
+        rx.recv().await.unwrap()
+    }
+
+    #[test]
+    fn ordered_out_of_order_completion() {
+        let (mut txs, rxs) = controlled(3);
+        let mut map = MapConcurrentStream::new(from_iter(rxs), 3, resolve);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+
+        txs[2].try_send(3).unwrap();
+        txs[1].try_send(2).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!((3, Some(3)), map.size_hint());
+
+        txs[0].try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn reorder_buffer_backpressure() {
+        let (mut txs, rxs) = controlled(4);
+        let pulled = Cell::new(0);
+        let source = from_iter(rxs.into_iter().inspect(|_| pulled.set(pulled.get() + 1)));
+        let mut map = MapConcurrentStream::new(source, 2, resolve);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(2, pulled.get());
+
+        // the second output is buffered behind the first, so no new item is pulled
+        txs[1].try_send(2).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(2, pulled.get());
+
+        txs[0].try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(3, pulled.get());
+
+        txs[3].try_send(4).unwrap();
+        txs[2].try_send(3).unwrap();
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(4, pulled.get());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn unordered_yields_on_completion() {
+        let (mut txs, rxs) = controlled(3);
+        let mut map = MapConcurrentUnorderedStream::new(from_iter(rxs), 3, resolve);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+
+        txs[2].try_send(3).unwrap();
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+
+        txs[0].try_send(1).unwrap();
+        txs[1].try_send(2).unwrap();
+        let mut rest = vec![
+            Pin::new(&mut map).poll_recv(&mut cx),
+            Pin::new(&mut map).poll_recv(&mut cx),
+        ];
+        rest.sort_by_key(|poll| match poll {
+            PollRecv::Ready(i) => *i,
+            _ => usize::MAX,
+        });
+        assert_eq!(vec![PollRecv::Ready(1), PollRecv::Ready(2)], rest);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn unordered_limit() {
+        let (mut txs, rxs) = controlled(3);
+        let pulled = Cell::new(0);
+        let source = from_iter(rxs.into_iter().inspect(|_| pulled.set(pulled.get() + 1)));
+        let mut map = MapConcurrentUnorderedStream::new(source, 1, resolve);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(1, pulled.get());
+
+        // the later futures are not started until the first completes
+        txs[2].try_send(3).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(1, pulled.get());
+
+        txs[0].try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(2, pulled.get());
+    }
+
+    #[test]
+    fn drains_after_source_closed() {
+        let (mut tx, rx) = oneshot::channel();
+        let mut map = MapConcurrentStream::new(from_iter(vec![rx]), 2, resolve);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!((1, Some(1)), map.size_hint());
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), map.size_hint());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_limit() {
+        let _ = MapConcurrentStream::new(from_iter(vec![1]), 0, |i: usize| async move { i });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_sleeps() {
+        use std::time::Duration;
+
+        use tokio::time::{sleep, Instant};
+
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        for delay in [30, 10, 20] {
+            tx.send(delay).await.unwrap();
+        }
+        drop(tx);
+
+        let start = Instant::now();
+        let mut map = rx.map_concurrent(3, |delay: u64| async move {
+            sleep(Duration::from_millis(delay)).await;
+            delay
+        });
+
+        assert_eq!(Some(30), map.recv().await);
+        assert_eq!(Some(10), map.recv().await);
+        assert_eq!(Some(20), map.recv().await);
+        assert_eq!(None, map.recv().await);
+
+        // the sleeps ran concurrently
+        assert_eq!(Duration::from_millis(30), start.elapsed());
+    }
+}
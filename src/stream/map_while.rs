@@ -0,0 +1,175 @@
+use std::{marker::PhantomData, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct MapWhileStream<From, Map, Into> {
+    #[pin]
+    from: From,
+    map: Map,
+    // set once the map returns none, or the stream closes.  the stream is not polled again
+    closed: bool,
+    into: PhantomData<Into>,
+}
+
+impl<From, Map, Into> MapWhileStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    pub fn new(from: From, map: Map) -> Self {
+        Self {
+            from,
+            map,
+            closed: false,
+            into: PhantomData,
+        }
+    }
+}
+
+impl<From, Map, Into> Stream for MapWhileStream<From, Map, Into>
+where
+    From: Stream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+    type Item = Into;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        match this.from.poll_recv(cx) {
+            PollRecv::Ready(value) => match (this.map)(value) {
+                Some(mapped) => PollRecv::Ready(mapped),
+                None => {
+                    *this.closed = true;
+                    PollRecv::Closed
+                }
+            },
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => {
+                *this.closed = true;
+                PollRecv::Closed
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        // the stream may close on any item
+        (0, self.from.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::MapWhileStream;
+
+    // frames are translated until the sentinel frame, which is zero
+    fn translate(frame: usize) -> Option<String> {
+        if frame == 0 {
+            return None;
+        }
+
+        Some(frame.to_string())
+    }
+
+    #[test]
+    fn sentinel_within_ready_items() {
+        let source = panic_after_close(from_iter(vec![1, 2, 0, 3, 4]));
+        let mut map = MapWhileStream::new(source, translate);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((0, Some(5)), map.size_hint());
+        assert_eq!(
+            PollRecv::Ready("1".to_string()),
+            Pin::new(&mut map).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready("2".to_string()),
+            Pin::new(&mut map).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), map.size_hint());
+    }
+
+    #[test]
+    fn sentinel_first() {
+        let source = from_iter(vec![0, 1]);
+        let mut map = MapWhileStream::new(source, translate);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_without_sentinel() {
+        let source = panic_after_close(from_iter(vec![1]));
+        let mut map = MapWhileStream::new(source, translate);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready("1".to_string()),
+            Pin::new(&mut map).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut map = MapWhileStream::new(rx, translate);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut map).poll_recv(&mut cx));
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready("1".to_string()),
+            Pin::new(&mut map).poll_recv(&mut cx)
+        );
+
+        tx.try_send(0).unwrap();
+        assert_eq!(PollRecv::Closed, Pin::new(&mut map).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 0]));
+        let mut map = Box::pin(source.map_while(translate));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready("1".to_string()),
+            map.as_mut().poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, map.as_mut().poll_recv(&mut cx));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::stream::{PollRecv, Stream};
+use crate::stream::{FuseStream, PollRecv, Stream};
 use pin_project::pin_project;
 use std::pin::Pin;
 
@@ -21,10 +21,11 @@ impl State {
 #[pin_project]
 pub struct MergeStream<Left, Right> {
     state: State,
+    // the sources are fused, so a closed source is not polled again while the other is open
     #[pin]
-    left: Left,
+    left: FuseStream<Left>,
     #[pin]
-    right: Right,
+    right: FuseStream<Right>,
 }
 
 impl<Left, Right> MergeStream<Left, Right>
@@ -35,8 +36,8 @@ where
     pub fn new(left: Left, right: Right) -> Self {
         Self {
             state: State::Left,
-            left,
-            right,
+            left: FuseStream::new(left),
+            right: FuseStream::new(right),
         }
     }
 }
@@ -62,6 +63,19 @@ where
 
         poll.into_recv()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left_lower, left_upper) = self.left.size_hint();
+        let (right_lower, right_upper) = self.right.size_hint();
+
+        let lower = left_lower.saturating_add(right_lower);
+        let upper = match (left_upper, right_upper) {
+            (Some(left), Some(right)) => left.checked_add(right),
+            _ => None,
+        };
+
+        (lower, upper)
+    }
 }
 
 enum MergePoll<T> {
@@ -77,12 +91,12 @@ impl<T> MergePoll<T> {
         }
     }
 
+    // the first side is polled first until it produces an item.  if the second side produces an item, the first side
+    // keeps priority, so neither side can produce two items in a row while the other is ready
     pub fn swap(&self) -> bool {
         match self {
             MergePoll::First(_) => true,
-            MergePoll::Second(PollRecv::Ready(_)) => true,
-            MergePoll::Second(PollRecv::Pending) => true,
-            MergePoll::Second(PollRecv::Closed) => false,
+            MergePoll::Second(_) => false,
         }
     }
 }
@@ -94,7 +108,11 @@ where
 {
     match first.poll_recv(cx) {
         PollRecv::Ready(v) => MergePoll::First(PollRecv::Ready(v)),
-        PollRecv::Pending => MergePoll::Second(second.poll_recv(cx)),
+        // both sides are polled, so the waker is registered with both.  closed is only returned once both are closed
+        PollRecv::Pending => match second.poll_recv(cx) {
+            PollRecv::Closed => MergePoll::Second(PollRecv::Pending),
+            poll => MergePoll::Second(poll),
+        },
         PollRecv::Closed => MergePoll::Second(second.poll_recv(cx)),
     }
 }
@@ -105,9 +123,11 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        sink::Sink,
+        stream::{repeat, PollRecv, Stream},
         Context,
     };
+    use futures_test::task::new_count_waker;
 
     use super::MergeStream;
 
@@ -188,6 +208,99 @@ mod tests {
         let mut cx = Context::empty();
 
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
     }
+
+    #[test]
+    fn pending_with_closed_side() {
+        let left = from_poll_iter(vec![PollRecv::<usize>::Pending, PollRecv::Pending]);
+        let right = closed();
+        let mut merge = MergeStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn fair_with_ready_left() {
+        let left = repeat(0);
+        let right = from_iter(vec![1, 2, 3]);
+        let mut merge = MergeStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        // the right side produces an item within every two polls, although the left side is always ready
+        for i in 1..=3 {
+            assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut merge).poll_recv(&mut cx));
+        }
+
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn fair_with_ready_right() {
+        let left = from_poll_iter(vec![
+            PollRecv::Pending,
+            PollRecv::Ready(1),
+            PollRecv::Ready(2),
+        ]);
+        let right = repeat(0);
+        let mut merge = MergeStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        // the left side keeps priority after the right side produces an item
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_with_both() {
+        let (mut left_tx, left_rx) = crate::mpsc::channel(4);
+        let (mut right_tx, right_rx) = crate::mpsc::channel(4);
+        let mut merge = MergeStream::new(left_rx, right_rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        right_tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        left_tx.try_send(2).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+
+        drop(left_tx);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        drop(right_tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let left = not_unpin(from_iter(vec![1]));
+        let right = not_unpin(from_iter(vec![2]));
+        let mut merge = Box::pin(left.merge(right));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), merge.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), merge.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, merge.as_mut().poll_recv(&mut cx));
+    }
 }
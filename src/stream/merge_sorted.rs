@@ -0,0 +1,233 @@
+use crate::stream::{PollRecv, Stream};
+use pin_project::pin_project;
+use std::pin::Pin;
+
+use crate::Context;
+
+enum Slot<T> {
+    Empty,
+    Ready(T),
+    Closed,
+}
+
+impl<T> Slot<T> {
+    fn fill<S>(&mut self, stream: Pin<&mut S>, cx: &mut Context<'_>)
+    where
+        S: Stream<Item = T>,
+    {
+        if let Slot::Empty = self {
+            *self = match stream.poll_recv(cx) {
+                PollRecv::Ready(value) => Slot::Ready(value),
+                PollRecv::Pending => Slot::Empty,
+                PollRecv::Closed => Slot::Closed,
+            };
+        }
+    }
+
+    fn size_hint<S>(&self, stream: &S) -> (usize, Option<usize>)
+    where
+        S: Stream<Item = T>,
+    {
+        match self {
+            Slot::Empty => stream.size_hint(),
+            Slot::Ready(_) => {
+                let (lower, upper) = stream.size_hint();
+                (
+                    lower.saturating_add(1),
+                    upper.and_then(|upper| upper.checked_add(1)),
+                )
+            }
+            Slot::Closed => (0, Some(0)),
+        }
+    }
+
+    fn take(&mut self) -> T {
+        match std::mem::replace(self, Slot::Empty) {
+            Slot::Ready(value) => value,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[pin_project]
+pub struct MergeSortedStream<Left, Right, Key>
+where
+    Left: Stream,
+{
+    #[pin]
+    left: Left,
+    #[pin]
+    right: Right,
+    left_next: Slot<Left::Item>,
+    right_next: Slot<Left::Item>,
+    key: Key,
+}
+
+impl<Left, Right, Key, K> MergeSortedStream<Left, Right, Key>
+where
+    Left: Stream,
+    Right: Stream<Item = Left::Item>,
+    Key: FnMut(&Left::Item) -> K,
+    K: Ord,
+{
+    pub fn new(left: Left, right: Right, key: Key) -> Self {
+        Self {
+            left,
+            right,
+            left_next: Slot::Empty,
+            right_next: Slot::Empty,
+            key,
+        }
+    }
+}
+
+impl<Left, Right, Key, K> Stream for MergeSortedStream<Left, Right, Key>
+where
+    Left: Stream,
+    Right: Stream<Item = Left::Item>,
+    Key: FnMut(&Left::Item) -> K,
+    K: Ord,
+{
+    type Item = Left::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        // each side buffers at most one item.  an empty side is polled on every call, so it registers the waker
+        this.left_next.fill(this.left, cx);
+        this.right_next.fill(this.right, cx);
+
+        match (&*this.left_next, &*this.right_next) {
+            (Slot::Ready(left), Slot::Ready(right)) => {
+                // ties prefer the left stream
+                if (this.key)(right) < (this.key)(left) {
+                    PollRecv::Ready(this.right_next.take())
+                } else {
+                    PollRecv::Ready(this.left_next.take())
+                }
+            }
+            (Slot::Ready(_), Slot::Closed) => PollRecv::Ready(this.left_next.take()),
+            (Slot::Closed, Slot::Ready(_)) => PollRecv::Ready(this.right_next.take()),
+            (Slot::Closed, Slot::Closed) => PollRecv::Closed,
+            // the pending side may produce the smaller item
+            _ => PollRecv::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left_lower, left_upper) = self.left_next.size_hint(&self.left);
+        let (right_lower, right_upper) = self.right_next.size_hint(&self.right);
+
+        // items are only ready while both sides have an item, or one side is exhausted
+        let lower = match (left_upper, right_upper) {
+            (Some(0), _) => right_lower,
+            (_, Some(0)) => left_lower,
+            _ => left_lower.min(right_lower),
+        };
+
+        let upper = match (left_upper, right_upper) {
+            (Some(left), Some(right)) => left.checked_add(right),
+            _ => None,
+        };
+
+        (lower, upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::MergeSortedStream;
+
+    #[test]
+    fn simple_merge() {
+        let left = from_iter(vec![1, 4, 5]);
+        let right = from_iter(vec![2, 3, 6]);
+        let mut merge = MergeSortedStream::new(left, right, |i: &usize| *i);
+
+        let mut cx = Context::empty();
+
+        for i in 1..=6 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut merge).poll_recv(&mut cx));
+        }
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn ties_prefer_left() {
+        let left = from_iter(vec![(1, 'l'), (2, 'l')]);
+        let right = from_iter(vec![(1, 'r'), (2, 'r')]);
+        let mut merge = MergeSortedStream::new(left, right, |item: &(usize, char)| item.0);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready((1, 'l')),
+            Pin::new(&mut merge).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((1, 'r')),
+            Pin::new(&mut merge).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((2, 'l')),
+            Pin::new(&mut merge).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((2, 'r')),
+            Pin::new(&mut merge).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn waits_for_pending_side() {
+        let left = from_poll_iter(vec![PollRecv::Ready(2), PollRecv::Ready(3)]);
+        let right = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut merge = MergeSortedStream::new(left, right, |i: &usize| *i);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn left_closed_flows_right() {
+        let left = from_iter(vec![]);
+        let right = from_iter(vec![3, 1, 2]);
+        let mut merge = MergeSortedStream::new(left, right, |i: &usize| *i);
+
+        let mut cx = Context::empty();
+
+        // with one side closed, items are forwarded in the order received
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn right_closed_flows_left() {
+        let left = from_iter(vec![1, 2]);
+        let right = from_poll_iter(vec![PollRecv::Ready(0), PollRecv::Closed]);
+        let mut merge = MergeSortedStream::new(left, right, |i: &usize| *i);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+}
@@ -49,6 +49,13 @@ impl<T> Stream for OnceStream<T> {
 
         PollRecv::Closed
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.state.load(Ordering::Acquire) {
+            State::Ready => (1, Some(1)),
+            State::Taken => (0, Some(0)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,308 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+/// A stream returned by [Stream::peekable](./trait.Stream.html#method.peekable), which can look at the next item
+/// without consuming it.
+///
+/// At most one item is buffered.  The buffered item is returned by the next `poll_recv`, before the inner stream is
+/// polled again.
+#[pin_project]
+pub struct PeekableStream<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    peeked: Option<S::Item>,
+}
+
+impl<S> PeekableStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            peeked: None,
+        }
+    }
+
+    /// Attempts to receive a reference to the next item, without consuming it.
+    ///
+    /// Returns `PollRecv::Closed` if the stream is closed, and no item is buffered.
+    pub fn poll_peek(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<&S::Item> {
+        let this = self.project();
+
+        if this.peeked.is_none() {
+            match this.stream.poll_recv(cx) {
+                PollRecv::Ready(item) => *this.peeked = Some(item),
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+
+        PollRecv::Ready(this.peeked.as_ref().unwrap())
+    }
+
+    /// Returns a reference to the next item, without consuming it.  Resolves to `None` if the stream is closed.
+    pub fn peek(&mut self) -> PeekFuture<'_, S>
+    where
+        Self: Unpin,
+    {
+        PeekFuture {
+            peekable: Some(self),
+        }
+    }
+}
+
+impl<S> Stream for PeekableStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if let Some(item) = this.peeked.take() {
+            return PollRecv::Ready(item);
+        }
+
+        this.stream.poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let peeked = if self.peeked.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+
+        (
+            lower.saturating_add(peeked),
+            upper.and_then(|upper| upper.checked_add(peeked)),
+        )
+    }
+}
+
+/// A future returned by [PeekableStream::peek](./struct.PeekableStream.html#method.peek).
+///
+/// Panics if polled after completion.
+#[must_use = "futures do nothing unless polled"]
+pub struct PeekFuture<'s, S>
+where
+    S: Stream,
+{
+    // taken when the future completes, so the item can be borrowed for the lifetime of the future
+    peekable: Option<&'s mut PeekableStream<S>>,
+}
+
+impl<'s, S> Future for PeekFuture<'s, S>
+where
+    S: Stream + Unpin,
+{
+    type Output = Option<&'s S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let peekable = this
+            .peekable
+            .as_mut()
+            .expect("PeekFuture polled after completion");
+
+        let mut cx: Context<'_> = cx.into();
+        match Pin::new(&mut **peekable).poll_peek(&mut cx) {
+            PollRecv::Ready(_) => {}
+            PollRecv::Pending => return Poll::Pending,
+            PollRecv::Closed => {
+                this.peekable = None;
+                return Poll::Ready(None);
+            }
+        }
+
+        let peekable = this.peekable.take().unwrap();
+        Poll::Ready(peekable.peeked.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::PeekableStream;
+
+    #[test]
+    fn peek_then_recv() {
+        let source = from_iter(vec![1, 2]);
+        let mut peekable = PeekableStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(&1),
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(&1),
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut peekable).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn interleaved() {
+        let source = from_iter(vec![1, 2, 3]);
+        let mut peekable = PeekableStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(&2),
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(3),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut peekable).poll_peek(&mut cx));
+    }
+
+    #[test]
+    fn peek_identical_item() {
+        let source = from_iter(vec![String::from("a")]);
+        let mut peekable = PeekableStream::new(source);
+
+        let mut cx = Context::empty();
+
+        let peeked = match Pin::new(&mut peekable).poll_peek(&mut cx) {
+            PollRecv::Ready(item) => item.as_ptr(),
+            poll => panic!("unexpected poll: {:?}", poll),
+        };
+
+        match Pin::new(&mut peekable).poll_recv(&mut cx) {
+            PollRecv::Ready(item) => assert_eq!(peeked, item.as_ptr()),
+            poll => panic!("unexpected poll: {:?}", poll),
+        }
+    }
+
+    #[test]
+    fn peek_after_close() {
+        let mut peekable = PeekableStream::new(closed::<usize>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut peekable).poll_peek(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut peekable).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn peek_pending() {
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut peekable = PeekableStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(&1),
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn size_hint() {
+        let source = from_iter(vec![1, 2]);
+        let mut peekable = PeekableStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((2, Some(2)), peekable.size_hint());
+        assert_eq!(
+            PollRecv::Ready(&1),
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!((2, Some(2)), peekable.size_hint());
+    }
+
+    #[test]
+    fn registers_waker_on_peek() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut peekable = PeekableStream::new(rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(0, count.get());
+
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready(&1),
+            Pin::new(&mut peekable).poll_peek(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut peekable).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut peekable = Box::pin(source.peekable());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(&1), peekable.as_mut().poll_peek(&mut cx));
+        assert_eq!(PollRecv::Ready(1), peekable.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), peekable.as_mut().poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn peek_future() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut peekable = rx.peekable();
+
+        tx.send(1).await.unwrap();
+        assert_eq!(Some(&1), peekable.peek().await);
+        assert_eq!(Some(1), peekable.recv().await);
+
+        drop(tx);
+        assert_eq!(None, peekable.peek().await);
+    }
+}
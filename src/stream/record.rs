@@ -0,0 +1,258 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use crate::sink::{PollSend, Sink};
+use crate::stream::{PollRecv, Stream};
+use crate::time::Sleep;
+use crate::Context;
+use pin_project::pin_project;
+
+/// An item captured by [Stream::record](./trait.Stream.html#method.record), with the time it was received.
+///
+/// Records can be replayed with [replay](./fn.replay.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<T> {
+    /// The time between the start of the recording and the item.
+    pub elapsed: Duration,
+    /// The item.
+    pub item: T,
+}
+
+impl<T> Record<T> {
+    /// Constructs a record of `item`, received `elapsed` after the start of the recording.
+    pub fn new(elapsed: Duration, item: T) -> Self {
+        Self { elapsed, item }
+    }
+}
+
+#[pin_project]
+pub struct RecordingStream<S, K, T>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    #[pin]
+    sink: K,
+    timer: T,
+    start: Instant,
+    // records which the sink has not yet accepted, in order
+    backlog: VecDeque<Record<S::Item>>,
+    // set when the sink rejects a record, and the recording stops
+    rejected: bool,
+    stream_closed: bool,
+}
+
+impl<S, K, T> RecordingStream<S, K, T>
+where
+    S: Stream,
+    S::Item: Clone,
+    K: Sink<Item = Record<S::Item>>,
+    T: Sleep,
+{
+    pub fn new(stream: S, sink: K, timer: T) -> Self {
+        Self {
+            stream,
+            sink,
+            start: timer.now(),
+            timer,
+            backlog: VecDeque::new(),
+            rejected: false,
+            stream_closed: false,
+        }
+    }
+}
+
+impl<S, K, T> Stream for RecordingStream<S, K, T>
+where
+    S: Stream,
+    S::Item: Clone,
+    K: Sink<Item = Record<S::Item>>,
+    T: Sleep,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // the sink never stalls the stream.  records which are not accepted are kept in order, and sent later
+        while let Some(record) = this.backlog.pop_front() {
+            match this.sink.as_mut().poll_send(cx, record) {
+                PollSend::Ready => {}
+                PollSend::Pending(record) => {
+                    this.backlog.push_front(record);
+                    break;
+                }
+                PollSend::Rejected(_) => {
+                    *this.rejected = true;
+                    this.backlog.clear();
+                }
+            }
+        }
+
+        if !*this.stream_closed {
+            match this.stream.poll_recv(cx) {
+                PollRecv::Ready(item) => {
+                    if !*this.rejected {
+                        let elapsed = this.timer.now().saturating_duration_since(*this.start);
+                        let record = Record::new(elapsed, item.clone());
+
+                        if this.backlog.is_empty() {
+                            match this.sink.as_mut().poll_send(cx, record) {
+                                PollSend::Ready => {}
+                                PollSend::Pending(record) => this.backlog.push_back(record),
+                                PollSend::Rejected(_) => *this.rejected = true,
+                            }
+                        } else {
+                            this.backlog.push_back(record);
+                        }
+                    }
+
+                    return PollRecv::Ready(item);
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => *this.stream_closed = true,
+            }
+        }
+
+        // the stream is closed once the records are flushed, and the sink is closed
+        if !this.backlog.is_empty() {
+            return PollRecv::Pending;
+        }
+
+        if !*this.rejected {
+            match this.sink.poll_close(cx) {
+                PollSend::Pending(()) => return PollRecv::Pending,
+                PollSend::Ready | PollSend::Rejected(()) => *this.rejected = true,
+            }
+        }
+
+        PollRecv::Closed
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.stream_closed {
+            return (0, Some(0));
+        }
+
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::{
+        mpsc,
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream, TryRecvError},
+        test::{noop_context, sink::test_sink, stream::from_iter, time::MockClock},
+    };
+
+    use super::{Record, RecordingStream};
+
+    #[test]
+    fn records_elapsed_time() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let (mut tx, rx) = mpsc::channel(4);
+        let (records, mut records_rx) = mpsc::channel(4);
+        let mut stream = RecordingStream::new(rx, records, clock.clone());
+
+        clock.advance(Duration::from_millis(10));
+        tx.try_send(1usize).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(15));
+        tx.try_send(2).unwrap();
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        assert_eq!(
+            Ok(Record::new(Duration::from_millis(10), 1)),
+            records_rx.try_recv()
+        );
+        assert_eq!(
+            Ok(Record::new(Duration::from_millis(25), 2)),
+            records_rx.try_recv()
+        );
+    }
+
+    #[test]
+    fn closes_sink_with_stream() {
+        let mut cx = noop_context();
+        let (records, mut records_rx) = mpsc::channel(4);
+        let mut stream = RecordingStream::new(from_iter(vec![1, 2]), records, MockClock::new());
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        assert_eq!(Ok(1), records_rx.try_recv().map(|record| record.item));
+        assert_eq!(Ok(2), records_rx.try_recv().map(|record| record.item));
+        assert_eq!(Err(TryRecvError::Closed), records_rx.try_recv());
+    }
+
+    #[test]
+    fn pending_sink_does_not_stall_stream() {
+        let mut cx = noop_context();
+        let (records, mut records_rx) = mpsc::channel(1);
+        let mut stream = RecordingStream::new(from_iter(vec![1, 2, 3]), records, MockClock::new());
+
+        for i in 1..=3 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut stream).poll_recv(&mut cx));
+        }
+
+        // the stream is closed, but the records have not been flushed
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        let mut items = Vec::new();
+        loop {
+            match records_rx.try_recv() {
+                Ok(record) => items.push(record.item),
+                Err(TryRecvError::Pending) => {
+                    Pin::new(&mut stream).poll_recv(&mut cx);
+                }
+                Err(TryRecvError::Closed) => break,
+            }
+        }
+
+        assert_eq!(vec![1, 2, 3], items);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn rejected_sink_stops_recording() {
+        let mut cx = noop_context();
+        let sink = test_sink(vec![
+            PollSend::Ready,
+            PollSend::Rejected(Record::new(Duration::ZERO, 2)),
+        ]);
+        let mut stream = RecordingStream::new(from_iter(vec![1, 2, 3]), sink, MockClock::new());
+
+        // items are forwarded after the sink rejects a record
+
+        for i in 1..=3 {
+            assert_eq!(PollRecv::Ready(i), Pin::new(&mut stream).poll_recv(&mut cx));
+        }
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn record_with_timer() {
+        let (records, mut records_rx) = mpsc::channel(4);
+        let mut stream = from_iter(vec![1usize]).record_with(records, MockClock::new());
+
+        assert_eq!(Some(1), stream.recv().await);
+        assert_eq!(None, stream.recv().await);
+
+        let mut records = Vec::new();
+        while let Some(record) = records_rx.recv().await {
+            records.push(record);
+        }
+        assert_eq!(vec![Record::new(Duration::ZERO, 1)], records);
+    }
+}
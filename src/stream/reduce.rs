@@ -0,0 +1,163 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::stream::{PollRecv, Stream};
+
+/// A future returned by [Stream::reduce](./trait.Stream.html#method.reduce), which combines the items of a stream.
+///
+/// Resolves to the combined item once the stream is closed, or `None` if the stream had no items.  Panics if polled
+/// after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct ReduceFuture<S, F>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    acc: Option<S::Item>,
+    f: F,
+    complete: bool,
+}
+
+impl<S, F> ReduceFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item, S::Item) -> S::Item,
+{
+    pub fn new(stream: S, f: F) -> Self {
+        Self {
+            stream,
+            acc: None,
+            f,
+            complete: false,
+        }
+    }
+}
+
+impl<S, F> Future for ReduceFuture<S, F>
+where
+    S: Stream,
+    F: FnMut(S::Item, S::Item) -> S::Item,
+{
+    type Output = Option<S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "ReduceFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready(item) => {
+                    *this.acc = Some(match this.acc.take() {
+                        Some(acc) => (this.f)(acc, item),
+                        None => item,
+                    });
+                }
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(this.acc.take());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use futures_test::task::noop_context;
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter},
+    };
+
+    use super::ReduceFuture;
+
+    #[test]
+    fn reduce_empty() {
+        let mut reduce = ReduceFuture::new(closed::<usize>(), |a, b| a + b);
+
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn reduce_single() {
+        let mut reduce = ReduceFuture::new(from_iter(vec![7]), |_, _| panic!("single item"));
+
+        assert_eq!(
+            Poll::Ready(Some(7)),
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn reduce_ordering() {
+        let stream = from_iter(vec![1, 2, 3, 4]);
+        let mut reduce = ReduceFuture::new(stream, |acc, i| acc * 10 + i);
+
+        assert_eq!(
+            Poll::Ready(Some(1234)),
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn keeps_acc_while_pending() {
+        let stream = from_poll_iter(vec![
+            PollRecv::Ready(8),
+            PollRecv::Pending,
+            PollRecv::Ready(4),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut reduce = ReduceFuture::new(stream, |acc, i| acc / i);
+
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let mut reduce = ReduceFuture::new(closed::<usize>(), |a, b| a + b);
+
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut reduce).poll(&mut noop_context())
+        );
+        let _ = Pin::new(&mut reduce).poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn reduce_channel() {
+        let (mut tx, rx) = mpsc::channel(4);
+        for i in [3, 9, 2] {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(Some(9), rx.reduce(usize::max).await);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use crate::stream::{PollRecv, Record, Stream};
+use crate::time::{Delay, Sleep};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ReplayStream<I, T>
+where
+    I: Iterator,
+    T: Sleep,
+{
+    records: I,
+    next: Option<I::Item>,
+    speed: f64,
+    delay: Delay<T>,
+    // set on the first poll, and moved forward by the time spent paused
+    start: Option<Instant>,
+    paused_at: Option<Instant>,
+}
+
+impl<I, Item, T> ReplayStream<I, T>
+where
+    I: Iterator<Item = Record<Item>>,
+    T: Sleep,
+{
+    pub fn new(records: I, speed: f64, timer: T) -> Self {
+        assert!(
+            speed.is_finite() && speed > 0.0,
+            "replay speed must be positive and finite, got {}",
+            speed
+        );
+
+        Self {
+            records,
+            next: None,
+            speed,
+            delay: Delay::new(timer, Duration::ZERO),
+            start: None,
+            paused_at: None,
+        }
+    }
+
+    /// Pauses the replay.  While paused, the stream returns `Pending`, and the gaps between records do not elapse.
+    ///
+    /// The stream is not woken while paused, so the task which polls the stream should call
+    /// [resume](#method.resume).
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(self.delay.now());
+        }
+    }
+
+    /// Resumes a paused replay.  The remaining records keep their original gaps, delayed by the time spent paused.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            if let Some(start) = self.start.as_mut() {
+                *start += self.delay.now().saturating_duration_since(paused_at);
+            }
+        }
+    }
+
+    /// Returns true if the replay is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+}
+
+impl<I, Item, T> Stream for ReplayStream<I, T>
+where
+    I: Iterator<Item = Record<Item>>,
+    T: Sleep,
+{
+    type Item = Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if this.paused_at.is_some() {
+            return PollRecv::Pending;
+        }
+
+        if this.next.is_none() {
+            *this.next = this.records.next();
+        }
+
+        let elapsed = match this.next.as_ref() {
+            Some(record) => record.elapsed,
+            None => return PollRecv::Closed,
+        };
+
+        let now = this.delay.now();
+        let start = *this.start.get_or_insert(now);
+        let due = start + elapsed.div_f64(*this.speed);
+
+        if now < due {
+            this.delay.reset(due - now);
+
+            if this.delay.poll_elapsed(cx).is_pending() {
+                return PollRecv::Pending;
+            }
+        }
+
+        match this.next.take() {
+            Some(record) => PollRecv::Ready(record.item),
+            None => PollRecv::Closed,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = if self.next.is_some() { 1 } else { 0 };
+        let (_, upper) = self.records.size_hint();
+
+        // records are never ready without waiting for the timer
+        (0, upper.and_then(|upper| upper.checked_add(buffered)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context, time::Duration};
+
+    use crate::{
+        stream::{PollRecv, Record, Stream},
+        test::{noop_context, time::MockClock},
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::ReplayStream;
+
+    fn records() -> Vec<Record<usize>> {
+        vec![
+            Record::new(Duration::from_millis(10), 1),
+            Record::new(Duration::from_millis(30), 2),
+            Record::new(Duration::from_millis(30), 3),
+        ]
+    }
+
+    #[test]
+    fn honors_gaps() {
+        let clock = MockClock::new();
+        let mut stream = ReplayStream::new(records().into_iter(), 1.0, clock.clone());
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        clock.advance(Duration::from_millis(9));
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(2, w1_count.get());
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        // records with equal offsets are produced together
+        clock.advance(Duration::from_millis(20));
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+        assert_eq!(
+            PollRecv::Ready(3),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+        assert_eq!(
+            PollRecv::Closed,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn scales_gaps_by_speed() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let mut stream = ReplayStream::new(records().into_iter(), 2.0, clock.clone());
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(5));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(9));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pause_delays_remaining_records() {
+        let mut cx = noop_context();
+        let clock = MockClock::new();
+        let mut stream = ReplayStream::new(records().into_iter(), 1.0, clock.clone());
+
+        clock.advance(Duration::from_millis(10));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        clock.advance(Duration::from_millis(10));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        stream.pause();
+        assert!(stream.is_paused());
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        stream.resume();
+        assert!(!stream.is_paused());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_millis(20));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn empty_records_close() {
+        let mut cx = noop_context();
+        let mut stream = ReplayStream::new(
+            Vec::<Record<usize>>::new().into_iter(),
+            1.0,
+            MockClock::new(),
+        );
+
+        assert_eq!((0, Some(0)), stream.size_hint());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_speed_panics() {
+        let _stream = ReplayStream::new(records().into_iter(), 0.0, MockClock::new());
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::time::{sleep, Instant};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{replay, Stream},
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn record_and_replay() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let (records_tx, mut records_rx) = mpsc::channel(4);
+        let mut rx = rx.record(records_tx);
+
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            tx.send(1usize).await.expect("send failed");
+            sleep(Duration::from_millis(50)).await;
+            tx.send(2).await.expect("send failed");
+        });
+
+        while rx.recv().await.is_some() {}
+
+        let mut records = Vec::new();
+        while let Some(record) = records_rx.recv().await {
+            records.push(record);
+        }
+
+        let start = Instant::now();
+        let mut replayed = Vec::new();
+        let mut stream = replay(records, 0.5);
+        while let Some(item) = stream.recv().await {
+            replayed.push((item, start.elapsed().as_millis()));
+        }
+
+        assert_eq!(vec![(1, 200), (2, 300)], replayed);
+    }
+}
@@ -0,0 +1,162 @@
+use std::{marker::PhantomData, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ScanStream<S, State, F, Into> {
+    #[pin]
+    stream: S,
+    state: State,
+    scan: F,
+    // set once the closure returns None, or the stream closes.  the stream is not polled again
+    closed: bool,
+    into: PhantomData<Into>,
+}
+
+impl<S, State, F, Into> ScanStream<S, State, F, Into>
+where
+    S: Stream,
+    F: FnMut(&mut State, S::Item) -> Option<Into>,
+{
+    pub fn new(stream: S, initial: State, scan: F) -> Self {
+        Self {
+            stream,
+            state: initial,
+            scan,
+            closed: false,
+            into: PhantomData,
+        }
+    }
+}
+
+impl<S, State, F, Into> Stream for ScanStream<S, State, F, Into>
+where
+    S: Stream,
+    F: FnMut(&mut State, S::Item) -> Option<Into>,
+{
+    type Item = Into;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => match (this.scan)(this.state, value) {
+                Some(mapped) => PollRecv::Ready(mapped),
+                None => {
+                    *this.closed = true;
+                    PollRecv::Closed
+                }
+            },
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => {
+                *this.closed = true;
+                PollRecv::Closed
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        // the closure may end the stream at any item
+        (0, self.stream.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::ScanStream;
+
+    fn running_total(total: &mut usize, i: usize) -> Option<usize> {
+        *total += i;
+        Some(*total)
+    }
+
+    #[test]
+    fn scan() {
+        let source = from_iter(vec![1, 2, 3]);
+        let mut scan = ScanStream::new(source, 0, running_total);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((0, Some(3)), scan.size_hint());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(6), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut scan).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn state_persists_across_pending() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+        ]);
+        let mut scan = ScanStream::new(source, 0, running_total);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(6), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut scan).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn early_termination() {
+        let polls = Cell::new(0);
+        let source = from_iter((1..).inspect(|_| polls.set(polls.get() + 1)));
+        let mut scan = ScanStream::new(source, 0, |total: &mut usize, i: usize| {
+            *total += i;
+            if *total > 5 {
+                None
+            } else {
+                Some(*total)
+            }
+        });
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut scan).poll_recv(&mut cx));
+        assert_eq!(3, polls.get());
+        assert_eq!((0, Some(0)), scan.size_hint());
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut scan = Box::pin(source.scan(0, running_total));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), scan.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), scan.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, scan.as_mut().poll_recv(&mut cx));
+    }
+}
@@ -0,0 +1,367 @@
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Wake, Waker},
+};
+
+use crate::stream::{PollRecv, Stream};
+use crate::sync::notifier::WakerSet;
+use crate::Context;
+
+/// A stream returned by [select_all](./fn.select_all.html), which receives items from a dynamic set of streams.
+///
+/// Members are polled round-robin, starting after the member which produced the last item.  Members are removed
+/// once they are closed, and the stream returns `PollRecv::Closed` while the set is empty.  Streams can be added
+/// with [push](#method.push), which re-opens an empty set.
+///
+/// Each member is polled with its own waker.  A member which has returned `PollRecv::Pending` is not polled again
+/// until it wakes, so a poll does not re-register the task with every member.
+pub struct SelectAll<S> {
+    members: Vec<Member<S>>,
+    // the index of the member which is polled first
+    next: usize,
+    task: Arc<WakerSet>,
+}
+
+struct Member<S> {
+    stream: S,
+    state: Arc<MemberWaker>,
+    waker: Waker,
+}
+
+// set when the member may have an item, and wakes the task which polls the set
+struct MemberWaker {
+    woken: AtomicBool,
+    task: Arc<WakerSet>,
+}
+
+impl Wake for MemberWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+        self.task.notify();
+    }
+}
+
+impl<S> SelectAll<S>
+where
+    S: Stream + Unpin,
+{
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            next: 0,
+            task: Arc::new(WakerSet::new()),
+        }
+    }
+
+    /// Adds a stream to the set.  The stream is polled on the next poll of the set.
+    pub fn push(&mut self, stream: S) {
+        let state = Arc::new(MemberWaker {
+            woken: AtomicBool::new(true),
+            task: self.task.clone(),
+        });
+
+        let waker = Waker::from(state.clone());
+
+        self.members.push(Member {
+            stream,
+            state,
+            waker,
+        });
+    }
+
+    /// Returns the number of streams in the set.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns true if the set contains no streams.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl<S> Default for SelectAll<S>
+where
+    S: Stream + Unpin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Extend<S> for SelectAll<S>
+where
+    S: Stream + Unpin,
+{
+    fn extend<I: IntoIterator<Item = S>>(&mut self, streams: I) {
+        for stream in streams {
+            self.push(stream);
+        }
+    }
+}
+
+// the members are Unpin, and are never pinned
+impl<S> Unpin for SelectAll<S> {}
+
+impl<S> Stream for SelectAll<S>
+where
+    S: Stream + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+        let has_waker = cx.waker().is_some();
+
+        // the task subscribes before the members are checked, so a member woken during the poll wakes the task
+        this.task.subscribe(cx);
+
+        let mut polled = 0;
+        while polled < this.members.len() {
+            let index = (this.next + polled) % this.members.len();
+            let member = &mut this.members[index];
+
+            // without a waker, the registration of the members is not changed
+            let poll = if has_waker {
+                if !member.state.woken.swap(false, Ordering::AcqRel) {
+                    polled += 1;
+                    continue;
+                }
+
+                let mut member_cx = Context::from_waker(&member.waker);
+                Pin::new(&mut member.stream).poll_recv(&mut member_cx)
+            } else {
+                Pin::new(&mut member.stream).poll_recv(&mut Context::empty())
+            };
+
+            match poll {
+                PollRecv::Ready(item) => {
+                    // the member may have more items, and it has not registered a waker
+                    member.state.woken.store(true, Ordering::Release);
+                    this.next = (index + 1) % this.members.len();
+                    return PollRecv::Ready(item);
+                }
+                PollRecv::Pending => {
+                    if !has_waker {
+                        member.state.woken.store(true, Ordering::Release);
+                    }
+
+                    polled += 1;
+                }
+                PollRecv::Closed => {
+                    this.members.remove(index);
+
+                    // the following member moves into the removed index, and is polled next
+                    if index < this.next {
+                        this.next -= 1;
+                    }
+                }
+            }
+        }
+
+        if this.members.is_empty() {
+            this.next = 0;
+            return PollRecv::Closed;
+        }
+
+        PollRecv::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.members
+            .iter()
+            .map(|member| member.stream.size_hint())
+            .fold(
+                (0, Some(0)),
+                |(lower, upper), (member_lower, member_upper)| {
+                    let upper = match (upper, member_upper) {
+                        (Some(upper), Some(member_upper)) => upper.checked_add(member_upper),
+                        _ => None,
+                    };
+
+                    (lower.saturating_add(member_lower), upper)
+                },
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::SelectAll;
+
+    #[test]
+    fn round_robin() {
+        let mut select = SelectAll::new();
+        select.push(from_iter(vec![1, 4]));
+        select.push(from_iter(vec![2]));
+        select.push(from_iter(vec![3, 5]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!((5, Some(5)), select.size_hint());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(5), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+        assert!(select.is_empty());
+    }
+
+    #[test]
+    fn empty() {
+        let mut select = SelectAll::<mpsc::Receiver<usize>>::new();
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn removes_closed() {
+        let mut select = SelectAll::new();
+        select.push(from_iter(vec![]));
+        select.push(from_iter(vec![1]));
+        select.push(from_iter(vec![]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(2, select.len());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(0, select.len());
+    }
+
+    #[test]
+    fn push_after_close() {
+        let mut select = SelectAll::new();
+        select.push(from_iter(vec![1]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+
+        select.push(from_iter(vec![2]));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn fair_with_ready_member() {
+        let mut select = SelectAll::new();
+        select.push(from_iter(vec![0, 0, 0, 0]));
+        select.push(from_iter(vec![1, 2]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn dynamic_receivers() {
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut select = SelectAll::new();
+        let (mut tx1, rx1) = mpsc::channel(4);
+        select.push(rx1);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+
+        let (mut tx2, rx2) = mpsc::channel(4);
+        select.push(rx2);
+        tx2.try_send(2).unwrap();
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+
+        tx1.try_send(1).unwrap();
+        assert!(count.get() > 0);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut select).poll_recv(&mut cx));
+
+        drop(tx1);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(1, select.len());
+
+        drop(tx2);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut select).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registered_member_not_polled() {
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // the first poll registers the member.  a second poll would close the source
+        let mut select = SelectAll::new();
+        select.push(from_poll_iter(vec![PollRecv::<usize>::Pending]));
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(1, select.len());
+        assert_eq!(0, count.get());
+    }
+
+    #[test]
+    fn woken_member_is_polled() {
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let (_, closed_rx) = mpsc::channel(4);
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut select = SelectAll::new();
+        select.push(closed_rx);
+        select.push(rx);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(1, select.len());
+
+        tx.try_send(1).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut select).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut select).poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn select_all_recv() {
+        let (mut tx1, rx1) = mpsc::channel(4);
+        let (mut tx2, rx2) = mpsc::channel(4);
+        let mut select = crate::stream::select_all(vec![rx1, rx2]);
+
+        tx1.send(1).await.unwrap();
+        tx2.send(2).await.unwrap();
+        drop(tx1);
+        drop(tx2);
+
+        let mut items = vec![select.recv().await.unwrap(), select.recv().await.unwrap()];
+        items.sort_unstable();
+
+        assert_eq!(vec![1, 2], items);
+        assert_eq!(None, select.recv().await);
+    }
+}
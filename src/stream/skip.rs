@@ -0,0 +1,151 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct SkipStream<S> {
+    #[pin]
+    stream: S,
+    // the number of items which must still be discarded
+    remaining: usize,
+}
+
+impl<S> SkipStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S, n: usize) -> Self {
+        Self {
+            stream,
+            remaining: n,
+        }
+    }
+}
+
+impl<S> Stream for SkipStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // ready items are discarded within the call, so pending is only returned if the inner stream is pending
+        while *this.remaining > 0 {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(_) => *this.remaining -= 1,
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+
+        this.stream.poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+
+        (
+            lower.saturating_sub(self.remaining),
+            upper.map(|upper| upper.saturating_sub(self.remaining)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::SkipStream;
+
+    #[test]
+    fn skip() {
+        let source = from_iter(vec![1, 2, 3, 4]);
+        let mut skip = SkipStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((2, Some(2)), skip.size_hint());
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn skip_zero() {
+        let source = from_iter(vec![1]);
+        let mut skip = SkipStream::new(source, 0);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pending_during_skip() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Ready(3),
+        ]);
+        let mut skip = SkipStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_during_skip() {
+        let source = from_iter(vec![1]);
+        let mut skip = SkipStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_during_skip() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut skip = SkipStream::new(rx, 2);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(0, count.get());
+
+        tx.try_send(2).unwrap();
+        assert_eq!(1, count.get());
+        tx.try_send(3).unwrap();
+
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut skip = Box::pin(source.skip(1));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), skip.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, skip.as_mut().poll_recv(&mut cx));
+    }
+}
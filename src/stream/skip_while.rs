@@ -0,0 +1,165 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct SkipWhileStream<S, F> {
+    #[pin]
+    stream: S,
+    predicate: F,
+    // set once the predicate returns false.  the predicate is not evaluated again
+    skipped: bool,
+}
+
+impl<S, F> SkipWhileStream<S, F>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> bool,
+{
+    pub fn new(stream: S, predicate: F) -> Self {
+        Self {
+            stream,
+            predicate,
+            skipped: false,
+        }
+    }
+}
+
+impl<S, F> Stream for SkipWhileStream<S, F>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        if *this.skipped {
+            return this.stream.poll_recv(cx);
+        }
+
+        loop {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    if !(this.predicate)(&value) {
+                        *this.skipped = true;
+                        return PollRecv::Ready(value);
+                    }
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.skipped {
+            return self.stream.size_hint();
+        }
+
+        (0, self.stream.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::SkipWhileStream;
+
+    #[test]
+    fn skip_while() {
+        let source = from_iter(vec![1, 2, 3, 1]);
+        let mut skip = SkipWhileStream::new(source, |i| *i < 3);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn predicate_not_evaluated_after_skip() {
+        let calls = Cell::new(0);
+        let source = from_iter(vec![1, 2, 1]);
+        let mut skip = SkipWhileStream::new(source, |i| {
+            calls.set(calls.get() + 1);
+            *i < 2
+        });
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(2, calls.get());
+    }
+
+    #[test]
+    fn pending_during_skip() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+        ]);
+        let mut skip = SkipWhileStream::new(source, |i| *i < 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_during_skip() {
+        let source = from_iter(vec![1, 1]);
+        let mut skip = SkipWhileStream::new(source, |i| *i < 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_during_skip() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut skip = SkipWhileStream::new(rx, |i| *i < 2);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut skip).poll_recv(&mut cx));
+        assert_eq!(0, count.get());
+
+        tx.try_send(2).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut skip).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut skip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut skip = Box::pin(source.skip_while(|i| *i == 1));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), skip.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, skip.as_mut().poll_recv(&mut cx));
+    }
+}
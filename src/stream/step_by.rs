@@ -0,0 +1,187 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct StepByStream<S> {
+    #[pin]
+    stream: S,
+    step: usize,
+    // the number of items which must be discarded before the next item is returned
+    remaining: usize,
+}
+
+impl<S> StepByStream<S>
+where
+    S: Stream,
+{
+    #[track_caller]
+    pub fn new(stream: S, step: usize) -> Self {
+        assert!(step > 0, "the step must be greater than zero");
+
+        Self {
+            stream,
+            step,
+            remaining: 0,
+        }
+    }
+}
+
+impl<S> Stream for StepByStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // ready items are discarded within the call, so pending is only returned if the inner stream is pending
+        loop {
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    if *this.remaining == 0 {
+                        *this.remaining = *this.step - 1;
+                        return PollRecv::Ready(value);
+                    }
+
+                    *this.remaining -= 1;
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+
+        // the number of items returned from `len` inner items
+        let steps = |len: usize| {
+            let len = len.saturating_sub(self.remaining);
+            len.div_ceil(self.step)
+        };
+
+        (steps(lower), upper.map(steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::StepByStream;
+
+    #[test]
+    fn step_by() {
+        let source = from_iter(vec![1, 2, 3, 4, 5, 6, 7]);
+        let mut step = StepByStream::new(source, 3);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((3, Some(3)), step.size_hint());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!((2, Some(2)), step.size_hint());
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(7), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut step).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn step_one() {
+        let source = from_iter(vec![1, 2, 3]);
+        let mut step = StepByStream::new(source, 1);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((3, Some(3)), step.size_hint());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut step).poll_recv(&mut cx));
+    }
+
+    #[test]
+    #[should_panic(expected = "the step must be greater than zero")]
+    fn step_zero() {
+        let _ = StepByStream::new(closed::<usize>(), 0);
+    }
+
+    #[test]
+    fn pending_within_window() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+            PollRecv::Pending,
+            PollRecv::Ready(4),
+            PollRecv::Ready(5),
+        ]);
+        let mut step = StepByStream::new(source, 3);
+
+        let mut cx = Context::empty();
+
+        // the count continues across pending gaps
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut step).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_within_window() {
+        let source = from_iter(vec![1, 2]);
+        let mut step = StepByStream::new(source, 3);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut step).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_within_window() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut step = StepByStream::new(rx, 2);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut step).poll_recv(&mut cx));
+        assert_eq!(0, count.get());
+
+        tx.try_send(3).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut step).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut step).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2, 3]));
+        let mut step = Box::pin(source.step_by(2));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), step.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), step.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, step.as_mut().poll_recv(&mut cx));
+    }
+}
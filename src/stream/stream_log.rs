@@ -45,6 +45,10 @@ where
             PollRecv::Closed => PollRecv::Closed,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
 }
 
 #[cfg(test)]
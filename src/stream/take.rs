@@ -0,0 +1,182 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct TakeStream<S> {
+    #[pin]
+    stream: S,
+    // the number of items which can still be forwarded.  once zero, the stream is not polled again
+    remaining: usize,
+}
+
+impl<S> TakeStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S, n: usize) -> Self {
+        Self {
+            stream,
+            remaining: n,
+        }
+    }
+}
+
+impl<S> Stream for TakeStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.remaining == 0 {
+            return PollRecv::Closed;
+        }
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                *this.remaining -= 1;
+                PollRecv::Ready(value)
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => {
+                *this.remaining = 0;
+                PollRecv::Closed
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.remaining == 0 {
+            return (0, Some(0));
+        }
+
+        let (lower, upper) = self.stream.size_hint();
+        let upper = match upper {
+            Some(upper) => upper.min(self.remaining),
+            None => self.remaining,
+        };
+
+        (lower.min(self.remaining), Some(upper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::TakeStream;
+
+    #[test]
+    fn take() {
+        let source = from_iter(vec![1, 2, 3]);
+        let mut take = TakeStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn take_zero() {
+        let source = from_iter(vec![1]);
+        let mut take = TakeStream::new(source, 0);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((0, Some(0)), take.size_hint());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn stops_polling_inner() {
+        let polls = Cell::new(0);
+        let source = from_iter((1..).inspect(|_| polls.set(polls.get() + 1)));
+        let mut take = TakeStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(2, polls.get());
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut take = TakeStream::new(source, 1);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut take = TakeStream::new(source, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), take.size_hint());
+    }
+
+    #[test]
+    fn size_hint() {
+        let take = TakeStream::new(from_iter(vec![1, 2, 3]), 2);
+        assert_eq!((2, Some(2)), take.size_hint());
+
+        let take = TakeStream::new(from_iter(vec![1]), 2);
+        assert_eq!((1, Some(1)), take.size_hint());
+
+        let take = TakeStream::new(pending::<usize>(), 2);
+        assert_eq!((0, Some(2)), take.size_hint());
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2, 3]));
+        let mut take = Box::pin(source.take(1));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), take.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, take.as_mut().poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn take_borrowed_receiver() {
+        use crate::{mpsc, sink::Sink};
+
+        let (mut tx, mut rx) = mpsc::channel(4);
+        for i in 1..=3 {
+            tx.send(i).await.unwrap();
+        }
+
+        // the receiver is borrowed, and keeps the messages which were not taken
+        let mut take = (&mut rx).take(2);
+        assert_eq!(Some(1), take.recv().await);
+        assert_eq!(Some(2), take.recv().await);
+        assert_eq!(None, take.recv().await);
+
+        assert_eq!(Some(3), rx.recv().await);
+    }
+}
@@ -0,0 +1,218 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[derive(Copy, Clone)]
+enum State {
+    // the signal is polled before each item
+    Watching,
+    // the signal was closed without an item, and is not polled again
+    Ignored,
+    // the signal fired, or the stream closed.  neither is polled again
+    Closed,
+}
+
+#[pin_project]
+pub struct TakeUntilStream<S, Signal> {
+    #[pin]
+    stream: S,
+    #[pin]
+    signal: Signal,
+    state: State,
+    close_with_signal: bool,
+}
+
+impl<S, Signal> TakeUntilStream<S, Signal>
+where
+    S: Stream,
+    Signal: Stream,
+{
+    pub fn new(stream: S, signal: Signal) -> Self {
+        Self {
+            stream,
+            signal,
+            state: State::Watching,
+            close_with_signal: false,
+        }
+    }
+
+    /// Configures the behavior when the signal is closed without producing an item.
+    ///
+    /// By default, the signal is ignored, and items are forwarded until the stream is closed.  If enabled, the
+    /// stream is closed instead.
+    pub fn close_with_signal(mut self, close: bool) -> Self {
+        self.close_with_signal = close;
+        self
+    }
+}
+
+impl<S, Signal> Stream for TakeUntilStream<S, Signal>
+where
+    S: Stream,
+    Signal: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        // the signal is polled first, so a pending shutdown takes priority over buffered items
+        if let State::Watching = this.state {
+            match this.signal.poll_recv(cx) {
+                PollRecv::Ready(_) => *this.state = State::Closed,
+                PollRecv::Pending => {}
+                PollRecv::Closed => {
+                    *this.state = if *this.close_with_signal {
+                        State::Closed
+                    } else {
+                        State::Ignored
+                    };
+                }
+            }
+        }
+
+        if let State::Closed = this.state {
+            return PollRecv::Closed;
+        }
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => PollRecv::Ready(value),
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => {
+                *this.state = State::Closed;
+                PollRecv::Closed
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.state {
+            State::Watching => (0, self.stream.size_hint().1),
+            State::Ignored => self.stream.size_hint(),
+            State::Closed => (0, Some(0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        barrier,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::TakeUntilStream;
+
+    #[test]
+    fn signal_fires_with_buffered_items() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let (mut signal_tx, signal_rx) = barrier::channel();
+        let mut take = TakeUntilStream::new(rx, signal_rx);
+
+        let mut cx = Context::empty();
+
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+        tx.try_send(3).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+
+        signal_tx.try_send(()).unwrap();
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), take.size_hint());
+    }
+
+    #[test]
+    fn stream_closes_first() {
+        let source = from_iter(vec![1]);
+        let mut take = TakeUntilStream::new(source, pending::<()>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn signal_ready_before_first_item() {
+        let source = panic_after_close(from_iter(vec![1, 2]));
+        let mut take = TakeUntilStream::new(source, from_iter(vec![()]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn signal_closed_is_ignored() {
+        let source = from_iter(vec![1, 2]);
+        let signal = panic_after_close(closed::<()>());
+        let mut take = TakeUntilStream::new(source, signal);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!((1, Some(1)), take.size_hint());
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn close_with_signal() {
+        let source = from_iter(vec![1, 2]);
+        let mut take = TakeUntilStream::new(source, closed::<()>()).close_with_signal(true);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn registers_waker_with_signal() {
+        let (_tx, rx) = crate::mpsc::channel::<usize>(4);
+        let (mut signal_tx, signal_rx) = barrier::channel();
+        let mut take = TakeUntilStream::new(rx, signal_rx);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut take).poll_recv(&mut cx));
+        signal_tx.try_send(()).unwrap();
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1]));
+        let signal = not_unpin(pending::<()>());
+        let mut take = Box::pin(source.take_until(signal));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), take.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, take.as_mut().poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn barrier_shutdown() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let (signal_tx, signal_rx) = barrier::channel();
+        let mut rx = rx.take_until(signal_rx);
+
+        tx.send(1).await.unwrap();
+        assert_eq!(Some(1), rx.recv().await);
+
+        tx.send(2).await.unwrap();
+        drop(signal_tx);
+        assert_eq!(None, rx.recv().await);
+    }
+}
@@ -0,0 +1,174 @@
+use std::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct TakeWhileStream<S, F> {
+    #[pin]
+    stream: S,
+    predicate: F,
+    // set once the predicate fails, or the stream closes.  the stream is not polled again
+    closed: bool,
+}
+
+impl<S, F> TakeWhileStream<S, F>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> bool,
+{
+    pub fn new(stream: S, predicate: F) -> Self {
+        Self {
+            stream,
+            predicate,
+            closed: false,
+        }
+    }
+}
+
+impl<S, F> Stream for TakeWhileStream<S, F>
+where
+    S: Stream,
+    F: FnMut(&S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                if (this.predicate)(&value) {
+                    return PollRecv::Ready(value);
+                }
+
+                // the failing item is dropped
+                *this.closed = true;
+                PollRecv::Closed
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed => {
+                *this.closed = true;
+                PollRecv::Closed
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        (0, self.stream.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::TakeWhileStream;
+
+    #[test]
+    fn take_while() {
+        let source = from_iter(vec![1, 2, 3, 1]);
+        let mut take = TakeWhileStream::new(source, |i| *i < 3);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn stops_polling_inner() {
+        let polls = Cell::new(0);
+        let source = from_iter((1..).inspect(|_| polls.set(polls.get() + 1)));
+        let mut take = TakeWhileStream::new(source, |i| *i != 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(2, polls.get());
+        assert_eq!((0, Some(0)), take.size_hint());
+    }
+
+    #[test]
+    fn predicate_called_once_per_item() {
+        let calls = Cell::new(0);
+        let source = from_iter(vec![1, 2, 3]);
+        let mut take = TakeWhileStream::new(source, |i| {
+            calls.set(calls.get() + 1);
+            *i < 2
+        });
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(2, calls.get());
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut take = TakeWhileStream::new(source, |_| true);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut take).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut take = TakeWhileStream::new(source, |_| true);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut take).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let source = not_unpin(from_iter(vec![1, 2]));
+        let mut take = Box::pin(source.take_while(|i| *i == 1));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), take.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, take.as_mut().poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn until_shutdown_marker() {
+        use crate::{mpsc, sink::Sink};
+
+        let (mut tx, rx) = mpsc::channel(4);
+        tx.send("started").await.unwrap();
+        tx.send("shutdown").await.unwrap();
+        tx.send("unreachable").await.unwrap();
+
+        let mut log = rx.take_while(|line| *line != "shutdown");
+        assert_eq!(Some("started"), log.recv().await);
+        assert_eq!(None, log.recv().await);
+    }
+}
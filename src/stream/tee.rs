@@ -0,0 +1,232 @@
+use std::pin::Pin;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+    Context,
+};
+use pin_project::pin_project;
+
+/// Determines how a [TeeStream](./struct.TeeStream.html) behaves when the side sink is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeePolicy {
+    /// The clone is held until the sink accepts it.  The next item is not received until the clone is sent.
+    Block,
+    /// The clone is dropped, and the item is returned without waiting for the sink.
+    DropOnFull,
+}
+
+/// A stream returned by [Stream::tee](./trait.Stream.html#method.tee), which sends a clone of each item to a side
+/// sink.
+///
+/// If the sink is closed, the stream continues without it.
+#[pin_project]
+pub struct TeeStream<S, K>
+where
+    K: Sink,
+{
+    #[pin]
+    stream: S,
+    #[pin]
+    sink: K,
+    policy: TeePolicy,
+    // a clone which the sink has not yet accepted.  only held with TeePolicy::Block
+    pending: Option<K::Item>,
+    // set once the sink rejects a clone.  the sink is not used again
+    sink_closed: bool,
+}
+
+impl<S, K> TeeStream<S, K>
+where
+    S: Stream<Item = K::Item>,
+    K: Sink,
+    K::Item: Clone,
+{
+    pub fn new(stream: S, sink: K) -> Self {
+        Self {
+            stream,
+            sink,
+            policy: TeePolicy::Block,
+            pending: None,
+            sink_closed: false,
+        }
+    }
+
+    /// Configures the behavior when the sink is full.  By default, `TeePolicy::Block` is used.
+    pub fn policy(mut self, policy: TeePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<S, K> Stream for TeeStream<S, K>
+where
+    S: Stream<Item = K::Item>,
+    K: Sink,
+    K::Item: Clone,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // the held clone is sent before another item is received, so the sink applies backpressure
+        if let Some(value) = this.pending.take() {
+            match this.sink.as_mut().poll_send(cx, value) {
+                PollSend::Ready => {}
+                PollSend::Pending(value) => {
+                    *this.pending = Some(value);
+                    return PollRecv::Pending;
+                }
+                PollSend::Rejected(_value) => *this.sink_closed = true,
+            }
+        }
+
+        let value = match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => value,
+            PollRecv::Pending => return PollRecv::Pending,
+            PollRecv::Closed => return PollRecv::Closed,
+        };
+
+        if !*this.sink_closed {
+            match this.sink.poll_send(cx, value.clone()) {
+                PollSend::Ready => {}
+                PollSend::Pending(clone) => {
+                    if *this.policy == TeePolicy::Block {
+                        *this.pending = Some(clone);
+                    }
+                }
+                PollSend::Rejected(_clone) => *this.sink_closed = true,
+            }
+        }
+
+        PollRecv::Ready(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        mpsc,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{TeePolicy, TeeStream};
+
+    #[test]
+    fn tee() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut tee = TeeStream::new(from_iter(vec![1, 2]), tx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!((2, Some(2)), tee.size_hint());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut tee).poll_recv(&mut cx));
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut rx));
+    }
+
+    #[test]
+    fn block() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut tee = TeeStream::new(from_iter(vec![1, 2, 3]), tx);
+
+        let mut cx = Context::empty();
+
+        // the clone of 2 is held, and 3 is not received until it is sent
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut tee).poll_recv(&mut cx));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(0, count.get());
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        assert_eq!(1, count.get());
+
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut rx));
+
+        // the final clone is sent before the closure is returned
+        assert_eq!(PollRecv::Closed, Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), recv(&mut rx));
+    }
+
+    #[test]
+    fn drop_on_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut tee = TeeStream::new(from_iter(vec![1, 2, 3]), tx).policy(TeePolicy::DropOnFull);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut tee).poll_recv(&mut cx));
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut tee).poll_recv(&mut cx));
+
+        // the clone of 2 was dropped
+        assert_eq!(PollRecv::Ready(3), recv(&mut rx));
+        assert_eq!(PollRecv::Pending, recv(&mut rx));
+    }
+
+    #[test]
+    fn sink_closed() {
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+
+        let mut tee = TeeStream::new(from_iter(vec![1, 2]), tx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut tee).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn sink_closed_while_blocked() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut tee = TeeStream::new(from_iter(vec![1, 2, 3]), tx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut tee).poll_recv(&mut cx));
+
+        // the held clone is dropped, and the stream continues without the sink
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+        drop(rx);
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut tee).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut tee).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let source = not_unpin(from_iter(vec![1]));
+        let mut tee = Box::pin(source.tee(tx));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), tee.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, tee.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), recv(&mut rx));
+    }
+}
@@ -0,0 +1,199 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ThenStream<S, F, Fut> {
+    #[pin]
+    stream: S,
+    then: F,
+    // the future for the last item.  no item is received while it is in flight
+    #[pin]
+    future: Option<Fut>,
+    closed: bool,
+}
+
+impl<S, F, Fut> ThenStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+    pub fn new(stream: S, then: F) -> Self {
+        Self {
+            stream,
+            then,
+            future: None,
+            closed: false,
+        }
+    }
+}
+
+impl<S, F, Fut> Stream for ThenStream<S, F, Fut>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+    type Item = Fut::Output;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(future) = this.future.as_mut().as_pin_mut() {
+                return match cx.with_std(|cx| future.poll(cx)) {
+                    Poll::Ready(output) => {
+                        this.future.set(None);
+                        PollRecv::Ready(output)
+                    }
+                    Poll::Pending => PollRecv::Pending,
+                };
+            }
+
+            if *this.closed {
+                return PollRecv::Closed;
+            }
+
+            match this.stream.as_mut().poll_recv(cx) {
+                PollRecv::Ready(item) => this.future.set(Some((this.then)(item))),
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => {
+                    *this.closed = true;
+                    return PollRecv::Closed;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let in_flight = if self.future.is_some() { 1 } else { 0 };
+        let (lower, upper) = if self.closed {
+            (0, Some(0))
+        } else {
+            self.stream.size_hint()
+        };
+
+        (
+            lower.saturating_add(in_flight),
+            upper.and_then(|upper| upper.checked_add(in_flight)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, pin::Pin};
+
+    use crate::test::{future::pending_once, stream::*};
+    use crate::{
+        oneshot,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::ThenStream;
+
+    #[test]
+    fn then() {
+        let source = from_iter(vec![1, 2]);
+        let mut then = ThenStream::new(source, |i| pending_once(i * 2));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut then).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn wakes_with_future() {
+        let source = from_iter(vec![1]);
+        let mut then = ThenStream::new(source, pending_once);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut then).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn no_pull_while_in_flight() {
+        let pulled = Cell::new(0);
+        let source = from_iter((1..).inspect(|_| pulled.set(pulled.get() + 1)));
+        let mut then = ThenStream::new(source, pending_once);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(1, pulled.get());
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(1, pulled.get());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut then).poll_recv(&mut cx));
+        assert_eq!(2, pulled.get());
+    }
+
+    #[test]
+    fn drains_after_source_closed() {
+        let (mut tx, rx) = oneshot::channel::<usize>();
+        let source = from_iter(vec![rx]);
+        let mut then = Box::pin(ThenStream::new(source, |mut rx| async move {
+            rx.recv().await.unwrap()
+        }));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, then.as_mut().poll_recv(&mut cx));
+        assert_eq!((1, Some(1)), then.size_hint());
+
+        // the source has no more items, but the closure waits for the in-flight future
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), then.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, then.as_mut().poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), then.size_hint());
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut then = Box::pin(ThenStream::new(source, |i| async move { i }));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, then.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), then.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, then.as_mut().poll_recv(&mut cx));
+    }
+
+    #[tokio::test]
+    async fn async_lookup() {
+        use crate::mpsc;
+
+        let (mut tx, rx) = mpsc::channel(4);
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        drop(tx);
+
+        // boxing the futures keeps the stream Unpin
+        let mut lookups = rx.then(|id| {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                format!("user-{}", id)
+            })
+        });
+
+        assert_eq!(Some("user-1".to_string()), lookups.recv().await);
+        assert_eq!(Some("user-2".to_string()), lookups.recv().await);
+        assert_eq!(None, lookups.recv().await);
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    pin::Pin,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+use crate::stream::{PollRecv, Stream};
+use crate::time::{Delay, Sleep};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ThrottleStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    #[pin]
+    stream: S,
+    interval: Duration,
+    // only polled while an item is waiting
+    delay: Delay<T>,
+    // the time the last item was produced
+    last: Option<Instant>,
+    // an item which was received before the interval elapsed
+    waiting: Option<S::Item>,
+}
+
+impl<S, T> ThrottleStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    pub fn new(stream: S, interval: Duration, timer: T) -> Self {
+        Self {
+            stream,
+            interval,
+            delay: Delay::new(timer, Duration::ZERO),
+            last: None,
+            waiting: None,
+        }
+    }
+}
+
+impl<S, T> Stream for ThrottleStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if this.waiting.is_none() {
+            let item = match this.stream.poll_recv(cx) {
+                PollRecv::Ready(item) => item,
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed => return PollRecv::Closed,
+            };
+
+            let now = this.delay.now();
+            let elapsed = this.last.map(|last| now.saturating_duration_since(last));
+
+            match elapsed {
+                Some(elapsed) if elapsed < *this.interval => {
+                    this.delay.reset(*this.interval - elapsed);
+                    *this.waiting = Some(item);
+                }
+                _ => {
+                    *this.last = Some(now);
+                    return PollRecv::Ready(item);
+                }
+            }
+        }
+
+        match this.delay.poll_elapsed(cx) {
+            Poll::Ready(()) => {
+                *this.last = Some(this.delay.now());
+                PollRecv::Ready(this.waiting.take().unwrap())
+            }
+            Poll::Pending => PollRecv::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let waiting = if self.waiting.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+
+        (
+            lower.saturating_add(waiting),
+            upper.and_then(|upper| upper.checked_add(waiting)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::{
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::{
+            stream::{closed, from_iter},
+            time::MockClock,
+        },
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::ThrottleStream;
+
+    #[test]
+    fn first_item_immediate() {
+        let clock = MockClock::new();
+        let mut stream = ThrottleStream::new(from_iter(vec![1]), Duration::from_secs(1), clock);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn delays_ready_items() {
+        let clock = MockClock::new();
+        let mut stream = ThrottleStream::new(
+            from_iter(vec![1, 2, 3]),
+            Duration::from_secs(1),
+            clock.clone(),
+        );
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!((2, Some(2)), stream.size_hint());
+
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        let woken = count.get();
+        clock.advance(Duration::from_millis(500));
+        assert!(count.get() > woken);
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        // the interval restarts when an item is produced
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn interval_elapsed_before_item() {
+        let clock = MockClock::new();
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut stream = ThrottleStream::new(rx, Duration::from_secs(1), clock.clone());
+
+        let mut cx = Context::empty();
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_secs(2));
+        tx.try_send(2).unwrap();
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn timer_not_armed_without_item() {
+        let clock = MockClock::new();
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut stream = ThrottleStream::new(rx, Duration::from_secs(1), clock.clone());
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        // no item is waiting, so the clock doesn't wake the task
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(0, count.get());
+    }
+
+    #[test]
+    fn closed_mid_interval() {
+        let clock = MockClock::new();
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut stream = ThrottleStream::new(rx, Duration::from_secs(1), clock);
+
+        let mut cx = Context::empty();
+
+        tx.try_send(1).unwrap();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closed_without_items() {
+        let clock = MockClock::new();
+        let mut stream = ThrottleStream::new(closed::<usize>(), Duration::from_secs(1), clock);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::time::{pause, sleep, Instant};
+
+    use crate::{mpsc, sink::Sink, stream::Stream};
+
+    #[tokio::test]
+    async fn paces_delivery() {
+        pause();
+
+        let (mut tx, rx) = mpsc::channel(8);
+        let mut rx = rx.throttle(Duration::from_millis(100));
+
+        for i in 1..=3usize {
+            tx.send(i).await.expect("send failed");
+        }
+
+        // the tokio timer rounds deadlines up to the next millisecond
+        let within = |elapsed: Duration, expected: u64| {
+            elapsed >= Duration::from_millis(expected)
+                && elapsed < Duration::from_millis(expected + 10)
+        };
+
+        let start = Instant::now();
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(Duration::from_millis(0), start.elapsed());
+        assert_eq!(Some(2), rx.recv().await);
+        assert!(within(start.elapsed(), 100));
+        assert_eq!(Some(3), rx.recv().await);
+        assert!(within(start.elapsed(), 200));
+
+        // the interval has elapsed, so the next item is not delayed
+        sleep(Duration::from_millis(300)).await;
+        tx.send(4).await.expect("send failed");
+        let resumed = Instant::now();
+        assert_eq!(Some(4), rx.recv().await);
+        assert_eq!(Duration::from_millis(0), resumed.elapsed());
+
+        // the closure is not delayed
+        drop(tx);
+        assert_eq!(None, rx.recv().await);
+        assert_eq!(Duration::from_millis(0), resumed.elapsed());
+    }
+}
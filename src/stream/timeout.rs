@@ -0,0 +1,429 @@
+use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+use crate::stream::{Elapsed, PollRecv, Stream};
+use crate::time::{Delay, Sleep};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct TimeoutStream<S, T: Sleep> {
+    #[pin]
+    stream: S,
+    duration: Duration,
+    delay: Delay<T>,
+    armed: bool,
+    reset_on_elapsed: bool,
+}
+
+impl<S, T> TimeoutStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    pub fn new(stream: S, duration: Duration, timer: T) -> Self {
+        Self {
+            stream,
+            duration,
+            delay: Delay::new(timer, duration),
+            armed: true,
+            reset_on_elapsed: true,
+        }
+    }
+
+    /// Configures whether the timer restarts after an `Elapsed` error is produced (the default).
+    ///
+    /// If disabled, at most one `Elapsed` error is produced for each gap between items.
+    pub fn reset_on_elapsed(mut self, reset: bool) -> Self {
+        self.reset_on_elapsed = reset;
+        self
+    }
+}
+
+impl<S, T> Stream for TimeoutStream<S, T>
+where
+    S: Stream,
+    T: Sleep,
+{
+    type Item = Result<S::Item, Elapsed>;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                this.delay.reset(*this.duration);
+                *this.armed = true;
+                return PollRecv::Ready(Ok(value));
+            }
+            PollRecv::Pending => {}
+            PollRecv::Closed => return PollRecv::Closed,
+        }
+
+        if !*this.armed {
+            return PollRecv::Pending;
+        }
+
+        match this.delay.poll_elapsed(cx) {
+            Poll::Ready(()) => {
+                if *this.reset_on_elapsed {
+                    this.delay.reset(*this.duration);
+                } else {
+                    *this.armed = false;
+                }
+
+                PollRecv::Ready(Err(Elapsed))
+            }
+            Poll::Pending => PollRecv::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // any number of errors may be produced while waiting
+        (self.stream.size_hint().0, None)
+    }
+}
+
+/// A future returned by [Stream::recv_timeout](./trait.Stream.html#method.recv_timeout), which receives an item, or
+/// times out.
+///
+/// Resolves to `Ok(Some(item))` if an item is received within the duration, `Ok(None)` if the stream is closed, and
+/// `Err(Elapsed)` if the timer fires first.
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvTimeoutFuture<'s, S, T>
+where
+    S: Stream + ?Sized,
+    T: Sleep,
+{
+    recv: &'s mut S,
+    delay: Delay<T>,
+}
+
+impl<'s, S, T> RecvTimeoutFuture<'s, S, T>
+where
+    S: Stream + ?Sized,
+    T: Sleep,
+{
+    pub fn new(recv: &'s mut S, duration: Duration, timer: T) -> Self {
+        Self {
+            recv,
+            delay: Delay::new(timer, duration),
+        }
+    }
+}
+
+// the sleep future is boxed, and the timer is never pinned
+impl<'s, S, T> Unpin for RecvTimeoutFuture<'s, S, T>
+where
+    S: Stream + ?Sized,
+    T: Sleep,
+{
+}
+
+impl<'s, S, T> Future for RecvTimeoutFuture<'s, S, T>
+where
+    S: Stream + Unpin + ?Sized,
+    T: Sleep,
+{
+    type Output = Result<Option<S::Item>, Elapsed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut cx: Context<'_> = cx.into();
+
+        // the stream is polled first, so an item which is ready when the timer fires is still received
+        match Pin::new(&mut *this.recv).poll_recv(&mut cx) {
+            PollRecv::Ready(value) => return Poll::Ready(Ok(Some(value))),
+            PollRecv::Pending => {}
+            PollRecv::Closed => return Poll::Ready(Ok(None)),
+        }
+
+        match this.delay.poll_elapsed(&cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Elapsed)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use crate::{
+        stream::{Elapsed, PollRecv, Stream},
+        test::{
+            stream::{from_iter, pending},
+            time::MockClock,
+        },
+    };
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use super::{RecvTimeoutFuture, TimeoutStream};
+
+    #[test]
+    fn elapsed_on_mock_clock() {
+        let clock = MockClock::new();
+        let mut stream =
+            TimeoutStream::new(pending::<usize>(), Duration::from_secs(1), clock.clone());
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1).into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        clock.advance(Duration::from_millis(999));
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(2, w1_count.get());
+        assert_eq!(
+            PollRecv::Ready(Err(Elapsed)),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+
+        // the timer restarts after the error
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            PollRecv::Ready(Err(Elapsed)),
+            Pin::new(&mut stream).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn timer_starts_on_first_poll() {
+        let clock = MockClock::new();
+        let mut stream =
+            TimeoutStream::new(pending::<usize>(), Duration::from_secs(1), clock.clone());
+
+        // the stream is constructed long before it is polled
+        clock.advance(Duration::from_secs(5));
+
+        let mut cx = noop_context().into();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            PollRecv::Ready(Err(Elapsed)),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn recv_timeout_on_mock_clock() {
+        let clock = MockClock::new();
+        let mut source = pending::<usize>();
+        let mut recv = RecvTimeoutFuture::new(&mut source, Duration::from_secs(1), clock.clone());
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Poll::Pending, Pin::new(&mut recv).poll(&mut cx));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(1, count.get());
+        assert_eq!(Poll::Ready(Err(Elapsed)), Pin::new(&mut recv).poll(&mut cx));
+    }
+
+    #[test]
+    fn recv_timeout_ready() {
+        let clock = MockClock::new();
+        let mut source = from_iter(vec![1]);
+
+        let mut cx = noop_context();
+
+        let mut recv = RecvTimeoutFuture::new(&mut source, Duration::from_secs(1), clock.clone());
+        assert_eq!(Poll::Ready(Ok(Some(1))), Pin::new(&mut recv).poll(&mut cx));
+
+        let mut recv = RecvTimeoutFuture::new(&mut source, Duration::from_secs(1), clock);
+        assert_eq!(Poll::Ready(Ok(None)), Pin::new(&mut recv).poll(&mut cx));
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::time::{sleep, timeout, Instant};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{Elapsed, Stream},
+    };
+
+    #[test]
+    fn constructed_outside_runtime() {
+        let (_tx, rx) = mpsc::channel::<usize>(4);
+        let mut rx = rx.timeout(Duration::from_millis(10));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("failed to build the runtime");
+        runtime.block_on(async move {
+            assert_eq!(Some(Err(Elapsed)), rx.recv().await);
+        });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ready_then_elapsed() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx.timeout(Duration::from_millis(100));
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(Some(Ok(1)), rx.recv().await);
+
+        let start = Instant::now();
+        assert_eq!(Some(Err(Elapsed)), rx.recv().await);
+        assert_eq!(Duration::from_millis(100), start.elapsed());
+
+        assert_eq!(Some(Err(Elapsed)), rx.recv().await);
+        assert_eq!(Duration::from_millis(200), start.elapsed());
+
+        tx.send(2usize).await.expect("send failed");
+        assert_eq!(Some(Ok(2)), rx.recv().await);
+
+        drop(tx);
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn item_resets_timer() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx.timeout(Duration::from_millis(100));
+
+        tokio::spawn(async move {
+            for i in 0..3usize {
+                sleep(Duration::from_millis(60)).await;
+                tx.send(i).await.expect("send failed");
+            }
+        });
+
+        assert_eq!(Some(Ok(0)), rx.recv().await);
+        assert_eq!(Some(Ok(1)), rx.recv().await);
+        assert_eq!(Some(Ok(2)), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn elapsed_once_per_gap() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx
+            .timeout(Duration::from_millis(100))
+            .reset_on_elapsed(false);
+
+        assert_eq!(Some(Err(Elapsed)), rx.recv().await);
+        assert!(timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .is_err());
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(Some(Ok(1)), rx.recv().await);
+        assert_eq!(Some(Err(Elapsed)), rx.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recv_timeout() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+
+        let start = Instant::now();
+        assert_eq!(
+            Err(Elapsed),
+            rx.recv_timeout(Duration::from_millis(100)).await
+        );
+        assert_eq!(Duration::from_millis(100), start.elapsed());
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(
+            Ok(Some(1)),
+            rx.recv_timeout(Duration::from_millis(100)).await
+        );
+
+        drop(tx);
+        assert_eq!(Ok(None), rx.recv_timeout(Duration::from_millis(100)).await);
+    }
+}
+
+#[cfg(all(test, feature = "async-std"))]
+mod async_std_tests {
+    use std::time::{Duration, Instant};
+
+    use async_std::task::{sleep, spawn};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{Elapsed, Stream},
+        time::AsyncStd,
+    };
+
+    #[async_std::test]
+    async fn ready_then_elapsed() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx.timeout_with(Duration::from_millis(50), AsyncStd);
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(Some(Ok(1)), rx.recv().await);
+
+        let start = Instant::now();
+        assert_eq!(Some(Err(Elapsed)), rx.recv().await);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        tx.send(2usize).await.expect("send failed");
+        assert_eq!(Some(Ok(2)), rx.recv().await);
+
+        drop(tx);
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[async_std::test]
+    async fn item_resets_timer() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let mut rx = rx.timeout_with(Duration::from_millis(500), AsyncStd);
+
+        spawn(async move {
+            for i in 0..3usize {
+                sleep(Duration::from_millis(20)).await;
+                tx.send(i).await.expect("send failed");
+            }
+        });
+
+        assert_eq!(Some(Ok(0)), rx.recv().await);
+        assert_eq!(Some(Ok(1)), rx.recv().await);
+        assert_eq!(Some(Ok(2)), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+
+    #[async_std::test]
+    async fn recv_timeout() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+
+        let start = Instant::now();
+        assert_eq!(
+            Err(Elapsed),
+            rx.recv_timeout_with(Duration::from_millis(50), AsyncStd)
+                .await
+        );
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(
+            Ok(Some(1)),
+            rx.recv_timeout_with(Duration::from_millis(50), AsyncStd)
+                .await
+        );
+    }
+}
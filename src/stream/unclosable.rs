@@ -0,0 +1,441 @@
+use std::{future::Future, marker::PhantomPinned, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::sink::Sink;
+use crate::stream::{
+    chain::ChainStream,
+    chunks::ChunksStream,
+    cycle::CycleStream,
+    dedup::{DedupByKeyStream, DedupStream},
+    enumerate::EnumerateStream,
+    filter::FilterStream,
+    filter_async::FilterAsyncStream,
+    filter_map::FilterMapStream,
+    flat_map::FlatMapStream,
+    flatten::FlattenStream,
+    fuse::FuseStream,
+    inspect::InspectStream,
+    map::MapStream,
+    map_concurrent::{MapConcurrentStream, MapConcurrentUnorderedStream},
+    merge::MergeStream,
+    merge_sorted::MergeSortedStream,
+    peekable::PeekableStream,
+    record::{Record, RecordingStream},
+    repeat::RepeatStream,
+    skip::SkipStream,
+    skip_while::SkipWhileStream,
+    step_by::StepByStream,
+    tee::TeeStream,
+    then::ThenStream,
+    zip::ZipStream,
+    DebounceStream, HeartbeatStream, PollRecv, Stream, ThrottleStream, TimeoutStream,
+};
+use crate::time::Sleep;
+
+/// A marker for streams which never return `PollRecv::Closed`.
+///
+/// Unclosable streams can be received with [recv_always](#method.recv_always), which returns the item without
+/// an `Option`.  The marker is implemented by [repeat](./fn.repeat.html), and propagated by combinators which can
+/// only close when their source closes, such as `map`, `filter`, `enumerate`, `then` and `timeout`.
+///
+/// Combinators which may close on their own drop the marker: `find`, `find_map`, `gate`, `map_while`, `scan`,
+/// `take`, `take_until` and `take_while`.  So do `buffered`, `boxed`, `boxed_local` and `chain_all`, as they
+/// erase the type of their source.
+///
+/// Channel receivers do not implement the marker, as they are closed when the senders are dropped.
+///
+/// ```rust
+/// use postage::stream::{self, Stream, UnclosableStream};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut ticks = stream::repeat(1usize).map(|i| i * 2);
+///     let tick: usize = ticks.recv_always().await;
+///     assert_eq!(2, tick);
+/// }
+/// ```
+///
+/// Streams which may close do not provide `recv_always`:
+/// ```compile_fail
+/// use postage::stream::{self, Stream, UnclosableStream};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut once = stream::once(1usize);
+///     once.recv_always().await;
+/// }
+/// ```
+pub trait UnclosableStream: Stream {
+    /// Retrieves a message from the stream.  As the stream is never closed, the message is always returned.
+    fn recv_always(&mut self) -> RecvAlwaysFuture<'_, Self>
+    where
+        Self: Unpin,
+    {
+        RecvAlwaysFuture::new(self)
+    }
+}
+
+/// A future returned by `UnclosableStream::recv_always`.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvAlwaysFuture<'s, S>
+where
+    S: UnclosableStream + ?Sized,
+{
+    recv: &'s mut S,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl<'s, S> RecvAlwaysFuture<'s, S>
+where
+    S: UnclosableStream + ?Sized,
+{
+    pub fn new(recv: &'s mut S) -> RecvAlwaysFuture<'s, S> {
+        Self {
+            recv,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl<'s, S> Future for RecvAlwaysFuture<'s, S>
+where
+    S: UnclosableStream + Unpin + ?Sized,
+{
+    type Output = S::Item;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut cx: crate::Context<'_> = cx.into();
+        match Pin::new(this.recv).poll_recv(&mut cx) {
+            PollRecv::Ready(v) => Poll::Ready(v),
+            PollRecv::Pending => Poll::Pending,
+            PollRecv::Closed => panic!("an UnclosableStream returned PollRecv::Closed"),
+        }
+    }
+}
+
+impl<S> UnclosableStream for &mut S where S: UnclosableStream + Unpin + ?Sized {}
+
+impl<P, S> UnclosableStream for Pin<P>
+where
+    P: std::ops::DerefMut<Target = S> + Unpin,
+    S: UnclosableStream + Unpin + ?Sized,
+{
+}
+
+// repeat produces a clone of the item on every poll
+impl<T> UnclosableStream for RepeatStream<T> where T: Clone {}
+
+// map, filter, timeout and heartbeat are closed only when the source is closed
+impl<From, Map, Into> UnclosableStream for MapStream<From, Map, Into>
+where
+    From: UnclosableStream,
+    Map: Fn(From::Item) -> Into,
+{
+}
+
+impl<From, Filter> UnclosableStream for FilterStream<From, Filter>
+where
+    From: UnclosableStream,
+    Filter: FnMut(&From::Item) -> bool,
+{
+}
+
+impl<S, T> UnclosableStream for TimeoutStream<S, T>
+where
+    S: UnclosableStream,
+    T: Sleep,
+{
+}
+
+impl<S, F, T> UnclosableStream for HeartbeatStream<S, F, T>
+where
+    S: UnclosableStream,
+    F: FnMut() -> S::Item,
+    T: Sleep,
+{
+}
+
+#[cfg(feature = "logging")]
+impl<S> UnclosableStream for crate::stream::stream_log::StreamLog<S>
+where
+    S: UnclosableStream,
+    S::Item: std::fmt::Debug,
+{
+}
+
+// the combinators below are also closed only when the source is closed
+impl<S, F> UnclosableStream for InspectStream<S, F>
+where
+    S: UnclosableStream,
+    F: FnMut(&S::Item),
+{
+}
+
+impl<S, F, Fut> UnclosableStream for ThenStream<S, F, Fut>
+where
+    S: UnclosableStream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+}
+
+impl<S, F, Fut> UnclosableStream for MapConcurrentStream<S, F, Fut>
+where
+    S: UnclosableStream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+}
+
+impl<S, F, Fut> UnclosableStream for MapConcurrentUnorderedStream<S, F, Fut>
+where
+    S: UnclosableStream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future,
+{
+}
+
+impl<S, F, Fut> UnclosableStream for FilterAsyncStream<S, F, Fut>
+where
+    S: UnclosableStream,
+    F: FnMut(&S::Item) -> Fut,
+    Fut: Future<Output = bool>,
+{
+}
+
+impl<From, Map, Into> UnclosableStream for FilterMapStream<From, Map, Into>
+where
+    From: UnclosableStream,
+    Map: FnMut(From::Item) -> Option<Into>,
+{
+}
+
+// flatten and flat_map move on to the next inner stream when one is closed
+impl<S> UnclosableStream for FlattenStream<S>
+where
+    S: UnclosableStream,
+    S::Item: Stream,
+{
+}
+
+impl<S, F, Inner> UnclosableStream for FlatMapStream<S, F, Inner>
+where
+    S: UnclosableStream,
+    F: FnMut(S::Item) -> Inner,
+    Inner: Stream,
+{
+}
+
+impl<S> UnclosableStream for ChunksStream<S> where S: UnclosableStream {}
+
+impl<S> UnclosableStream for CycleStream<S>
+where
+    S: UnclosableStream,
+    S::Item: Clone,
+{
+}
+
+impl<S> UnclosableStream for PeekableStream<S> where S: UnclosableStream {}
+
+impl<S> UnclosableStream for FuseStream<S> where S: UnclosableStream {}
+
+impl<S> UnclosableStream for EnumerateStream<S> where S: UnclosableStream {}
+
+impl<S> UnclosableStream for SkipStream<S> where S: UnclosableStream {}
+
+impl<S, F> UnclosableStream for SkipWhileStream<S, F>
+where
+    S: UnclosableStream,
+    F: FnMut(&S::Item) -> bool,
+{
+}
+
+impl<S> UnclosableStream for StepByStream<S> where S: UnclosableStream {}
+
+impl<S> UnclosableStream for DedupStream<S>
+where
+    S: UnclosableStream,
+    S::Item: PartialEq + Clone,
+{
+}
+
+impl<S, F, K> UnclosableStream for DedupByKeyStream<S, F, K>
+where
+    S: UnclosableStream,
+    F: FnMut(&S::Item) -> K,
+    K: PartialEq,
+{
+}
+
+impl<S, T> UnclosableStream for DebounceStream<S, T>
+where
+    S: UnclosableStream,
+    T: Sleep,
+{
+}
+
+impl<S, T> UnclosableStream for ThrottleStream<S, T>
+where
+    S: UnclosableStream,
+    T: Sleep,
+{
+}
+
+// tee and record keep forwarding items after their sink is closed
+impl<S, K> UnclosableStream for TeeStream<S, K>
+where
+    S: UnclosableStream<Item = K::Item>,
+    K: Sink,
+    K::Item: Clone,
+{
+}
+
+impl<S, K, T> UnclosableStream for RecordingStream<S, K, T>
+where
+    S: UnclosableStream,
+    S::Item: Clone,
+    K: Sink<Item = Record<S::Item>>,
+    T: Sleep,
+{
+}
+
+// a zip is closed when either stream is closed
+impl<Left, Right> UnclosableStream for ZipStream<Left, Right>
+where
+    Left: UnclosableStream,
+    Right: UnclosableStream,
+{
+}
+
+// a chain is closed when the right stream is closed
+impl<Left, Right> UnclosableStream for ChainStream<Left, Right>
+where
+    Left: Stream,
+    Right: UnclosableStream<Item = Left::Item>,
+{
+}
+
+// a merge is closed when both streams are closed.  only the left stream must be unclosable,
+// as impls for either side would overlap
+impl<Left, Right> UnclosableStream for MergeStream<Left, Right>
+where
+    Left: UnclosableStream,
+    Right: Stream<Item = Left::Item>,
+{
+}
+
+// a sorted merge is closed when both streams are closed
+impl<Left, Right, Key, K> UnclosableStream for MergeSortedStream<Left, Right, Key>
+where
+    Left: UnclosableStream,
+    Right: Stream<Item = Left::Item>,
+    Key: FnMut(&Left::Item) -> K,
+    K: Ord,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use crate::{
+        mpsc,
+        stream::{
+            self, chain::ChainStream, enumerate::EnumerateStream, filter::FilterStream,
+            find::FindStream, gate::GateStream, inspect::InspectStream, map::MapStream,
+            map_while::MapWhileStream, merge::MergeStream, merge_sorted::MergeSortedStream,
+            once::OnceStream, repeat::RepeatStream, scan::ScanStream, skip::SkipStream,
+            take::TakeStream, take_while::TakeWhileStream, then::ThenStream, zip::ZipStream,
+            HeartbeatStream, Stream, ThrottleStream, TimeoutStream,
+        },
+        test::time::MockClock,
+        watch,
+    };
+
+    use super::UnclosableStream;
+
+    type Repeat = RepeatStream<usize>;
+    type Once = OnceStream<usize>;
+    type Map = fn(usize) -> usize;
+    type Filter = fn(&usize) -> bool;
+    type Key = fn(&usize) -> usize;
+    type Heartbeat = fn() -> usize;
+    type Inspect = fn(&usize);
+    type Then = fn(usize) -> std::future::Ready<usize>;
+    type MapWhile = fn(usize) -> Option<usize>;
+    type Scan = fn(&mut usize, usize) -> Option<usize>;
+
+    assert_impl_all!(Repeat: UnclosableStream);
+    assert_impl_all!(&'static mut Repeat: UnclosableStream);
+    assert_impl_all!(Pin<Box<Repeat>>: UnclosableStream);
+    assert_impl_all!(MapStream<Repeat, Map, usize>: UnclosableStream);
+    assert_impl_all!(FilterStream<Repeat, Filter>: UnclosableStream);
+    assert_impl_all!(TimeoutStream<Repeat, MockClock>: UnclosableStream);
+    assert_impl_all!(HeartbeatStream<Repeat, Heartbeat, MockClock>: UnclosableStream);
+    assert_impl_all!(ChainStream<Once, Repeat>: UnclosableStream);
+    assert_impl_all!(MergeStream<Repeat, Repeat>: UnclosableStream);
+    assert_impl_all!(MergeStream<Repeat, Once>: UnclosableStream);
+    assert_impl_all!(MergeSortedStream<Repeat, Once, Key>: UnclosableStream);
+    assert_impl_all!(EnumerateStream<Repeat>: UnclosableStream);
+    assert_impl_all!(InspectStream<Repeat, Inspect>: UnclosableStream);
+    assert_impl_all!(SkipStream<Repeat>: UnclosableStream);
+    assert_impl_all!(ThenStream<Repeat, Then, std::future::Ready<usize>>: UnclosableStream);
+    assert_impl_all!(ThrottleStream<Repeat, MockClock>: UnclosableStream);
+    assert_impl_all!(ZipStream<Repeat, Repeat>: UnclosableStream);
+
+    assert_not_impl_any!(Once: UnclosableStream);
+    assert_not_impl_any!(MapStream<Once, Map, usize>: UnclosableStream);
+    assert_not_impl_any!(FilterStream<Once, Filter>: UnclosableStream);
+    assert_not_impl_any!(FindStream<Repeat, Filter>: UnclosableStream);
+    assert_not_impl_any!(GateStream<Repeat>: UnclosableStream);
+    assert_not_impl_any!(ChainStream<Repeat, Once>: UnclosableStream);
+    assert_not_impl_any!(MergeStream<Once, Once>: UnclosableStream);
+    assert_not_impl_any!(MergeSortedStream<Once, Repeat, Key>: UnclosableStream);
+    assert_not_impl_any!(EnumerateStream<Once>: UnclosableStream);
+    assert_not_impl_any!(ZipStream<Repeat, Once>: UnclosableStream);
+    assert_not_impl_any!(TakeStream<Repeat>: UnclosableStream);
+    assert_not_impl_any!(TakeWhileStream<Repeat, Filter>: UnclosableStream);
+    assert_not_impl_any!(MapWhileStream<Repeat, MapWhile, usize>: UnclosableStream);
+    assert_not_impl_any!(ScanStream<Repeat, usize, Scan, usize>: UnclosableStream);
+    assert_not_impl_any!(mpsc::Receiver<usize>: UnclosableStream);
+    assert_not_impl_any!(watch::Receiver<usize>: UnclosableStream);
+
+    #[tokio::test]
+    async fn recv_always() {
+        let mut stream = stream::once(1usize)
+            .chain(stream::repeat(2usize))
+            .map(|i| i * 10);
+
+        assert_eq!(10, stream.recv_always().await);
+        assert_eq!(20, stream.recv_always().await);
+    }
+
+    #[tokio::test]
+    async fn recv_always_merge() {
+        let mut stream = stream::repeat(1usize).merge(stream::once(2usize));
+
+        for _ in 0..4 {
+            let item = stream.recv_always().await;
+            assert!(item == 1 || item == 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn recv_always_combinators() {
+        let mut stream = stream::repeat(1usize)
+            .enumerate()
+            .skip(2)
+            .inspect(|_| {})
+            .then(|(i, item)| std::future::ready(i * item));
+
+        assert_eq!(2, stream.recv_always().await);
+        assert_eq!(3, stream.recv_always().await);
+    }
+}
@@ -0,0 +1,324 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+};
+
+/// A future returned by [Stream::unzip](./trait.Stream.html#method.unzip), which sends each half of the stream's
+/// pairs to a separate sink.
+///
+/// Resolves to `Ok(())` once the stream is closed and every half has been sent.  If either sink rejects a half,
+/// resolves to `Err((left, right))` with the halves which were not sent.  Panics if polled after completion.
+#[pin_project]
+#[must_use = "futures do nothing unless polled"]
+pub struct UnzipFuture<S, SA, SB>
+where
+    SA: Sink,
+    SB: Sink,
+{
+    #[pin]
+    stream: S,
+    #[pin]
+    left: SA,
+    #[pin]
+    right: SB,
+    // halves are kept until the sink accepts them.  a new pair is only received once both are sent
+    left_value: Option<SA::Item>,
+    right_value: Option<SB::Item>,
+    complete: bool,
+}
+
+impl<S, SA, SB> UnzipFuture<S, SA, SB>
+where
+    S: Stream<Item = (SA::Item, SB::Item)>,
+    SA: Sink,
+    SB: Sink,
+{
+    pub fn new(stream: S, left: SA, right: SB) -> Self {
+        Self {
+            stream,
+            left,
+            right,
+            left_value: None,
+            right_value: None,
+            complete: false,
+        }
+    }
+}
+
+impl<S, SA, SB> Future for UnzipFuture<S, SA, SB>
+where
+    S: Stream<Item = (SA::Item, SB::Item)>,
+    SA: Sink,
+    SB: Sink,
+{
+    type Output = Result<(), (Option<SA::Item>, Option<SB::Item>)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        assert!(!*this.complete, "UnzipFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            let mut rejected = false;
+
+            if let Some(value) = this.left_value.take() {
+                match this.left.as_mut().poll_send(&mut cx, value) {
+                    PollSend::Ready => {}
+                    PollSend::Pending(value) => *this.left_value = Some(value),
+                    PollSend::Rejected(value) => {
+                        *this.left_value = Some(value);
+                        rejected = true;
+                    }
+                }
+            }
+
+            if !rejected {
+                if let Some(value) = this.right_value.take() {
+                    match this.right.as_mut().poll_send(&mut cx, value) {
+                        PollSend::Ready => {}
+                        PollSend::Pending(value) => *this.right_value = Some(value),
+                        PollSend::Rejected(value) => {
+                            *this.right_value = Some(value);
+                            rejected = true;
+                        }
+                    }
+                }
+            }
+
+            if rejected {
+                *this.complete = true;
+                return Poll::Ready(Err((this.left_value.take(), this.right_value.take())));
+            }
+
+            if this.left_value.is_some() || this.right_value.is_some() {
+                return Poll::Pending;
+            }
+
+            match this.stream.as_mut().poll_recv(&mut cx) {
+                PollRecv::Ready((left, right)) => {
+                    *this.left_value = Some(left);
+                    *this.right_value = Some(right);
+                }
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed => {
+                    *this.complete = true;
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, task::Poll};
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{PollRecv, Stream},
+        test::stream::{closed, from_iter, from_poll_iter, recv},
+    };
+
+    use super::UnzipFuture;
+
+    #[test]
+    fn empty() {
+        let (left, _left_rx) = mpsc::channel::<usize>(1);
+        let (right, _right_rx) = mpsc::channel::<char>(1);
+        let mut unzip = Box::pin(UnzipFuture::new(closed(), left, right));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn unzip() {
+        let (left, mut left_rx) = mpsc::channel(4);
+        let (right, mut right_rx) = mpsc::channel(4);
+        let stream = from_iter(vec![(1, 'a'), (2, 'b')]);
+        let mut unzip = Box::pin(UnzipFuture::new(stream, left, right));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        drop(unzip);
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut left_rx));
+        assert_eq!(PollRecv::Closed, recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready('a'), recv(&mut right_rx));
+        assert_eq!(PollRecv::Ready('b'), recv(&mut right_rx));
+        assert_eq!(PollRecv::Closed, recv(&mut right_rx));
+    }
+
+    #[test]
+    fn pending_stream() {
+        let (left, mut left_rx) = mpsc::channel(4);
+        let (right, mut right_rx) = mpsc::channel(4);
+        let stream = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready((1, 'a'))]);
+        let mut unzip = Box::pin(UnzipFuture::new(stream, left, right));
+
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut noop_context()));
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready('a'), recv(&mut right_rx));
+    }
+
+    #[test]
+    fn left_backpressure() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, mut right_rx) = mpsc::channel(4);
+
+        tx.try_send((1, 'a')).unwrap();
+        tx.try_send((2, 'b')).unwrap();
+        tx.try_send((3, 'c')).unwrap();
+
+        let mut unzip = Box::pin(UnzipFuture::new(rx, left, right));
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut noop_context()));
+
+        // 2 is in flight on the left, and 3 has not been received
+        assert_eq!(PollRecv::Ready('a'), recv(&mut right_rx));
+        assert_eq!(PollRecv::Ready('b'), recv(&mut right_rx));
+        assert_eq!(PollRecv::Pending, recv(&mut right_rx));
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Ready('c'), recv(&mut right_rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut left_rx));
+
+        drop(tx);
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Ready(3), recv(&mut left_rx));
+    }
+
+    #[test]
+    fn right_backpressure() {
+        let (mut tx, rx) = mpsc::channel(4);
+        let (left, mut left_rx) = mpsc::channel(4);
+        let (right, mut right_rx) = mpsc::channel(1);
+
+        tx.try_send((1, 'a')).unwrap();
+        tx.try_send((2, 'b')).unwrap();
+        tx.try_send((3, 'c')).unwrap();
+
+        let mut unzip = Box::pin(UnzipFuture::new(rx, left, right));
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut noop_context()));
+
+        // the left has capacity, but 3 is not received while 'b' is pending on the right
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready(2), recv(&mut left_rx));
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Pending, recv(&mut left_rx));
+
+        assert_eq!(PollRecv::Ready('a'), recv(&mut right_rx));
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut noop_context()));
+        assert_eq!(PollRecv::Ready(3), recv(&mut left_rx));
+        assert_eq!(PollRecv::Ready('b'), recv(&mut right_rx));
+
+        drop(tx);
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Ready('c'), recv(&mut right_rx));
+    }
+
+    #[test]
+    fn wakes_when_sink_accepts() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, _right_rx) = mpsc::channel(1);
+        let stream = from_iter(vec![(1, 'a'), (2, 'b')]);
+        let mut unzip = Box::pin(UnzipFuture::new(stream, left, right));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        assert_eq!(Poll::Pending, unzip.as_mut().poll(&mut cx));
+        assert_eq!(0, count.get());
+
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(1, count.get());
+    }
+
+    #[test]
+    fn left_rejected() {
+        let (left, left_rx) = mpsc::channel::<usize>(4);
+        let (right, mut right_rx) = mpsc::channel(4);
+        drop(left_rx);
+
+        let stream = from_iter(vec![(1, 'a'), (2, 'b')]);
+        let mut unzip = Box::pin(UnzipFuture::new(stream, left, right));
+
+        assert_eq!(
+            Poll::Ready(Err((Some(1), Some('a')))),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Pending, recv(&mut right_rx));
+    }
+
+    #[test]
+    fn right_rejected() {
+        let (left, mut left_rx) = mpsc::channel(4);
+        let (right, right_rx) = mpsc::channel::<char>(4);
+        drop(right_rx);
+
+        let stream = from_iter(vec![(1, 'a'), (2, 'b')]);
+        let mut unzip = Box::pin(UnzipFuture::new(stream, left, right));
+
+        assert_eq!(
+            Poll::Ready(Err((None, Some('a')))),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        assert_eq!(PollRecv::Ready(1), recv(&mut left_rx));
+        assert_eq!(PollRecv::Pending, recv(&mut left_rx));
+    }
+
+    #[test]
+    #[should_panic]
+    fn poll_after_completion() {
+        let (left, _left_rx) = mpsc::channel::<usize>(1);
+        let (right, _right_rx) = mpsc::channel::<char>(1);
+        let mut unzip = Box::pin(UnzipFuture::new(closed(), left, right));
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            unzip.as_mut().poll(&mut noop_context())
+        );
+        let _ = unzip.as_mut().poll(&mut noop_context());
+    }
+
+    #[tokio::test]
+    async fn unzip_channels() {
+        let (left, mut left_rx) = mpsc::channel(1);
+        let (right, mut right_rx) = mpsc::channel(1);
+        let stream = from_iter((0..16).map(|i| (i, i * 2)));
+
+        let join = tokio::spawn(stream.unzip(left, right));
+
+        for i in 0..16 {
+            assert_eq!(Some(i), left_rx.recv().await);
+            assert_eq!(Some(i * 2), right_rx.recv().await);
+        }
+
+        assert_eq!(Ok(()), join.await.unwrap());
+        assert_eq!(None, left_rx.recv().await);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::stream::{PollRecv, Stream};
+use pin_project::pin_project;
+use std::pin::Pin;
+
+use crate::Context;
+
+#[pin_project]
+pub struct ZipStream<Left, Right>
+where
+    Left: Stream,
+    Right: Stream,
+{
+    #[pin]
+    left: Left,
+    #[pin]
+    right: Right,
+    left_item: Option<Left::Item>,
+    right_item: Option<Right::Item>,
+    closed: bool,
+}
+
+impl<Left, Right> ZipStream<Left, Right>
+where
+    Left: Stream,
+    Right: Stream,
+{
+    pub fn new(left: Left, right: Right) -> Self {
+        Self {
+            left,
+            right,
+            left_item: None,
+            right_item: None,
+            closed: false,
+        }
+    }
+}
+
+impl<Left, Right> Stream for ZipStream<Left, Right>
+where
+    Left: Stream,
+    Right: Stream,
+{
+    type Item = (Left::Item, Right::Item);
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.closed {
+            return PollRecv::Closed;
+        }
+
+        // a side is only polled while it has no buffered item.  if it closes, the pair can never be completed
+        if this.left_item.is_none() {
+            match this.left.poll_recv(cx) {
+                PollRecv::Ready(item) => *this.left_item = Some(item),
+                PollRecv::Pending => {}
+                PollRecv::Closed => {
+                    *this.closed = true;
+                    this.right_item.take();
+                    return PollRecv::Closed;
+                }
+            }
+        }
+
+        if this.right_item.is_none() {
+            match this.right.poll_recv(cx) {
+                PollRecv::Ready(item) => *this.right_item = Some(item),
+                PollRecv::Pending => {}
+                PollRecv::Closed => {
+                    *this.closed = true;
+                    this.left_item.take();
+                    return PollRecv::Closed;
+                }
+            }
+        }
+
+        if this.left_item.is_some() && this.right_item.is_some() {
+            let left = this.left_item.take().unwrap();
+            let right = this.right_item.take().unwrap();
+            return PollRecv::Ready((left, right));
+        }
+
+        PollRecv::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.closed {
+            return (0, Some(0));
+        }
+
+        let (left_lower, left_upper) = hint_with_buffer(self.left.size_hint(), &self.left_item);
+        let (right_lower, right_upper) = hint_with_buffer(self.right.size_hint(), &self.right_item);
+
+        let lower = left_lower.min(right_lower);
+        let upper = match (left_upper, right_upper) {
+            (Some(left), Some(right)) => Some(left.min(right)),
+            (Some(left), None) => Some(left),
+            (None, Some(right)) => Some(right),
+            (None, None) => None,
+        };
+
+        (lower, upper)
+    }
+}
+
+fn hint_with_buffer<T>(
+    (lower, upper): (usize, Option<usize>),
+    buffered: &Option<T>,
+) -> (usize, Option<usize>) {
+    match buffered {
+        Some(_) => (
+            lower.saturating_add(1),
+            upper.and_then(|upper| upper.checked_add(1)),
+        ),
+        None => (lower, upper),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::ZipStream;
+
+    #[test]
+    fn zip() {
+        let left = from_iter(vec![1, 2]);
+        let right = from_iter(vec!['a', 'b', 'c']);
+        let mut zip = ZipStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready((1, 'a')),
+            Pin::new(&mut zip).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((2, 'b')),
+            Pin::new(&mut zip).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut zip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, Pin::new(&mut zip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn buffers_left_until_right_ready() {
+        // the left side would close if it were polled again before the pair is complete
+        let left = from_poll_iter(vec![PollRecv::Ready(1), PollRecv::Closed]);
+        let right = from_poll_iter(vec![
+            PollRecv::Pending,
+            PollRecv::Pending,
+            PollRecv::Pending,
+            PollRecv::Ready('a'),
+        ]);
+        let mut zip = ZipStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut zip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut zip).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut zip).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready((1, 'a')),
+            Pin::new(&mut zip).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Closed, Pin::new(&mut zip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn closes_with_buffered_item() {
+        let left = from_poll_iter(vec![PollRecv::Ready(1), PollRecv::Ready(2)]);
+        let right = from_poll_iter(vec![PollRecv::<char>::Pending, PollRecv::Closed]);
+        let mut zip = ZipStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut zip).poll_recv(&mut cx));
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut zip).poll_recv(&mut cx));
+        assert_eq!((0, Some(0)), zip.size_hint());
+        assert_eq!(PollRecv::Closed, Pin::new(&mut zip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pending_registers_both() {
+        let (left_tx, left) = crate::mpsc::channel::<usize>(4);
+        let (right_tx, right) = crate::mpsc::channel::<usize>(4);
+        let mut zip = ZipStream::new(left, right);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker).into();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut zip).poll_recv(&mut cx));
+
+        drop(right_tx);
+        assert_eq!(1, count.get());
+
+        drop(left_tx);
+        assert_eq!(2, count.get());
+
+        assert_eq!(PollRecv::Closed, Pin::new(&mut zip).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn not_unpin_inner() {
+        let left = not_unpin(from_iter(vec![1]));
+        let right = not_unpin(from_iter(vec![2]));
+        let mut zip = Box::pin(left.zip(right));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready((1, 2)), zip.as_mut().poll_recv(&mut cx));
+        assert_eq!(PollRecv::Closed, zip.as_mut().poll_recv(&mut cx));
+    }
+}
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 use notifier::Notifier;
 use ref_count::RefCount;
@@ -8,12 +11,14 @@ use crate::Context;
 
 use self::{notifier::NotificationGuard, ref_count::TryDecrement};
 
+pub(crate) mod deferred;
 pub mod mpmc_circular_buffer;
 pub mod notifier;
 mod oneshot_cell;
 mod ref_count;
 // mod rr_lock;
 mod state_cell;
+pub(crate) mod static_queue;
 pub(crate) mod transfer;
 
 pub(crate) fn shared<E>(extension: E) -> (SenderShared<E>, ReceiverShared<E>) {
@@ -28,12 +33,69 @@ pub(crate) fn shared<E>(extension: E) -> (SenderShared<E>, ReceiverShared<E>) {
     (sender, receiver)
 }
 
+// The counters of a channel, which do not depend on the message type.
+// With the `registry` feature, they are shared with the registry, which reads them from the snapshot thread.
+#[derive(Debug)]
+pub(crate) struct ChannelCounters {
+    pub(crate) senders: RefCount,
+    pub(crate) receivers: RefCount,
+    // the number of buffered messages, as last published by the channel
+    #[cfg(feature = "registry")]
+    pub(crate) len: std::sync::atomic::AtomicUsize,
+}
+
+impl ChannelCounters {
+    pub fn new() -> Self {
+        Self {
+            senders: RefCount::new(1),
+            receivers: RefCount::new(1),
+            #[cfg(feature = "registry")]
+            len: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    #[cfg(feature = "registry")]
+    pub fn senders(&self) -> usize {
+        self.senders.count()
+    }
+
+    #[cfg(feature = "registry")]
+    pub fn receivers(&self) -> usize {
+        self.receivers.count()
+    }
+
+    #[cfg(feature = "registry")]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    #[cfg(feature = "registry")]
+    pub fn publish_len(&self, len: usize) {
+        self.len.store(len, Ordering::Release);
+    }
+}
+
+#[cfg(feature = "registry")]
+type Counters = Arc<ChannelCounters>;
+#[cfg(not(feature = "registry"))]
+type Counters = ChannelCounters;
+
 #[derive(Debug)]
 pub struct Shared<E> {
     sender_notify: Notifier,
-    sender_count: RefCount,
     receiver_notify: Notifier,
-    receiver_count: RefCount,
+    counters: Counters,
+    // set when a sender closes the channel, before all senders are dropped
+    sender_closed: AtomicBool,
+    // if set, a sender which is dropped while the thread is panicking poisons the channel
+    poison_on_panic: AtomicBool,
+    poisoned: AtomicBool,
+    // the registry id, or zero if the channel is not registered
+    #[cfg(feature = "registry")]
+    registration: std::sync::atomic::AtomicU64,
+    // computes the number of buffered messages, which is published to the counters when endpoints are notified
+    #[cfg(feature = "registry")]
+    len: std::sync::OnceLock<fn(&E) -> usize>,
     pub(crate) extension: E,
 }
 
@@ -41,12 +103,39 @@ impl<E> Shared<E> {
     pub fn new(extension: E) -> Self {
         Self {
             sender_notify: Notifier::new(),
-            sender_count: RefCount::new(1),
             receiver_notify: Notifier::new(),
-            receiver_count: RefCount::new(1),
+            #[cfg(feature = "registry")]
+            counters: Arc::new(ChannelCounters::new()),
+            #[cfg(not(feature = "registry"))]
+            counters: ChannelCounters::new(),
+            sender_closed: AtomicBool::new(false),
+            poison_on_panic: AtomicBool::new(false),
+            poisoned: AtomicBool::new(false),
+            #[cfg(feature = "registry")]
+            registration: std::sync::atomic::AtomicU64::new(0),
+            #[cfg(feature = "registry")]
+            len: std::sync::OnceLock::new(),
             extension,
         }
     }
+
+    // publishes the number of buffered messages to the registry, if the channel is registered
+    fn publish_len(&self) {
+        #[cfg(feature = "registry")]
+        if let Some(len) = self.len.get() {
+            self.counters.publish_len(len(&self.extension));
+        }
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<E> Drop for Shared<E> {
+    fn drop(&mut self) {
+        let id = *self.registration.get_mut();
+        if id != 0 {
+            crate::registry::unregister(id);
+        }
+    }
 }
 
 pub struct SenderShared<E> {
@@ -59,9 +148,15 @@ impl<E> SenderShared<E> {
     }
 
     pub fn notify_receivers(&self) {
+        self.inner.publish_len();
         self.inner.receiver_notify.notify();
     }
 
+    // publishes the number of buffered messages, for channels which wake receivers without notifying them
+    pub fn publish_len(&self) {
+        self.inner.publish_len();
+    }
+
     pub fn notify_self(&self) {
         self.inner.sender_notify.notify();
     }
@@ -70,16 +165,16 @@ impl<E> SenderShared<E> {
         self.inner.sender_notify.subscribe(cx);
     }
 
-    pub fn recv_guard(&self) -> NotificationGuard {
+    pub fn recv_guard(&self) -> NotificationGuard<'_> {
         self.inner.sender_notify.guard()
     }
 
     pub fn is_alive(&self) -> bool {
-        self.inner.receiver_count.is_alive()
+        self.inner.counters.receivers.is_alive()
     }
 
     pub fn clone_receiver(&self) -> ReceiverShared<E> {
-        self.inner.receiver_count.increment();
+        self.inner.counters.receivers.increment();
 
         ReceiverShared {
             inner: self.inner.clone(),
@@ -87,7 +182,59 @@ impl<E> SenderShared<E> {
     }
 
     pub fn is_closed(&self) -> bool {
-        !self.is_alive()
+        !self.is_alive() || self.is_sender_closed()
+    }
+
+    // the number of live senders, including this one
+    pub fn sender_count(&self) -> usize {
+        self.inner.counters.senders.count()
+    }
+
+    // clones the sender, unless `max` senders are alive
+    pub fn try_clone(&self, max: usize) -> Option<Self> {
+        if !self.inner.counters.senders.try_increment(max) {
+            return None;
+        }
+
+        Some(Self {
+            inner: self.inner.clone(),
+        })
+    }
+
+    // true if a sender closed the channel, regardless of the receivers
+    pub fn is_sender_closed(&self) -> bool {
+        self.inner.sender_closed.load(Ordering::Acquire)
+    }
+
+    // registers the channel in the global registry.  `len` returns the number of buffered messages,
+    // and is published to the registry whenever the endpoints are notified
+    #[cfg(feature = "registry")]
+    pub fn register(
+        &self,
+        name: &'static str,
+        item_type: &'static str,
+        capacity: Option<usize>,
+        len: fn(&E) -> usize,
+    ) {
+        if self.inner.len.set(len).is_err() {
+            return;
+        }
+
+        self.inner.publish_len();
+        let id = crate::registry::register(name, item_type, capacity, self.inner.counters.clone());
+        self.inner.registration.store(id, Ordering::Release);
+    }
+
+    // configures the channel to be poisoned if a sender is dropped during a panic
+    pub fn set_poison_on_panic(&self, poison: bool) {
+        self.inner.poison_on_panic.store(poison, Ordering::Release);
+    }
+
+    // closes the channel for all senders.  receivers observe the closure once the buffer is drained
+    pub fn close(&self) {
+        self.inner.sender_closed.store(true, Ordering::Release);
+        self.notify_receivers();
+        self.notify_self();
     }
 }
 
@@ -103,7 +250,7 @@ where
 impl<E> Clone for SenderShared<E> {
     fn clone(&self) -> Self {
         let inner = self.inner.clone();
-        inner.sender_count.increment();
+        inner.counters.senders.increment();
 
         Self { inner }
     }
@@ -111,7 +258,12 @@ impl<E> Clone for SenderShared<E> {
 
 impl<E> Drop for SenderShared<E> {
     fn drop(&mut self) {
-        match self.inner.sender_count.decrement() {
+        // poisoned before the count is decremented, so receivers observe it when the channel closes
+        if std::thread::panicking() && self.inner.poison_on_panic.load(Ordering::Acquire) {
+            self.inner.poisoned.store(true, Ordering::Release);
+        }
+
+        match self.inner.counters.senders.decrement() {
             TryDecrement::Alive(_) => {}
             TryDecrement::Dead => {
                 self.notify_receivers();
@@ -130,30 +282,55 @@ impl<E> ReceiverShared<E> {
     }
 
     pub fn notify_senders(&self) {
+        self.inner.publish_len();
         self.inner.sender_notify.notify();
     }
 
+    // wakes the other receivers, if the receiver hands off messages when it is dropped
+    pub fn notify_receivers(&self) {
+        self.inner.publish_len();
+        self.inner.receiver_notify.notify();
+    }
+
+    // publishes the number of buffered messages, for channels which wake senders without notifying them
+    pub fn publish_len(&self) {
+        self.inner.publish_len();
+    }
+
     pub fn subscribe_send(&self, cx: &Context<'_>) {
         self.inner.receiver_notify.subscribe(cx);
     }
 
-    pub fn send_guard(&self) -> NotificationGuard {
+    pub fn send_guard(&self) -> NotificationGuard<'_> {
         self.inner.receiver_notify.guard()
     }
 
     pub fn is_alive(&self) -> bool {
-        self.inner.sender_count.is_alive()
+        self.inner.counters.senders.is_alive() && !self.inner.sender_closed.load(Ordering::Acquire)
     }
 
     pub fn is_closed(&self) -> bool {
         !self.is_alive()
     }
+
+    // closes the channel from the receiving side.  senders are rejected, and receivers observe the closure
+    // once the buffer is drained
+    pub fn close(&self) {
+        self.inner.sender_closed.store(true, Ordering::Release);
+        self.notify_senders();
+        self.notify_receivers();
+    }
+
+    // true if a sender was dropped while its thread was panicking, and the channel is configured to be poisoned
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.poisoned.load(Ordering::Acquire)
+    }
 }
 
 impl<E> Clone for ReceiverShared<E> {
     fn clone(&self) -> Self {
         let inner = self.inner.clone();
-        inner.receiver_count.increment();
+        inner.counters.receivers.increment();
 
         Self { inner }
     }
@@ -161,7 +338,7 @@ impl<E> Clone for ReceiverShared<E> {
 
 impl<E> Drop for ReceiverShared<E> {
     fn drop(&mut self) {
-        match self.inner.receiver_count.decrement() {
+        match self.inner.counters.receivers.decrement() {
             TryDecrement::Alive(_) => {}
             TryDecrement::Dead => {
                 self.notify_senders();
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+type Init<T> = Box<dyn FnOnce() -> T + Send>;
+
+// A buffered message.  Deferred values are shared by all readers, and constructed when first read
+pub enum Entry<T> {
+    Value(T),
+    Deferred(Arc<Deferred<T>>),
+}
+
+impl<T> Entry<T> {
+    pub fn deferred<F>(init: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        Entry::Deferred(Arc::new(Deferred::new(Box::new(init))))
+    }
+}
+
+impl<T> Entry<T>
+where
+    T: Clone,
+{
+    // Returns the value, constructing it if this is the first read of a deferred value
+    pub fn take(self) -> T {
+        match self {
+            Entry::Value(value) => value,
+            Entry::Deferred(deferred) => deferred.get_cloned(),
+        }
+    }
+}
+
+impl<T> Clone for Entry<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Entry::Value(value) => Entry::Value(value.clone()),
+            Entry::Deferred(deferred) => Entry::Deferred(deferred.clone()),
+        }
+    }
+}
+
+// The initializer runs at most once, under the lock.  Values that are dropped unread are never constructed
+pub struct Deferred<T> {
+    state: Mutex<DeferredState<T>>,
+}
+
+struct DeferredState<T> {
+    init: Option<Init<T>>,
+    value: Option<T>,
+}
+
+impl<T> Deferred<T> {
+    fn new(init: Init<T>) -> Self {
+        Self {
+            state: Mutex::new(DeferredState {
+                init: Some(init),
+                value: None,
+            }),
+        }
+    }
+
+    fn get_cloned(&self) -> T
+    where
+        T: Clone,
+    {
+        let mut state = self.state.lock();
+
+        if state.value.is_none() {
+            let init = state
+                .init
+                .take()
+                .expect("deferred value panicked during construction");
+            state.value = Some(init());
+        }
+
+        state.value.clone().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::Entry;
+
+    #[test]
+    fn constructs_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls2 = calls.clone();
+        let entry = Entry::deferred(move || {
+            calls2.fetch_add(1, Ordering::SeqCst);
+            1usize
+        });
+
+        assert_eq!(0, calls.load(Ordering::SeqCst));
+        assert_eq!(1, entry.clone().take());
+        assert_eq!(1, entry.take());
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn drop_unread() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls2 = calls.clone();
+        let entry = Entry::deferred(move || {
+            calls2.fetch_add(1, Ordering::SeqCst);
+            1usize
+        });
+
+        drop(entry.clone());
+        drop(entry);
+        assert_eq!(0, calls.load(Ordering::SeqCst));
+    }
+}
@@ -4,18 +4,26 @@ use crate::Context;
 use atomic::Ordering;
 use parking_lot::{Mutex, RwLock};
 
-use super::notifier::Notifier;
+use super::notifier::WakerSet;
 use std::fmt::Debug;
 
 // A lock-free multi-producer, multi-consumer circular buffer
 // Each reader will see each value created exactly once.
 // Cloned readers inherit the read location of the reader that was cloned.
+//
+// Slots are allocated when the buffer is created, and are reused for the lifetime of the channel.
+// Each slot stores the sequence number of the value it holds, which readers compare to their own position.
+// A write moves the value into the slot and advances the head, so the steady state does not allocate.
 
 pub struct MpmcCircularBuffer<T> {
     buffer: Box<[Slot<T>]>,
     head: AtomicUsize,
     maintenance: Mutex<()>,
     readers: AtomicUsize,
+    // readers wait for the head slot to be written, and writers wait for it to be released.
+    // only the head slot is contended, so the wakers are stored once for the whole buffer
+    on_write: WakerSet,
+    on_release: WakerSet,
 }
 
 impl<T> Debug for MpmcCircularBuffer<T> {
@@ -46,6 +54,8 @@ where
             head: AtomicUsize::new(1),
             readers: AtomicUsize::new(1),
             maintenance: Mutex::new(()),
+            on_write: WakerSet::new(),
+            on_release: WakerSet::new(),
         };
 
         let reader = BufferReader { index: 1 };
@@ -88,7 +98,7 @@ impl<T> MpmcCircularBuffer<T> {
             // try to write a value
             // if the write is accepted, release the head lock in the closure
             // this minimizes the time head is locked, and allows the move of value to occur after the lock is released
-            let try_write = head_slot.try_write(head_id, value, &self.readers, cx, || {
+            let try_write = head_slot.try_write(head_id, value, self, cx, || {
                 if let Err(_e) = self.head.compare_exchange(
                     head_id,
                     head_id + 1,
@@ -132,6 +142,35 @@ impl<T> MpmcCircularBuffer<T> {
         }
     }
 
+    // Returns the number of messages that the slowest reader has not yet read.
+    // Slots are inspected atomically, without taking the maintenance lock, so the result may be briefly stale.
+    pub fn max_lag(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let readers = self.readers.load(Ordering::Acquire);
+
+        self.buffer
+            .iter()
+            .filter_map(|slot| {
+                let index = slot.index.load(Ordering::Acquire);
+                if index == 0 || index >= head {
+                    return None;
+                }
+
+                if slot.reads.load(Ordering::Acquire) >= readers {
+                    return None;
+                }
+
+                Some(head - index)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Returns the number of readers, including readers which have been handed over
+    pub fn readers(&self) -> usize {
+        self.readers.load(Ordering::Acquire)
+    }
+
     pub fn new_reader(&self) -> BufferReader {
         let _maint = self.maintenance.lock();
         let index = self.head.load(Ordering::Acquire);
@@ -148,7 +187,9 @@ impl<T> MpmcCircularBuffer<T> {
     fn mark_read_in_range(&self, min: usize, max: usize) {
         for slot in self.buffer.iter() {
             let readers = self.readers.load(Ordering::Acquire);
-            slot.mark_read_in_range(min, max, readers);
+            if slot.mark_read_in_range(min, max, readers) {
+                self.on_release.notify();
+            }
         }
     }
 
@@ -178,7 +219,7 @@ impl BufferReader {
         let index = self.index;
         let slot = buffer.get_slot(index);
 
-        let try_read = slot.try_read(index, &buffer.readers, cx);
+        let try_read = slot.try_read(index, buffer, cx);
 
         match &try_read {
             TryRead::Ready(_) => {
@@ -202,6 +243,14 @@ impl BufferReader {
         try_read
     }
 
+    // Returns the number of written messages that this reader has not yet read
+    pub fn lag<T>(&self, buffer: &MpmcCircularBuffer<T>) -> usize {
+        buffer
+            .head
+            .load(Ordering::Acquire)
+            .saturating_sub(self.index)
+    }
+
     // To avoid the need for shared Arc references, clone and drop are written as methods instead of using std traits
     pub fn clone_with<T>(&self, buffer: &MpmcCircularBuffer<T>) -> Self {
         let _maint = buffer.maintenance.lock();
@@ -216,6 +265,12 @@ impl BufferReader {
         BufferReader { index }
     }
 
+    // Moves the read location to a new reader, which takes over the reads of this reader.
+    // This reader must not be used, or dropped with the buffer
+    pub fn hand_over(&mut self) -> Self {
+        BufferReader { index: self.index }
+    }
+
     pub fn drop_with<T>(&mut self, buffer: &MpmcCircularBuffer<T>) {
         let _maint = buffer.maintenance.lock();
 
@@ -228,18 +283,22 @@ impl BufferReader {
         // then decrement the reader count
         buffer.readers.fetch_sub(1, Ordering::AcqRel);
 
-        // then go through the buffer, and release any slots that should be released
+        // then go through the buffer, and wake writers if any slot should be released
+        #[allow(clippy::unused_enumerate_index)]
         for (_id, slot) in buffer.buffer.iter().enumerate() {
             #[cfg(feature = "debug")]
             log::debug!(
-                "[{}] Dropping reader, notifying slot {} with reads {:?} of new reader count {:?}",
+                "[{}] Dropping reader, checking slot {} with reads {:?} of new reader count {:?}",
                 self.index,
                 _id,
                 slot.reads,
                 buffer.readers,
             );
 
-            slot.notify_readers_decreased(&buffer.readers);
+            if slot.is_released(&buffer.readers) {
+                buffer.on_release.notify();
+                break;
+            }
         }
 
         #[cfg(feature = "debug")]
@@ -254,9 +313,8 @@ impl BufferReader {
 pub struct Slot<T> {
     data: RwLock<Option<T>>,
     reads: AtomicUsize,
+    // the sequence number of the value in the slot, or zero if the slot has never been written
     index: AtomicUsize,
-    on_write: Notifier,
-    on_release: Notifier,
 }
 
 impl<T> Slot<T> {
@@ -265,8 +323,6 @@ impl<T> Slot<T> {
             data: RwLock::new(None),
             reads: AtomicUsize::new(0),
             index: AtomicUsize::new(index),
-            on_write: Notifier::new(),
-            on_release: Notifier::new(),
         }
     }
 
@@ -274,13 +330,15 @@ impl<T> Slot<T> {
         &self,
         index: usize,
         value: T,
-        readers: &AtomicUsize,
+        buffer: &MpmcCircularBuffer<T>,
         cx: &Context<'_>,
         on_write: OnWrite,
     ) -> SlotTryWrite<T>
     where
         OnWrite: FnOnce(),
     {
+        let readers = &buffer.readers;
+
         loop {
             let prev_index = self.index.load(Ordering::Acquire);
 
@@ -289,7 +347,7 @@ impl<T> Slot<T> {
             } else if prev_index != 0
                 && self.reads.load(Ordering::Acquire) < readers.load(Ordering::Acquire)
             {
-                self.on_release.subscribe(cx);
+                buffer.on_release.subscribe(cx);
 
                 if prev_index < self.index.load(Ordering::Acquire) {
                     #[cfg(feature = "debug")]
@@ -335,14 +393,18 @@ impl<T> Slot<T> {
             }
 
             on_write();
+            // the previous value is dropped in place, and the slot is reused
             *data = Some(value);
             self.reads.store(0, Ordering::Release);
-            self.on_write.notify();
+            drop(data);
+
+            buffer.on_write.notify();
             return SlotTryWrite::Ready;
         }
     }
 
-    fn mark_read_in_range(&self, min: usize, max: usize, readers: usize) {
+    // returns true if the slot was released
+    fn mark_read_in_range(&self, min: usize, max: usize, readers: usize) -> bool {
         // prevent the index from changing while maintenance is performed
         let _read = self.data.read();
         let index = self.index.load(Ordering::Acquire);
@@ -357,10 +419,10 @@ impl<T> Slot<T> {
                 readers
             );
 
-            if reads >= readers {
-                self.on_release.notify();
-            }
+            return reads >= readers;
         }
+
+        false
     }
 
     fn decrement_read_in_range(&self, min: usize, max: usize) {
@@ -392,10 +454,8 @@ impl<T> Slot<T> {
         }
     }
 
-    fn notify_readers_decreased(&self, readers: &AtomicUsize) {
-        if self.reads.load(Ordering::Acquire) >= readers.load(Ordering::Acquire) {
-            self.on_release.notify();
-        }
+    fn is_released(&self, readers: &AtomicUsize) -> bool {
+        self.reads.load(Ordering::Acquire) >= readers.load(Ordering::Acquire)
     }
 }
 
@@ -404,11 +464,16 @@ where
     T: Clone,
 {
     #[allow(clippy::comparison_chain)]
-    pub fn try_read(&self, index: usize, readers: &AtomicUsize, cx: &Context<'_>) -> TryRead<T> {
+    pub fn try_read(
+        &self,
+        index: usize,
+        buffer: &MpmcCircularBuffer<T>,
+        cx: &Context<'_>,
+    ) -> TryRead<T> {
         loop {
             let slot_index = self.index.load(Ordering::Acquire);
             if slot_index < index {
-                self.on_write.subscribe(cx);
+                buffer.on_write.subscribe(cx);
 
                 // if the index has advanced, continue and attempt to read again
                 if self.index.load(Ordering::Acquire) >= index {
@@ -442,8 +507,10 @@ where
             let data_ref = data_lock.as_ref().unwrap();
             let data_cloned = data_ref.clone();
 
-            if reads >= readers.load(Ordering::Acquire) {
-                self.on_release.notify();
+            drop(data_lock);
+
+            if reads >= buffer.readers.load(Ordering::Acquire) {
+                buffer.on_release.notify();
             }
 
             break TryRead::Ready(data_cloned);
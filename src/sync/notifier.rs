@@ -1,11 +1,17 @@
 use atomic::Ordering;
 use crossbeam_queue::SegQueue;
-use std::{sync::atomic::AtomicUsize, task::Waker};
+use parking_lot::Mutex;
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize},
+    task::Waker,
+};
 
 #[derive(Debug)]
 pub struct Notifier {
     generation: AtomicUsize,
     wakers: SegQueue<Waker>,
+    // set after a waker is pushed, and cleared before the wakers are taken
+    subscribed: AtomicBool,
 }
 
 impl Notifier {
@@ -13,11 +19,21 @@ impl Notifier {
         Self {
             generation: AtomicUsize::new(0),
             wakers: SegQueue::new(),
+            subscribed: AtomicBool::new(false),
         }
     }
 
-    pub fn guard(&self) -> NotificationGuard {
-        let generation = self.generation.load(Ordering::Relaxed);
+    // Waiters take a guard, check the channel state, subscribe, and then check the guard.  The state is always
+    // changed before `notify` is called.
+    //
+    // The guard is taken with an acquire load, so if the waiter observes a notification in its guard, it also
+    // observes the state change which preceded it, and finds the state actionable.  Otherwise, the waiter
+    // would consume the notification without observing the change, and park until the next one.
+    //
+    // The handshake between `subscribe` and `is_expired`, and the increment and flag check in `notify`, are seqcst.
+    // So either the notifier observes the subscription and takes the waker, or the waiter observes the increment.
+    pub fn guard(&self) -> NotificationGuard<'_> {
+        let generation = self.generation.load(Ordering::Acquire);
 
         NotificationGuard {
             generation,
@@ -26,7 +42,17 @@ impl Notifier {
     }
 
     pub fn notify(&self) {
-        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        // the wakers are only taken if a task has subscribed since the last notification.  the load avoids
+        // a write to the shared flag when there are no subscribers
+        if !self.subscribed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if !self.subscribed.swap(false, Ordering::SeqCst) {
+            return;
+        }
 
         #[cfg(feature = "debug")]
         let mut woken = 0usize;
@@ -49,6 +75,7 @@ impl Notifier {
     pub fn subscribe(&self, cx: &crate::Context<'_>) {
         if let Some(waker) = cx.waker() {
             self.wakers.push(waker.clone());
+            self.subscribed.store(true, Ordering::SeqCst);
         }
     }
 }
@@ -60,6 +87,125 @@ pub struct NotificationGuard<'a> {
 
 impl<'a> NotificationGuard<'a> {
     pub fn is_expired(&self) -> bool {
-        self.stored_generation.load(Ordering::Relaxed) != self.generation
+        self.stored_generation.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+// A notifier which registers each task once, and reuses the storage of its waker list.
+// Once the list has grown to the number of waiting tasks, subscribe and notify do not allocate.
+#[derive(Debug)]
+pub struct WakerSet {
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl WakerSet {
+    pub fn new() -> Self {
+        Self {
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn notify(&self) {
+        // wakers are called without holding the lock, and the emptied list is returned afterwards
+        let mut woken = {
+            let mut wakers = self.wakers.lock();
+            if wakers.is_empty() {
+                return;
+            }
+
+            std::mem::take(&mut *wakers)
+        };
+
+        #[cfg(feature = "debug")]
+        log::info!("Woke {} tasks", woken.len());
+
+        for waker in woken.drain(..) {
+            waker.wake();
+        }
+
+        let mut wakers = self.wakers.lock();
+        if wakers.is_empty() {
+            *wakers = woken;
+        }
+    }
+
+    pub fn subscribe(&self, cx: &crate::Context<'_>) {
+        if let Some(waker) = cx.waker() {
+            let mut wakers = self.wakers.lock();
+            if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Barrier,
+    };
+
+    use futures_test::task::new_count_waker;
+
+    use super::Notifier;
+    use crate::Context;
+
+    #[test]
+    fn notify_wakes_or_expires() {
+        for _ in 0..10_000 {
+            let notifier = Arc::new(Notifier::new());
+            let ready = Arc::new(AtomicBool::new(false));
+            let barrier = Arc::new(Barrier::new(2));
+
+            let waiter = {
+                let (notifier, ready, barrier) = (notifier.clone(), ready.clone(), barrier.clone());
+                std::thread::spawn(move || {
+                    let (waker, wakes) = new_count_waker();
+                    barrier.wait();
+
+                    let guard = notifier.guard();
+                    if ready.load(Ordering::Acquire) {
+                        return None;
+                    }
+
+                    notifier.subscribe(&Context::from_waker(&waker));
+                    if guard.is_expired() {
+                        return None;
+                    }
+
+                    // the waiter parks, and must be woken by the notification
+                    Some(wakes)
+                })
+            };
+
+            let notifier = std::thread::spawn(move || {
+                barrier.wait();
+                ready.store(true, Ordering::Release);
+                notifier.notify();
+            });
+
+            let parked = waiter.join().expect("the waiter panicked");
+            notifier.join().expect("the notifier panicked");
+
+            if let Some(wakes) = parked {
+                assert_eq!(1, wakes.get(), "the parked waiter was not woken");
+            }
+        }
+    }
+
+    #[test]
+    fn notify_without_subscribers() {
+        let notifier = Notifier::new();
+        let guard = notifier.guard();
+
+        notifier.notify();
+        assert!(guard.is_expired());
+
+        let (waker, wakes) = new_count_waker();
+        notifier.subscribe(&Context::from_waker(&waker));
+        notifier.notify();
+        notifier.notify();
+        assert_eq!(1, wakes.get());
     }
 }
@@ -1,3 +1,5 @@
+use std::marker::PhantomData;
+
 use atomic::Ordering;
 
 use super::state_cell::StateCell;
@@ -43,6 +45,14 @@ impl<T> OneshotCell<T> {
         Ok(())
     }
 
+    pub fn is_ready(&self) -> bool {
+        matches!(self.state.load(Ordering::Acquire), State::Ready)
+    }
+
+    pub fn is_taken(&self) -> bool {
+        matches!(self.state.load(Ordering::Acquire), State::Taken)
+    }
+
     pub fn try_recv(&self) -> Result<T, TryRecvError> {
         unsafe {
             match self.state.compare_take(
@@ -62,3 +72,48 @@ impl<T> OneshotCell<T> {
         }
     }
 }
+
+// A oneshot cell which retains the value, so it can be read any number of times
+pub struct RetainingCell<T> {
+    state: StateCell<State, T>,
+    // the value is shared by reference across threads, so the cell is only Sync if T is Sync
+    _sync: PhantomData<*const T>,
+}
+
+impl<T> RetainingCell<T> {
+    pub fn new() -> Self {
+        Self {
+            state: StateCell::new(State::None),
+            _sync: PhantomData,
+        }
+    }
+
+    pub fn send(&self, value: T) -> Result<(), T> {
+        unsafe {
+            self.state
+                .compare_store(
+                    State::None,
+                    State::Writing,
+                    value,
+                    State::Ready,
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                )
+                .map_err(|err| err.1)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        match self.state.load(Ordering::Acquire) {
+            // once the value is ready, it is never written or taken
+            State::Ready => Some(unsafe { self.state.get() }),
+            _ => None,
+        }
+    }
+}
+
+unsafe impl<T> Send for RetainingCell<T> where T: Send {}
+
+unsafe impl<T> Sync for RetainingCell<T> where T: Send + Sync {}
@@ -6,6 +6,7 @@ pub struct RefCount {
 }
 
 pub enum TryDecrement {
+    #[allow(dead_code)]
     Alive(usize),
     Dead,
 }
@@ -27,6 +28,10 @@ impl RefCount {
         }
     }
 
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
     pub fn is_alive(&self) -> bool {
         self.count.load(Ordering::Acquire) > 0
     }
@@ -35,6 +40,19 @@ impl RefCount {
         self.count.fetch_add(1, Ordering::AcqRel);
     }
 
+    // increments the count, unless it has reached `max`
+    pub fn try_increment(&self, max: usize) -> bool {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                if count < max {
+                    Some(count + 1)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
+
     pub fn decrement(&self) -> TryDecrement {
         loop {
             let state = self.count.load(Ordering::Acquire);
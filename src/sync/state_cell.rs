@@ -23,6 +23,10 @@ where
         }
     }
 
+    pub fn load(&self, ordering: Ordering) -> S {
+        self.state.load(ordering)
+    }
+
     pub unsafe fn compare_store(
         &self,
         current: S,
@@ -58,6 +62,12 @@ where
         }
     }
 
+    // the caller must ensure the data is stored, and is not written or taken while the reference is held
+    pub unsafe fn get(&self) -> &T {
+        let reference = self.data.get().as_ref().unwrap();
+        reference.as_ref().unwrap()
+    }
+
     unsafe fn take_internal(&self) -> T {
         let reference = self.data.get().as_mut().unwrap();
         reference.take().unwrap()
@@ -0,0 +1,204 @@
+use std::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// A lock-free, bounded multi-producer, multi-consumer queue with a compile-time capacity.
+// The slots are stored inline, so the queue does not require a separate allocation.
+//
+// Each slot has a stamp.  A slot with stamp == 2 * position is empty and can be written at that position,
+// and a slot with stamp == 2 * position + 1 is full and can be read at that position.
+
+pub struct StaticQueue<T, const N: usize> {
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    buffer: [Slot<T>; N],
+}
+
+unsafe impl<T: Send, const N: usize> Send for StaticQueue<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for StaticQueue<T, N> {}
+
+struct Slot<T> {
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T, const N: usize> StaticQueue<T, N> {
+    const NONZERO_CAPACITY: () = assert!(N > 0, "static channel capacity must be nonzero");
+
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::NONZERO_CAPACITY;
+
+        Self {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            buffer: std::array::from_fn(|i| Slot {
+                stamp: AtomicUsize::new(i * 2),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+
+            if self.tail.load(Ordering::SeqCst) == tail {
+                return tail.wrapping_sub(head).min(N);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.buffer[tail % N];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+            let diff = stamp.wrapping_sub(empty_stamp(tail)) as isize;
+
+            if diff == 0 {
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    tail.wrapping_add(1),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // the successful compare_exchange grants exclusive access to the slot
+                        unsafe { slot.value.get().write(MaybeUninit::new(value)) };
+                        slot.stamp.store(full_stamp(tail), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => tail = current,
+                }
+            } else if diff < 0 {
+                return Err(value);
+            } else {
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.buffer[head % N];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+            let diff = stamp.wrapping_sub(full_stamp(head)) as isize;
+
+            if diff == 0 {
+                match self.head.compare_exchange_weak(
+                    head,
+                    head.wrapping_add(1),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // the successful compare_exchange grants exclusive access to the slot,
+                        // and the stamp guarantees it was initialized by push
+                        let value = unsafe { slot.value.get().read().assume_init() };
+                        slot.stamp
+                            .store(empty_stamp(head.wrapping_add(N)), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => head = current,
+                }
+            } else if diff < 0 {
+                return None;
+            } else {
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+fn empty_stamp(position: usize) -> usize {
+    position.wrapping_mul(2)
+}
+
+fn full_stamp(position: usize) -> usize {
+    position.wrapping_mul(2).wrapping_add(1)
+}
+
+impl<T, const N: usize> Drop for StaticQueue<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T, const N: usize> Debug for StaticQueue<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::StaticQueue;
+
+    #[test]
+    fn push_pop_wraps() {
+        let queue = StaticQueue::<usize, 3>::new();
+
+        for i in 0..10 {
+            assert_eq!(Ok(()), queue.push(i));
+            assert_eq!(Ok(()), queue.push(i + 1));
+            assert_eq!(2, queue.len());
+            assert_eq!(Some(i), queue.pop());
+            assert_eq!(Some(i + 1), queue.pop());
+            assert_eq!(None, queue.pop());
+        }
+    }
+
+    #[test]
+    fn single_slot() {
+        let queue = StaticQueue::<usize, 1>::new();
+
+        for i in 0..3 {
+            assert_eq!(Ok(()), queue.push(i));
+            assert_eq!(Err(i + 1), queue.push(i + 1));
+            assert_eq!(Some(i), queue.pop());
+            assert_eq!(None, queue.pop());
+        }
+    }
+
+    #[test]
+    fn push_full() {
+        let queue = StaticQueue::<usize, 2>::new();
+
+        assert_eq!(Ok(()), queue.push(1));
+        assert_eq!(Ok(()), queue.push(2));
+        assert_eq!(2, queue.len());
+        assert_eq!(Err(3), queue.push(3));
+        assert_eq!(Some(1), queue.pop());
+        assert_eq!(Ok(()), queue.push(3));
+    }
+
+    #[test]
+    fn drop_remaining() {
+        let value = Arc::new(());
+        let queue = StaticQueue::<Arc<()>, 4>::new();
+
+        queue.push(value.clone()).unwrap();
+        queue.push(value.clone()).unwrap();
+        assert_eq!(3, Arc::strong_count(&value));
+
+        drop(queue);
+        assert_eq!(1, Arc::strong_count(&value));
+    }
+}
@@ -4,7 +4,8 @@ use crate::{stream::PollRecv, Context};
 
 use super::{
     notifier::Notifier,
-    oneshot_cell::{OneshotCell, TryRecvError},
+    oneshot_cell::{OneshotCell, RetainingCell, TryRecvError},
+    ref_count::RefCount,
 };
 
 #[derive(Copy, Clone)]
@@ -35,6 +36,10 @@ impl<T> Transfer<T> {
             return Err(value);
         }
 
+        if let State::Dead = self.sender.load(Ordering::Acquire) {
+            return Err(value);
+        }
+
         self.value.send(value)?;
         self.notify_rx.notify();
 
@@ -68,6 +73,28 @@ impl<T> Transfer<T> {
         }
     }
 
+    // once the value has been received, the transfer is closed
+    pub fn is_consumed(&self) -> bool {
+        self.value.is_taken()
+    }
+
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.value.is_ready() {
+            return (1, Some(1));
+        }
+
+        if self.value.is_taken() {
+            return (0, Some(0));
+        }
+
+        match self.sender.load(Ordering::Acquire) {
+            State::Alive => (0, Some(1)),
+            // the sender may have stored the value before disconnecting
+            State::Dead if self.value.is_ready() => (1, Some(1)),
+            State::Dead => (0, Some(0)),
+        }
+    }
+
     pub fn sender_disconnect(&self) {
         self.sender.store(State::Dead, Ordering::Release);
         self.notify_rx.notify();
@@ -77,3 +104,84 @@ impl<T> Transfer<T> {
         self.receiver.store(State::Dead, Ordering::Release);
     }
 }
+
+// A transfer which retains the value, so that any number of receivers can observe it
+pub struct RetainingTransfer<T> {
+    sender: Atomic<State>,
+    receivers: RefCount,
+    value: RetainingCell<T>,
+    notify_rx: Notifier,
+}
+
+impl<T> RetainingTransfer<T> {
+    pub fn new() -> Self {
+        Self {
+            sender: Atomic::new(State::Alive),
+            receivers: RefCount::new(1),
+            value: RetainingCell::new(),
+            notify_rx: Notifier::new(),
+        }
+    }
+
+    pub fn send(&self, value: T) -> Result<(), T> {
+        if !self.receivers.is_alive() {
+            return Err(value);
+        }
+
+        if let State::Dead = self.sender.load(Ordering::Acquire) {
+            return Err(value);
+        }
+
+        self.value.send(value)?;
+        self.notify_rx.notify();
+
+        Ok(())
+    }
+
+    // returns a reference to the value, without consuming it
+    pub fn recv(&self, cx: &Context<'_>) -> PollRecv<&T> {
+        loop {
+            let guard = self.notify_rx.guard();
+            if let Some(value) = self.value.get() {
+                return PollRecv::Ready(value);
+            }
+
+            if let State::Dead = self.sender.load(Ordering::Acquire) {
+                // the sender may have stored the value before disconnecting
+                return match self.value.get() {
+                    Some(value) => PollRecv::Ready(value),
+                    None => PollRecv::Closed,
+                };
+            }
+
+            self.notify_rx.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    pub fn is_sender_alive(&self) -> bool {
+        matches!(self.sender.load(Ordering::Acquire), State::Alive)
+    }
+
+    pub fn sender_disconnect(&self) {
+        self.sender.store(State::Dead, Ordering::Release);
+        self.notify_rx.notify();
+    }
+
+    pub fn receiver_connect(&self) {
+        self.receivers.increment();
+    }
+
+    pub fn receiver_disconnect(&self) {
+        self.receivers.decrement();
+    }
+}
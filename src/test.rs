@@ -1,11 +1,17 @@
+pub mod alloc;
+pub mod future;
 pub mod sink;
 pub mod stream;
 mod test_messages;
+pub mod time;
 pub use test_messages::*;
 
 use crate::Context;
 use std::time::Duration;
 
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
 pub const CHANNEL_TEST_ITERATIONS: usize = 2000;
 pub const CHANNEL_TEST_SENDERS: usize = 10;
 pub const CHANNEL_TEST_RECEIVERS: usize = 5;
@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Returns a future which is pending on the first poll, and wakes the task, before resolving to `value`.
+pub fn pending_once<T>(value: T) -> PendingOnce<T> {
+    PendingOnce {
+        value: Some(value),
+        polled: false,
+    }
+}
+
+pub struct PendingOnce<T> {
+    value: Option<T>,
+    polled: bool,
+}
+
+impl<T: Unpin> Future for PendingOnce<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        if !self.polled {
+            self.polled = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        Poll::Ready(self.value.take().unwrap())
+    }
+}
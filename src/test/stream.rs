@@ -1,7 +1,8 @@
 #![allow(dead_code)]
 
 use pin_project::pin_project;
-use std::marker::PhantomData;
+use static_assertions::assert_not_impl_all;
+use std::marker::{PhantomData, PhantomPinned};
 
 use crate::stream::{PollRecv, Stream};
 
@@ -34,6 +35,27 @@ where
     PollIterStream::new(iter.into_iter())
 }
 
+/// Polls the stream once, without a waker.
+pub fn recv<S: Stream + Unpin>(rx: &mut S) -> PollRecv<S::Item> {
+    std::pin::Pin::new(rx).poll_recv(&mut crate::Context::empty())
+}
+
+/// Wraps the stream in a stream which is `!Unpin`, so combinators can be tested with pinned inner streams.
+pub fn not_unpin<S>(stream: S) -> NotUnpinStream<S>
+where
+    S: Stream,
+{
+    NotUnpinStream::new(stream)
+}
+
+/// Wraps the stream in a stream which panics if it is polled after returning `PollRecv::Closed`.
+pub fn panic_after_close<S>(stream: S) -> PanicAfterCloseStream<S>
+where
+    S: Stream,
+{
+    PanicAfterCloseStream::new(stream)
+}
+
 struct PendingStream<T> {
     _t: PhantomData<T>,
 }
@@ -73,6 +95,10 @@ impl<T> Stream for ClosedStream<T> {
     ) -> crate::stream::PollRecv<Self::Item> {
         PollRecv::Closed
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(0))
+    }
 }
 #[pin_project]
 pub struct IterStream<I: Iterator> {
@@ -104,6 +130,10 @@ where
             None => PollRecv::Closed,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 #[pin_project]
@@ -141,3 +171,82 @@ where
         }
     }
 }
+
+#[pin_project]
+pub struct NotUnpinStream<S> {
+    #[pin]
+    stream: S,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+assert_not_impl_all!(NotUnpinStream<IterStream<std::vec::IntoIter<usize>>>: Unpin);
+
+impl<S> NotUnpinStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl<S> Stream for NotUnpinStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        self.project().stream.poll_recv(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[pin_project]
+pub struct PanicAfterCloseStream<S> {
+    #[pin]
+    stream: S,
+    closed: bool,
+}
+
+impl<S> PanicAfterCloseStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            closed: false,
+        }
+    }
+}
+
+impl<S> Stream for PanicAfterCloseStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.project();
+        assert!(!*this.closed, "stream polled after returning Closed");
+
+        let poll = this.stream.poll_recv(cx);
+        if let PollRecv::Closed = poll {
+            *this.closed = true;
+        }
+
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
@@ -0,0 +1,95 @@
+#![allow(dead_code)]
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::time::Sleep;
+
+/// A manually advanced clock.  Sleeps complete when the clock is advanced past their deadline.
+#[derive(Clone)]
+pub struct MockClock {
+    state: Arc<Mutex<MockState>>,
+    epoch: Instant,
+}
+
+#[derive(Default)]
+struct MockState {
+    now: Duration,
+    wakers: Vec<Waker>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState::default())),
+            epoch: Instant::now(),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let wakers = {
+            let mut state = self.state.lock();
+            state.now += duration;
+            std::mem::take(&mut state.wakers)
+        };
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Returns a function which reads the clock as an Instant, for APIs which accept a `now` function.
+    pub fn now_fn(&self) -> impl Fn() -> Instant + Send + Sync + 'static {
+        let epoch = self.epoch;
+        let state = self.state.clone();
+
+        move || epoch + state.lock().now
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sleep for MockClock {
+    type Future = MockSleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Future {
+        MockSleep {
+            deadline: self.state.lock().now + duration,
+            clock: self.clone(),
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.epoch + self.state.lock().now
+    }
+}
+
+pub struct MockSleep {
+    deadline: Duration,
+    clock: MockClock,
+}
+
+impl Future for MockSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.clock.state.lock();
+        if state.now >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        state.wakers.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,286 @@
+//! Helpers for testing code built on the postage [Sink](../sink/trait.Sink.html) and [Stream](../stream/trait.Stream.html) traits.
+//!
+//! A [WakeProbe](./struct.WakeProbe.html) provides a context which counts wakeups.  This is the recommended way to test
+//! that `Pending` registers the waker, and that tasks are not woken spuriously.
+//!
+//! The [poll_pending_then_wake](../macro.poll_pending_then_wake.html) macro polls an expression which must return `Pending`,
+//! runs a trigger, and asserts that the waker was called exactly once.
+//!
+//! [drive](./fn.drive.html) runs a future to completion on the current thread, without an async runtime.
+//! The future is polled again each time it is woken, and if it is pending without being woken, it has stalled.
+//! [drive_until_stalled](./fn.drive_until_stalled.html) returns `Pending` in that case, so deadlocks can be asserted deterministically.
+//! Only wakeups made during a poll are observed, so the future should not depend on timers, IO or other threads.
+//!
+//! Requires the `test-util` feature.
+//!
+//! ```rust
+//! use postage::{mpsc, poll_pending_then_wake, sink::Sink, stream::Stream};
+//! use std::pin::Pin;
+//!
+//! let (mut tx, mut rx) = mpsc::channel(4);
+//!
+//! let probe = poll_pending_then_wake!(
+//!     |cx| Pin::new(&mut rx).poll_recv(cx),
+//!     tx.try_send(1usize).unwrap()
+//! );
+//!
+//! tx.try_send(2usize).unwrap();
+//! probe.assert_woken(1);
+//! ```
+//!
+//! ```rust
+//! use postage::{mpsc, sink::Sink, stream::Stream, test_util::{drive, drive_until_stalled}};
+//! use std::task::Poll;
+//!
+//! let (mut tx, mut rx) = mpsc::channel(2);
+//!
+//! let sum = drive(async move {
+//!     tx.send(1usize).await.unwrap();
+//!     tx.send(2usize).await.unwrap();
+//!     drop(tx);
+//!
+//!     let mut sum = 0;
+//!     while let Some(v) = rx.recv().await {
+//!         sum += v;
+//!     }
+//!     sum
+//! });
+//! assert_eq!(3, sum);
+//!
+//! // the channel is full, and nothing receives from it
+//! let (mut tx, _rx) = mpsc::channel(1);
+//! assert_eq!(
+//!     Poll::Pending,
+//!     drive_until_stalled(async move {
+//!         tx.send(1usize).await.unwrap();
+//!         tx.send(2usize).await.unwrap();
+//!     })
+//! );
+//! ```
+use std::{
+    fmt::Debug,
+    future::Future,
+    task::{Poll, Waker},
+};
+
+use futures_test::task::{new_count_waker, AwokenCount};
+
+use crate::{sink::PollSend, stream::PollRecv, Context};
+
+/// A waker which counts the number of times it has been woken.
+pub struct WakeProbe {
+    waker: Waker,
+    count: AwokenCount,
+}
+
+impl WakeProbe {
+    pub fn new() -> Self {
+        let (waker, count) = new_count_waker();
+        Self { waker, count }
+    }
+
+    /// Returns a context which wakes this probe.
+    pub fn context(&self) -> Context<'_> {
+        Context::from_waker(&self.waker)
+    }
+
+    /// Returns the number of times the probe has been woken.
+    pub fn count(&self) -> usize {
+        self.count.get()
+    }
+
+    /// Asserts that the probe has been woken exactly `n` times.
+    #[track_caller]
+    pub fn assert_woken(&self, n: usize) {
+        assert_eq!(
+            n,
+            self.count(),
+            "expected the probe to be woken {} times",
+            n
+        );
+    }
+
+    /// Asserts that the probe has never been woken.
+    #[track_caller]
+    pub fn assert_not_woken(&self) {
+        assert_eq!(0, self.count(), "expected the probe not to be woken");
+    }
+}
+
+impl Default for WakeProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for WakeProbe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WakeProbe")
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+/// Runs the future to completion on the current thread.
+///
+/// Panics if the future stalls: it returns `Pending` without being woken, and cannot make progress.
+#[track_caller]
+pub fn drive<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    match drive_until_stalled(future) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the future stalled: it is pending, and was not woken"),
+    }
+}
+
+/// Polls the future on the current thread, until it completes or stalls.
+///
+/// Returns `Pending` if the future returns `Pending` without being woken.  To keep polling the future
+/// after it stalls, pass `&mut future`.
+pub fn drive_until_stalled<F>(future: F) -> Poll<F::Output>
+where
+    F: Future,
+{
+    let mut future = Box::pin(future);
+    let probe = WakeProbe::new();
+    let mut cx = std::task::Context::from_waker(&probe.waker);
+
+    loop {
+        let wakeups = probe.count();
+
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Poll::Ready(output);
+        }
+
+        // without a wakeup, nothing on this thread can make progress
+        if probe.count() == wakeups {
+            return Poll::Pending;
+        }
+    }
+}
+
+/// Poll results which can be pending.  Used by the `poll_pending_then_wake` macro.
+pub trait IsPending: Debug {
+    fn is_pending(&self) -> bool;
+}
+
+impl<T: Debug> IsPending for PollRecv<T> {
+    fn is_pending(&self) -> bool {
+        matches!(self, PollRecv::Pending)
+    }
+}
+
+impl<T: Debug> IsPending for PollSend<T> {
+    fn is_pending(&self) -> bool {
+        matches!(self, PollSend::Pending(_))
+    }
+}
+
+/// Polls an expression with the context of a new [WakeProbe](./test_util/struct.WakeProbe.html), and asserts that it returns `Pending`
+/// without waking the probe.  Then evaluates the trigger, and asserts that the probe was woken exactly once.
+///
+/// Returns the probe, so further wakeups can be asserted.
+///
+/// Requires the `test-util` feature.
+#[macro_export]
+macro_rules! poll_pending_then_wake {
+    (|$cx:ident| $poll:expr, $trigger:expr $(,)?) => {{
+        let probe = $crate::test_util::WakeProbe::new();
+
+        {
+            let mut context = probe.context();
+            let $cx = &mut context;
+            let poll = $poll;
+            assert!(
+                $crate::test_util::IsPending::is_pending(&poll),
+                "expected Pending, found {:?}",
+                poll
+            );
+        }
+
+        probe.assert_not_woken();
+        $trigger;
+        probe.assert_woken(1);
+
+        probe
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Poll;
+
+    use crate::{
+        mpsc,
+        sink::Sink,
+        stream::{bridge, Stream},
+    };
+
+    use super::{drive, drive_until_stalled};
+
+    #[test]
+    fn drive_pipeline() {
+        let (mut in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, out_rx) = mpsc::channel(4);
+        let (err_tx, _err_rx) = mpsc::channel(4);
+
+        let report = drive(async move {
+            for value in ["1", "x", "2"].iter().copied() {
+                in_tx.send(value).await.unwrap();
+            }
+            drop(in_tx);
+
+            bridge(in_rx, out_tx, |v: &str| v.parse::<usize>(), err_tx).await
+        });
+        assert_eq!(2, report.forwarded);
+
+        let mut out_rx = out_rx.map(|v| v * 10);
+        let received = drive(async move {
+            let mut received = Vec::new();
+            while let Some(v) = out_rx.recv().await {
+                received.push(v);
+            }
+            received
+        });
+        assert_eq!(vec![10, 20], received);
+    }
+
+    #[test]
+    fn drive_yielding_receiver() {
+        // the receiver yields while draining, and wakes itself
+        let (mut tx, mut rx) = mpsc::channel(1024);
+        for i in 0..1024usize {
+            tx.try_send(i).unwrap();
+        }
+        drop(tx);
+
+        let sum = drive(async move {
+            let mut sum = 0;
+            while let Some(v) = rx.recv().await {
+                sum += v;
+            }
+            sum
+        });
+        assert_eq!(1024 * 1023 / 2, sum);
+    }
+
+    #[test]
+    fn stalled_until_sent() {
+        let (mut tx, mut rx) = mpsc::channel(4);
+        let mut recv = Box::pin(rx.recv());
+
+        assert_eq!(Poll::Pending, drive_until_stalled(&mut recv));
+
+        tx.try_send(1usize).unwrap();
+        assert_eq!(Poll::Ready(Some(1)), drive_until_stalled(&mut recv));
+    }
+
+    #[test]
+    #[should_panic]
+    fn drive_stalled() {
+        let (_tx, mut rx) = mpsc::channel::<usize>(4);
+        drive(rx.recv());
+    }
+}
@@ -0,0 +1,130 @@
+//! Timers used by time-based combinators.
+//!
+//! Combinators such as [Stream::timeout_with](../stream/trait.Stream.html#method.timeout_with) accept any
+//! [Sleep](./trait.Sleep.html) implementation, which allows custom runtimes, or test-controlled time.
+//!
+//! The `tokio` and `async-std` features provide implementations backed by the runtime timer.
+//! If both features are enabled, the tokio timer is used by the default combinators.
+use std::{
+    future::Future,
+    pin::Pin,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+use crate::Context;
+
+/// A source of sleep futures, which complete after a given duration.
+pub trait Sleep {
+    /// The future returned by [sleep](#tymethod.sleep).
+    type Future: Future<Output = ()>;
+
+    /// Creates a future which completes `duration` from now.
+    fn sleep(&self, duration: Duration) -> Self::Future;
+
+    /// Restarts a future created by this timer, so that it completes `duration` from now.
+    ///
+    /// By default, the future is replaced with a new sleep.
+    fn reset(&self, mut future: Pin<&mut Self::Future>, duration: Duration) {
+        future.set(self.sleep(duration));
+    }
+
+    /// Returns the current time, as measured by this timer.
+    ///
+    /// By default, this is `Instant::now`.  Timers with a virtual clock, such as paused tokio time, override it.
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// The tokio timer.  Futures must be polled within the tokio runtime.
+///
+/// Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Tokio;
+
+#[cfg(feature = "tokio")]
+impl Sleep for Tokio {
+    type Future = tokio::time::Sleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Future {
+        tokio::time::sleep(duration)
+    }
+
+    fn reset(&self, future: Pin<&mut Self::Future>, duration: Duration) {
+        future.reset(tokio::time::Instant::now() + duration);
+    }
+
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+}
+
+/// The async-std timer.
+///
+/// Requires the `async-std` feature.
+#[cfg(feature = "async-std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AsyncStd;
+
+#[cfg(feature = "async-std")]
+impl Sleep for AsyncStd {
+    type Future = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn sleep(&self, duration: Duration) -> Self::Future {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// The timer of the enabled async runtime.
+#[cfg(feature = "tokio")]
+pub(crate) type RuntimeSleep = Tokio;
+
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+pub(crate) type RuntimeSleep = AsyncStd;
+
+/// A resettable delay, backed by a `Sleep` implementation.
+///
+/// The sleep is created when the delay is first polled or reset, so combinators can be constructed outside the
+/// runtime, and the first period starts when the combinator is first polled.
+pub(crate) struct Delay<T: Sleep> {
+    timer: T,
+    // the duration of the first sleep
+    duration: Duration,
+    sleep: Option<Pin<Box<T::Future>>>,
+}
+
+impl<T: Sleep> Delay<T> {
+    pub fn new(timer: T, duration: Duration) -> Self {
+        Self {
+            timer,
+            duration,
+            sleep: None,
+        }
+    }
+
+    /// Restarts the delay, so that it completes `duration` from now.
+    pub fn reset(&mut self, duration: Duration) {
+        match &mut self.sleep {
+            Some(sleep) => self.timer.reset(sleep.as_mut(), duration),
+            None => self.sleep = Some(Box::pin(self.timer.sleep(duration))),
+        }
+    }
+
+    /// Returns the current time of the timer.
+    pub fn now(&self) -> Instant {
+        self.timer.now()
+    }
+
+    /// Polls the delay, registering the waker in `cx` if it has not yet elapsed.
+    pub fn poll_elapsed(&mut self, cx: &Context<'_>) -> Poll<()> {
+        let (timer, duration) = (&self.timer, self.duration);
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(timer.sleep(duration)))
+            .as_mut();
+
+        cx.with_std(|cx| sleep.poll(cx))
+    }
+}